@@ -1,10 +1,12 @@
-use log::debug;
 use std::{collections::HashMap, rc::Rc};
+use web_time::{Duration, Instant};
 use winit::event::{ElementState, Event, WindowEvent};
 
+use crate::trace::debug;
 use crate::ui::{
+    layout::Point,
     sync::{Signal, Trigger},
-    widget::BaseWidget,
+    widget::WidgetI,
 };
 
 use super::ActionHandler;
@@ -31,13 +33,42 @@ pub enum MouseButton {
     OtherButtonReleased(u16),
     OtherButton(u16),
 }
+impl MouseButton {
+    /// The pressed counterpart of a released button
+    fn pressed(self) -> MouseButton {
+        match self {
+            MouseButton::LeftButtonRelease => MouseButton::LeftButton,
+            MouseButton::RightButtonRelease => MouseButton::RightButton,
+            MouseButton::MiddleButtonRelease => MouseButton::MiddleButton,
+            MouseButton::ForwardButtonRelease => MouseButton::ForwardButton,
+            MouseButton::BackButtonRelease => MouseButton::BackButton,
+            MouseButton::OtherButtonReleased(v) => MouseButton::OtherButton(v),
+            pressed => pressed,
+        }
+    }
+}
+
+type ClickCallback<State> =
+    Rc<dyn Fn(&mut State, Rc<Trigger>, &Rc<dyn WidgetI>, Event<Signal>, Point)>;
+
+/// The default time allowed between presses
+/// for them to count as the same click
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
 /// The `Click` struct allows widgets to have the ability
 /// to respond to any mouse click event
+///
+/// Presses of the same button arriving within the
+/// interval of each other are counted so double
+/// and triple clicks can be told apart
 #[derive(Clone)]
 pub struct Click<State> {
     state: State,
-    button_map:
-        HashMap<MouseButton, Rc<dyn Fn(&mut State, Rc<Trigger>, &mut BaseWidget, Event<Signal>)>>,
+    button_map: HashMap<MouseButton, ClickCallback<State>>,
+    count_map: HashMap<(MouseButton, u32), ClickCallback<State>>,
+    interval: Duration,
+    last_press: Option<(MouseButton, Instant)>,
+    count: u32,
 }
 impl<State> Click<State> {
     /// Create a new `Click` action
@@ -49,17 +80,34 @@ impl<State> Click<State> {
         Self {
             state,
             button_map: HashMap::default(),
+            count_map: HashMap::default(),
+            interval: DEFAULT_INTERVAL,
+            last_press: None,
+            count: 0,
         }
     }
+    /// Set the longest time allowed between presses for
+    /// them to count as the same click
+    ///
+    /// Defaults to 500ms
+    pub fn set_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
     /// Set a handler for a specific button type
     ///
+    /// The handler is given where the widget was clicked
+    /// relative to its top-left corner
+    ///
     /// Types:
     /// - LeftButton
     /// - RightButton
     /// - MiddleButton
     /// - BackButton
     /// - ForwardButton
-    pub fn on<F: Fn(&mut State, Rc<Trigger>, &mut BaseWidget, Event<Signal>) + Clone + 'static>(
+    pub fn on<
+        F: Fn(&mut State, Rc<Trigger>, &Rc<dyn WidgetI>, Event<Signal>, Point) + Clone + 'static,
+    >(
         mut self,
         btn: MouseButton,
         callback: F,
@@ -67,13 +115,56 @@ impl<State> Click<State> {
         self.button_map.insert(btn, Rc::new(callback));
         self
     }
+    /// Set a handler for a specific button type that only
+    /// fires on the `count` click in a row
+    ///
+    /// A `count` of 2 responds to double clicks, 3 to triple
+    /// clicks and so on
+    pub fn on_count<
+        F: Fn(&mut State, Rc<Trigger>, &Rc<dyn WidgetI>, Event<Signal>, Point) + Clone + 'static,
+    >(
+        mut self,
+        btn: MouseButton,
+        count: u32,
+        callback: F,
+    ) -> Self {
+        self.count_map.insert((btn, count), Rc::new(callback));
+        self
+    }
+    /// Counts a press of `button` at `now` and returns how
+    /// many clicks in a row it makes
+    fn register_press(&mut self, button: MouseButton, now: Instant) -> u32 {
+        self.count = match self.last_press {
+            Some((last, at)) if last == button && now.duration_since(at) <= self.interval => {
+                self.count + 1
+            }
+            _ => 1,
+        };
+        self.last_press = Some((button, now));
+        self.count
+    }
+    /// How many clicks in a row the latest press of
+    /// `button` made
+    fn current_count(&self, button: MouseButton) -> u32 {
+        match self.last_press {
+            Some((last, _)) if last == button.pressed() => self.count,
+            _ => 1,
+        }
+    }
 }
 impl<State: Clone> ActionHandler for Click<State> {
-    fn apply(&mut self, trigger: Rc<Trigger>, widget: &mut BaseWidget, e: Event<Signal>) {
+    fn apply(
+        &mut self,
+        trigger: Rc<Trigger>,
+        widget: &Rc<dyn WidgetI>,
+        e: Event<Signal>,
+        cursor: Point,
+    ) {
         match e {
             Event::WindowEvent { ref event, .. } => match event {
-                WindowEvent::CursorMoved { position, .. } => {
-                    widget.state.hovered = widget.layout.is_inbounds(position.x, position.y);
+                WindowEvent::CursorMoved { .. } => {
+                    let mut base = widget.base_mut();
+                    base.state.hovered = base.state.hit;
                 }
                 WindowEvent::MouseInput { button, state, .. } => {
                     let button = match (button, state) {
@@ -115,11 +206,25 @@ impl<State: Clone> ActionHandler for Click<State> {
                         }
                     };
 
-                    if widget.state.hovered {
-                        let handler = self.button_map.get(&button);
-                        if let Some(handler) = handler {
-                            debug!("triggered {:?} for widget: {}", button, widget.id);
-                            handler(&mut self.state, trigger, widget, e)
+                    if widget.base().state.hovered {
+                        let count = if *state == ElementState::Pressed {
+                            self.register_press(button, Instant::now())
+                        } else {
+                            self.current_count(button)
+                        };
+
+                        if let Some(handler) = self.button_map.get(&button).cloned() {
+                            debug!("triggered {:?} for widget: {}", button, widget.base().id);
+                            handler(&mut self.state, trigger.clone(), widget, e.clone(), cursor)
+                        }
+                        if let Some(handler) = self.count_map.get(&(button, count)).cloned() {
+                            debug!(
+                                "triggered {:?} x{} for widget: {}",
+                                button,
+                                count,
+                                widget.base().id
+                            );
+                            handler(&mut self.state, trigger, widget, e, cursor)
                         }
                     }
                 }
@@ -129,3 +234,90 @@ impl<State: Clone> ActionHandler for Click<State> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_presses_within_interval() {
+        let mut click = Click::new(()).set_interval(Duration::from_millis(100));
+        let start = Instant::now();
+
+        assert_eq!(click.register_press(MouseButton::LeftButton, start), 1);
+        assert_eq!(
+            click.register_press(MouseButton::LeftButton, start + Duration::from_millis(50)),
+            2
+        );
+        assert_eq!(click.current_count(MouseButton::LeftButtonRelease), 2);
+        assert_eq!(
+            click.register_press(MouseButton::LeftButton, start + Duration::from_millis(100)),
+            3
+        );
+        // Too slow starts over
+        assert_eq!(
+            click.register_press(MouseButton::LeftButton, start + Duration::from_millis(300)),
+            1
+        );
+        // Switching buttons starts over
+        assert_eq!(
+            click.register_press(MouseButton::RightButton, start + Duration::from_millis(310)),
+            1
+        );
+        assert_eq!(click.current_count(MouseButton::LeftButtonRelease), 1);
+    }
+
+    #[test]
+    fn handlers_are_given_the_cursor_within_the_widget() {
+        use std::cell::Cell;
+
+        use crate::action::Action;
+        use crate::ui::{
+            dom::DOM,
+            widget::{label::Label, Widget},
+        };
+
+        let clicked = Rc::new(Cell::new(None));
+        let click = Click::new(()).on(MouseButton::LeftButtonRelease, {
+            let clicked = clicked.clone();
+            move |_, _, _, _, cursor| clicked.set(Some(cursor))
+        });
+        let mut dom = DOM::headless(100, 100);
+        dom.add_widget(
+            Label::new()
+                .set_x(20.0)
+                .set_y(30.0)
+                .set_width(40.0)
+                .set_height(40.0)
+                .on_action(Action::Click(Box::new(click))),
+        );
+
+        dom.click(25.0, 40.0);
+        assert_eq!(clicked.get(), Some(Point::new(5.0, 10.0)));
+    }
+
+    #[test]
+    fn handlers_reach_the_concrete_widget() {
+        use crate::ui::{
+            dom::DOM,
+            widget::{
+                icon::{Icon, IconName},
+                Widget,
+            },
+        };
+
+        let mut dom = DOM::headless(100, 100);
+        let id = dom.add_widget(Icon::new(IconName::Play).on_click(|widget, _| {
+            if let Some(icon) = widget.downcast::<Icon>() {
+                icon.set_icon(IconName::Pause);
+            }
+        }));
+        let widget = dom.widget(id).unwrap();
+
+        dom.click(10.0, 10.0);
+        assert_eq!(
+            widget.downcast::<Icon>().map(Icon::name),
+            Some(IconName::Pause)
+        );
+    }
+}
@@ -1,20 +1,27 @@
-use log::debug;
 use std::rc::Rc;
 use winit::event::{Event, WindowEvent};
 
+use crate::trace::debug;
 use crate::ui::{
+    layout::Point,
     sync::{Signal, Trigger},
-    widget::BaseWidget,
+    widget::WidgetI,
 };
 
 use super::ActionHandler;
 
+type CursorCallback<State> =
+    Rc<dyn Fn(&mut State, Rc<Trigger>, &Rc<dyn WidgetI>, Event<Signal>, Point)>;
+
 /// The `CursorMove` struct allows widgets to have the ability
 /// to respond to any mouse move event
+///
+/// The handler is given where the cursor is relative to the
+/// top-left corner of the widget
 #[derive(Clone)]
 pub struct CursorMove<State> {
     state: State,
-    handler: Rc<dyn Fn(&mut State, Rc<Trigger>, &mut BaseWidget, Event<Signal>)>,
+    handler: CursorCallback<State>,
 }
 impl<State> CursorMove<State> {
     /// Create a new `CursorMove` action
@@ -22,7 +29,9 @@ impl<State> CursorMove<State> {
     /// The `state` provides the ability
     /// to react to the current state of any
     /// arbitrary instance
-    pub fn new<F: Fn(&mut State, Rc<Trigger>, &mut BaseWidget, Event<Signal>) + Clone + 'static>(
+    pub fn new<
+        F: Fn(&mut State, Rc<Trigger>, &Rc<dyn WidgetI>, Event<Signal>, Point) + Clone + 'static,
+    >(
         state: State,
         callback: F,
     ) -> Self {
@@ -33,16 +42,26 @@ impl<State> CursorMove<State> {
     }
 }
 impl<State: Clone> ActionHandler for CursorMove<State> {
-    fn apply(&mut self, trigger: Rc<Trigger>, widget: &mut BaseWidget, e: Event<Signal>) {
+    fn apply(
+        &mut self,
+        trigger: Rc<Trigger>,
+        widget: &Rc<dyn WidgetI>,
+        e: Event<Signal>,
+        cursor: Point,
+    ) {
         match e {
             Event::WindowEvent { ref event, .. } => match event {
-                WindowEvent::CursorMoved { position, .. } => {
-                    widget.state.hovered = widget.layout.is_inbounds(position.x, position.y);
+                WindowEvent::CursorMoved { .. } => {
+                    let hovered = {
+                        let mut base = widget.base_mut();
+                        base.state.hovered = base.state.hit;
+                        base.state.hovered
+                    };
 
-                    if widget.state.hovered {
-                        debug!("triggered on cursor move for widget: {}", widget.id);
+                    if hovered {
+                        debug!("triggered on cursor move for widget: {}", widget.base().id);
                         let handler = &self.handler;
-                        handler(&mut self.state, trigger, widget, e)
+                        handler(&mut self.state, trigger, widget, e, cursor)
                     }
                 }
                 _ => (),
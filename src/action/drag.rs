@@ -0,0 +1,286 @@
+use std::rc::Rc;
+use winit::event::{ElementState, Event, MouseButton, WindowEvent};
+
+use crate::trace::debug;
+use crate::ui::{
+    arena::WidgetId,
+    layout::{Layout, Point},
+    sync::{Signal, Trigger},
+    widget::BaseWidget,
+};
+
+use dyn_clone::{clone_trait_object, DynClone};
+
+type DragCallback<State> = Rc<dyn Fn(&mut State, Rc<Trigger>, &mut BaseWidget, DragEvent)>;
+
+/// The `DragEvent` struct describes the progress of a drag
+///
+/// - `start`: Where the cursor grabbed the widget
+/// - `position`: Where the cursor currently is
+/// - `delta`: How far the cursor moved since the last event
+/// - `target`: The drop target under the cursor when dropped
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+pub struct DragEvent {
+    pub start: Point,
+    pub position: Point,
+    pub delta: Point,
+    pub target: Option<WidgetId>,
+}
+
+/// The `Drag` struct allows widgets to have the ability
+/// to be picked up with the left mouse button and moved
+///
+/// The widget follows the cursor while dragged unless
+/// `set_fixed` is used. Widgets marked with `set_drop_target`
+/// are reported as the target of the drop when the widget is
+/// released above them.
+#[derive(Clone)]
+pub struct Drag<State> {
+    state: State,
+    fixed: bool,
+    dragging: bool,
+    origin: Point,
+    start: Point,
+    last: Point,
+    on_drag_start: Option<DragCallback<State>>,
+    on_drag_move: Option<DragCallback<State>>,
+    on_drop: Option<DragCallback<State>>,
+}
+impl<State> Drag<State> {
+    /// Create a new `Drag` action
+    ///
+    /// The `state` provides the ability
+    /// to react to the current state of any
+    /// arbitrary instance
+    pub fn new(state: State) -> Self {
+        Self {
+            state,
+            fixed: false,
+            dragging: false,
+            origin: Point::default(),
+            start: Point::default(),
+            last: Point::default(),
+            on_drag_start: None,
+            on_drag_move: None,
+            on_drop: None,
+        }
+    }
+    /// Keep the widget in place while dragging so the
+    /// callbacks decide what moves
+    pub fn set_fixed(mut self) -> Self {
+        self.fixed = true;
+        self
+    }
+    /// Set a handler for when the widget gets picked up
+    pub fn on_drag_start<
+        F: Fn(&mut State, Rc<Trigger>, &mut BaseWidget, DragEvent) + Clone + 'static,
+    >(
+        mut self,
+        callback: F,
+    ) -> Self {
+        self.on_drag_start = Some(Rc::new(callback));
+        self
+    }
+    /// Set a handler for every cursor movement while dragged
+    pub fn on_drag_move<
+        F: Fn(&mut State, Rc<Trigger>, &mut BaseWidget, DragEvent) + Clone + 'static,
+    >(
+        mut self,
+        callback: F,
+    ) -> Self {
+        self.on_drag_move = Some(Rc::new(callback));
+        self
+    }
+    /// Set a handler for when the widget gets released
+    pub fn on_drop<F: Fn(&mut State, Rc<Trigger>, &mut BaseWidget, DragEvent) + Clone + 'static>(
+        mut self,
+        callback: F,
+    ) -> Self {
+        self.on_drop = Some(Rc::new(callback));
+        self
+    }
+    fn event(&self, position: Point, target: Option<WidgetId>) -> DragEvent {
+        DragEvent {
+            start: self.start,
+            position,
+            delta: Point::new(position.x - self.last.x, position.y - self.last.y),
+            target,
+        }
+    }
+}
+
+/// The trait `DragHandler` provides the drag
+/// specific counterpart of `ActionHandler` which
+/// is also handed the topmost drop target under the
+/// cursor other than the widget itself
+pub trait DragHandler: DynClone {
+    fn apply(
+        &mut self,
+        trigger: Rc<Trigger>,
+        widget: &mut BaseWidget,
+        e: Event<Signal>,
+        cursor_pos: Point,
+        target: Option<WidgetId>,
+    );
+}
+clone_trait_object!(DragHandler);
+
+impl<State: Clone> DragHandler for Drag<State> {
+    fn apply(
+        &mut self,
+        trigger: Rc<Trigger>,
+        widget: &mut BaseWidget,
+        e: Event<Signal>,
+        cursor_pos: Point,
+        target: Option<WidgetId>,
+    ) {
+        let Event::WindowEvent { ref event, .. } = e else {
+            return;
+        };
+
+        match event {
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+                ..
+            } if widget.state.hit => {
+                debug!("drag started for widget: {}", widget.id);
+                self.dragging = true;
+                self.origin = Point::new(widget.layout.x, widget.layout.y);
+                self.start = cursor_pos;
+                self.last = cursor_pos;
+
+                let drag = self.event(cursor_pos, None);
+                if let Some(handler) = &self.on_drag_start {
+                    handler(&mut self.state, trigger, widget, drag);
+                }
+            }
+            WindowEvent::CursorMoved { position, .. } if self.dragging => {
+                let position = Point::new(position.x, position.y);
+                let drag = self.event(position, None);
+                self.last = position;
+
+                if !self.fixed {
+                    // Where the widget was needs clearing too
+                    trigger.invalidate(Layout {
+                        x: widget.offset.x + widget.layout.x,
+                        y: widget.offset.y + widget.layout.y,
+                        ..widget.layout
+                    });
+                    widget.layout.x = self.origin.x + position.x - self.start.x;
+                    widget.layout.y = self.origin.y + position.y - self.start.y;
+                    trigger.update();
+                }
+
+                if let Some(handler) = &self.on_drag_move {
+                    handler(&mut self.state, trigger, widget, drag);
+                }
+            }
+            WindowEvent::MouseInput {
+                state: ElementState::Released,
+                button: MouseButton::Left,
+                ..
+            } if self.dragging => {
+                self.dragging = false;
+                debug!("dropped widget: {} on {:?}", widget.id, target);
+
+                let drag = self.event(cursor_pos, target);
+                if let Some(handler) = &self.on_drop {
+                    handler(&mut self.state, trigger, widget, drag);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use crate::{
+        action::Action,
+        ui::{
+            arena::WidgetId,
+            dom::DOM,
+            layout::{FlexLayout, Point},
+            widget::{container::Container, label::Label, widget_id, Widget},
+        },
+    };
+
+    use super::{Drag, DragEvent};
+
+    /// A label dragged around that records where it was dropped
+    fn draggable(x: f64, dropped: &Rc<Cell<Option<DragEvent>>>) -> Label {
+        let dropped = dropped.clone();
+        let drag = Drag::new(()).on_drop(move |_, _, _, event| dropped.set(Some(event)));
+        Label::new()
+            .set_x(x)
+            .set_width(20.0)
+            .set_height(20.0)
+            .on_action(Action::Drag(Box::new(drag)))
+    }
+
+    fn target(x: f64) -> Label {
+        Label::new()
+            .set_x(x)
+            .set_width(50.0)
+            .set_height(50.0)
+            .set_drop_target()
+    }
+
+    #[test]
+    fn dragged_widgets_follow_the_cursor_onto_siblings() {
+        let mut dom = DOM::headless(200, 200);
+        let dropped = Rc::new(Cell::new(None));
+        let target = dom.add_widget(target(0.0));
+        let id = dom.add_widget(draggable(0.0, &dropped));
+
+        dom.drag(Point::new(10.0, 10.0), Point::new(20.0, 25.0));
+        let layout = dom.widget(id).unwrap().base().layout;
+        assert_eq!((layout.x, layout.y), (10.0, 15.0));
+
+        let event = dropped.get().unwrap();
+        assert_eq!(event.start, Point::new(10.0, 10.0));
+        assert_eq!(event.position, Point::new(20.0, 25.0));
+        assert_eq!(event.target, Some(target));
+
+        // Nothing to drop onto out here
+        dom.drag(Point::new(25.0, 20.0), Point::new(150.0, 150.0));
+        assert_eq!(dropped.get().unwrap().target, None);
+    }
+
+    #[test]
+    fn drop_targets_are_hit_where_they_are_scrolled_to() {
+        let mut dom = DOM::headless(200, 200);
+        let dropped = Rc::new(Cell::new(None));
+        let mut container = Container::new()
+            .set_width(100.0)
+            .set_height(100.0)
+            .set_flex_layout(FlexLayout::Col)
+            .on_scroll();
+        container.add_widget(Label::new().set_width(50.0).set_height(100.0));
+        container.add_widget(target(0.0));
+        let id = dom.add_widget(container);
+        dom.add_widget(draggable(150.0, &dropped));
+        // Drops the label at `to` before bringing it back
+        let drop = |dom: &mut DOM, to: Point| -> Option<WidgetId> {
+            dom.drag(Point::new(160.0, 10.0), to);
+            let target = dropped.get().unwrap().target;
+            dom.drag(to, Point::new(160.0, 10.0));
+            target
+        };
+        let scrolled = dom.widget(id).unwrap();
+        let target = widget_id(&scrolled.children()[1]);
+
+        // Clipped away below the container
+        assert_eq!(drop(&mut dom, Point::new(25.0, 125.0)), None);
+
+        scrolled
+            .downcast::<Container>()
+            .unwrap()
+            .scroll_to(0.0, 50.0);
+        assert_eq!(drop(&mut dom, Point::new(25.0, 75.0)), target);
+        assert_eq!(drop(&mut dom, Point::new(25.0, 125.0)), None);
+    }
+}
@@ -1,17 +1,20 @@
 use std::rc::Rc;
 
-use log::debug;
 use winit::{
     event::{Event, WindowEvent},
     window::Window,
 };
 
+use crate::trace::debug;
 use crate::ui::{
     color::{Color, ColorMode},
+    layout::Point,
     sync::{Signal, Trigger},
-    widget::BaseWidget,
+    widget::{BaseWidget, WidgetI},
 };
 
+use super::ActionHandler;
+
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Hover {
     pub hover_color: Color,
@@ -31,10 +34,10 @@ impl Hover {
     ) {
         match event {
             Event::WindowEvent { event, .. } => match event {
-                WindowEvent::CursorMoved { position, .. } => {
+                WindowEvent::CursorMoved { .. } => {
                     let previous_hover_state = widget.state.hovered;
 
-                    widget.state.hovered = widget.layout.is_inbounds(position.x, position.y);
+                    widget.state.hovered = widget.state.hit;
 
                     if previous_hover_state != widget.state.hovered {
                         if widget.state.hovered {
@@ -57,3 +60,173 @@ impl Hover {
         }
     }
 }
+
+type HoverCallback<State> =
+    Rc<dyn Fn(&mut State, Rc<Trigger>, &Rc<dyn WidgetI>, Event<Signal>, Point)>;
+
+/// The `HoverHandler` struct allows widgets to run logic of
+/// their own once the cursor enters or leaves them
+///
+/// Unlike `Hover` which only shades the widget, the callbacks
+/// may change labels, start animations or show tooltips.
+///
+/// ## Example
+/// ```ignore
+/// let hover = HoverHandler::new(())
+///     .on_enter(|_, trigger, widget, _, _| {
+///         widget.base_mut().text.label = "Release to drop".into();
+///         trigger.update();
+///     })
+///     .on_leave(|_, trigger, widget, _, _| {
+///         widget.base_mut().text.label = "Drop here".into();
+///         trigger.update();
+///     });
+/// let label = Label::new().on_action(Action::HoverChange(Box::new(hover)));
+/// ```
+#[derive(Clone)]
+pub struct HoverHandler<State> {
+    state: State,
+    /// Whether the cursor was over the widget before
+    hovered: bool,
+    enter: Option<HoverCallback<State>>,
+    leave: Option<HoverCallback<State>>,
+}
+impl<State> HoverHandler<State> {
+    /// Create a new `HoverHandler` action
+    ///
+    /// The `state` provides the ability
+    /// to react to the current state of any
+    /// arbitrary instance
+    pub fn new(state: State) -> Self {
+        Self {
+            state,
+            hovered: false,
+            enter: None,
+            leave: None,
+        }
+    }
+    /// Set the handler called once the cursor enters the widget
+    pub fn on_enter<
+        F: Fn(&mut State, Rc<Trigger>, &Rc<dyn WidgetI>, Event<Signal>, Point) + Clone + 'static,
+    >(
+        mut self,
+        callback: F,
+    ) -> Self {
+        self.enter = Some(Rc::new(callback));
+        self
+    }
+    /// Set the handler called once the cursor leaves the widget
+    pub fn on_leave<
+        F: Fn(&mut State, Rc<Trigger>, &Rc<dyn WidgetI>, Event<Signal>, Point) + Clone + 'static,
+    >(
+        mut self,
+        callback: F,
+    ) -> Self {
+        self.leave = Some(Rc::new(callback));
+        self
+    }
+}
+impl<State: Clone> ActionHandler for HoverHandler<State> {
+    fn apply(
+        &mut self,
+        trigger: Rc<Trigger>,
+        widget: &Rc<dyn WidgetI>,
+        e: Event<Signal>,
+        cursor: Point,
+    ) {
+        // Other actions may have taken note of the hover
+        // already so it is kept track of apart
+        let hovered = match &e {
+            Event::WindowEvent {
+                event: WindowEvent::CursorMoved { .. },
+                ..
+            } => widget.base().state.hit,
+            Event::WindowEvent {
+                event: WindowEvent::CursorLeft { .. },
+                ..
+            } => false,
+            _ => return,
+        };
+        if hovered == self.hovered {
+            return;
+        }
+        self.hovered = hovered;
+
+        let callback = if hovered {
+            debug!("triggered hover enter for widget: {}", widget.base().id);
+            &self.enter
+        } else {
+            debug!("triggered hover leave for widget: {}", widget.base().id);
+            &self.leave
+        };
+        if let Some(callback) = callback {
+            callback(&mut self.state, trigger, widget, e, cursor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::action::Action;
+    use crate::ui::{
+        dom::DOM,
+        widget::{label::Label, Widget},
+    };
+
+    use super::HoverHandler;
+
+    #[test]
+    fn hover_callbacks_fire_on_entering_and_leaving() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut dom = DOM::headless(100, 100);
+        dom.add_widget(
+            Label::new()
+                .set_width(50.0)
+                .set_height(50.0)
+                .on_hover_enter({
+                    let log = log.clone();
+                    move |_, _| log.borrow_mut().push("enter")
+                })
+                .on_hover_exit({
+                    let log = log.clone();
+                    move |_, _| log.borrow_mut().push("exit")
+                }),
+        );
+
+        dom.move_cursor(10.0, 10.0);
+        dom.move_cursor(20.0, 20.0);
+        dom.move_cursor(80.0, 80.0);
+        assert_eq!(*log.borrow(), ["enter", "exit"]);
+    }
+
+    #[test]
+    fn handlers_change_the_widget_on_entering_and_leaving() {
+        let hover = HoverHandler::new(0)
+            .on_enter(|entered, trigger, widget, _, _| {
+                *entered += 1;
+                widget.base_mut().text.label = format!("entered {entered}");
+                trigger.update();
+            })
+            .on_leave(|_, trigger, widget, _, _| {
+                widget.base_mut().text.label = "left".into();
+                trigger.update();
+            });
+        let mut dom = DOM::headless(100, 100);
+        let id = dom.add_widget(
+            Label::new()
+                .set_width(50.0)
+                .set_height(50.0)
+                .on_action(Action::HoverChange(Box::new(hover))),
+        );
+        let label = dom.widget(id).unwrap();
+
+        dom.move_cursor(10.0, 10.0);
+        assert_eq!(label.base().text.label, "entered 1");
+        dom.move_cursor(80.0, 80.0);
+        assert_eq!(label.base().text.label, "left");
+        dom.move_cursor(10.0, 10.0);
+        assert_eq!(label.base().text.label, "entered 2");
+    }
+}
@@ -0,0 +1,274 @@
+use std::{collections::HashMap, rc::Rc};
+use winit::{
+    event::{ElementState, Event, Ime, WindowEvent},
+    keyboard::{Key, ModifiersState, NamedKey},
+};
+
+use crate::trace::debug;
+use crate::ui::{
+    layout::Point,
+    sync::{Signal, Trigger},
+    text::Preedit,
+    widget::WidgetI,
+};
+
+use super::ActionHandler;
+
+type KeyCallback<State> = Rc<dyn Fn(&mut State, Rc<Trigger>, &Rc<dyn WidgetI>, Event<Signal>)>;
+type TextCallback<State> = Rc<dyn Fn(&mut State, Rc<Trigger>, &Rc<dyn WidgetI>, &str)>;
+
+/// The `KeyCombo` struct is a key pressed while
+/// holding a set of modifier keys
+///
+/// Character keys are matched case-insensitively so
+/// `KeyCombo::char('s').shift()` matches `Shift + S`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyCombo {
+    key: Key,
+    modifiers: ModifiersState,
+}
+impl KeyCombo {
+    /// Create a combo of a character key such as `'s'`
+    pub fn char(c: char) -> Self {
+        Self {
+            key: Key::Character(c.to_lowercase().to_string().into()),
+            modifiers: ModifiersState::empty(),
+        }
+    }
+    /// Create a combo of a named key such as `NamedKey::Enter`
+    pub fn named(key: NamedKey) -> Self {
+        Self {
+            key: Key::Named(key),
+            modifiers: ModifiersState::empty(),
+        }
+    }
+    /// Require the control key to be held
+    pub fn ctrl(mut self) -> Self {
+        self.modifiers |= ModifiersState::CONTROL;
+        self
+    }
+    /// Require the shift key to be held
+    pub fn shift(mut self) -> Self {
+        self.modifiers |= ModifiersState::SHIFT;
+        self
+    }
+    /// Require the alt key to be held
+    pub fn alt(mut self) -> Self {
+        self.modifiers |= ModifiersState::ALT;
+        self
+    }
+    /// Require the super (windows/command) key to be held
+    pub fn super_key(mut self) -> Self {
+        self.modifiers |= ModifiersState::SUPER;
+        self
+    }
+    /// Create the combo of `key` pressed while holding `modifiers`
+    fn pressed(key: &Key, modifiers: ModifiersState) -> Self {
+        let key = match key {
+            Key::Character(c) => Key::Character(c.to_lowercase().into()),
+            key => key.clone(),
+        };
+        Self { key, modifiers }
+    }
+}
+
+/// The `KeyPress` struct allows widgets to have the ability
+/// to respond to keyboard shortcuts
+///
+/// Handlers run on every press of the combo including the
+/// repeats of a held key. Keyboard input is only routed to the
+/// focused widget so the widget must be focusable.
+#[derive(Clone)]
+pub struct KeyPress<State> {
+    state: State,
+    modifiers: ModifiersState,
+    key_map: HashMap<KeyCombo, KeyCallback<State>>,
+    paste: Option<TextCallback<State>>,
+    text: Option<TextCallback<State>>,
+}
+impl<State> KeyPress<State> {
+    /// Create a new `KeyPress` action
+    ///
+    /// The `state` provides the ability
+    /// to react to the current state of any
+    /// arbitrary instance
+    pub fn new(state: State) -> Self {
+        Self {
+            state,
+            modifiers: ModifiersState::empty(),
+            key_map: HashMap::default(),
+            paste: None,
+            text: None,
+        }
+    }
+    /// Set a handler for a specific key combo
+    ///
+    /// ## Example
+    /// ```ignore
+    /// KeyPress::new(()).on(KeyCombo::char('s').ctrl(), |_, _, _, _| save());
+    /// ```
+    pub fn on<F: Fn(&mut State, Rc<Trigger>, &Rc<dyn WidgetI>, Event<Signal>) + Clone + 'static>(
+        mut self,
+        combo: KeyCombo,
+        callback: F,
+    ) -> Self {
+        self.key_map.insert(combo, Rc::new(callback));
+        self
+    }
+    /// Set a handler for text pasted from the clipboard
+    ///
+    /// Runs after any handler of the paste shortcut itself
+    ///
+    /// ## Example
+    /// ```ignore
+    /// KeyPress::new(()).on_paste(|_, trigger, widget, text| {
+    ///     widget.base_mut().text.label.push_str(text);
+    ///     trigger.update();
+    /// });
+    /// ```
+    pub fn on_paste<F: Fn(&mut State, Rc<Trigger>, &Rc<dyn WidgetI>, &str) + Clone + 'static>(
+        mut self,
+        callback: F,
+    ) -> Self {
+        self.paste = Some(Rc::new(callback));
+        self
+    }
+    /// Set a handler for text committed by an input method
+    ///
+    /// Setting it turns the input method on while the widget is
+    /// focused. Text still being composed is shown underlined after
+    /// the text of the widget and the candidate window of the input
+    /// method opens next to it.
+    ///
+    /// ## Example
+    /// ```ignore
+    /// KeyPress::new(()).on_text(|_, trigger, widget, text| {
+    ///     widget.base_mut().text.label.push_str(text);
+    ///     trigger.update();
+    /// });
+    /// ```
+    pub fn on_text<F: Fn(&mut State, Rc<Trigger>, &Rc<dyn WidgetI>, &str) + Clone + 'static>(
+        mut self,
+        callback: F,
+    ) -> Self {
+        self.text = Some(Rc::new(callback));
+        self
+    }
+    /// Shows the text being composed on the widget
+    fn compose(trigger: &Trigger, widget: &Rc<dyn WidgetI>, preedit: Option<Preedit>) {
+        let mut base = widget.base_mut();
+        if base.text.preedit != preedit {
+            base.text.preedit = preedit;
+            drop(base);
+            trigger.update();
+        }
+    }
+}
+impl<State: Clone> ActionHandler for KeyPress<State> {
+    fn apply(
+        &mut self,
+        trigger: Rc<Trigger>,
+        widget: &Rc<dyn WidgetI>,
+        e: Event<Signal>,
+        _cursor: Point,
+    ) {
+        match e {
+            Event::WindowEvent { ref event, .. } => match event {
+                // Modifiers are reported separately from the key
+                // presses so they must be tracked
+                WindowEvent::ModifiersChanged(modifiers) => {
+                    self.modifiers = modifiers.state();
+                }
+                WindowEvent::KeyboardInput { event, .. } => {
+                    if event.state != ElementState::Pressed {
+                        return;
+                    }
+
+                    let combo = KeyCombo::pressed(&event.logical_key, self.modifiers);
+                    if let Some(handler) = self.key_map.get(&combo) {
+                        debug!("triggered {:?} for widget: {}", combo, widget.base().id);
+                        handler(&mut self.state, trigger, widget, e.clone())
+                    }
+                }
+                WindowEvent::Ime(ime) if self.text.is_some() => match ime {
+                    Ime::Preedit(text, cursor) => {
+                        let preedit = (!text.is_empty()).then(|| Preedit {
+                            text: text.clone(),
+                            cursor: cursor.map(|(start, _)| start),
+                        });
+                        Self::compose(&trigger, widget, preedit);
+                    }
+                    Ime::Commit(text) => {
+                        Self::compose(&trigger, widget, None);
+                        debug!("committed text into widget: {}", widget.base().id);
+                        if let Some(handler) = &self.text {
+                            handler(&mut self.state, trigger, widget, text)
+                        }
+                    }
+                    Ime::Disabled => Self::compose(&trigger, widget, None),
+                    Ime::Enabled => (),
+                },
+                _ => (),
+            },
+            Event::UserEvent(Signal::Paste(text)) => {
+                if let Some(handler) = &self.paste {
+                    debug!("pasted into widget: {}", widget.base().id);
+                    handler(&mut self.state, trigger, widget, &text)
+                }
+            }
+            _ => (),
+        }
+    }
+    fn accepts_text(&self) -> bool {
+        self.text.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+    use super::{KeyCombo, KeyPress};
+
+    #[test]
+    fn character_combos_ignore_case() {
+        assert_eq!(KeyCombo::char('S'), KeyCombo::char('s'));
+        assert_eq!(
+            KeyCombo::pressed(&Key::Character("S".into()), ModifiersState::SHIFT),
+            KeyCombo::char('s').shift()
+        );
+        // Named keys are left alone
+        assert_eq!(
+            KeyCombo::pressed(&Key::Named(NamedKey::Enter), ModifiersState::empty()),
+            KeyCombo::named(NamedKey::Enter)
+        );
+    }
+
+    #[test]
+    fn combos_match_the_exact_modifiers_held() {
+        let press = KeyPress::new(())
+            .on(KeyCombo::char('s').ctrl(), |_, _, _, _| ())
+            .on(KeyCombo::named(NamedKey::Enter), |_, _, _, _| ());
+        let bound = |key: &str, modifiers| {
+            let combo = KeyCombo::pressed(&Key::Character(key.into()), modifiers);
+            press.key_map.contains_key(&combo)
+        };
+
+        assert!(bound("s", ModifiersState::CONTROL));
+        // Caps lock does not get in the way
+        assert!(bound("S", ModifiersState::CONTROL));
+        assert!(!bound("s", ModifiersState::empty()));
+        assert!(!bound("S", ModifiersState::CONTROL | ModifiersState::SHIFT));
+        assert_eq!(
+            KeyCombo::char('s').shift().ctrl(),
+            KeyCombo::char('s').ctrl().shift()
+        );
+
+        let enter = |modifiers| {
+            let combo = KeyCombo::pressed(&Key::Named(NamedKey::Enter), modifiers);
+            press.key_map.contains_key(&combo)
+        };
+        assert!(enter(ModifiersState::empty()));
+        assert!(!enter(ModifiersState::ALT));
+    }
+}
@@ -10,19 +10,36 @@
 use std::rc::Rc;
 
 use dyn_clone::{clone_trait_object, DynClone};
+use drag::DragHandler;
 use hover::Hover;
+use paint::Paint;
+use pan::Pan;
+use plot_hover::PlotHover;
+use pointer::PointerHandler;
+use press::Press;
 use scroll::Scroll;
 use winit::{dpi::PhysicalPosition, event::Event};
 use zoom::Zoom;
 
 use crate::ui::{
+    hit::HitIndex,
+    layout::Point,
     sync::{Signal, Trigger},
-    widget::{container::Container, BaseWidget, WidgetI},
+    widget::{
+        button::Button, container::Container, minimap::Minimap, plot::Plot, widget_id, WidgetI,
+    },
 };
 
 pub mod click;
 pub mod cursor;
+pub mod drag;
 pub mod hover;
+pub mod key;
+pub mod paint;
+pub mod pan;
+pub mod plot_hover;
+pub mod pointer;
+pub mod press;
 pub(crate) mod scroll;
 pub mod zoom;
 
@@ -39,12 +56,39 @@ pub enum Action {
     ///
     /// Similiar to `onhover` in javascript
     Hover(Hover),
+    /// Allows the user to respond to the cursor entering
+    /// and leaving the widget
+    HoverChange(Box<dyn ActionHandler>),
     /// Allows the user to respond to clicks on the widget
     Click(Box<dyn ActionHandler>),
     /// Allows the user to respond to mouse movement on the widget
     CursorMove(Box<dyn ActionHandler>),
+    /// Allows the user to respond to keyboard shortcuts
+    Key(Box<dyn ActionHandler>),
+    /// Allows the user to drag the widget around and
+    /// drop it onto other widgets
+    Drag(Box<dyn DragHandler>),
+    /// Allows the user to intercept mouse events on their
+    /// way down to the widget under the cursor and back up
+    Pointer(Box<dyn PointerHandler>),
     /// Allows `Container` to be scrollable
     Scroll(Scroll),
+    /// Allows `Canvas` to be painted on with its active tool
+    /// while the left mouse button is held
+    Paint(Paint),
+    /// Allows `Plot` to show a tooltip for the data
+    /// point under the cursor
+    PlotHover(PlotHover),
+    /// Allows `Button` to be shaded while hovered and pressed
+    Press(Press),
+    /// Allows `Minimap` to scroll its target by dragging
+    /// the viewport
+    Pan(Pan),
+    /// Any action labeled with a tag so it can be removed
+    /// or swapped out later on
+    ///
+    /// See `Widget::on_action_tagged`
+    Tagged(String, Box<Action>),
     // Allows the user to zoom in and out of this widget
     // ZoomInOut(Zoom),
 }
@@ -55,29 +99,258 @@ impl Action {
         widget: &Rc<dyn WidgetI>,
         event: Event<Signal>,
         cursor_pos: PhysicalPosition<f64>,
+        hits: &HitIndex,
     ) {
+        // Handlers are given the cursor within the widget
+        let local = widget
+            .base()
+            .to_local(Point::new(cursor_pos.x, cursor_pos.y));
         match self {
+            // Disabled widgets do not react to the pointer
+            Action::Hover(_)
+            | Action::HoverChange(_)
+            | Action::Click(_)
+            | Action::Paint(_)
+            | Action::PlotHover(_)
+            | Action::Pan(_)
+                if widget.base().state.disabled => (),
             Action::Hover(hover) => hover.apply(trigger, &mut widget.base_mut(), event),
+            Action::HoverChange(hover) => hover.apply(trigger, widget, event, local),
             Action::Scroll(scroll) => scroll.apply(
                 trigger,
-                widget.as_any().downcast_ref::<Container>().unwrap(),
+                widget.downcast::<Container>().unwrap(),
                 event,
                 cursor_pos,
             ),
-            Action::Click(click) => click.apply(trigger, &mut widget.base_mut(), event),
-            Action::CursorMove(cursor_move) => {
-                cursor_move.apply(trigger, &mut widget.base_mut(), event)
+            Action::Click(click) => click.apply(trigger, widget, event, local),
+            Action::CursorMove(cursor_move) => cursor_move.apply(trigger, widget, event, local),
+            Action::Key(key) => key.apply(trigger, widget, event, local),
+            Action::Drag(drag) => {
+                // Resolved before the dragged widget gets borrowed
+                let target = widget_id(widget)
+                    .and_then(|id| hits.drop_target(cursor_pos.x, cursor_pos.y, id));
+                drag.apply(
+                    trigger,
+                    &mut widget.base_mut(),
+                    event,
+                    Point::new(cursor_pos.x, cursor_pos.y),
+                    target,
+                )
+            }
+            Action::Paint(paint) => {
+                paint.apply(widget, event, Point::new(cursor_pos.x, cursor_pos.y))
+            }
+            Action::PlotHover(hover) => {
+                if let Some(plot) = widget.downcast::<Plot>() {
+                    hover.apply(trigger, plot, event, Point::new(cursor_pos.x, cursor_pos.y))
+                }
+            }
+            Action::Press(press) => {
+                // Only buttons know how to be shaded
+                if let Some(button) = widget.downcast::<Button>() {
+                    press.apply(trigger, button, event)
+                }
+            }
+            Action::Pan(pan) => {
+                if let Some(minimap) = widget.downcast::<Minimap>() {
+                    pan.apply(minimap, event, Point::new(cursor_pos.x, cursor_pos.y))
+                }
+            }
+            Action::Tagged(_, action) => {
+                action.apply_action(trigger, widget, event, cursor_pos, hits)
             }
+            // Pointer events propagate through the hit path instead
+            Action::Pointer(_) => (),
             // _ => (),
         }
     }
+    /// Returns `true` if the action takes text typed
+    /// with an input method
+    pub(crate) fn accepts_text(&self) -> bool {
+        match self {
+            Action::Key(key) => key.accepts_text(),
+            Action::Tagged(_, action) => action.accepts_text(),
+            _ => false,
+        }
+    }
+    /// Returns the `Scroll` action of scrolling containers
+    /// even when tagged
+    pub(crate) fn scroll_mut(&mut self) -> Option<&mut Scroll> {
+        match self {
+            Action::Scroll(scroll) => Some(scroll),
+            Action::Tagged(_, action) => action.scroll_mut(),
+            _ => None,
+        }
+    }
+    /// Returns the kind of the action
+    ///
+    /// Tagged actions are of the kind of the action they label
+    pub fn kind(&self) -> ActionKind {
+        match self {
+            Action::Hover(_) => ActionKind::Hover,
+            Action::HoverChange(_) => ActionKind::HoverChange,
+            Action::Click(_) => ActionKind::Click,
+            Action::CursorMove(_) => ActionKind::CursorMove,
+            Action::Key(_) => ActionKind::Key,
+            Action::Drag(_) => ActionKind::Drag,
+            Action::Pointer(_) => ActionKind::Pointer,
+            Action::Scroll(_) => ActionKind::Scroll,
+            Action::Paint(_) => ActionKind::Paint,
+            Action::PlotHover(_) => ActionKind::PlotHover,
+            Action::Press(_) => ActionKind::Press,
+            Action::Pan(_) => ActionKind::Pan,
+            Action::Tagged(_, action) => action.kind(),
+        }
+    }
+    /// Returns the tag the action was labeled with
+    pub fn tag(&self) -> Option<&str> {
+        match self {
+            Action::Tagged(tag, _) => Some(tag),
+            _ => None,
+        }
+    }
+    /// Returns the action a tag labels or the
+    /// action itself if untagged
+    pub(crate) fn untagged(&mut self) -> &mut Action {
+        match self {
+            Action::Tagged(_, action) => action.untagged(),
+            action => action,
+        }
+    }
+    /// Returns the name of the variant
+    pub(crate) fn name(&self) -> &'static str {
+        self.kind().name()
+    }
+}
+
+/// The `ActionKind` enum names the variants of `Action`
+/// so actions can be picked out by kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionKind {
+    Hover,
+    HoverChange,
+    Click,
+    CursorMove,
+    Key,
+    Drag,
+    Pointer,
+    Scroll,
+    Paint,
+    PlotHover,
+    Press,
+    Pan,
+}
+impl ActionKind {
+    /// Returns the name of the kind
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            ActionKind::Hover => "Hover",
+            ActionKind::HoverChange => "HoverChange",
+            ActionKind::Click => "Click",
+            ActionKind::CursorMove => "CursorMove",
+            ActionKind::Key => "Key",
+            ActionKind::Drag => "Drag",
+            ActionKind::Pointer => "Pointer",
+            ActionKind::Scroll => "Scroll",
+            ActionKind::Paint => "Paint",
+            ActionKind::PlotHover => "PlotHover",
+            ActionKind::Press => "Press",
+            ActionKind::Pan => "Pan",
+        }
+    }
 }
 
 /// The trait `ActionHandler` provides a
 /// way for ergonomic use for
 /// users to specify actions with states at
 /// runtime
+///
+/// The `widget` is given whole so handlers may downcast it
+/// to reach the API of the concrete widget, see
+/// `downcast`. The `cursor` is where the cursor is relative
+/// to the top-left corner of the widget as drawn
 pub trait ActionHandler: DynClone {
-    fn apply(&mut self, trigger: Rc<Trigger>, widget: &mut BaseWidget, e: Event<Signal>);
+    fn apply(
+        &mut self,
+        trigger: Rc<Trigger>,
+        widget: &Rc<dyn WidgetI>,
+        e: Event<Signal>,
+        cursor: Point,
+    );
+    /// Whether the handler takes text typed with an input method
+    /// which turns the input method on while its widget is focused
+    fn accepts_text(&self) -> bool {
+        false
+    }
 }
 clone_trait_object!(ActionHandler);
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::ui::{
+        dom::DOM,
+        widget::{label::Label, Widget},
+    };
+
+    use super::{
+        click::{Click, MouseButton},
+        hover::Hover,
+        pan::Pan,
+        plot_hover::PlotHover,
+        press::Press,
+        Action, ActionKind,
+    };
+
+    #[test]
+    fn actions_are_swapped_out_by_tag_and_kind() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let tool = |name: &'static str| {
+            let log = log.clone();
+            Action::Click(Box::new(Click::new(()).on(
+                MouseButton::LeftButtonRelease,
+                move |_, _, _, _, _| log.borrow_mut().push(name),
+            )))
+        };
+        let mut dom = DOM::headless(100, 100);
+        let id = dom.add_widget(
+            Label::new()
+                .set_width(50.0)
+                .set_height(50.0)
+                .on_action(Action::Hover(Hover::default()))
+                .on_action_tagged("tool", tool("paint")),
+        );
+        let widget = dom.widget(id).unwrap();
+
+        dom.click(10.0, 10.0);
+        widget.replace_action_tagged("tool", tool("erase"));
+        dom.click(10.0, 10.0);
+        assert_eq!(*log.borrow(), ["paint", "erase"]);
+        assert_eq!(widget.action().len(), 2);
+
+        widget.remove_action(ActionKind::Click);
+        dom.click(10.0, 10.0);
+        assert_eq!(log.borrow().len(), 2);
+        assert_eq!(widget.action()[0].kind(), ActionKind::Hover);
+
+        widget.clear_actions();
+        assert!(widget.action().is_empty());
+    }
+
+    #[test]
+    fn widget_specific_actions_ignore_other_widgets() {
+        let mut dom = DOM::headless(100, 100);
+        let id = dom.add_widget(
+            Label::new()
+                .set_width(50.0)
+                .set_height(50.0)
+                .on_action(Action::Press(Press::new()))
+                .on_action(Action::PlotHover(PlotHover::new()))
+                .on_action(Action::Pan(Pan::new())),
+        );
+        dom.move_cursor(10.0, 10.0);
+        dom.click(10.0, 10.0);
+        assert_eq!(dom.widget(id).unwrap().action().len(), 3);
+    }
+}
@@ -0,0 +1,376 @@
+use std::{
+    any::Any,
+    rc::{Rc, Weak},
+};
+
+use winit::event::{ElementState, Event, MouseButton, WindowEvent};
+
+use crate::history::Command;
+use crate::trace::debug;
+use crate::ui::{
+    buffer::CanvasBuffer,
+    color::Color,
+    layout::Point,
+    sync::Signal,
+    widget::{canvas::Canvas, Widget, WidgetHooks, WidgetI},
+};
+
+/// The `Tool` enum lists the ways `Paint` draws on a `Canvas`
+///
+/// Shapes span from the cell a stroke started in to the cell
+/// under the cursor. They are previewed while dragging and
+/// only kept once the button is released.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tool {
+    /// Paints every cell the cursor passes over
+    #[default]
+    Brush,
+    /// Draws a straight line
+    Line,
+    /// Draws the outline of a rectangle
+    Rect,
+    /// Draws the outline of the ellipse fitting in a rectangle
+    Ellipse,
+    /// Fills the region of same colored cells clicked on
+    Fill,
+    /// Selects the rectangle of cells dragged over without
+    /// painting
+    ///
+    /// Clicking without dragging lets go of the selection.
+    /// Pixels pasted floating over the canvas are dragged
+    /// around and painted once clicked outside of.
+    Select,
+}
+
+/// The `Paint` struct allows a `Canvas` to be drawn on with
+/// its active `Tool`
+///
+/// While the left mouse button is held the brush tool paints
+/// every cell the cursor passes over in the brush color of the
+/// canvas. The cells between two cursor positions are painted
+/// as well so fast strokes leave no gaps.
+///
+/// Every stroke is recorded as a single step in the history
+/// of the canvas if it has one.
+///
+/// The select tool marks a rectangle of cells on the canvas
+/// instead, see `Canvas::selection`.
+///
+/// NoOp on canvases without a grid
+#[derive(Clone, Debug, Default)]
+pub struct Paint {
+    /// The cell the stroke started in
+    start: Option<(isize, isize)>,
+    /// The cell the stroke reached last while painting
+    last: Option<(isize, isize)>,
+    /// The pixels as they were before the stroke
+    before: Option<CanvasBuffer>,
+    /// Whether pasted pixels are dragged instead
+    moving: bool,
+}
+impl Paint {
+    /// Create a new `Paint` action
+    pub fn new() -> Self {
+        Paint::default()
+    }
+    pub(crate) fn apply(
+        &mut self,
+        widget: &Rc<dyn WidgetI>,
+        event: Event<Signal>,
+        cursor_pos: Point,
+    ) {
+        let Some(canvas) = widget.downcast::<Canvas>() else {
+            return;
+        };
+        let Event::WindowEvent { event, .. } = event else {
+            return;
+        };
+
+        match event {
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+                ..
+            } if is_hit(canvas) => {
+                let Some(cell) = canvas.cell_at(cursor_pos.x, cursor_pos.y) else {
+                    return;
+                };
+                debug!("painting started for widget: {}", canvas.base().id);
+                self.start = Some(cell);
+                self.last = Some(cell);
+                if canvas.tool() == Tool::Select {
+                    let over_paste = match (usize::try_from(cell.0), usize::try_from(cell.1)) {
+                        (Ok(x), Ok(y)) => {
+                            canvas.pasting().is_some_and(|paste| paste.contains(x, y))
+                        }
+                        _ => false,
+                    };
+                    if over_paste {
+                        self.moving = true;
+                    } else {
+                        canvas.confirm_paste();
+                        canvas.select_span(cell, cell);
+                    }
+                    return;
+                }
+                self.before = canvas.buffer().map(|buffer| buffer.clone());
+                self.stroke(canvas, cell);
+            }
+            WindowEvent::CursorMoved { .. } => {
+                let (Some(last), Some(cell)) =
+                    (self.last, canvas.cell_at(cursor_pos.x, cursor_pos.y))
+                else {
+                    return;
+                };
+                if cell != last {
+                    match (canvas.tool(), self.start) {
+                        (Tool::Select, _) if self.moving => {
+                            canvas.drag_paste((cell.0 - last.0, cell.1 - last.1))
+                        }
+                        (Tool::Select, Some(start)) => canvas.select_span(start, cell),
+                        _ => self.stroke(canvas, cell),
+                    }
+                    self.last = Some(cell);
+                }
+            }
+            WindowEvent::MouseInput {
+                state: ElementState::Released,
+                button: MouseButton::Left,
+                ..
+            } if self.last.is_some() => {
+                debug!("painting ended for widget: {}", canvas.base().id);
+                // Clicks let go of the selection
+                if canvas.tool() == Tool::Select && self.start == self.last && !self.moving {
+                    canvas.set_selection(None);
+                }
+                self.moving = false;
+                self.start = None;
+                self.last = None;
+                if let Some(before) = self.before.take() {
+                    commit(Rc::downgrade(widget), canvas, &before);
+                }
+            }
+            _ => (),
+        }
+    }
+    /// Draws with the active tool of `canvas` now that the
+    /// stroke reached `to`
+    fn stroke(&self, canvas: &Canvas, to: (isize, isize)) {
+        let (Some(start), Some(last)) = (self.start, self.last) else {
+            return;
+        };
+        let color = canvas.brush_color();
+        let tool = canvas.tool();
+        let disabled = canvas.disabled_cells();
+        canvas.paint(|buffer| {
+            let kept: Vec<_> = disabled
+                .iter()
+                .filter_map(|&(x, y)| buffer.get_pixel(x, y).map(|color| (x, y, color)))
+                .collect();
+            // Shapes are drawn over the pixels as they were
+            // so only the latest preview is shown
+            if matches!(tool, Tool::Line | Tool::Rect | Tool::Ellipse) {
+                if let Some(before) = &self.before {
+                    buffer.restore(before);
+                }
+            }
+            match tool {
+                Tool::Brush => buffer.draw_line(last, to, color),
+                Tool::Line => buffer.draw_line(start, to, color),
+                Tool::Rect => buffer.draw_rect(start, to, color),
+                Tool::Ellipse => buffer.draw_ellipse(start, to, color),
+                Tool::Fill if to == start => {
+                    if let (Ok(x), Ok(y)) = (usize::try_from(to.0), usize::try_from(to.1)) {
+                        buffer.flood_fill(x, y, color);
+                    }
+                }
+                Tool::Fill | Tool::Select => (),
+            }
+            // Disabled cells keep their color
+            for (x, y, color) in kept {
+                buffer.set_pixel(x, y, color);
+            }
+        });
+    }
+}
+
+/// Records the pixels the stroke changed since `before` in
+/// the history of `canvas` which `widget` refers to
+pub(crate) fn commit(widget: Weak<dyn WidgetI>, canvas: &Canvas, before: &CanvasBuffer) {
+    let Some(history) = canvas.history() else {
+        return;
+    };
+    let Some(buffer) = canvas.buffer() else {
+        return;
+    };
+    let mut pixels = Vec::new();
+    for y in 0..buffer.height() {
+        for x in 0..buffer.width() {
+            if let (Some(old), Some(new)) = (before.get_pixel(x, y), buffer.get_pixel(x, y)) {
+                if old != new {
+                    pixels.push((x, y, old, new));
+                }
+            }
+        }
+    }
+    if !pixels.is_empty() {
+        history.borrow_mut().push(Stroke {
+            canvas: widget,
+            pixels,
+        });
+    }
+}
+
+/// Whether the cursor is over the canvas or any of its cells
+fn is_hit(canvas: &Canvas) -> bool {
+    canvas.base().state.hit || canvas.children().iter().any(|cell| cell.base().state.hit)
+}
+
+/// The `Stroke` struct is the `Command` recorded for the
+/// pixels changed by a stroke of `Paint`
+struct Stroke {
+    canvas: Weak<dyn WidgetI>,
+    /// The position of every changed pixel along with its
+    /// color before and after the stroke
+    pixels: Vec<(usize, usize, Color, Color)>,
+}
+impl Stroke {
+    fn set_pixels(&self, pick: impl Fn(Color, Color) -> Color) {
+        // NoOp once the canvas was dropped
+        let Some(widget) = self.canvas.upgrade() else {
+            return;
+        };
+        if let Some(canvas) = widget.downcast::<Canvas>() {
+            canvas.paint(|buffer| {
+                for (x, y, before, after) in &self.pixels {
+                    buffer.set_pixel(*x, *y, pick(*before, *after));
+                }
+            });
+        }
+    }
+}
+impl Command for Stroke {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn execute(&mut self) {
+        self.set_pixels(|_, after| after);
+    }
+    fn undo(&mut self) {
+        self.set_pixels(|before, _| before);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        action::Action,
+        history::History,
+        ui::{
+            color::{RED, TRANSPARENT},
+            dom::DOM,
+            layout::Point,
+            widget::{canvas::Canvas, Widget},
+        },
+    };
+
+    use super::{Paint, Tool};
+
+    #[test]
+    fn fast_strokes_leave_no_gaps() {
+        let mut dom = DOM::headless(100, 100);
+        let canvas = Canvas::new()
+            .set_width(80.0)
+            .set_height(80.0)
+            .set_grid(8, 0.0, TRANSPARENT)
+            .on_action(Action::Paint(Paint::new()));
+        canvas.set_brush_color(RED);
+        let id = dom.add_widget(canvas);
+
+        // A single movement across the whole diagonal
+        dom.drag(Point::new(5.0, 5.0), Point::new(75.0, 75.0));
+        // Moving without the button held paints nothing
+        dom.move_cursor(5.0, 75.0);
+
+        let widget = dom.widget(id).unwrap();
+        let canvas = widget.downcast::<Canvas>().unwrap();
+        let buffer = canvas.buffer().unwrap();
+        for i in 0..8 {
+            assert_eq!(buffer.get_pixel(i, i), Some(RED));
+        }
+        assert_eq!(buffer.get_pixel(0, 7), Some(TRANSPARENT));
+        assert_eq!(buffer.get_pixel(1, 0), Some(TRANSPARENT));
+    }
+
+    #[test]
+    fn disabled_cells_are_left_unpainted() {
+        let mut dom = DOM::headless(100, 100);
+        let canvas = Canvas::new()
+            .set_width(80.0)
+            .set_height(80.0)
+            .set_grid(8, 0.0, TRANSPARENT)
+            .on_action(Action::Paint(Paint::new()));
+        canvas.set_brush_color(RED);
+        canvas.set_cells_disabled(0..8, 4..8, true);
+        let id = dom.add_widget(canvas);
+
+        // A stroke across the whole top row
+        dom.drag(Point::new(5.0, 5.0), Point::new(75.0, 5.0));
+
+        let widget = dom.widget(id).unwrap();
+        let canvas = widget.downcast::<Canvas>().unwrap();
+        assert_eq!(canvas.is_cell_disabled((2, 5)), Some(true));
+        assert_eq!(canvas.is_cell_disabled((2, 3)), Some(false));
+        assert_eq!(canvas.is_cell_disabled((8, 0)), None);
+        let buffer = canvas.buffer().unwrap();
+        for x in 0..8 {
+            let painted = if x < 4 { RED } else { TRANSPARENT };
+            assert_eq!(buffer.get_pixel(x, 0), Some(painted));
+        }
+    }
+
+    #[test]
+    fn shapes_are_kept_on_release_and_undone_as_one_step() {
+        let mut dom = DOM::headless(100, 100);
+        let history = Rc::new(RefCell::new(History::new()));
+        let canvas = Canvas::new()
+            .set_width(80.0)
+            .set_height(80.0)
+            .set_grid(8, 0.0, TRANSPARENT)
+            .set_history(history.clone())
+            .on_action(Action::Paint(Paint::new()));
+        canvas.set_brush_color(RED);
+        canvas.set_tool(Tool::Rect);
+        let id = dom.add_widget(canvas);
+        let widget = dom.widget(id).unwrap();
+        let canvas = widget.downcast::<Canvas>().unwrap();
+        let painted = || {
+            let buffer = canvas.buffer().unwrap();
+            (0..8)
+                .flat_map(|y| (0..8).map(move |x| (x, y)))
+                .filter(|&(x, y)| buffer.get_pixel(x, y) == Some(RED))
+                .count()
+        };
+
+        dom.drag(Point::new(15.0, 15.0), Point::new(55.0, 35.0));
+        // The outline of the 5x3 cells between both corners
+        assert_eq!(painted(), 12);
+        let buffer = canvas.buffer().unwrap().clone();
+        assert_eq!(buffer.get_pixel(1, 1), Some(RED));
+        assert_eq!(buffer.get_pixel(5, 3), Some(RED));
+        assert_eq!(buffer.get_pixel(3, 2), Some(TRANSPARENT));
+
+        canvas.set_tool(Tool::Fill);
+        dom.click(35.0, 25.0);
+        assert_eq!(painted(), 15);
+
+        assert!(history.borrow_mut().undo());
+        assert_eq!(painted(), 12);
+        assert!(history.borrow_mut().undo());
+        assert_eq!(painted(), 0);
+        assert!(history.borrow_mut().redo());
+        assert_eq!(*canvas.buffer().unwrap(), buffer);
+    }
+}
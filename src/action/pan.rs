@@ -0,0 +1,65 @@
+use winit::event::{ElementState, Event, MouseButton, WindowEvent};
+
+use crate::trace::debug;
+use crate::ui::{
+    layout::Point,
+    sync::Signal,
+    widget::{minimap::Minimap, Widget},
+};
+
+/// The `Pan` struct allows a `Minimap` to scroll its target
+/// by dragging the viewport around
+///
+/// Pressing outside of the viewport centers it under the
+/// cursor before it is dragged along
+///
+/// NoOp on widgets other than `Minimap`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Pan {
+    /// Where the viewport was grabbed relative to its
+    /// top-left corner
+    grab: Option<Point>,
+}
+impl Pan {
+    /// Create a new `Pan` action
+    pub fn new() -> Self {
+        Pan::default()
+    }
+    pub(crate) fn apply(&mut self, widget: &Minimap, event: Event<Signal>, cursor_pos: Point) {
+        let Event::WindowEvent { event, .. } = event else {
+            return;
+        };
+        match event {
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+                ..
+            } if widget.base().state.hit => {
+                let Some(viewport) = widget.viewport() else {
+                    return;
+                };
+                debug!("panning started for widget: {}", widget.base().id);
+                let grab = match viewport.is_inbounds(cursor_pos.x, cursor_pos.y) {
+                    true => Point::new(cursor_pos.x - viewport.x, cursor_pos.y - viewport.y),
+                    false => Point::new(viewport.w / 2.0, viewport.h / 2.0),
+                };
+                self.grab = Some(grab);
+                widget.pan_to(cursor_pos.x - grab.x, cursor_pos.y - grab.y);
+            }
+            WindowEvent::CursorMoved { .. } => {
+                if let Some(grab) = self.grab {
+                    widget.pan_to(cursor_pos.x - grab.x, cursor_pos.y - grab.y);
+                }
+            }
+            WindowEvent::MouseInput {
+                state: ElementState::Released,
+                button: MouseButton::Left,
+                ..
+            } if self.grab.is_some() => {
+                debug!("panning ended for widget: {}", widget.base().id);
+                self.grab = None;
+            }
+            _ => (),
+        }
+    }
+}
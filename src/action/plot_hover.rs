@@ -0,0 +1,46 @@
+use std::rc::Rc;
+
+use winit::event::{Event, WindowEvent};
+
+use crate::ui::{
+    layout::Point,
+    sync::{Signal, Trigger},
+    widget::{plot::Plot, Widget},
+};
+
+/// The `PlotHover` struct allows a `Plot` to show the values
+/// of the data point under the cursor in a tooltip
+///
+/// The closest point to the cursor is picked as long as
+/// the cursor is over the plot
+///
+/// NoOp on widgets other than `Plot`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PlotHover;
+impl PlotHover {
+    /// Create a new `PlotHover` action
+    pub fn new() -> Self {
+        PlotHover
+    }
+    pub(crate) fn apply(
+        &mut self,
+        trigger: Rc<Trigger>,
+        widget: &Plot,
+        event: Event<Signal>,
+        cursor_pos: Point,
+    ) {
+        let Event::WindowEvent { event, .. } = event else {
+            return;
+        };
+        let hovered = match event {
+            WindowEvent::CursorMoved { .. } if widget.base().state.hit => {
+                widget.point_at(cursor_pos)
+            }
+            WindowEvent::CursorMoved { .. } | WindowEvent::CursorLeft { .. } => None,
+            _ => return,
+        };
+        if widget.hover(hovered) {
+            trigger.update();
+        }
+    }
+}
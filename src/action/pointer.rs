@@ -0,0 +1,204 @@
+use std::rc::Rc;
+use winit::event::WindowEvent;
+
+use crate::trace::debug;
+use crate::ui::{
+    arena::WidgetId,
+    layout::Point,
+    sync::Trigger,
+    widget::{BaseWidget, WidgetI},
+};
+
+use dyn_clone::{clone_trait_object, DynClone};
+
+use super::Action;
+
+type PointerCallback<State> =
+    Rc<dyn Fn(&mut State, Rc<Trigger>, &mut BaseWidget, &mut PointerEvent)>;
+
+/// The `Phase` enum describes where a `PointerEvent`
+/// currently is on its way through the tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Phase {
+    /// Travelling from the top-level widget down to the target
+    Capture,
+    /// Arrived at the widget under the cursor
+    Target,
+    /// Travelling from the target back up to the top-level widget
+    Bubble,
+}
+
+/// The `PointerEvent` struct describes a mouse event
+/// travelling through the widgets under the cursor
+///
+/// The event first travels down from the top-level widget to
+/// the topmost widget under the cursor and then back up again.
+/// Any handler along the way may stop it from going further or
+/// prevent the built-in actions from reacting to it.
+#[derive(Debug, Clone)]
+pub struct PointerEvent {
+    event: WindowEvent,
+    position: Point,
+    target: WidgetId,
+    phase: Phase,
+    stopped: bool,
+    prevented: bool,
+}
+impl PointerEvent {
+    pub(crate) fn new(event: WindowEvent, position: Point, target: WidgetId) -> Self {
+        Self {
+            event,
+            position,
+            target,
+            phase: Phase::Capture,
+            stopped: false,
+            prevented: false,
+        }
+    }
+    /// Returns the underlying window event
+    pub fn event(&self) -> &WindowEvent {
+        &self.event
+    }
+    /// Returns where the cursor is
+    pub fn position(&self) -> Point {
+        self.position
+    }
+    /// Returns the topmost widget under the cursor
+    pub fn target(&self) -> WidgetId {
+        self.target
+    }
+    /// Returns where the event is on its way through the tree
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+    /// Keeps the event from reaching any further widgets
+    pub fn stop_propagation(&mut self) {
+        self.stopped = true;
+    }
+    /// Keeps the built-in actions such as `Click` and `Hover`
+    /// from reacting to the event
+    pub fn prevent_default(&mut self) {
+        self.prevented = true;
+    }
+    /// Returns whether `stop_propagation` was called
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.stopped
+    }
+    /// Returns whether `prevent_default` was called
+    pub fn is_default_prevented(&self) -> bool {
+        self.prevented
+    }
+}
+
+/// The `Pointer` struct allows widgets to take part in
+/// the propagation of mouse events
+///
+/// Handlers run when the event arrives at the widget or
+/// bubbles up through it. Using `set_capture` runs them on
+/// the way down instead so parents see the event before
+/// their children do.
+#[derive(Clone)]
+pub struct Pointer<State> {
+    state: State,
+    capture: bool,
+    handler: PointerCallback<State>,
+}
+impl<State> Pointer<State> {
+    /// Create a new `Pointer` action
+    ///
+    /// The `state` provides the ability
+    /// to react to the current state of any
+    /// arbitrary instance
+    pub fn new<
+        F: Fn(&mut State, Rc<Trigger>, &mut BaseWidget, &mut PointerEvent) + Clone + 'static,
+    >(
+        state: State,
+        callback: F,
+    ) -> Self {
+        Self {
+            state,
+            capture: false,
+            handler: Rc::new(callback),
+        }
+    }
+    /// Run the handler while the event travels down
+    /// to the target rather than back up
+    pub fn set_capture(mut self) -> Self {
+        self.capture = true;
+        self
+    }
+}
+
+/// The trait `PointerHandler` provides the propagation
+/// specific counterpart of `ActionHandler`
+pub trait PointerHandler: DynClone {
+    /// Returns whether the handler wants events in `phase`
+    fn listens(&self, phase: Phase) -> bool;
+    fn apply(&mut self, trigger: Rc<Trigger>, widget: &mut BaseWidget, e: &mut PointerEvent);
+}
+clone_trait_object!(PointerHandler);
+
+impl<State: Clone> PointerHandler for Pointer<State> {
+    fn listens(&self, phase: Phase) -> bool {
+        match phase {
+            Phase::Capture => self.capture,
+            Phase::Target => true,
+            Phase::Bubble => !self.capture,
+        }
+    }
+    fn apply(&mut self, trigger: Rc<Trigger>, widget: &mut BaseWidget, e: &mut PointerEvent) {
+        debug!("triggered pointer {:?} for widget: {}", e.phase, widget.id);
+        let handler = &self.handler;
+        handler(&mut self.state, trigger, widget, e)
+    }
+}
+
+/// Sends `event` down the hit `path` to its last widget and
+/// back up again
+///
+/// Returns the index into `path` of the widget that stopped
+/// the propagation and the phase it was stopped in
+pub(crate) fn propagate(
+    path: &[Rc<dyn WidgetI>],
+    event: &mut PointerEvent,
+) -> Option<(Phase, usize)> {
+    let target = path.len().checked_sub(1)?;
+    let order = (0..target)
+        .map(|i| (Phase::Capture, i))
+        .chain([(Phase::Target, target)])
+        .chain((0..target).rev().map(|i| (Phase::Bubble, i)));
+
+    for (phase, i) in order {
+        event.phase = phase;
+        let widget = &path[i];
+        for action in widget.action_mut().iter_mut() {
+            if let Action::Pointer(handler) = action.untagged() {
+                if handler.listens(phase) {
+                    handler.apply(widget.trigger(), &mut widget.base_mut(), event);
+                }
+            }
+        }
+        if event.stopped {
+            return Some((phase, i));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listens_on_the_way_down_or_up() {
+        let bubble = Pointer::new((), |_, _, _, _: &mut PointerEvent| {});
+        assert!(!bubble.listens(Phase::Capture));
+        assert!(bubble.listens(Phase::Target));
+        assert!(bubble.listens(Phase::Bubble));
+
+        let capture = bubble.set_capture();
+        assert!(capture.listens(Phase::Capture));
+        assert!(capture.listens(Phase::Target));
+        assert!(!capture.listens(Phase::Bubble));
+    }
+}
@@ -0,0 +1,79 @@
+use std::rc::Rc;
+
+use winit::event::{ElementState, Event, MouseButton, WindowEvent};
+
+use crate::trace::debug;
+use crate::ui::{
+    color::{Color, ColorMode},
+    sync::{Signal, Trigger},
+    widget::{button::Button, Widget},
+};
+
+/// How opaque the shade laid over hovered buttons is
+const HOVER_ALPHA: u8 = 25;
+/// How opaque the shade laid over pressed buttons is
+const PRESS_ALPHA: u8 = 50;
+
+/// The `Press` struct allows `Button`s to show when they
+/// are hovered and pressed
+///
+/// The color of the button is shaded by the theme, darker on
+/// light themes and lighter on dark ones, the more so while the
+/// left mouse button is held on it. Disabled buttons are shown
+/// in their plain color.
+///
+/// Buttons given a `Hover` action of their own are left to it.
+///
+/// NoOp on widgets other than `Button`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Press {
+    hovered: bool,
+    pressed: bool,
+    /// The shade laid over the button last
+    shaded: Option<Color>,
+}
+impl Press {
+    /// Create a new `Press` action
+    pub fn new() -> Self {
+        Press::default()
+    }
+    pub(crate) fn apply(&mut self, trigger: Rc<Trigger>, widget: &Button, event: Event<Signal>) {
+        let Event::WindowEvent { event, .. } = event else {
+            return;
+        };
+        let mut base = widget.base_mut();
+        // Colors shown otherwise by anyone else stay so
+        match base.style.color.mode() {
+            ColorMode::Solid => (),
+            ColorMode::Overlay(color) if Some(color) == self.shaded => (),
+            _ => return,
+        }
+        match event {
+            WindowEvent::CursorMoved { .. } => self.hovered = base.state.hit,
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => self.pressed = state == ElementState::Pressed && base.state.hit,
+            _ => return,
+        }
+
+        let alpha = match (self.pressed, self.hovered) {
+            _ if base.state.disabled => None,
+            (true, _) => Some(PRESS_ALPHA),
+            (false, true) => Some(HOVER_ALPHA),
+            (false, false) => None,
+        };
+        self.shaded = alpha.map(|alpha| {
+            let (r, g, b): (u8, u8, u8) = widget.shade.get().into();
+            Color::RGBA(r, g, b, alpha)
+        });
+        let mode = self.shaded.map_or(ColorMode::Solid, ColorMode::Overlay);
+        if base.style.color.mode() != mode {
+            debug!("shaded button: {} as {:?}", base.id, mode);
+            base.style.color.set_mode(mode);
+            drop(base);
+            trigger.update();
+        }
+    }
+}
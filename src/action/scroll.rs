@@ -1,13 +1,15 @@
 use std::rc::Rc;
 
-use log::debug;
+use web_time::{Duration, Instant};
 use winit::{
     dpi::PhysicalPosition,
-    event::{ElementState, Event, MouseButton, WindowEvent},
+    event::{ElementState, Event, MouseButton, MouseScrollDelta, WindowEvent},
     window::Window,
 };
 
+use crate::trace::debug;
 use crate::ui::{
+    animate::FRAME,
     sync::{Signal, Trigger},
     widget::{container::Container, Widget},
 };
@@ -19,8 +21,19 @@ enum Axis {
     /// Y-axix scrollbar
     Y,
 }
+/// The amount of pixels content moves for every line
+/// of a mouse wheel by default
+pub(crate) const DEFAULT_SCROLL_STEP: f64 = 20.0;
+/// How long auto hiding scrollbars take to fade in or out
+const FADE: Duration = Duration::from_millis(150);
+
 /// The `Scroll` struct allows `Container`s to have the ability
 /// to respond to scroll movements on x or y axis
+///
+/// Content moves either by dragging a scrollbar or
+/// by turning the mouse wheel above the container. Only the
+/// innermost scrolling container under the cursor takes the
+/// wheel which the `DOM` hands to it through `Scroll::wheel`
 #[derive(Clone, Default, Copy)]
 pub(crate) struct Scroll {
     /// The current selected scrollbar
@@ -73,6 +86,7 @@ impl Scroll {
                 let container_width = widget_base.layout.w + widget_base.layout.x;
                 let overflow_x = widget
                     .children
+                    .borrow()
                     .iter()
                     .fold(container_width, |acc, child| child.base().layout.w.max(acc));
                 let total_overflow = overflow_x - container_width;
@@ -93,7 +107,8 @@ impl Scroll {
             Some(Axis::Y) => {
                 let y_base = y.base();
 
-                let last_child = &widget.children[widget.children.len() - 1];
+                let children = widget.children.borrow();
+                let last_child = &children[children.len() - 1];
                 let last_child_base = last_child.base();
 
                 let overflow_y = last_child_base.layout.y + last_child_base.layout.h;
@@ -116,28 +131,78 @@ impl Scroll {
             _ => unreachable!(),
         }
     }
-    fn on_cursor_movement(&self, widget: &Container, pos: PhysicalPosition<f64>) {
+    /// Returns whether a scrollbar started or stopped being hovered
+    fn on_cursor_movement(&self, widget: &Container, pos: PhysicalPosition<f64>) -> bool {
         let (x, y) = widget.scrollbar.as_ref().unwrap();
+        let thickness = |hovered: bool, thickness: f64| match hovered {
+            true => widget.scroll_options.hover_thickness,
+            false => thickness,
+        };
 
         // Determine if in view
-        let ishovered = x.base().layout.is_inbounds(pos.x, pos.y);
-        if ishovered {
-            x.base_mut().state.hovered = true;
+        let x_hovered = x.base().layout.is_inbounds(pos.x, pos.y);
+        let x_changed = x.base().state.hovered != x_hovered;
+        if x_changed {
+            let mut x_base = x.base_mut();
+            x_base.state.hovered = x_hovered;
+            // Hovered scrollbars grow thicker to be easier to grab
+            x_base.layout.h = thickness(x_hovered, x.thickness);
             debug!(
-                "triggered hover for x scrollbar for widget: {}",
+                "toggled hover for x scrollbar for widget: {}",
                 widget.base().id
             );
         }
-        let ishovered = y.base().layout.is_inbounds(pos.x, pos.y);
-        if ishovered {
-            y.base_mut().state.hovered = true;
+        let y_hovered = y.base().layout.is_inbounds(pos.x, pos.y);
+        let y_changed = y.base().state.hovered != y_hovered;
+        if y_changed {
+            let mut y_base = y.base_mut();
+            y_base.state.hovered = y_hovered;
+            y_base.layout.w = thickness(y_hovered, y.thickness);
             debug!(
-                "triggered hover for y scrollbar for widget: {}",
+                "toggled hover for y scrollbar for widget: {}",
                 widget.base().id
             );
         }
+
+        x_changed || y_changed
+    }
+    /// Fades the scrollbars of auto hiding containers in until
+    /// they are neither hovered nor `active` for a while
+    fn reveal(widget: &Container, trigger: &Trigger, active: bool) {
+        if !widget.scroll_options.auto_hide {
+            return;
+        }
+        if active {
+            widget.scroll_activity.set(Some(Instant::now()));
+        }
+        if widget.scroll_fade.get().is_some() {
+            return;
+        }
+
+        // Every frame of the fade steps the opacity a bit further
+        let step = (FRAME.as_secs_f64() / FADE.as_secs_f64()) as f32;
+        let id = trigger.set_interval(FRAME, move |widget| {
+            let Some(container) = widget.downcast::<Container>() else {
+                return;
+            };
+            if !container.fade_scrollbars(step) {
+                if let Some(id) = container.scroll_fade.take() {
+                    widget.trigger().clear_timer(id);
+                }
+            }
+        });
+        widget.scroll_fade.set(Some(id));
     }
     fn on_scroll_movement(&self, widget: &Container, pos: PhysicalPosition<f64>) {
+        match self.axis {
+            Some(Axis::X) => self.move_thumb(widget, pos.x - self.cursor_offset),
+            Some(Axis::Y) => self.move_thumb(widget, pos.y - self.cursor_offset),
+            _ => unreachable!(),
+        }
+    }
+    /// Moves the scrollbar thumb of the selected axis to `thumb_pos`
+    /// and shifts the container content to match
+    fn move_thumb(&self, widget: &Container, thumb_pos: f64) {
         let (x, y) = widget.scrollbar.as_ref().unwrap();
 
         match self.axis {
@@ -146,12 +211,11 @@ impl Scroll {
                 let widget_base = widget.base();
 
                 // Move scrollbar
-                x_base.layout.x =
-                    (pos.x - self.cursor_offset).clamp(widget_base.layout.x, self.max_scroll_range);
+                x_base.layout.x = thumb_pos.clamp(widget_base.layout.x, self.max_scroll_range);
 
                 // Move container content
                 let shift = (x_base.layout.x - widget_base.layout.x) * self.scroll_delta;
-                for child in &widget.children {
+                for child in widget.children.borrow().iter() {
                     child.base_mut().offset.x = -shift;
                 }
 
@@ -165,12 +229,11 @@ impl Scroll {
                 let widget_base = widget.base();
 
                 // Move scrollbar
-                y_base.layout.y =
-                    (pos.y - self.cursor_offset).clamp(widget_base.layout.y, self.max_scroll_range);
+                y_base.layout.y = thumb_pos.clamp(widget_base.layout.y, self.max_scroll_range);
 
                 // Move container content
                 let shift = (y_base.layout.y - widget_base.layout.y) * self.scroll_delta;
-                for child in &widget.children {
+                for child in widget.children.borrow().iter() {
                     child.base_mut().offset.y = -shift;
                 }
 
@@ -182,6 +245,61 @@ impl Scroll {
             _ => unreachable!(),
         }
     }
+    /// Shifts the content along `axis` by `amount` pixels of
+    /// content, keeping the scrollbar thumb in sync
+    fn on_wheel(&mut self, widget: &Container, axis: Axis, amount: f64) {
+        if amount == 0.0 || widget.children.borrow().is_empty() {
+            return;
+        }
+
+        self.axis = Some(axis);
+        self.compute_scroll(widget);
+
+        // Nothing overflows so there is nothing to scroll
+        if self.scroll_delta.is_finite() && self.scroll_delta > 0.0 {
+            let (x, y) = widget.scrollbar.as_ref().unwrap();
+            let thumb = match axis {
+                Axis::X => x.base().layout.x,
+                Axis::Y => y.base().layout.y,
+            };
+            self.move_thumb(widget, thumb + amount / self.scroll_delta);
+        }
+
+        self.axis = None;
+    }
+    /// Shifts the content by `dx`, `dy` pixels as turning the
+    /// mouse wheel would
+    pub(crate) fn scroll_by(&mut self, widget: &Container, dx: f64, dy: f64) {
+        self.on_wheel(widget, Axis::X, dx);
+        self.on_wheel(widget, Axis::Y, dy);
+    }
+    /// Scrolls the content by the `delta` of a mouse wheel
+    /// turned above the container
+    ///
+    /// NoOp while a scrollbar is dragged
+    pub(crate) fn wheel(
+        &mut self,
+        trigger: &Trigger,
+        widget: &Container,
+        delta: &MouseScrollDelta,
+    ) {
+        if self.axis.is_some() {
+            return;
+        }
+
+        // Wheel deltas point where the content moves so the
+        // scroll goes the opposite way
+        let step = widget.scroll_step.unwrap_or(DEFAULT_SCROLL_STEP);
+        let (dx, dy) = match delta {
+            MouseScrollDelta::LineDelta(x, y) => (*x as f64 * step, *y as f64 * step),
+            MouseScrollDelta::PixelDelta(pos) => (pos.x, pos.y),
+        };
+        debug!("triggered wheel scroll for widget: {}", widget.base().id);
+
+        self.scroll_by(widget, -dx, -dy);
+        Scroll::reveal(widget, trigger, true);
+        trigger.update();
+    }
     pub(crate) fn apply(
         &mut self,
         trigger: Rc<Trigger>,
@@ -194,9 +312,11 @@ impl Scroll {
                 WindowEvent::CursorMoved { position, .. } => {
                     if self.axis.is_some() {
                         self.on_scroll_movement(widget, position);
+                        Scroll::reveal(widget, &trigger, true);
+                        trigger.update();
+                    } else if self.on_cursor_movement(widget, position) {
+                        Scroll::reveal(widget, &trigger, false);
                         trigger.update();
-                    } else {
-                        self.on_cursor_movement(widget, position);
                     }
                 }
                 WindowEvent::MouseInput {
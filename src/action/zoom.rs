@@ -1,9 +1,9 @@
-use log::debug;
 use winit::{
     event::{Event, MouseScrollDelta, WindowEvent::MouseWheel},
     window::Window,
 };
 
+use crate::trace::debug;
 use crate::ui::{sync::Signal, widget::BaseWidget};
 
 /// The UI zoom levels for user scaling
@@ -99,13 +99,18 @@ impl Zoom {
                         let (final_scaled_h, final_scaled_w) =
                             if let Some(bounds) = self.lower_upper {
                                 let (min_h, max_h, min_w, max_w) = bounds;
-                                (scaled_h.clamp(min_h, max_h), scaled_w.clamp(min_w, max_w))
+                                // Windows smaller than the lower bound win over it
+                                (
+                                    scaled_h.max(min_h).min(max_h),
+                                    scaled_w.max(min_w).min(max_w),
+                                )
                             } else {
                                 (scaled_h, scaled_w)
                             };
 
-                        widget.layout.w = final_scaled_w;
-                        widget.layout.h = final_scaled_h;
+                        // Zooming out stops once the widget is gone
+                        widget.layout.w = final_scaled_w.max(0.0);
+                        widget.layout.h = final_scaled_h.max(0.0);
 
                         window.request_redraw();
                     }
@@ -0,0 +1,354 @@
+//! A Model-Update-View architecture for state-heavy apps.
+//!
+//! An [`App`] owns the state of the app as a plain `Model` which only
+//! changes through messages handed to `update`. After every batch of
+//! messages the tree is described again by `view` and compared against
+//! the tree described before. Only the widgets whose description
+//! changed are built again and reconciled with the mounted ones so
+//! widgets keep their focus, hover and scroll state.
+//!
+//! Widgets report back by the messages attached to them which spares
+//! apps from sharing their state through `Rc<RefCell<_>>` handles.
+//!
+//! ## Example
+//! ```ignore
+//! #[derive(Clone)]
+//! enum Msg {
+//!     Increment,
+//! }
+//!
+//! let mut dom = DOM::new(800, 600);
+//! App::new(
+//!     0,
+//!     |count: &mut i32, msg| match msg {
+//!         Msg::Increment => *count += 1,
+//!     },
+//!     |count| {
+//!         WidgetTree::new(Container::new())
+//!             .child(WidgetTree::new(Label::new().set_label(&count.to_string())))
+//!             .child(WidgetTree::new(Button::new().set_label("+")).on_click(Msg::Increment))
+//!     },
+//! )
+//! .mount(&mut dom);
+//! dom.run()?;
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+};
+
+use crate::{
+    action::{
+        click::{Click, MouseButton},
+        Action,
+    },
+    ui::{
+        arena::WidgetId,
+        dom::DOM,
+        sync::{Proxy, Signal},
+        tree::WidgetNode,
+        widget::{widget_id, WidgetI},
+    },
+};
+
+type Handlers<Msg> = Rc<RefCell<HashMap<WidgetId, Msg>>>;
+type Update<Model, Msg> = Box<dyn Fn(&mut Model, Msg)>;
+type View<Model, Msg> = Box<dyn Fn(&Model) -> WidgetTree<Msg>>;
+
+/// The `WidgetTree` struct describes a widget, the message it
+/// sends once clicked and its children
+pub struct WidgetTree<Msg> {
+    /// The widget described without its children
+    node: WidgetNode,
+    on_click: Option<Msg>,
+    children: Vec<WidgetTree<Msg>>,
+}
+impl<Msg: Clone + 'static> WidgetTree<Msg> {
+    /// Describes `widget` along with any children it holds
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the widget is of a kind
+    /// a `WidgetNode` can not describe
+    pub fn new<W: WidgetI + 'static>(widget: W) -> Self {
+        let widget: Rc<dyn WidgetI> = Rc::new(widget);
+        let node = WidgetNode::from_widget(&widget)
+            .expect("widget should be of a kind a `WidgetNode` describes");
+        WidgetTree::from_node(node)
+    }
+    fn from_node(mut node: WidgetNode) -> Self {
+        let children = std::mem::take(&mut node.children)
+            .into_iter()
+            .map(WidgetTree::from_node)
+            .collect();
+        Self {
+            node,
+            on_click: None,
+            children,
+        }
+    }
+    /// Appends `child` after the children of the widget
+    pub fn child(mut self, child: WidgetTree<Msg>) -> Self {
+        self.children.push(child);
+        self
+    }
+    /// Sends `msg` to the app every time the widget is clicked
+    pub fn on_click(mut self, msg: Msg) -> Self {
+        self.on_click = Some(msg);
+        self
+    }
+    /// Describes the whole tree as a `WidgetNode`
+    fn describe(&self) -> WidgetNode {
+        WidgetNode {
+            children: self.children.iter().map(WidgetTree::describe).collect(),
+            ..self.node.clone()
+        }
+    }
+    /// Whether the widget itself is described the same leaving
+    /// its children aside
+    fn same_widget(&self, other: &WidgetTree<Msg>) -> bool {
+        self.node == other.node
+            && self.on_click.is_some() == other.on_click.is_some()
+            && self.children.len() == other.children.len()
+    }
+    /// Builds the described tree sending the messages of
+    /// clicked widgets into `queue`
+    fn build(&self, queue: &Sender<Msg>, handlers: &Handlers<Msg>) -> Rc<dyn WidgetI> {
+        let widget = self.describe().build();
+        self.attach(&widget, queue, handlers);
+        widget
+    }
+    fn attach(&self, widget: &Rc<dyn WidgetI>, queue: &Sender<Msg>, handlers: &Handlers<Msg>) {
+        if self.on_click.is_some() {
+            // The message is looked up on every click since views
+            // may attach another one to the same widget
+            let (queue, handlers) = (queue.clone(), handlers.clone());
+            let click = Click::new(()).on(
+                MouseButton::LeftButtonRelease,
+                move |_, trigger, _, _, _| {
+                    if let Some(msg) = handlers.borrow().get(&trigger.id()).cloned() {
+                        let _ = queue.send(msg);
+                    }
+                },
+            );
+            widget.action_mut().push(Action::Click(Box::new(click)));
+        }
+        for (child, widget) in self.children.iter().zip(widget.children()) {
+            child.attach(&widget, queue, handlers);
+        }
+    }
+    /// Records the messages of the tree by the widgets
+    /// they were built into
+    fn register(&self, widget: &Rc<dyn WidgetI>, handlers: &mut HashMap<WidgetId, Msg>) {
+        if let (Some(msg), Some(id)) = (&self.on_click, widget_id(widget)) {
+            handlers.insert(id, msg.clone());
+        }
+        for (child, widget) in self.children.iter().zip(widget.children()) {
+            child.register(&widget, handlers);
+        }
+    }
+}
+
+/// The `Dispatcher` struct sends messages to the `App`
+/// from any thread
+pub struct Dispatcher<Msg> {
+    queue: Sender<Msg>,
+    proxy: Arc<Mutex<Proxy>>,
+}
+impl<Msg> Clone for Dispatcher<Msg> {
+    fn clone(&self) -> Self {
+        Self {
+            queue: self.queue.clone(),
+            proxy: self.proxy.clone(),
+        }
+    }
+}
+impl<Msg> Dispatcher<Msg> {
+    /// Hands `msg` to `update` on the UI thread
+    pub fn dispatch(&self, msg: Msg) {
+        if self.queue.send(msg).is_ok() {
+            let _ = self.proxy.lock().unwrap().send_event(Signal::App);
+        }
+    }
+}
+
+/// The `App` struct runs the Model-Update-View loop
+/// of a `Model` inside a `DOM`
+pub struct App<Model, Msg> {
+    model: Model,
+    update: Update<Model, Msg>,
+    view: View<Model, Msg>,
+}
+impl<Model: 'static, Msg: Clone + 'static> App<Model, Msg> {
+    /// Create an app starting out with `model` which is
+    /// changed by `update` and shown as `view`
+    pub fn new<U, V>(model: Model, update: U, view: V) -> Self
+    where
+        U: Fn(&mut Model, Msg) + 'static,
+        V: Fn(&Model) -> WidgetTree<Msg> + 'static,
+    {
+        Self {
+            model,
+            update: Box::new(update),
+            view: Box::new(view),
+        }
+    }
+    /// Adds the view of the model to `dom` and keeps it up
+    /// to date from then on
+    ///
+    /// Returns a dispatcher for sending messages from
+    /// outside of the widgets
+    pub fn mount(self, dom: &mut DOM) -> Dispatcher<Msg> {
+        let (queue, messages) = mpsc::channel();
+        let handlers = Handlers::default();
+        let tree = (self.view)(&self.model);
+        let root = tree.build(&queue, &handlers);
+        dom.add_root(root.clone());
+        tree.register(&root, &mut handlers.borrow_mut());
+
+        let dispatcher = Dispatcher {
+            queue: queue.clone(),
+            proxy: dom.proxy(),
+        };
+        dom.set_program(Box::new(Running {
+            app: self,
+            tree,
+            root,
+            queue,
+            messages,
+            handlers,
+        }));
+        dispatcher
+    }
+}
+
+/// The `Program` trait is run by the `DOM` after
+/// handling every event
+pub(crate) trait Program {
+    fn process(&mut self, dom: &mut DOM);
+}
+
+/// An `App` mounted into a `DOM`
+struct Running<Model, Msg> {
+    app: App<Model, Msg>,
+    /// The tree last described by the view
+    tree: WidgetTree<Msg>,
+    root: Rc<dyn WidgetI>,
+    queue: Sender<Msg>,
+    messages: Receiver<Msg>,
+    handlers: Handlers<Msg>,
+}
+impl<Model: 'static, Msg: Clone + 'static> Running<Model, Msg> {
+    /// Builds the widgets described differently in `new` than in
+    /// `old` again and reconciles them with the mounted ones
+    ///
+    /// Returns the replacement of `widget` if any
+    fn patch(
+        &self,
+        dom: &mut DOM,
+        widget: &Rc<dyn WidgetI>,
+        old: &WidgetTree<Msg>,
+        new: &WidgetTree<Msg>,
+    ) -> Option<Rc<dyn WidgetI>> {
+        if !old.same_widget(new) {
+            let rebuilt = new.build(&self.queue, &self.handlers);
+            let kept = dom.reconcile_rc(widget_id(widget)?, rebuilt)?;
+            return (!Rc::ptr_eq(&kept, widget)).then_some(kept);
+        }
+        for ((old, new), child) in old
+            .children
+            .iter()
+            .zip(&new.children)
+            .zip(widget.children())
+        {
+            self.patch(dom, &child, old, new);
+        }
+        None
+    }
+}
+impl<Model: 'static, Msg: Clone + 'static> Program for Running<Model, Msg> {
+    fn process(&mut self, dom: &mut DOM) {
+        let messages: Vec<_> = self.messages.try_iter().collect();
+        if messages.is_empty() {
+            return;
+        }
+        for msg in messages {
+            (self.app.update)(&mut self.app.model, msg);
+        }
+
+        let tree = (self.app.view)(&self.app.model);
+        if let Some(root) = self.patch(dom, &self.root, &self.tree, &tree) {
+            self.root = root;
+        }
+        let mut handlers = self.handlers.borrow_mut();
+        handlers.clear();
+        tree.register(&self.root, &mut handlers);
+        drop(handlers);
+        self.tree = tree;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::ui::{
+        dom::DOM,
+        layout::FlexLayout,
+        widget::{button::Button, container::Container, label::Label, Widget},
+    };
+
+    use super::{App, WidgetTree};
+
+    #[derive(Clone)]
+    enum Msg {
+        Increment,
+    }
+
+    #[test]
+    fn only_changed_widgets_are_rebuilt() {
+        let mut dom = DOM::headless(200, 100);
+        let dispatcher = App::new(
+            0,
+            |count: &mut i32, msg| match msg {
+                Msg::Increment => *count += 1,
+            },
+            |count| {
+                WidgetTree::new(
+                    Container::new()
+                        .set_flex_layout(FlexLayout::Row)
+                        .set_width(200.0)
+                        .set_height(20.0),
+                )
+                .child(WidgetTree::new(
+                    Label::new().set_label(&count.to_string()).set_width(100.0),
+                ))
+                .child(
+                    WidgetTree::new(Button::new().set_width(100.0).set_height(20.0))
+                        .on_click(Msg::Increment),
+                )
+            },
+        )
+        .mount(&mut dom);
+        let children =
+            |dom: &DOM| dom.query_all(|widget| widget.as_any().is::<Container>())[0].children();
+        let label = children(&dom)[0].clone();
+        let button = children(&dom)[1].clone();
+
+        dom.click(150.0, 10.0);
+        assert_eq!(label.base().text.label, "1");
+        // The label was patched and the button described the same
+        assert!(Rc::ptr_eq(&children(&dom)[0], &label));
+        assert!(Rc::ptr_eq(&children(&dom)[1], &button));
+
+        dispatcher.dispatch(Msg::Increment);
+        dom.move_cursor(0.0, 0.0);
+        assert_eq!(children(&dom)[0].base().text.label, "2");
+    }
+}
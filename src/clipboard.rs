@@ -0,0 +1,138 @@
+//! Access to the clipboard shared with other apps.
+//!
+//! The system clipboard is only reached when built with the
+//! `clipboard` feature on desktop platforms. Everywhere else, or
+//! when the system clipboard is unavailable, copied text is only
+//! kept within the app.
+//!
+//! Pressing Ctrl+V (Cmd+V on macOS) hands the clipboard text to the
+//! focused widget as a `Signal::Paste` which `KeyPress::on_paste`
+//! responds to.
+//!
+//! Pixels copied off a `Canvas` are placed on the system clipboard as
+//! an image other apps can paste. Copying an image or a PNG file in
+//! another app, such as a file manager, hands its pixels to the app.
+
+use std::cell::RefCell;
+
+use crate::ui::buffer::CanvasBuffer;
+
+thread_local! {
+    /// Text copied while the system clipboard is out of reach
+    static LOCAL: RefCell<String> = const { RefCell::new(String::new()) };
+    /// Pixels copied off a canvas
+    static PIXELS: RefCell<Option<CanvasBuffer>> = const { RefCell::new(None) };
+    /// The system clipboard is kept open since some platforms
+    /// drop the copied text along with it
+    #[cfg(all(
+        feature = "clipboard",
+        not(any(target_arch = "wasm32", target_os = "android"))
+    ))]
+    static SYSTEM: RefCell<Option<arboard::Clipboard>> = const { RefCell::new(None) };
+}
+
+/// The `Clipboard` struct reads and writes the text
+/// other apps can paste
+///
+/// ## Example
+/// ```ignore
+/// KeyPress::new(()).on(KeyCombo::char('d').ctrl(), |_, _, widget, _| {
+///     Clipboard::set_text(&widget.base().text.label)
+/// });
+/// ```
+pub struct Clipboard;
+impl Clipboard {
+    /// Returns the text on the clipboard
+    ///
+    /// `None` when the clipboard is empty or holds
+    /// something other than text
+    pub fn get_text() -> Option<String> {
+        #[cfg(all(
+            feature = "clipboard",
+            not(any(target_arch = "wasm32", target_os = "android"))
+        ))]
+        if let Some(text) = Clipboard::with_system(|system| system.get_text().ok()) {
+            return Some(text);
+        }
+
+        let text = LOCAL.with_borrow(|local| local.clone());
+        (!text.is_empty()).then_some(text)
+    }
+    /// Places `text` on the clipboard
+    pub fn set_text(text: &str) {
+        #[cfg(all(
+            feature = "clipboard",
+            not(any(target_arch = "wasm32", target_os = "android"))
+        ))]
+        if Clipboard::with_system(|system| system.set_text(text).ok()).is_some() {
+            return;
+        }
+
+        LOCAL.with_borrow_mut(|local| *local = text.into());
+    }
+    /// Returns the pixels on the clipboard
+    ///
+    /// Copied PNG files are read before images. `None` when
+    /// no pixels were copied yet
+    pub fn get_pixels() -> Option<CanvasBuffer> {
+        #[cfg(all(
+            feature = "clipboard",
+            not(any(target_arch = "wasm32", target_os = "android"))
+        ))]
+        if let Some(pixels) = Clipboard::with_system(|system| {
+            let png = system.get().file_list().ok().and_then(|files| {
+                files
+                    .iter()
+                    .filter(|file| {
+                        file.extension()
+                            .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+                    })
+                    .find_map(|file| CanvasBuffer::from_png(&std::fs::read(file).ok()?))
+            });
+            png.or_else(|| {
+                let image = system.get_image().ok()?;
+                CanvasBuffer::from_rgba(image.width, image.height, &image.bytes)
+            })
+        }) {
+            return Some(pixels);
+        }
+
+        PIXELS.with_borrow(|pixels| pixels.clone())
+    }
+    /// Places `pixels` on the clipboard
+    ///
+    /// Other apps get them as an image. If the system clipboard
+    /// does not take images it is cleared instead, so what other
+    /// apps copied before is not pasted in place of the pixels
+    pub fn set_pixels(pixels: CanvasBuffer) {
+        #[cfg(all(
+            feature = "clipboard",
+            not(any(target_arch = "wasm32", target_os = "android"))
+        ))]
+        Clipboard::with_system(|system| {
+            let image = arboard::ImageData {
+                width: pixels.width(),
+                height: pixels.height(),
+                bytes: pixels.to_rgba().into(),
+            };
+            system.set_image(image).or_else(|_| system.clear()).ok()
+        });
+
+        PIXELS.with_borrow_mut(|copied| *copied = Some(pixels));
+    }
+    /// Runs `f` on the system clipboard opening it on first use
+    #[cfg(all(
+        feature = "clipboard",
+        not(any(target_arch = "wasm32", target_os = "android"))
+    ))]
+    fn with_system<T>(f: impl FnOnce(&mut arboard::Clipboard) -> Option<T>) -> Option<T> {
+        SYSTEM.with_borrow_mut(|system| {
+            if system.is_none() {
+                *system = arboard::Clipboard::new()
+                    .map_err(|e| log::error!("system clipboard unavailable: {e}"))
+                    .ok();
+            }
+            f(system.as_mut()?)
+        })
+    }
+}
@@ -0,0 +1,60 @@
+//! Errors the UI runs into while drawing and managing windows.
+//!
+//! Failures in the middle of the event loop are not returned to anyone
+//! so they are handed to the error hook of the `DOM` instead. By default
+//! the hook logs them and the UI carries on with the next frame. Apps
+//! set their own hook with `DOM::on_error` to recover or report them.
+//!
+//! ## Example
+//! ```ignore
+//! let mut dom = DOM::try_new(800, 600)?;
+//! dom.on_error(|err| match err {
+//!     Error::Render(_) => metrics::increment("dropped_frames"),
+//!     err => log::warn!("{err}"),
+//! });
+//! dom.run()?;
+//! ```
+
+use crate::ui::arena::WidgetId;
+
+/// The `Error` enum lists everything that can go wrong
+/// while showing the UI
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("failed to run the event loop: {0}")]
+    EventLoop(#[from] winit::error::EventLoopError),
+    #[error("failed to create the window: {0}")]
+    Window(#[from] winit::error::OsError),
+    #[error("failed to draw onto the window: {0}")]
+    Render(#[from] pixels::Error),
+    #[error("failed to resize the window surface: {0}")]
+    Resize(#[from] pixels::TextureError),
+    #[error("failed to allocate a {width}x{height} pixmap")]
+    Pixmap { width: u32, height: u32 },
+    #[error("no widget is mounted as {0:?}")]
+    StaleWidget(WidgetId),
+    #[error("widget {0:?} can not be taken out of its parent")]
+    Fixed(WidgetId),
+    #[error("headless DOMs have no event loop to run")]
+    Headless,
+}
+
+/// The `ErrorHook` struct hands the errors the UI
+/// recovered from to the app
+pub(crate) struct ErrorHook(Box<dyn FnMut(Error)>);
+impl ErrorHook {
+    pub(crate) fn new<F: FnMut(Error) + 'static>(hook: F) -> Self {
+        ErrorHook(Box::new(hook))
+    }
+    /// Hands the error of `result` to the hook if any
+    pub(crate) fn report(&mut self, result: Result<(), Error>) {
+        if let Err(err) = result {
+            (self.0)(err);
+        }
+    }
+}
+impl Default for ErrorHook {
+    fn default() -> Self {
+        ErrorHook::new(|err| log::error!("{err}"))
+    }
+}
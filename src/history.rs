@@ -0,0 +1,317 @@
+//! A reusable undo/redo system for applications built on the crate.
+//!
+//! Operations are modeled as `Command`s which know how to apply and
+//! revert themselves. The `History` records executed commands so any
+//! mix of operations (painting, moving widgets, renaming, ...) can be
+//! walked back and forth with a single undo/redo stack.
+
+use std::any::Any;
+
+/// A trait representing a single reversible operation.
+///
+/// Types that implement `Command` can be recorded by a `History`
+/// to later be undone and redone.
+pub trait Command: Any {
+    /// Allows downcasting to concrete types by returning a reference to `Any`.
+    fn as_any(&self) -> &dyn Any;
+    /// Applies the operation
+    fn execute(&mut self);
+    /// Reverts everything `execute` applied
+    fn undo(&mut self);
+    /// Re-applies the operation after an undo
+    ///
+    /// Defaults to executing the command again
+    fn redo(&mut self) {
+        self.execute();
+    }
+    /// Attempts to absorb a newer `other` command into this one so
+    /// both are undone as a single step (e.g. consecutive keystrokes
+    /// or a drag producing many moves)
+    ///
+    /// Returns `true` if `other` was merged, in which case `other`
+    /// is dropped from the history. `other` has already been executed.
+    fn merge(&mut self, _other: &dyn Command) -> bool {
+        false
+    }
+}
+
+/// The `Transaction` struct groups many commands so they are
+/// undone and redone together as a single step
+#[derive(Default)]
+pub struct Transaction {
+    commands: Vec<Box<dyn Command>>,
+}
+impl Transaction {
+    /// Returns `true` if the transaction holds no commands
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+impl Command for Transaction {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn execute(&mut self) {
+        for command in self.commands.iter_mut() {
+            command.execute();
+        }
+    }
+    fn undo(&mut self) {
+        // Commands must be reverted in the opposite order they were applied
+        for command in self.commands.iter_mut().rev() {
+            command.undo();
+        }
+    }
+    fn redo(&mut self) {
+        for command in self.commands.iter_mut() {
+            command.redo();
+        }
+    }
+}
+
+/// The `History` struct is the command manager keeping track of
+/// what can be undone and redone.
+///
+/// Executing a new command always discards the redo stack.
+///
+/// ## Example
+/// ```ignore
+/// let mut history = History::new();
+///
+/// history.begin_transaction();
+/// history.execute(SetColor::new(cell, RED));
+/// history.execute(SetColor::new(other_cell, RED));
+/// history.commit_transaction();
+///
+/// history.undo(); // both cells are restored
+/// ```
+#[derive(Default)]
+pub struct History {
+    undo_stack: Vec<Box<dyn Command>>,
+    redo_stack: Vec<Box<dyn Command>>,
+    transaction: Option<Transaction>,
+    /// Nested transactions are folded into the outermost one
+    transaction_depth: usize,
+    limit: Option<usize>,
+}
+impl History {
+    /// Create a new unbounded `History`
+    pub fn new() -> Self {
+        History::default()
+    }
+    /// Create a new `History` that only remembers the last `limit` steps
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Default::default()
+        }
+    }
+    /// Executes the `command` and records it so it can be undone
+    pub fn execute<C: Command>(&mut self, mut command: C) {
+        command.execute();
+        self.record(Box::new(command));
+    }
+    /// Records a `command` that was already applied by the caller
+    pub fn push<C: Command>(&mut self, command: C) {
+        self.record(Box::new(command));
+    }
+    fn record(&mut self, command: Box<dyn Command>) {
+        self.redo_stack.clear();
+
+        let stack = match &mut self.transaction {
+            Some(transaction) => &mut transaction.commands,
+            None => &mut self.undo_stack,
+        };
+        let merged = stack
+            .last_mut()
+            .is_some_and(|last| last.merge(command.as_ref()));
+        if !merged {
+            stack.push(command);
+        }
+        if self.transaction.is_some() {
+            return;
+        }
+
+        // Forget the oldest steps once over the limit
+        if let Some(limit) = self.limit {
+            if self.undo_stack.len() > limit {
+                let overflow = self.undo_stack.len() - limit;
+                self.undo_stack.drain(..overflow);
+            }
+        }
+    }
+    /// Starts grouping every following command into a single step
+    /// until `commit_transaction` is called
+    pub fn begin_transaction(&mut self) {
+        if self.transaction.is_none() {
+            self.transaction = Some(Transaction::default());
+        }
+        self.transaction_depth += 1;
+    }
+    /// Finishes the current transaction and records it as a single step
+    ///
+    /// NoOp if no transaction was started
+    pub fn commit_transaction(&mut self) {
+        if self.transaction_depth == 0 {
+            return;
+        }
+        self.transaction_depth -= 1;
+
+        if self.transaction_depth == 0 {
+            if let Some(transaction) = self.transaction.take() {
+                if !transaction.is_empty() {
+                    self.record(Box::new(transaction));
+                }
+            }
+        }
+    }
+    /// Reverts every command of the current transaction and discards it
+    ///
+    /// NoOp if no transaction was started
+    pub fn rollback_transaction(&mut self) {
+        if let Some(mut transaction) = self.transaction.take() {
+            transaction.undo();
+        }
+        self.transaction_depth = 0;
+    }
+    /// Undoes the last step
+    ///
+    /// Returns `false` if there was nothing to undo
+    pub fn undo(&mut self) -> bool {
+        if let Some(mut command) = self.undo_stack.pop() {
+            command.undo();
+            self.redo_stack.push(command);
+            true
+        } else {
+            false
+        }
+    }
+    /// Redoes the last undone step
+    ///
+    /// Returns `false` if there was nothing to redo
+    pub fn redo(&mut self) -> bool {
+        if let Some(mut command) = self.redo_stack.pop() {
+            command.redo();
+            self.undo_stack.push(command);
+            true
+        } else {
+            false
+        }
+    }
+    /// Returns `true` if there is a step to undo
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+    /// Returns `true` if there is a step to redo
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+    /// Forgets every recorded step
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.transaction = None;
+        self.transaction_depth = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{any::Any, cell::RefCell, rc::Rc};
+
+    use super::{Command, History};
+
+    struct Add {
+        total: Rc<RefCell<i32>>,
+        amount: i32,
+    }
+    impl Command for Add {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+        fn execute(&mut self) {
+            *self.total.borrow_mut() += self.amount;
+        }
+        fn undo(&mut self) {
+            *self.total.borrow_mut() -= self.amount;
+        }
+        fn merge(&mut self, other: &dyn Command) -> bool {
+            if let Some(other) = other.as_any().downcast_ref::<Add>() {
+                self.amount += other.amount;
+                true
+            } else {
+                false
+            }
+        }
+    }
+
+    struct Set {
+        total: Rc<RefCell<i32>>,
+        value: i32,
+        previous: i32,
+    }
+    impl Command for Set {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+        fn execute(&mut self) {
+            self.previous = *self.total.borrow();
+            *self.total.borrow_mut() = self.value;
+        }
+        fn undo(&mut self) {
+            *self.total.borrow_mut() = self.previous;
+        }
+    }
+
+    #[test]
+    fn test_undo_redo_with_merge_and_transactions() {
+        let total = Rc::new(RefCell::new(0));
+        let mut history = History::new();
+
+        // Consecutive adds collapse into a single step
+        history.execute(Add {
+            total: total.clone(),
+            amount: 2,
+        });
+        history.execute(Add {
+            total: total.clone(),
+            amount: 3,
+        });
+        history.execute(Set {
+            total: total.clone(),
+            value: 10,
+            previous: 0,
+        });
+        assert!(*total.borrow() == 10);
+
+        history.undo();
+        assert!(*total.borrow() == 5);
+        history.undo();
+        assert!(*total.borrow() == 0);
+        assert!(!history.undo());
+
+        history.redo();
+        assert!(*total.borrow() == 5);
+
+        // Transactions are a single step
+        history.begin_transaction();
+        history.execute(Set {
+            total: total.clone(),
+            value: 1,
+            previous: 0,
+        });
+        history.execute(Set {
+            total: total.clone(),
+            value: 7,
+            previous: 0,
+        });
+        history.commit_transaction();
+        assert!(!history.can_redo());
+        assert!(*total.borrow() == 7);
+
+        history.undo();
+        assert!(*total.borrow() == 5);
+        history.redo();
+        assert!(*total.borrow() == 7);
+    }
+}
@@ -1,3 +1,15 @@
 pub mod action;
+pub mod app;
+pub mod clipboard;
+pub mod error;
+pub mod history;
 pub mod render;
-pub mod ui;
\ No newline at end of file
+pub mod state;
+pub mod test;
+mod trace;
+pub mod ui;
+
+pub use error::Error;
+
+#[doc(hidden)]
+pub use paste;
\ No newline at end of file
@@ -0,0 +1,179 @@
+//! Confines drawing to the regions of the containers drawn in.
+//!
+//! Every container narrows the region its children are drawn in
+//! down to the part shared with the region it was drawn in itself.
+//! Containers with rounded corners carry a coverage mask along so
+//! their children fade out along the curve instead of poking out
+//! of the corners.
+
+use std::{ops::Range, rc::Rc};
+
+use tiny_skia::{FillRule, Mask, Transform};
+
+use crate::ui::{
+    layout::{intersect_clips, Layout},
+    style::{CornerShape, Radius},
+};
+
+use super::pixels_backend::PixelsRenderer;
+
+/// The coverage of the rounded corners clipping the drawing
+/// placed with its top left corner at `left`, `top`
+#[derive(Debug, Clone)]
+struct ClipMask {
+    left: i32,
+    top: i32,
+    mask: Mask,
+}
+impl ClipMask {
+    /// Returns how much of the pixel at `x`, `y` is inside the mask
+    fn coverage(&self, x: i32, y: i32) -> u8 {
+        let (x, y) = (x - self.left, y - self.top);
+        if x < 0 || y < 0 || x as u32 >= self.mask.width() || y as u32 >= self.mask.height() {
+            return 0;
+        }
+        self.mask.data()[(y as u32 * self.mask.width() + x as u32) as usize]
+    }
+    /// Returns the coverage of the `columns` of row `y` narrowing
+    /// them down to the ones inside the mask
+    fn row(&self, y: i32, columns: &mut Range<i32>) -> Option<&[u8]> {
+        let (width, height) = (self.mask.width() as i32, self.mask.height() as i32);
+        let y = y - self.top;
+        columns.start = columns.start.max(self.left);
+        columns.end = columns.end.min(self.left + width);
+        if y < 0 || y >= height || columns.start >= columns.end {
+            return None;
+        }
+        let start = (y * width + columns.start - self.left) as usize;
+        Some(&self.mask.data()[start..start + columns.len()])
+    }
+}
+
+/// The `Clip` struct describes the region drawing is confined to
+///
+/// - `rect`: The absolute bounds on screen when drawing is confined
+/// - `mask`: The rounded corners of every container drawn in
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Clip {
+    pub(crate) rect: Option<Layout>,
+    mask: Option<Rc<ClipMask>>,
+}
+impl Clip {
+    pub(crate) fn new(rect: Option<Layout>) -> Self {
+        Clip { rect, mask: None }
+    }
+    /// Narrows the clip down to the part shared with `rect`
+    pub(crate) fn intersect(&self, rect: Option<Layout>) -> Clip {
+        Clip {
+            rect: intersect_clips(rect, self.rect),
+            mask: self.mask.clone(),
+        }
+    }
+    /// Narrows the clip down to the part shared with `rect` and the
+    /// `bounds` on screen rounded at the corners by `radius`
+    /// in `shape`
+    pub(crate) fn round(
+        &self,
+        rect: Option<Layout>,
+        bounds: Layout,
+        radius: Radius,
+        shape: CornerShape,
+    ) -> Clip {
+        let clip = self.intersect(rect);
+        if radius.is_zero() {
+            return clip;
+        }
+        let (left, top) = (bounds.x.round() as i32, bounds.y.round() as i32);
+        let Some(mut mask) = Mask::new(bounds.w.round() as u32, bounds.h.round() as u32) else {
+            // Nothing fits in an empty container
+            return Clip {
+                rect: Some(Layout::default()),
+                mask: None,
+            };
+        };
+        if let Some(path) = PixelsRenderer::rounded_rect_path(
+            0.0,
+            0.0,
+            mask.width() as f32,
+            mask.height() as f32,
+            radius,
+            shape,
+        ) {
+            mask.fill_path(&path, FillRule::Winding, true, Transform::identity());
+        }
+
+        // Corners of the containers drawn in cut into this one too
+        if let Some(outer) = &clip.mask {
+            let width = mask.width() as i32;
+            for (i, cell) in mask.data_mut().iter_mut().enumerate() {
+                let (x, y) = (left + i as i32 % width, top + i as i32 / width);
+                *cell = (u16::from(*cell) * u16::from(outer.coverage(x, y)) / 255) as u8;
+            }
+        }
+
+        Clip {
+            rect: clip.rect,
+            mask: Some(Rc::new(ClipMask { left, top, mask })),
+        }
+    }
+    /// Returns the part of the `columns` of row `y` that may be
+    /// drawn along with how much of each pixel in it may be drawn
+    ///
+    /// The coverage is `None` when every pixel is drawn in full
+    /// and the row is `None` when it falls outside of the clip
+    pub(crate) fn row(
+        &self,
+        y: i32,
+        mut columns: Range<i32>,
+    ) -> Option<(Range<i32>, Option<&[u8]>)> {
+        if let Some(rect) = self.rect {
+            // Pixels are inside once their top left corner is
+            if f64::from(y) < rect.y || f64::from(y) >= rect.y + rect.h {
+                return None;
+            }
+            columns.start = columns.start.max(rect.x.ceil() as i32);
+            columns.end = columns.end.min((rect.x + rect.w).ceil() as i32);
+        }
+        let coverage = match &self.mask {
+            Some(mask) => Some(mask.row(y, &mut columns)?),
+            None => None,
+        };
+        (!columns.is_empty()).then_some((columns, coverage))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::{
+        layout::Layout,
+        style::{CornerShape, Radius},
+    };
+
+    use super::Clip;
+
+    /// Returns how much of the pixel at `x`, `y` may be drawn
+    fn coverage(clip: &Clip, x: i32, y: i32) -> Option<u8> {
+        let (_, coverage) = clip.row(y, x..x + 1)?;
+        Some(coverage.map_or(u8::MAX, |coverage| coverage[0])).filter(|coverage| *coverage > 0)
+    }
+
+    #[test]
+    fn rounded_clips_stack() {
+        let bounds = Layout {
+            x: 10.0,
+            y: 10.0,
+            w: 40.0,
+            h: 40.0,
+        };
+        let outer = Clip::default().round(None, bounds, Radius::all(10), CornerShape::Round);
+        assert_eq!(coverage(&outer, 30, 30), Some(255));
+        // The corners are cut off
+        assert_eq!(coverage(&outer, 10, 10), None);
+        assert_eq!(coverage(&outer, 5, 30), None);
+
+        // A square container inside keeps the rounded corners
+        let inner = outer.round(None, bounds, Radius::all(2), CornerShape::Round);
+        assert_eq!(coverage(&inner, 11, 11), None);
+        assert_eq!(coverage(&inner, 30, 30), Some(255));
+    }
+}
@@ -0,0 +1,136 @@
+//! Tracks the regions of the screen that need to be redrawn.
+//!
+//! Widgets report the rect they cover whenever they change. The rects
+//! are accumulated between frames and merged with any overlapping rect
+//! so a frame only clears and repaints the damaged parts of the screen.
+
+use crate::ui::layout::Layout;
+
+/// The `Damage` struct accumulates dirty rects until
+/// the next frame is drawn
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Damage {
+    rects: Vec<Layout>,
+    full: bool,
+}
+impl Default for Damage {
+    fn default() -> Self {
+        // Nothing has been drawn before the first frame
+        Self {
+            rects: Vec::new(),
+            full: true,
+        }
+    }
+}
+impl Damage {
+    pub(crate) fn new() -> Self {
+        Damage::default()
+    }
+    /// Marks `rect` as needing a redraw
+    ///
+    /// Rects overlapping `rect` are merged into their union
+    /// so no region is ever drawn twice in a frame
+    pub(crate) fn add(&mut self, mut rect: Layout) {
+        if rect.w <= 0.0 || rect.h <= 0.0 || self.full {
+            return;
+        }
+
+        // A union may grow into rects it did not overlap
+        // before so keep merging until nothing overlaps
+        while let Some(i) = self.rects.iter().position(|other| other.intersects(&rect)) {
+            rect = rect.union(&self.rects.swap_remove(i));
+        }
+        self.rects.push(rect);
+    }
+    /// Marks `rect` as needing a redraw only if the next
+    /// frame is drawn for damage elsewhere
+    pub(crate) fn add_alongside(&mut self, rect: Layout) {
+        if !self.rects.is_empty() {
+            self.add(rect);
+        }
+    }
+    /// Marks the whole screen as needing a redraw
+    pub(crate) fn add_full(&mut self) {
+        self.full = true;
+        self.rects.clear();
+    }
+    /// Takes the damaged rects leaving no damage behind
+    ///
+    /// Returns `None` when the whole screen must be redrawn
+    pub(crate) fn take(&mut self) -> Option<Vec<Layout>> {
+        let full = std::mem::take(&mut self.full);
+        let rects = std::mem::take(&mut self.rects);
+        // Redraws nobody asked for come from the platform
+        // (e.g. an uncovered window) and need everything
+        (!full && !rects.is_empty()).then_some(rects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Damage;
+    use crate::ui::layout::Layout;
+
+    fn rect(x: f64, y: f64, w: f64, h: f64) -> Layout {
+        Layout { x, y, w, h }
+    }
+
+    #[test]
+    fn test_first_frame_is_full() {
+        let mut damage = Damage::new();
+        damage.add(rect(0.0, 0.0, 10.0, 10.0));
+        assert!(damage.take().is_none());
+        assert!(
+            damage
+                == Damage {
+                    rects: vec![],
+                    full: false
+                }
+        );
+    }
+
+    #[test]
+    fn test_overlapping_rects_merge() {
+        let mut damage = Damage::new();
+        damage.take();
+
+        damage.add(rect(0.0, 0.0, 10.0, 10.0));
+        damage.add(rect(5.0, 5.0, 10.0, 10.0));
+        assert!(damage.take() == Some(vec![rect(0.0, 0.0, 15.0, 15.0)]));
+    }
+
+    #[test]
+    fn test_disjoint_rects_stay_apart() {
+        let mut damage = Damage::new();
+        damage.take();
+
+        damage.add(rect(0.0, 0.0, 10.0, 10.0));
+        // Sharing an edge is not overlapping
+        damage.add(rect(10.0, 0.0, 10.0, 10.0));
+        damage.add(rect(50.0, 50.0, 0.0, 10.0));
+        assert!(damage.take().map(|rects| rects.len()) == Some(2));
+    }
+
+    #[test]
+    fn test_union_merges_transitively() {
+        let mut damage = Damage::new();
+        damage.take();
+
+        damage.add(rect(0.0, 0.0, 10.0, 10.0));
+        damage.add(rect(20.0, 0.0, 10.0, 10.0));
+        // Bridges both rects
+        damage.add(rect(5.0, 5.0, 20.0, 2.0));
+        assert!(damage.take() == Some(vec![rect(0.0, 0.0, 30.0, 10.0)]));
+    }
+
+    #[test]
+    fn test_full_damage_swallows_rects() {
+        let mut damage = Damage::new();
+        damage.take();
+
+        damage.add(rect(0.0, 0.0, 10.0, 10.0));
+        damage.add_full();
+        damage.add(rect(5.0, 5.0, 10.0, 10.0));
+        assert!(damage.take().is_none());
+    }
+}
@@ -0,0 +1,129 @@
+//! Caches rasterized glyphs so redrawing text skips outlining.
+//!
+//! Coverage masks are keyed by glyph id, font size and the
+//! fraction of a pixel the glyph starts at so text drawn again
+//! on the next frame is only blended rather than rasterized.
+
+use std::collections::HashMap;
+
+use ab_glyph::{point, Font, FontRef, GlyphId, PxScale};
+
+/// Positions within a pixel are rounded to this many steps
+const SUBPIXEL_STEPS: f32 = 4.0;
+/// Cached glyphs are dropped past this many so changing
+/// font sizes cannot grow the cache forever
+const MAX_GLYPHS: usize = 4096;
+
+/// The coverage of a rasterized glyph
+///
+/// - `left`: Where the mask starts relative to the pixel the caret is on
+/// - `top`: Where the mask starts relative to the baseline which
+///   is negative for the part above it
+/// - `coverage`: How much of every pixel the glyph covers in row order
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct GlyphMask {
+    pub(crate) left: i32,
+    pub(crate) top: i32,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) coverage: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct GlyphKey {
+    id: GlyphId,
+    size: u32,
+    subpixel: u8,
+}
+
+/// The `GlyphCache` struct holds the rasterized glyphs of a font
+#[derive(Debug, Clone)]
+pub(crate) struct GlyphCache {
+    font: FontRef<'static>,
+    masks: HashMap<GlyphKey, GlyphMask>,
+}
+impl GlyphCache {
+    pub(crate) fn new(font: FontRef<'static>) -> Self {
+        Self {
+            font,
+            masks: HashMap::new(),
+        }
+    }
+    /// Returns the font the glyphs are rasterized from
+    pub(crate) fn font(&self) -> &FontRef<'static> {
+        &self.font
+    }
+    /// Returns the coverage of the glyph of `c` drawn at
+    /// `font_size` with the caret `x` pixels into the text
+    /// along with the pixel the mask is placed relative to
+    pub(crate) fn rasterize(&mut self, c: char, font_size: f32, x: f32) -> (i32, &GlyphMask) {
+        let steps = (x * SUBPIXEL_STEPS).round();
+        let pixel = (steps / SUBPIXEL_STEPS).floor();
+        let key = GlyphKey {
+            id: self.font.glyph_id(c),
+            size: font_size.to_bits(),
+            subpixel: (steps - pixel * SUBPIXEL_STEPS) as u8,
+        };
+
+        if !self.masks.contains_key(&key) {
+            if self.masks.len() >= MAX_GLYPHS {
+                self.masks.clear();
+            }
+            let mask = self.outline(key);
+            self.masks.insert(key, mask);
+        }
+        (pixel as i32, &self.masks[&key])
+    }
+    fn outline(&self, key: GlyphKey) -> GlyphMask {
+        let scale = PxScale::from(f32::from_bits(key.size));
+        let glyph = key
+            .id
+            .with_scale_and_position(scale, point(f32::from(key.subpixel) / SUBPIXEL_STEPS, 0.0));
+        let Some(outline) = self.font.outline_glyph(glyph) else {
+            return GlyphMask::default();
+        };
+
+        let bounds = outline.px_bounds();
+        let (width, height) = (bounds.width() as u32, bounds.height() as u32);
+        let mut coverage = vec![0.0; (width * height) as usize];
+        outline.draw(|x, y, c| {
+            if let Some(cell) = coverage.get_mut((y * width + x) as usize) {
+                *cell = c;
+            }
+        });
+
+        GlyphMask {
+            left: bounds.min.x as i32,
+            top: bounds.min.y as i32,
+            width,
+            height,
+            coverage,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::text::default_font;
+
+    use super::*;
+
+    #[test]
+    fn reuses_rasterized_glyphs() {
+        let mut cache = GlyphCache::new(default_font().clone());
+
+        let (pixel, first) = cache.rasterize('a', 12.0, 0.0);
+        let first = first.clone();
+        assert_eq!(pixel, 0);
+        assert!(first.width > 0 && first.height > 0);
+        assert!(first.coverage.iter().any(|c| *c > 0.0));
+        assert_eq!(cache.rasterize('a', 12.0, 10.0), (10, &first));
+        // Almost reaching the next pixel draws from there
+        assert_eq!(cache.rasterize('a', 12.0, 10.9), (11, &first));
+        assert_eq!(cache.masks.len(), 1);
+
+        cache.rasterize('a', 14.0, 0.0);
+        cache.rasterize('a', 12.0, 0.5);
+        assert_eq!(cache.masks.len(), 3);
+    }
+}
@@ -0,0 +1,235 @@
+//! Draws the UI into memory without a window.
+//!
+//! The `HeadlessRenderer` draws exactly like the window does but
+//! into a plain RGBA buffer so how widgets are laid out and painted
+//! can be checked where no display is available such as in CI.
+
+use std::rc::Rc;
+
+use crate::{
+    error::Error,
+    ui::{color::Color, layout::Layout, widget::WidgetI},
+};
+
+use super::{
+    pixels_backend::{PixelsRenderer, Surface},
+    row_major, Painter, Renderer,
+};
+
+/// The `Buffer` struct is a frame kept in memory
+/// which is never shown
+struct Buffer {
+    width: u32,
+    height: u32,
+    frame: Vec<u8>,
+}
+impl Buffer {
+    fn new(width: u32, height: u32) -> Self {
+        Buffer {
+            width,
+            height,
+            frame: vec![0; row_major(0, height, width)],
+        }
+    }
+}
+impl Surface for Buffer {
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+    fn frame(&self) -> &[u8] {
+        &self.frame
+    }
+    fn frame_mut(&mut self) -> &mut [u8] {
+        &mut self.frame
+    }
+    fn present(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+    fn resize(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        *self = Buffer::new(width, height);
+        Ok(())
+    }
+}
+
+/// A renderer drawing into an in memory RGBA buffer.
+///
+/// The `HeadlessRenderer` struct needs neither a window nor a GPU
+/// and is meant for testing widgets. Pixels are stored row by row
+/// with 4 bytes each.
+///
+/// ## Example
+/// ```ignore
+/// let mut renderer = HeadlessRenderer::new(100, 100);
+/// renderer.clear();
+/// renderer.draw(&widget);
+/// assert_eq!(renderer.pixel(10, 10), Some(RED));
+/// ```
+pub struct HeadlessRenderer {
+    renderer: PixelsRenderer,
+}
+impl HeadlessRenderer {
+    /// Creates a renderer drawing into a `width` x `height` buffer
+    pub fn new(width: u32, height: u32) -> Self {
+        HeadlessRenderer {
+            renderer: PixelsRenderer::with_surface(Buffer::new(width, height)),
+        }
+    }
+    /// Returns the width of the buffer in pixels
+    pub fn width(&self) -> u32 {
+        self.renderer.size().0
+    }
+    /// Returns the height of the buffer in pixels
+    pub fn height(&self) -> u32 {
+        self.renderer.size().1
+    }
+    /// Returns the RGBA pixels drawn so far row by row
+    pub fn frame(&self) -> &[u8] {
+        self.renderer.frame()
+    }
+    /// Returns the color of the pixel at `x`, `y` or `None`
+    /// when it falls outside of the buffer
+    pub fn pixel(&self, x: u32, y: u32) -> Option<Color> {
+        let (width, height) = self.renderer.size();
+        if x >= width || y >= height {
+            return None;
+        }
+        let idx = row_major(x, y, width);
+        let pixel: [u8; 4] = self.frame()[idx..idx + 4].try_into().ok()?;
+        Some(pixel.into())
+    }
+}
+impl Renderer for HeadlessRenderer {
+    fn dirty_clear(&mut self, x: f64, y: f64, h: f64, w: f64) {
+        self.renderer.dirty_clear(x, y, h, w);
+    }
+    fn clear(&mut self) {
+        self.renderer.clear();
+    }
+    fn draw(&mut self, widget: &Rc<dyn WidgetI>) -> Result<(), Error> {
+        Renderer::draw(&mut self.renderer, widget)
+    }
+    fn draw_region(&mut self, widget: &Rc<dyn WidgetI>, region: Layout) -> Result<(), Error> {
+        self.renderer.draw_region(widget, region)
+    }
+    fn draw_overlay(&mut self, draw: &mut dyn FnMut(&mut dyn Painter)) -> Result<(), Error> {
+        self.renderer.draw_overlay(draw)
+    }
+    fn present(&mut self) -> Result<(), Error> {
+        self.renderer.present()
+    }
+    fn resize(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        self.renderer.resize(width, height)
+    }
+    fn set_subpixel(&mut self, subpixel: bool) {
+        self.renderer.set_subpixel(subpixel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::{
+        render::{headless::HeadlessRenderer, Renderer},
+        ui::{
+            color::{Fill, BLACK, BLUE, RED, TRANSPARENT},
+            dom::DOM,
+            layout::Point,
+            style::{Border, Shadow},
+            text::LineMetrics,
+            widget::{container::Container, label::Label, Widget, WidgetI},
+        },
+    };
+
+    #[test]
+    fn draws_widgets_without_a_window() {
+        let widgets: [Rc<dyn WidgetI>; 2] = [
+            Rc::new(
+                Container::new()
+                    .set_width(50.0)
+                    .set_height(50.0)
+                    .set_color(RED),
+            ),
+            Rc::new(
+                Container::new()
+                    .set_x(100.0)
+                    .set_y(20.0)
+                    .set_width(40.0)
+                    .set_height(40.0)
+                    .set_radius(8)
+                    .set_color(BLUE),
+            ),
+        ];
+        let frame = DOM::render_once_to_buffer(&widgets, 160, 80).unwrap();
+
+        assert_eq!(frame.frame().len(), 160 * 80 * 4);
+        assert_eq!(frame.pixel(10, 10), Some(RED));
+        assert_eq!(frame.pixel(120, 40), Some(BLUE));
+        // Rounded corners are cut off
+        assert_ne!(frame.pixel(100, 20), Some(BLUE));
+        assert_eq!(frame.pixel(70, 70), Some(TRANSPARENT));
+        assert_eq!(frame.pixel(160, 0), None);
+    }
+
+    #[test]
+    fn degenerate_widgets_draw_nothing() {
+        let widgets: [Rc<dyn WidgetI>; 3] = [
+            Rc::new(
+                Container::new()
+                    .set_width(0.0)
+                    .set_height(30.0)
+                    .set_radius(4)
+                    .set_border(Border::new(2.0, BLUE))
+                    .set_color(RED),
+            ),
+            Rc::new(
+                Container::new()
+                    .set_width(40.0)
+                    .set_height(-10.0)
+                    .set_fill(Fill::LinearGradient {
+                        stops: vec![(0.0, RED), (1.0, BLUE)],
+                        angle: 90.0,
+                    })
+                    .set_shadow(Shadow::new(Point::new(2.0, 2.0), 4.0, BLACK)),
+            ),
+            Rc::new(Label::new().set_label("gone").set_label_size(0.0)),
+        ];
+        let frame = DOM::render_once_to_buffer(&widgets, 60, 40).unwrap();
+
+        assert!(frame.frame().chunks_exact(4).all(|pixel| pixel == [0; 4]));
+    }
+
+    #[test]
+    fn subpixel_edges_are_blended() {
+        let widget: Rc<dyn WidgetI> = Rc::new(
+            Container::new()
+                .set_x(10.5)
+                .set_width(20.0)
+                .set_height(10.0)
+                .set_color(RED),
+        );
+        let mut renderer = HeadlessRenderer::new(40, 10);
+        renderer.set_subpixel(true);
+        renderer.clear();
+        renderer.draw(&widget).unwrap();
+
+        // Both edges only partly cover their pixel
+        let partly = |x| !matches!(renderer.pixel(x, 5), Some(RED | TRANSPARENT));
+        assert!(partly(10) && partly(30));
+        assert_eq!(renderer.pixel(11, 5), Some(RED));
+        assert_eq!(renderer.pixel(31, 5), Some(TRANSPARENT));
+    }
+
+    #[test]
+    fn descenders_are_drawn_below_the_baseline() {
+        let label = Label::new().set_label("gyp").set_label_size(40.0);
+        let line = LineMetrics::of(&label.base().text.resolved_spans());
+        let widgets: [Rc<dyn WidgetI>; 1] = [Rc::new(label)];
+        let frame = DOM::render_once_to_buffer(&widgets, 100, 80).unwrap();
+
+        let inked = |y| (0..100).any(|x| frame.pixel(x, y) != Some(TRANSPARENT));
+        let (baseline, height) = (line.baseline().round() as u32, line.height().ceil() as u32);
+        assert!(inked(baseline + line.descent as u32 / 2));
+        assert!((height..80).all(|y| !inked(y)));
+    }
+}
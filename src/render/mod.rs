@@ -11,8 +11,24 @@
 
 use std::rc::Rc;
 
-use crate::ui::widget::WidgetI;
+use tiny_skia::Path;
 
+use crate::{
+    error::Error,
+    ui::{
+        color::Color,
+        layout::{Layout, Point},
+        style::Outline,
+        widget::{Widget, WidgetI},
+    },
+};
+
+pub(crate) mod clip;
+pub(crate) mod damage;
+pub(crate) mod glyph;
+pub mod headless;
+pub(crate) mod raster;
+pub(crate) mod shadow;
 pub mod pixels_backend;
 pub mod pre;
 
@@ -30,9 +46,39 @@ pub trait Renderer {
     /// Clears the entire screen
     fn clear(&mut self);
     /// Draw all widgets to screen
-    fn draw(&mut self, widget: &Rc<dyn WidgetI>);
+    ///
+    /// Widgets failing to draw do not keep the others from
+    /// being drawn and the first failure is returned
+    fn draw(&mut self, widget: &Rc<dyn WidgetI>) -> Result<(), Error>;
+    /// Draw only the parts of widgets inside the `region`
+    fn draw_region(&mut self, widget: &Rc<dyn WidgetI>, region: Layout) -> Result<(), Error>;
+    /// Draw over everything drawn so far anywhere on screen
+    fn draw_overlay(&mut self, draw: &mut dyn FnMut(&mut dyn Painter)) -> Result<(), Error>;
     /// Show the drawings
-    fn present(&mut self);
+    fn present(&mut self) -> Result<(), Error>;
+    /// Match the drawing surface to the new window size
+    fn resize(&mut self, width: u32, height: u32) -> Result<(), Error>;
+    /// Draw widgets where they sit within a pixel instead
+    /// of from the nearest pixel
+    fn set_subpixel(&mut self, subpixel: bool);
+}
+
+/// A trait for drawing custom content from within widgets.
+///
+/// A `Painter` is handed to the `WidgetHooks` drawing hooks and
+/// already accounts for the clipping region the widget is drawn in.
+/// All coordinates are absolute screen coordinates.
+pub trait Painter {
+    /// Fills a rect region with a color blended over what is beneath
+    fn fill_rect(&mut self, rect: Layout, color: Color);
+    /// Strokes an anti aliased line of `width` with round caps
+    fn stroke_line(&mut self, from: Point, to: Point, width: f64, color: Color);
+    /// Fills an anti aliased `path` with `fill` and strokes its
+    /// edges by `outline` centered on them
+    fn draw_path(&mut self, path: &Path, fill: Option<Color>, outline: Option<Outline>);
+    /// Draws a widget background and text without its children
+    /// faded by its opacity
+    fn draw_widget(&mut self, widget: &dyn Widget);
 }
 
 /// Follows the row major formula
@@ -1,283 +1,486 @@
-use std::rc::Rc;
+use std::{borrow::Cow, rc::Rc};
 
-use ab_glyph::{point, Font as _, FontRef, Glyph, PxScale, ScaleFont as _};
+use ab_glyph::{Font as _, PxScale, ScaleFont as _};
 use pixels::Pixels;
-use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, Rect, Transform};
+use tiny_skia::{
+    BlendMode, FillRule, GradientStop, LineCap, LinearGradient, Paint, Path, PathBuilder, Pixmap,
+    RadialGradient, Rect, SpreadMode, Stroke, Transform,
+};
 
 use crate::{
+    error::Error,
     render::Renderer,
+    trace::widget_span,
     ui::{
-        color::{Color, BLACK, TRANSPARENT, WHITE},
-        layout::{Layout, Point},
-        text::DEFAULT_FONT,
-        widget::{canvas::Canvas, container::Container, Widget, WidgetI},
+        color::{Color, Fill, TRANSPARENT},
+        layout::{Insets, Layout, Point},
+        style::{Border, CornerShape, Outline, Radius, DISABLED_OVERLAY},
+        text::{default_font, FontWeight, LineMetrics, Text, TextOverflow},
+        widget::{z_ordered, Widget, WidgetI},
     },
 };
 
-use super::row_major;
+use super::{
+    clip::Clip,
+    glyph::GlyphCache,
+    raster::{Body, RasterCache},
+    row_major,
+    shadow::ShadowCache,
+    Painter,
+};
 
 type NoCustom = Option<fn(&mut PixelsRenderer)>;
 const NO_CUSTOM: NoCustom = None;
 
+/// The `Surface` trait describes the RGBA frame buffer
+/// widgets are drawn into
+pub(crate) trait Surface {
+    /// Returns the width and height of the frame in pixels
+    fn size(&self) -> (u32, u32);
+    fn frame(&self) -> &[u8];
+    fn frame_mut(&mut self) -> &mut [u8];
+    /// Shows the frame
+    fn present(&mut self) -> Result<(), Error>;
+    /// Match the frame to the new window size
+    fn resize(&mut self, width: u32, height: u32) -> Result<(), Error>;
+}
+impl Surface for Pixels {
+    fn size(&self) -> (u32, u32) {
+        (self.texture().width(), self.texture().height())
+    }
+    fn frame(&self) -> &[u8] {
+        Pixels::frame(self)
+    }
+    fn frame_mut(&mut self) -> &mut [u8] {
+        Pixels::frame_mut(self)
+    }
+    fn present(&mut self) -> Result<(), Error> {
+        Ok(self.render()?)
+    }
+    fn resize(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        self.resize_surface(width, height)?;
+        Ok(self.resize_buffer(width, height)?)
+    }
+}
+
 pub(crate) struct PixelsRenderer {
-    pixels: Pixels,
+    surface: Box<dyn Surface>,
+    glyphs: GlyphCache,
+    shadows: ShadowCache,
+    rasters: RasterCache,
+    /// The first error painters ran into while drawing
+    /// the widget at hand
+    painter_error: Option<Error>,
+    /// Whether rects and lines are drawn where they sit
+    /// within a pixel
+    subpixel: bool,
 }
 impl PixelsRenderer {
     pub(crate) fn new(pixels: Pixels) -> Self {
-        Self { pixels }
+        PixelsRenderer::with_surface(pixels)
     }
-    /// Returns either black or white based on the perceived brightness of a background color.
-    ///
-    /// This function calculates the luminance of the given `bg` color using the
-    /// standard formula for relative luminance:
-    /// `luminance = 0.299 * R + 0.587 * G + 0.114 * B`
+    /// Creates a renderer drawing into `surface` in
+    /// place of a window
+    pub(crate) fn with_surface<S: Surface + 'static>(surface: S) -> Self {
+        Self {
+            surface: Box::new(surface),
+            glyphs: GlyphCache::new(default_font().clone()),
+            shadows: ShadowCache::new(),
+            rasters: RasterCache::new(),
+            painter_error: None,
+            subpixel: false,
+        }
+    }
+    /// Splits `at` into the pixel drawing starts from and how
+    /// far into that pixel the drawing sits
     ///
-    fn get_contrast_color(bg: Color) -> Color {
-        let (r, g, b) = bg.into();
-        // Detect luminance
-        if 0.299 * (r as f32 / 255.0) + 0.587 * (g as f32 / 255.0) + 0.114 * (b as f32 / 255.0)
-            > 0.5
-        {
-            BLACK
-        } else {
-            WHITE
+    /// Without subpixel rendering drawing starts from the
+    /// nearest pixel
+    fn snap(&self, at: f64) -> (i32, f32) {
+        match self.subpixel {
+            true => (at.floor() as i32, (at - at.floor()) as f32),
+            false => (at.round() as i32, 0.0),
         }
     }
+    /// Returns the width and height of the frame in pixels
+    pub(crate) fn size(&self) -> (u32, u32) {
+        self.surface.size()
+    }
+    /// Returns the frame drawn so far
+    pub(crate) fn frame(&self) -> &[u8] {
+        self.surface.frame()
+    }
     /// Copies the pixel data from the given `Pixmap` onto the current frame buffer.
     ///
     /// This method performs a direct memory copy (blit) from the source `Pixmap`
     /// to the destination frame managed by the `pixels` instance. It assumes both
-    /// the source and destination have the same pixel format (e.g., RGBA, 4 bytes per pixel)
-    /// and that the destination frame is large enough to accommodate the pixmap.
-    fn blit_on(
-        &mut self,
-        offset_x: i32,
-        offset_y: i32,
-        map: &Pixmap,
-        clipping_region: Option<Layout>,
-    ) {
-        let frame_width = self.pixels.texture().width();
-        let frame = self.pixels.frame_mut();
-        let map_buffer = map.data();
+    /// the source and destination have the same pixel format (e.g., RGBA, 4 bytes per pixel).
+    /// Pixels falling off the frame or outside of the clip are left out.
+    fn blit_on(&mut self, offset_x: i32, offset_y: i32, map: &Pixmap, clip: &Clip) {
+        let (frame_width, frame_height) = self.surface.size();
+        let frame = self.surface.frame_mut();
 
-        for y in 0..map.height() {
-            for x in 0..map.width() {
-                // Ignore drawing pixels off screen
-                let x_normalized = x as i32 + offset_x;
-                let y_normalized = y as i32 + offset_y;
-                if x_normalized < 0 || y_normalized < 0 {
-                    continue;
-                }
+        // Ignore drawing rows off screen
+        let columns = offset_x.max(0)..(offset_x + map.width() as i32).min(frame_width as i32);
+        for y in offset_y.max(0)..(offset_y + map.height() as i32).min(frame_height as i32) {
+            // Ignore drawing pixels that fall outside Container range
+            let Some((columns, coverage)) = clip.row(y, columns.clone()) else {
+                continue;
+            };
 
-                // Ignore drawing pixels that fall outside Container range
-                if let Some(clipping) = clipping_region {
-                    if (x_normalized > clipping.w as i32 || x_normalized < clipping.x as i32)
-                        || y_normalized > clipping.h as i32
-                        || y_normalized < clipping.y as i32
-                    {
-                        continue;
-                    }
-                }
+            let frame_row = &mut frame[row_major(columns.start as u32, y as u32, frame_width)
+                ..row_major(columns.end as u32, y as u32, frame_width)];
+            let map_idx = row_major(
+                (columns.start - offset_x) as u32,
+                (y - offset_y) as u32,
+                map.width(),
+            );
+            blend_row(
+                frame_row,
+                &map.data()[map_idx..map_idx + frame_row.len()],
+                coverage,
+            );
+        }
+    }
+    /// Rasterizes a `w` x `h` rect rounded by `radius` in `shape`
+    /// with anti aliased corners
+    ///
+    /// The rect is drawn `x`, `y` into the first pixel of a pixmap
+    /// just large enough to hold it which is blit where the widget sits
+    fn draw_rounded_rect(
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        corners: (Radius, CornerShape),
+        color: &Color,
+        border: Option<&Border>,
+    ) -> Result<Pixmap, Error> {
+        let (radius, shape) = corners;
+        let mut pixmap = pixmap((x + w).ceil() as u32, (y + h).ceil() as u32)?;
+
+        // Map to blit to main buffer
+        let mut paint = Paint::default();
+        paint.set_color((*color).into());
+
+        match border {
+            Some(border) => {
+                // The border fills the outer shape and the widget
+                // fill replaces whatever is left inside of it
+                let Insets {
+                    top,
+                    right,
+                    bottom,
+                    left,
+                } = border.width;
+                let mut border_paint = Paint::default();
+                border_paint.set_color(border.color.into());
+                PixelsRenderer::fill_rounded_rect(&mut pixmap, x, y, w, h, corners, &border_paint);
 
-                let frame_idx = row_major(x_normalized as u32, y_normalized as u32, frame_width);
-                let map_idx = row_major(x, y, map.width());
-                if frame_idx + 3 < frame.len() {
-                    let out = &Color::src_over_blend(
-                        &map_buffer[map_idx..map_idx + 4],
-                        &frame[frame_idx..frame_idx + 4],
+                let inner_w = w - (left + right) as f32;
+                let inner_h = h - (top + bottom) as f32;
+                if inner_w > 0.0 && inner_h > 0.0 {
+                    paint.blend_mode = BlendMode::Source;
+                    PixelsRenderer::fill_rounded_rect(
+                        &mut pixmap,
+                        x + left as f32,
+                        y + top as f32,
+                        inner_w,
+                        inner_h,
+                        (radius.inset(&border.width), shape),
+                        &paint,
                     );
-                    frame[frame_idx..frame_idx + 4].copy_from_slice(out);
                 }
             }
+            None => PixelsRenderer::fill_rounded_rect(&mut pixmap, x, y, w, h, corners, &paint),
         }
+
+        Ok(pixmap)
     }
-    fn draw_rounded_rect(x: f32, y: f32, w: f32, h: f32, r: f32, color: &Color) -> Pixmap {
-        // Since the radius is created using contour we need to buffer some space for the map to
-        // be correctly blit later and account for rgba with 4bytes of room
-        let mut pixmap = Pixmap::new((w + (r * 4.0)) as u32, (h + (r * 4.0)) as u32).unwrap();
+    /// Shades a `w` x `h` rect rounded by `radius` in `shape`
+    /// with the gradient of `fill` drawn `x`, `y` into the
+    /// first pixel
+    ///
+    /// Returns `None` when there is nothing to shade
+    fn draw_fill(
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        (radius, shape): (Radius, CornerShape),
+        fill: &Fill,
+        border: Option<&Border>,
+    ) -> Option<Pixmap> {
+        let mut pixmap = Pixmap::new((x + w).ceil() as u32, (y + h).ceil() as u32)?;
+        let stops = fill
+            .stops()
+            .iter()
+            .map(|(offset, color)| GradientStop::new(*offset, (*color).into()))
+            .collect();
+        let center = tiny_skia::Point::from_xy(x + w / 2.0, y + h / 2.0);
+        let shader = match fill {
+            Fill::LinearGradient { angle, .. } => {
+                // The gradient line is long enough for the
+                // corners to reach the first and last stop
+                let (sin, cos) = angle.to_radians().sin_cos();
+                let half = (w * sin.abs() + h * cos.abs()) / 2.0;
+                LinearGradient::new(
+                    tiny_skia::Point::from_xy(center.x - sin * half, center.y + cos * half),
+                    tiny_skia::Point::from_xy(center.x + sin * half, center.y - cos * half),
+                    stops,
+                    SpreadMode::Pad,
+                    Transform::identity(),
+                )
+            }
+            Fill::RadialGradient { .. } => RadialGradient::new(
+                center,
+                center,
+                (w * w + h * h).sqrt() / 2.0,
+                stops,
+                SpreadMode::Pad,
+                Transform::identity(),
+            ),
+        }?;
+        let mut paint = Paint {
+            shader,
+            ..Default::default()
+        };
+
+        // The border fills the outer shape and the
+        // gradient whatever is left inside of it
+        let (mut x, mut y, mut w, mut h, mut radius) = (x, y, w, h, radius);
+        if let Some(border) = border {
+            let mut border_paint = Paint::default();
+            border_paint.set_color(border.color.into());
+            PixelsRenderer::fill_rounded_rect(
+                &mut pixmap,
+                x,
+                y,
+                w,
+                h,
+                (radius, shape),
+                &border_paint,
+            );
+
+            let Insets {
+                top,
+                right,
+                bottom,
+                left,
+            } = border.width;
+            x += left as f32;
+            y += top as f32;
+            w -= (left + right) as f32;
+            h -= (top + bottom) as f32;
+            radius = radius.inset(&border.width);
+            paint.blend_mode = BlendMode::Source;
+        }
+        if w > 0.0 && h > 0.0 {
+            PixelsRenderer::fill_rounded_rect(&mut pixmap, x, y, w, h, (radius, shape), &paint);
+        }
 
-        // Anti aliased a rounded rect
+        Some(pixmap)
+    }
+    /// Fills a `w` x `h` rect rounded by `radius` in `shape`
+    /// at `x`, `y` of `pixmap` with `paint`
+    fn fill_rounded_rect(
+        pixmap: &mut Pixmap,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        (radius, shape): (Radius, CornerShape),
+        paint: &Paint,
+    ) {
+        if let Some(path) = PixelsRenderer::rounded_rect_path(x, y, w, h, radius, shape) {
+            pixmap.fill_path(&path, paint, FillRule::Winding, Transform::identity(), None);
+        }
+    }
+    /// Anti aliased a rounded rect
+    ///
+    /// Corners are scaled down until they fit along their sides
+    /// so the path stays within the rect
+    ///
+    /// Returns `None` for rects covering no area
+    pub(crate) fn rounded_rect_path(
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        radius: Radius,
+        shape: CornerShape,
+    ) -> Option<Path> {
+        if !(w > 0.0 && h > 0.0) {
+            return None;
+        }
+        let [tl, tr, br, bl] = radius.fit(f64::from(w), f64::from(h)).map(|r| r as f32);
         let mut pb = PathBuilder::new();
+        // Every corner runs from the end of one side to the start
+        // of the next around the tip of the corner
+        let corner = |pb: &mut PathBuilder, tip: (f32, f32), to: (f32, f32)| match shape {
+            CornerShape::Round => pb.quad_to(tip.0, tip.1, to.0, to.1),
+            CornerShape::Squircle => squircle_to(pb, tip, to),
+        };
         // Start at top-left corner, move to start of top edge
-        pb.move_to(x + r, y);
+        pb.move_to(x + tl, y);
         // Top edge
-        pb.line_to(x + w - r, y);
+        pb.line_to(x + w - tr, y);
         // Top-right corner
-        pb.quad_to(x + w, y, x + w, y + r);
+        corner(&mut pb, (x + w, y), (x + w, y + tr));
         // Right edge
-        pb.line_to(x + w, y + h - r);
+        pb.line_to(x + w, y + h - br);
         // Bottom-right corner
-        pb.quad_to(x + w, y + h, x + w - r, y + h);
+        corner(&mut pb, (x + w, y + h), (x + w - br, y + h));
         // Bottom edge
-        pb.line_to(x + r, y + h);
+        pb.line_to(x + bl, y + h);
         // Bottom-left corner
-        pb.quad_to(x, y + h, x, y + h - r);
+        corner(&mut pb, (x, y + h), (x, y + h - bl));
         // Left edge
-        pb.line_to(x, y + r);
+        pb.line_to(x, y + tl);
         // Top-left corner
-        pb.quad_to(x, y, x + r, y);
+        corner(&mut pb, (x, y), (x + tl, y));
         pb.close();
-        let path = pb.finish().unwrap();
-
-        // Map to blit to main buffer
-        let mut paint = Paint::default();
-        paint.set_color((*color).into());
-        pixmap.fill_path(
-            &path,
-            &paint,
-            FillRule::Winding,
-            Transform::identity(),
-            None,
-        );
-
-        pixmap
+        pb.finish()
     }
-    /// # Note
-    ///
-    /// Round all floats to nearest
-    fn draw_line(w: f64, h: f64, color: &Color) -> Pixmap {
+    /// Rasterizes a `w` x `h` rect drawn `x`, `y` into the
+    /// first pixel with anti aliased edges
+    fn draw_line(x: f32, y: f32, w: f64, h: f64, color: &Color) -> Result<Pixmap, Error> {
         // We can not render anything lower than zero
         // since nothing will show...duhhh so we limit it to 1 minimal
-        let map_width = (w.round() as u32).max(1);
-        let map_height = (h.round() as u32).max(1);
-        let mut pixmap = Pixmap::new(map_width, map_height).unwrap();
+        let map_width = ((f64::from(x) + w).ceil() as u32).max(1);
+        let map_height = ((f64::from(y) + h).ceil() as u32).max(1);
+        let mut pixmap = pixmap(map_width, map_height)?;
         let mut paint = Paint::default();
         paint.set_color((*color).into());
-        pixmap.fill_rect(
-            Rect::from_xywh(0.0, 0.0, w as f32, h as f32).unwrap(),
-            &paint,
-            tiny_skia::Transform::identity(),
-            None,
-        );
-
-        pixmap
-    }
-    /// # Note
-    ///
-    /// Round all floats to nearest
-    fn draw_gridlines(
-        &mut self,
-        pos: (f64, f64),
-        width: f64,
-        height: f64,
-        spacing: Point,
-        color: Color,
-        thickness: f64,
-    ) {
-        let (x, y) = pos;
-
-        let h_lines_spacing = height / spacing.y;
-        let w_lines_spacing = width / spacing.x;
-        // Draw column gridlines
-        for col in 1..spacing.x as usize {
-            let spacing = w_lines_spacing * col as f64;
-            let line = PixelsRenderer::draw_line(
-                thickness,
-                height,
-                &PixelsRenderer::get_contrast_color(color),
-            );
-            self.blit_on((x + spacing).round() as i32, y.round() as i32, &line, None);
-        }
-        // Draw row gridlines
-        for row in 1..spacing.y as usize {
-            let spacing = h_lines_spacing * row as f64;
-            let line = PixelsRenderer::draw_line(
-                width,
-                thickness,
-                &PixelsRenderer::get_contrast_color(color),
-            );
-            self.blit_on(x.round() as i32, (y + spacing).round() as i32, &line, None);
+        if let Some(rect) = Rect::from_xywh(x, y, w as f32, h as f32) {
+            pixmap.fill_rect(rect, &paint, tiny_skia::Transform::identity(), None);
         }
-    }
-    fn draw_text(text: &str, font_size: f32, color: Color) -> Pixmap {
-        // Load font face with scale
-        let font = FontRef::try_from_slice(DEFAULT_FONT).unwrap();
-        let scale = PxScale::from(font_size);
-        let font_scaled = font.as_scaled(scale);
 
-        // We need the respective glyphs to know how to cutout our character
-        // styling (what it will look like)
-        let mut glyphs: Vec<Glyph> = Vec::new();
-        let mut caret = point(0.0, font_scaled.ascent());
-        for c in text.chars() {
-            let glyph = font_scaled
-                .glyph_id(c)
-                .with_scale_and_position(scale, caret);
-            let id = glyph.id;
+        Ok(pixmap)
+    }
+    fn draw_text(&mut self, text: &Text) -> Result<Pixmap, Error> {
+        let spans = text.visible_spans();
+        let font = self.glyphs.font().clone();
 
-            glyphs.push(glyph);
+        // Move over for every character coming
+        // as of now we support only horizontal text
+        let mut glyphs = Vec::new();
+        let mut caret = 0.0;
+        for span in &spans {
+            let font_size = span.font_size.unwrap_or(text.font_size);
+            // Text shrunk down to nothing is left out
+            if font_size <= 0.0 || font_size.is_nan() {
+                continue;
+            }
+            let font_scaled = font.as_scaled(PxScale::from(font_size));
 
-            // Move over for next character coming
-            // as of now we support only horizontal text
-            caret.x += font_scaled.h_advance(id);
+            for c in span.text.chars() {
+                let advance = font_scaled.h_advance(font_scaled.glyph_id(c));
+                glyphs.push((c, caret, advance, span));
+                caret += advance;
+            }
         }
 
         // We now have the expected total width and lenght to buffer these
         // pixels of each char in text
-        // Double height is needed for possible descent chars and
-        // could be done better but as of now this is fine
-        let text_height = (font_scaled.ascent() - font_scaled.descent()).ceil();
-        let mut pixmap = Pixmap::new(caret.x.ceil() as u32, text_height as u32).unwrap();
+        // The line reaches from the tallest ascent down to the deepest
+        // descent so every span shares one baseline
+        // Bold strokes may reach one pixel further
+        let line = LineMetrics::of(&spans);
+        let text_height = line.height().ceil().max(1.0);
+        let baseline = line.baseline().round() as i32;
+        let mut pixmap = pixmap(caret.ceil() as u32 + 1, text_height as u32)?;
         let pixmap_buffer_width = pixmap.width();
         let pixmap_buffer = pixmap.data_mut();
 
-        let color: [u8; 4] = color.into();
-        for glyph in glyphs {
-            // Get outline of text so we can draw within
-            // bounds since all glyphs can be classified as
-            // as bounding box thats cutout
-            if let Some(outline) = font.outline_glyph(glyph) {
-                let bounds = outline.px_bounds();
-
-                // Now we know the points to draw
-                outline.draw(|x, y, c| {
-                    let x = x as u32 + bounds.min.x as u32;
-                    let y = y as u32 + bounds.min.y as u32;
+        // Selected characters are highlighted in their own color
+        // before any glyph is drawn over the highlight
+        let selected = text.selected_range();
+        let is_selected = |i: usize| selected.is_some_and(|(start, end)| i >= start && i < end);
+        for (i, (_, caret, advance, span)) in glyphs.iter().enumerate() {
+            if !is_selected(i) {
+                continue;
+            }
+            let color: [u8; 4] = span.color.unwrap_or(text.color).into();
+            for y in 0..text_height as u32 {
+                for x in
+                    caret.floor() as u32..((caret + advance).ceil() as u32).min(pixmap_buffer_width)
+                {
+                    let idx = row_major(x, y, pixmap_buffer_width);
+                    pixmap_buffer[idx..idx + 3].copy_from_slice(&color[..3]);
+                    pixmap_buffer[idx + 3] = 255;
+                }
+            }
+        }
 
+        // Underlines run just below the shared baseline
+        for (_, caret, advance, span) in glyphs.iter().filter(|glyph| glyph.3.underline) {
+            let font_size = span.font_size.unwrap_or(text.font_size);
+            let color: [u8; 4] = span.color.unwrap_or(text.color).into();
+            let top = baseline as u32 + 1;
+            let thickness = (font_size / 14.0).round().max(1.0) as u32;
+            for y in top..(top + thickness).min(text_height as u32) {
+                for x in
+                    caret.floor() as u32..((caret + advance).ceil() as u32).min(pixmap_buffer_width)
+                {
                     let idx = row_major(x, y, pixmap_buffer_width);
-                    if idx + 3 < pixmap_buffer.len() {
-                        pixmap_buffer[idx] = (color[0] as f32) as u8;
-                        pixmap_buffer[idx + 1] = (color[1] as f32) as u8;
-                        pixmap_buffer[idx + 2] = (color[2] as f32) as u8;
-                        // The c value is coverage multiplier to smooth out
-                        // drawing
-                        pixmap_buffer[idx + 3] =
-                            (color[3] as f32 * c).round().clamp(0.0, 255.0) as u8;
-                    }
-                });
+                    pixmap_buffer[idx..idx + 4].copy_from_slice(&color);
+                }
             }
         }
-        pixmap
-    }
-    fn draw_canvas(&mut self, widget: &Canvas, clipping_region: Option<Layout>) {
-        if let Some(grid) = &mut *widget.grid.borrow_mut() {
-            self.draw_widget(
-                widget,
-                Some(|renderer: &mut PixelsRenderer| {
-                    let widget = widget.base();
-
-                    // Draw gridlines
-                    renderer.draw_gridlines(
-                        (
-                            widget.offset.x + widget.layout.x,
-                            widget.offset.y + widget.layout.y,
-                        ),
-                        widget.layout.w,
-                        widget.layout.h,
-                        grid.size,
-                        widget.style.color.into(),
-                        grid.thickness,
-                    );
 
-                    grid.on_cell(|_, c| {
-                        renderer.draw_widget(c.as_ref(), NO_CUSTOM, clipping_region);
-                    });
-                }),
-                clipping_region,
-            );
-        } else {
-            self.draw_widget(widget, NO_CUSTOM, clipping_region);
+        for (i, (c, caret, _, span)) in glyphs.into_iter().enumerate() {
+            let font_size = span.font_size.unwrap_or(text.font_size);
+            let color: [u8; 4] = span.color.unwrap_or(text.color).into();
+            let selected = is_selected(i);
+            let inverted = span.color.unwrap_or(text.color).contrast();
+            // Bold is faked by drawing the glyph twice a pixel apart
+            let strokes = match span.weight {
+                FontWeight::Regular => 0..1,
+                FontWeight::Bold => 0..2,
+            };
+
+            // Glyphs are only outlined the first time they are
+            // drawn at a size and reused from then on
+            let (pixel, mask) = self.glyphs.rasterize(c, font_size, caret);
+            for stroke in strokes {
+                for y in 0..mask.height {
+                    for x in 0..mask.width {
+                        let coverage = mask.coverage[(y * mask.width + x) as usize];
+                        let x = pixel + mask.left + x as i32 + stroke;
+                        let y = baseline + mask.top + y as i32;
+                        if coverage == 0.0 || x < 0 || y < 0 || x as u32 >= pixmap_buffer_width {
+                            continue;
+                        }
+
+                        let idx = row_major(x as u32, y as u32, pixmap_buffer_width);
+                        if selected && idx + 3 < pixmap_buffer.len() {
+                            // Selected glyphs fade from the highlight
+                            // into the inverted color
+                            let highlight = Color::RGBA(
+                                pixmap_buffer[idx],
+                                pixmap_buffer[idx + 1],
+                                pixmap_buffer[idx + 2],
+                                255,
+                            );
+                            let color: [u8; 4] =
+                                highlight.lerp(inverted, f64::from(coverage)).into();
+                            pixmap_buffer[idx..idx + 4].copy_from_slice(&color);
+                        } else if idx + 3 < pixmap_buffer.len() {
+                            pixmap_buffer[idx] = color[0];
+                            pixmap_buffer[idx + 1] = color[1];
+                            pixmap_buffer[idx + 2] = color[2];
+                            // The coverage is a multiplier to smooth out
+                            // drawing
+                            let alpha =
+                                (color[3] as f32 * coverage).round().clamp(0.0, 255.0) as u8;
+                            pixmap_buffer[idx + 3] = pixmap_buffer[idx + 3].max(alpha);
+                        }
+                    }
+                }
+            }
         }
+        Ok(pixmap)
     }
     /// # Note
     ///
@@ -286,66 +489,178 @@ impl PixelsRenderer {
         &mut self,
         widget: &dyn Widget,
         custom_render: Option<F>,
-        clipping_region: Option<Layout>,
-    ) {
+        clip: &Clip,
+    ) -> Result<(), Error> {
         let widget_base = widget.base();
+        // Widgets sized down to nothing have nothing to draw
+        if widget_base.layout.is_empty() {
+            return Ok(());
+        }
 
-        let color = widget_base.style.color.into();
-
-        // Draw widget base with constraints
-        if widget_base.style.radius > 0 {
-            // Offshoot to skia for smooth draws (if needed)
-            let rounded_rect = PixelsRenderer::draw_rounded_rect(
-                (widget_base.offset.x + widget_base.layout.x) as f32,
-                (widget_base.offset.y + widget_base.layout.y) as f32,
-                widget_base.layout.w as f32,
-                widget_base.layout.h as f32,
-                widget_base.style.radius as f32,
-                &color,
-            );
+        // Disabled widgets are drawn dimmed
+        let dim = |color: Color| match widget_base.state.disabled {
+            true if color != TRANSPARENT => Color::blend(
+                color,
+                widget_base
+                    .style
+                    .disabled_overlay
+                    .unwrap_or(DISABLED_OVERLAY),
+            ),
+            _ => color,
+        };
+        let color = dim(widget_base.style.color.into());
 
-            self.blit_on(
-                (widget_base.offset.x + widget_base.layout.x).round() as i32,
-                (widget_base.offset.y + widget_base.layout.y).round() as i32,
-                &rounded_rect,
-                clipping_region,
-            );
+        // Shadows are cast beneath the widget body
+        if let Some(shadow) = widget_base.style.shadow {
+            // The cache is set aside while its shadow is blit
+            let mut shadows = std::mem::take(&mut self.shadows);
+            if let Some(pixmap) = shadows.rasterize(
+                widget_base.layout.w,
+                widget_base.layout.h,
+                (widget_base.style.radius, widget_base.style.corner_shape),
+                &shadow,
+            ) {
+                let spread = shadow.spread();
+                self.blit_on(
+                    (widget_base.offset.x + widget_base.layout.x + shadow.offset.x - spread).round()
+                        as i32,
+                    (widget_base.offset.y + widget_base.layout.y + shadow.offset.y - spread).round()
+                        as i32,
+                    pixmap,
+                    clip,
+                );
+            }
+            self.shadows = shadows;
+        }
+
+        // Widgets keep what was last rasterized for them
+        // until they look different
+        let id = widget as *const dyn Widget as *const () as usize;
+
+        // Gradients and rounded corners are shaded by skia
+        // along with rects sitting between pixels
+        let (left, x) = self.snap(widget_base.offset.x + widget_base.layout.x);
+        let (top, y) = self.snap(widget_base.offset.y + widget_base.layout.y);
+        let between_pixels = self.subpixel
+            && (x != 0.0
+                || y != 0.0
+                || widget_base.layout.w.fract() != 0.0
+                || widget_base.layout.h.fract() != 0.0);
+        let shaded = widget_base.style.fill.is_some()
+            || !widget_base.style.radius.is_zero()
+            || between_pixels;
+        if shaded {
+            let body = Body {
+                x,
+                y,
+                w: widget_base.layout.w,
+                h: widget_base.layout.h,
+                radius: widget_base.style.radius,
+                corner_shape: widget_base.style.corner_shape,
+                color,
+                fill: widget_base
+                    .style
+                    .fill
+                    .as_ref()
+                    .map(|fill| fill.map_colors(dim)),
+                border: widget_base.style.border,
+            };
+            let mut rasters = std::mem::take(&mut self.rasters);
+            let drawn = rasters.body(id, body, |body| match &body.fill {
+                // Gradients are shaded in place of the flat color
+                Some(fill) => Ok(PixelsRenderer::draw_fill(
+                    body.x,
+                    body.y,
+                    body.w as f32,
+                    body.h as f32,
+                    (body.radius, body.corner_shape),
+                    fill,
+                    body.border.as_ref(),
+                )),
+                None => PixelsRenderer::draw_rounded_rect(
+                    body.x,
+                    body.y,
+                    body.w as f32,
+                    body.h as f32,
+                    (body.radius, body.corner_shape),
+                    &body.color,
+                    body.border.as_ref(),
+                )
+                .map(Some),
+            });
+            if let Ok(Some(pixmap)) = &drawn {
+                self.blit_on(left, top, pixmap, clip);
+            }
+            let drawn = drawn.map(|_| ());
+            self.rasters = rasters;
+            drawn?;
         }
 
-        let frame_width = self.pixels.texture().width();
-        let frame = self.pixels.frame_mut();
+        let (frame_width, frame_height) = self.surface.size();
+        let frame = self.surface.frame_mut();
 
         // Draw normal widget base
-        if widget_base.style.radius == 0 {
+        if !shaded {
             let color: [u8; 4] = color.into();
-            for y in (widget_base.offset.y + widget_base.layout.y) as i32
-                ..(widget_base.offset.y + widget_base.layout.y + widget_base.layout.h).round()
-                    as i32
-            {
-                for x in (widget_base.offset.x + widget_base.layout.x) as i32
-                    ..(widget_base.offset.x + widget_base.layout.x + widget_base.layout.w).round()
-                        as i32
-                {
-                    // Ignore drawing pixels off screen
-                    if x < 0 || y < 0 {
-                        continue;
-                    }
+            let left = (widget_base.offset.x + widget_base.layout.x) as i32;
+            let top = (widget_base.offset.y + widget_base.layout.y) as i32;
+            let right =
+                (widget_base.offset.x + widget_base.layout.x + widget_base.layout.w).round() as i32;
+            let bottom =
+                (widget_base.offset.y + widget_base.layout.y + widget_base.layout.h).round() as i32;
+            // The edges within the border width of each side
+            // take the border color
+            let border = widget_base.style.border.map(|border| {
+                (
+                    <[u8; 4]>::from(border.color),
+                    left + border.width.left.round() as i32,
+                    top + border.width.top.round() as i32,
+                    right - border.width.right.round() as i32,
+                    bottom - border.width.bottom.round() as i32,
+                )
+            });
+            // Ignore drawing pixels off screen
+            let columns = left.max(0)..right.min(frame_width as i32);
+            for y in top.max(0)..bottom.min(frame_height as i32) {
+                // Ignore drawing pixels that fall outside Container range
+                let Some((columns, coverage)) = clip.row(y, columns.clone()) else {
+                    continue;
+                };
 
-                    // Ignore drawing pixels that fall outside Container range
-                    if let Some(clipping) = clipping_region {
-                        if (x > clipping.w as i32 || x < clipping.x as i32)
-                            || y > clipping.h as i32
-                            || y < clipping.y as i32
-                        {
-                            continue;
-                        }
+                // Rows are split into the sides taking the border
+                // color and whatever is left between them
+                let segments = match border {
+                    Some((border_color, inner_left, inner_top, inner_right, inner_bottom))
+                        if y >= inner_top && y < inner_bottom =>
+                    {
+                        [
+                            (columns.start..inner_left, border_color),
+                            (inner_left..inner_right, color),
+                            (inner_right..columns.end, border_color),
+                        ]
                     }
-
-                    // Row major layout follows this formula
-                    let idx = row_major(x as u32, y as u32, frame_width);
-                    if idx + 3 < frame.len() {
-                        frame[idx..idx + 4].copy_from_slice(&color);
+                    Some((border_color, ..)) => [
+                        (columns.clone(), border_color),
+                        (0..0, color),
+                        (0..0, color),
+                    ],
+                    None => [(columns.clone(), color), (0..0, color), (0..0, color)],
+                };
+                for (segment, color) in segments {
+                    let segment = segment.start.max(columns.start)..segment.end.min(columns.end);
+                    if segment.is_empty() {
+                        continue;
                     }
+                    let coverage = coverage.map(|coverage| {
+                        &coverage[(segment.start - columns.start) as usize
+                            ..(segment.end - columns.start) as usize]
+                    });
+                    fill_row(
+                        &mut frame[row_major(segment.start as u32, y as u32, frame_width)
+                            ..row_major(segment.end as u32, y as u32, frame_width)],
+                        color,
+                        coverage,
+                    );
                 }
             }
         }
@@ -356,76 +671,238 @@ impl PixelsRenderer {
 
         // Draw text
         if !widget_base.text.label.is_empty() {
-            let text = PixelsRenderer::draw_text(
-                &widget_base.text.label,
-                widget_base.text.font_size as f32,
-                BLACK,
-            );
-            self.blit_on(
-                (widget_base.offset.x + widget_base.layout.x + widget_base.text.pos.x).round()
-                    as i32,
-                (widget_base.offset.y + widget_base.layout.y + widget_base.text.pos.y).round()
-                    as i32,
-                &text,
-                clipping_region,
-            );
-        }
-    }
-    fn draw(&mut self, widget: &Rc<dyn WidgetI>, clipping_region: Option<Layout>) {
-        if let Some(widget) = widget.as_any().downcast_ref::<Container>() {
-            self.draw_widget(widget, NO_CUSTOM, clipping_region);
-
-            // Set clipping region for scrollbars (if any)
-            let clipping_region = if let Some(scroll) = widget.scrollbar.as_ref() {
-                let (x, y) = scroll;
-                let widget_base = widget.base();
-
-                // When scrollbars are placed they take up space
-                // and we want to leave room for them
-                let x_buffer = if x.base().layout.w > 0.0 {
-                    x.base().layout.h
-                } else {
-                    0.0
-                } + x.buffer;
-                let buffered_h = ((widget_base.layout.y + widget_base.layout.h) - x_buffer).abs();
-                let y_buffer = if y.base().layout.h > 0.0 {
-                    y.base().layout.w
-                } else {
-                    0.0
-                } + y.buffer;
-                let buffered_w = ((widget_base.layout.x + widget_base.layout.w) - y_buffer).abs();
-
-                Some(Layout {
-                    x: widget_base.layout.x,
-                    y: widget_base.layout.y,
-                    w: buffered_w,
-                    h: buffered_h,
-                })
+            let text = if widget_base.state.disabled {
+                let mut text = widget_base.text.clone();
+                text.color = dim(text.color);
+                for span in &mut text.spans {
+                    span.color = span.color.map(dim);
+                }
+                Cow::Owned(text)
             } else {
-                None
+                Cow::Borrowed(&widget_base.text)
             };
+            let x = widget_base.offset.x + widget_base.layout.x;
+            let scroll = match widget_base.text.overflow {
+                TextOverflow::Scroll => widget_base.text.scroll,
+                _ => 0.0,
+            };
+            // Text is cut off at the sides of the widget but may
+            // reach below it for descending characters
+            let text_clip = Layout {
+                x,
+                y: -f64::MAX / 2.0,
+                w: widget_base.layout.w,
+                h: f64::MAX,
+            };
+            let mut rasters = std::mem::take(&mut self.rasters);
+            let drawn = rasters.text(id, &text, |text| self.draw_text(text));
+            if let Ok(pixmap) = &drawn {
+                self.blit_on(
+                    (x + widget_base.text.pos.x - scroll).round() as i32,
+                    (widget_base.offset.y + widget_base.layout.y + widget_base.text.pos.y).round()
+                        as i32,
+                    pixmap,
+                    &clip.intersect(Some(text_clip)),
+                );
+            }
+            let drawn = drawn.map(|_| ());
+            self.rasters = rasters;
+            drawn?;
+        }
+        Ok(())
+    }
+    /// Draws `widget` and its children within `clip`
+    ///
+    /// Returns the first error drawing any of them ran into
+    fn draw(&mut self, widget: &Rc<dyn WidgetI>, clip: &Clip) -> Result<(), Error> {
+        let opacity = widget.base().style.opacity;
+        if opacity <= 0.0 {
+            return Ok(());
+        }
+        widget_span!("draw", widget);
+        // Translucent widgets are drawn solid first and then faded
+        // into what was underneath as a whole so their children do
+        // not show through each other
+        let backdrop = (opacity < 1.0).then(|| self.surface.frame().to_vec());
+
+        // Widgets outside the clipping region have nothing to
+        // draw but their children may still reach into it
+        let visible = is_visible(widget.as_ref(), clip.rect);
+
+        let mut drawn = Ok(());
+        if visible {
+            drawn = self.draw_widget(
+                widget.as_ref(),
+                Some(|renderer: &mut PixelsRenderer| {
+                    widget.custom_draw(&mut PixelsPainter {
+                        renderer,
+                        clip: clip.clone(),
+                    });
+                }),
+                clip,
+            );
+        }
 
-            // Children must always sit atop their parents
-            for child in &widget.children {
-                self.draw(child, clipping_region);
+        // Children must always sit atop their parents and are cut
+        // off along the rounded corners of containers clipping them
+        let children_clip = widget.children_clip();
+        let children_clip = match children_clip {
+            Some(_) => {
+                let base = widget.base();
+                let bounds = Layout {
+                    x: base.offset.x + base.layout.x,
+                    y: base.offset.y + base.layout.y,
+                    ..base.layout
+                };
+                clip.round(
+                    children_clip,
+                    bounds,
+                    base.style.radius,
+                    base.style.corner_shape,
+                )
             }
+            None => clip.clone(),
+        };
+        for child in z_ordered(&widget.children()) {
+            drawn = drawn.and(self.draw(&child, &children_clip));
+        }
+
+        if visible {
+            widget.custom_draw_over(&mut PixelsPainter {
+                renderer: self,
+                clip: clip.clone(),
+            });
+        }
 
-            // Scrollbar must sit atop everything
-            if let Some(scrollbar) = &widget.scrollbar {
-                self.draw_widget(&scrollbar.0, NO_CUSTOM, None);
-                self.draw_widget(&scrollbar.1, NO_CUSTOM, None);
+        if let Some(backdrop) = backdrop {
+            self.fade(&backdrop, opacity);
+        }
+        drawn
+    }
+    /// Returns the error a painter ran into while drawing
+    /// or else `drawn`
+    fn painted(&mut self, drawn: Result<(), Error>) -> Result<(), Error> {
+        match self.painter_error.take() {
+            Some(err) => Err(err),
+            None => drawn,
+        }
+    }
+    /// Blends everything drawn since the frame looked like
+    /// `backdrop` into it by `opacity`
+    fn fade(&mut self, backdrop: &[u8], opacity: f32) {
+        let alpha = (opacity * 255.0).round().clamp(0.0, 255.0) as u8;
+        let frame = self.surface.frame_mut();
+        for (pixel, below) in frame.chunks_exact_mut(4).zip(backdrop.chunks_exact(4)) {
+            if pixel != below {
+                let drawn = [pixel[0], pixel[1], pixel[2], alpha];
+                pixel.copy_from_slice(&Color::src_over_blend(&drawn, below));
             }
-        } else if let Some(widget) = widget.as_any().downcast_ref::<Canvas>() {
-            self.draw_canvas(widget, clipping_region);
-        } else {
-            self.draw_widget(widget.as_ref(), NO_CUSTOM, clipping_region);
         }
     }
 }
+
+/// Draws the corner of a squircle from the last point of `pb`
+/// to `to` around `tip`
+///
+/// The superellipse is followed in short lines
+fn squircle_to(pb: &mut PathBuilder, tip: (f32, f32), to: (f32, f32)) {
+    const SEGMENTS: usize = 16;
+    let Some(from) = pb.last_point() else {
+        return;
+    };
+    let center = (from.x + to.0 - tip.0, from.y + to.1 - tip.1);
+    let power = 2.0 / CornerShape::SQUIRCLE_EXPONENT as f32;
+    for i in 1..=SEGMENTS {
+        let (sin, cos) = (i as f32 / SEGMENTS as f32 * std::f32::consts::FRAC_PI_2).sin_cos();
+        let (along, across) = (cos.max(0.0).powf(power), sin.max(0.0).powf(power));
+        pb.line_to(
+            center.0 + (from.x - center.0) * along + (to.0 - center.0) * across,
+            center.1 + (from.y - center.1) * along + (to.1 - center.1) * across,
+        );
+    }
+}
+
+/// Allocates a transparent `width` x `height` pixmap
+fn pixmap(width: u32, height: u32) -> Result<Pixmap, Error> {
+    Pixmap::new(width, height).ok_or(Error::Pixmap { width, height })
+}
+
+/// Blends a row of `source` pixels over the `frame` pixels
+/// beneath them where pixels along rounded corners are only
+/// drawn as much as they are covered
+fn blend_row(frame: &mut [u8], source: &[u8], coverage: Option<&[u8]>) {
+    let Some(coverage) = coverage else {
+        // Opaque rows simply replace what is beneath
+        if source.chunks_exact(4).all(|pixel| pixel[3] == u8::MAX) {
+            frame.copy_from_slice(source);
+            return;
+        }
+        for (pixel, source) in frame.chunks_exact_mut(4).zip(source.chunks_exact(4)) {
+            blend_pixel(pixel, source, source[3]);
+        }
+        return;
+    };
+    for ((pixel, source), coverage) in frame
+        .chunks_exact_mut(4)
+        .zip(source.chunks_exact(4))
+        .zip(coverage)
+    {
+        let alpha = (u16::from(source[3]) * u16::from(*coverage) / 255) as u8;
+        blend_pixel(pixel, source, alpha);
+    }
+}
+
+/// Blends the `source` pixel drawn with `alpha` over `pixel`
+fn blend_pixel(pixel: &mut [u8], source: &[u8], alpha: u8) {
+    match alpha {
+        0 => {}
+        u8::MAX => {
+            pixel[..3].copy_from_slice(&source[..3]);
+            pixel[3] = u8::MAX;
+        }
+        alpha => {
+            let blended = Color::src_over_blend(&[source[0], source[1], source[2], alpha], pixel);
+            pixel.copy_from_slice(&blended);
+        }
+    }
+}
+
+/// Fills a row of `frame` pixels with `color` where translucent
+/// colors and pixels along rounded corners are blended into what
+/// is beneath
+fn fill_row(frame: &mut [u8], color: [u8; 4], coverage: Option<&[u8]>) {
+    let Some(coverage) = coverage else {
+        for pixel in frame.chunks_exact_mut(4) {
+            blend_pixel(pixel, &color, color[3]);
+        }
+        return;
+    };
+    for (pixel, coverage) in frame.chunks_exact_mut(4).zip(coverage) {
+        let alpha = (u16::from(color[3]) * u16::from(*coverage) / 255) as u8;
+        blend_pixel(pixel, &color, alpha);
+    }
+}
+
+/// Determines if any part of the `widget` falls
+/// inside the clipping region
+fn is_visible(widget: &dyn Widget, clipping_region: Option<Layout>) -> bool {
+    let Some(clipping) = clipping_region else {
+        return true;
+    };
+    let base = widget.base();
+    let x = base.offset.x + base.layout.x;
+    let y = base.offset.y + base.layout.y;
+
+    x <= clipping.x + clipping.w
+        && x + base.layout.w >= clipping.x
+        && y <= clipping.y + clipping.h
+        && y + base.layout.h >= clipping.y
+}
+
 impl Renderer for PixelsRenderer {
     fn dirty_clear(&mut self, x: f64, y: f64, h: f64, w: f64) {
-        let frame_width = self.pixels.texture().width();
-        let frame = self.pixels.frame_mut();
+        let (frame_width, _) = self.surface.size();
+        let frame = self.surface.frame_mut();
 
         let color: [u8; 4] = TRANSPARENT.into();
         for y in y as i32..(y + h).round() as i32 {
@@ -445,15 +922,250 @@ impl Renderer for PixelsRenderer {
     }
     fn clear(&mut self) {
         let color: [u8; 4] = TRANSPARENT.into();
-        let frame = self.pixels.frame_mut();
+        let frame = self.surface.frame_mut();
         for pixel in frame.chunks_exact_mut(4) {
             pixel.copy_from_slice(&color);
         }
     }
-    fn present(&mut self) {
-        self.pixels.render().unwrap();
+    fn present(&mut self) -> Result<(), Error> {
+        self.rasters.end_frame();
+        self.surface.present()
+    }
+    fn resize(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        // A minimized window reports a zero size which
+        // no surface can be created for
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+
+        self.surface.resize(width, height)
+    }
+    fn set_subpixel(&mut self, subpixel: bool) {
+        self.subpixel = subpixel;
+    }
+    fn draw(&mut self, widget: &Rc<dyn WidgetI>) -> Result<(), Error> {
+        let drawn = self.draw(widget, &Clip::default());
+        self.painted(drawn)
+    }
+    fn draw_region(&mut self, widget: &Rc<dyn WidgetI>, region: Layout) -> Result<(), Error> {
+        let drawn = self.draw(widget, &Clip::new(Some(region)));
+        self.painted(drawn)
+    }
+    fn draw_overlay(&mut self, draw: &mut dyn FnMut(&mut dyn Painter)) -> Result<(), Error> {
+        draw(&mut PixelsPainter {
+            renderer: self,
+            clip: Clip::default(),
+        });
+        self.painted(Ok(()))
+    }
+}
+
+/// The `PixelsPainter` struct exposes the `PixelsRenderer`
+/// drawing to widget hooks within the clipping region
+/// the widget is currently drawn in
+struct PixelsPainter<'a> {
+    renderer: &'a mut PixelsRenderer,
+    clip: Clip,
+}
+impl PixelsPainter<'_> {
+    /// Keeps `err` for the renderer to return unless
+    /// another error came first
+    fn fail(&mut self, err: Error) {
+        self.renderer.painter_error.get_or_insert(err);
+    }
+}
+impl Painter for PixelsPainter<'_> {
+    /// # Note
+    ///
+    /// Round all floats to nearest unless drawing
+    /// at subpixel positions
+    fn fill_rect(&mut self, rect: Layout, color: Color) {
+        if rect.is_empty() {
+            return;
+        }
+        let (left, x) = self.renderer.snap(rect.x);
+        let (top, y) = self.renderer.snap(rect.y);
+        let line = match PixelsRenderer::draw_line(x, y, rect.w, rect.h, &color) {
+            Ok(line) => line,
+            Err(err) => return self.fail(err),
+        };
+        self.renderer.blit_on(left, top, &line, &self.clip);
+    }
+    fn stroke_line(&mut self, from: Point, to: Point, width: f64, color: Color) {
+        // Room for the round caps around the ends
+        let min_x = from.x.min(to.x) - width;
+        let min_y = from.y.min(to.y) - width;
+        // The line keeps where it sits within the first pixel
+        // when drawing at subpixel positions
+        let (left, x) = self.renderer.snap(min_x);
+        let (top, y) = self.renderer.snap(min_y);
+        let (min_x, min_y) = (min_x - f64::from(x), min_y - f64::from(y));
+        let map_width = (f64::from(x) + (from.x - to.x).abs() + width * 2.0).ceil() as u32;
+        let map_height = (f64::from(y) + (from.y - to.y).abs() + width * 2.0).ceil() as u32;
+        let mut pixmap = match pixmap(map_width.max(1), map_height.max(1)) {
+            Ok(pixmap) => pixmap,
+            Err(err) => return self.fail(err),
+        };
+
+        let mut pb = PathBuilder::new();
+        pb.move_to((from.x - min_x) as f32, (from.y - min_y) as f32);
+        pb.line_to((to.x - min_x) as f32, (to.y - min_y) as f32);
+        let Some(path) = pb.finish() else {
+            return;
+        };
+
+        let mut paint = Paint::default();
+        paint.set_color(color.into());
+        paint.anti_alias = true;
+        let stroke = Stroke {
+            width: width as f32,
+            line_cap: LineCap::Round,
+            ..Default::default()
+        };
+        pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+
+        self.renderer.blit_on(left, top, &pixmap, &self.clip);
+    }
+    fn draw_path(&mut self, path: &Path, fill: Option<Color>, outline: Option<Outline>) {
+        // Room for the half of the stroke outside of the path
+        let reach = outline.map_or(0.0, |outline| outline.width / 2.0);
+        let bounds = path.bounds();
+        let (left, x) = self.renderer.snap(f64::from(bounds.left()) - reach);
+        let (top, y) = self.renderer.snap(f64::from(bounds.top()) - reach);
+        let map_width = (f64::from(x) + f64::from(bounds.width()) + reach * 2.0).ceil() as u32;
+        let map_height = (f64::from(y) + f64::from(bounds.height()) + reach * 2.0).ceil() as u32;
+        let mut pixmap = match pixmap(map_width.max(1), map_height.max(1)) {
+            Ok(pixmap) => pixmap,
+            Err(err) => return self.fail(err),
+        };
+        // The path keeps where it sits within the first pixel
+        let transform = Transform::from_translate(
+            x - bounds.left() + reach as f32,
+            y - bounds.top() + reach as f32,
+        );
+
+        if let Some(color) = fill {
+            let mut paint = Paint::default();
+            paint.set_color(color.into());
+            pixmap.fill_path(path, &paint, FillRule::Winding, transform, None);
+        }
+        if let Some(outline) = outline.filter(|outline| outline.width > 0.0) {
+            let mut paint = Paint::default();
+            paint.set_color(outline.color.into());
+            let stroke = Stroke {
+                width: outline.width as f32,
+                ..Default::default()
+            };
+            pixmap.stroke_path(path, &paint, &stroke, transform, None);
+        }
+
+        self.renderer.blit_on(left, top, &pixmap, &self.clip);
+    }
+    fn draw_widget(&mut self, widget: &dyn Widget) {
+        // Faded like widgets drawn with their children
+        let opacity = widget.base().style.opacity;
+        if opacity <= 0.0 {
+            return;
+        }
+        let backdrop = (opacity < 1.0).then(|| self.renderer.surface.frame().to_vec());
+
+        let drawn = self.renderer.draw_widget(widget, NO_CUSTOM, &self.clip);
+
+        if let Some(backdrop) = backdrop {
+            self.renderer.fade(&backdrop, opacity);
+        }
+        if let Err(err) = drawn {
+            self.fail(err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::{
+        color::{Fill, BLUE, RED},
+        style::{CornerShape, Radius},
+    };
+
+    use super::{blend_row, PixelsRenderer};
+
+    fn round(radius: u32) -> (Radius, CornerShape) {
+        (Radius::all(radius), CornerShape::Round)
+    }
+
+    #[test]
+    fn linear_gradient_runs_along_its_angle() {
+        let fill = Fill::LinearGradient {
+            stops: vec![(0.0, RED), (1.0, BLUE)],
+            angle: 90.0,
+        };
+        let pixmap =
+            PixelsRenderer::draw_fill(0.0, 0.0, 100.0, 10.0, round(0), &fill, None).unwrap();
+
+        let left = pixmap.pixel(0, 5).unwrap();
+        let right = pixmap.pixel(99, 5).unwrap();
+        assert!(left.red() > 240 && left.blue() < 15);
+        assert!(right.blue() > 240 && right.red() < 15);
+        // Left to right keeps every column the same
+        assert_eq!(pixmap.pixel(50, 0), pixmap.pixel(50, 9));
+    }
+
+    #[test]
+    fn rounded_rects_only_differ_from_square_ones_at_the_corners() {
+        let square =
+            PixelsRenderer::draw_rounded_rect(0.0, 0.0, 40.0, 20.0, round(0), &RED, None).unwrap();
+        let rounded =
+            PixelsRenderer::draw_rounded_rect(0.0, 0.0, 40.0, 20.0, round(6), &RED, None).unwrap();
+        assert_eq!((rounded.width(), rounded.height()), (40, 20));
+
+        for (x, y) in [(20, 0), (20, 19), (0, 10), (39, 10)] {
+            assert_eq!(rounded.pixel(x, y), square.pixel(x, y));
+        }
+        assert_eq!(square.pixel(0, 0).unwrap().alpha(), 255);
+        assert_eq!(rounded.pixel(0, 0).unwrap().alpha(), 0);
+        // The curve is anti aliased
+        let curve = rounded.pixel(1, 1).unwrap().alpha();
+        assert!(curve > 0 && curve < 255);
+
+        // Radii past half the shorter side round into a pill
+        let pill =
+            PixelsRenderer::draw_rounded_rect(0.0, 0.0, 40.0, 20.0, round(50), &RED, None).unwrap();
+        assert_eq!(pill.pixel(20, 0), square.pixel(20, 0));
+        assert_eq!(pill.pixel(0, 0).unwrap().alpha(), 0);
     }
-    fn draw(&mut self, widget: &Rc<dyn WidgetI>) {
-        self.draw(widget, None);
+
+    #[test]
+    fn corners_are_rounded_on_their_own() {
+        let corners = |shape| {
+            let radius = Radius::new(10, 0, 0, 0);
+            PixelsRenderer::draw_rounded_rect(0.0, 0.0, 40.0, 20.0, (radius, shape), &RED, None)
+                .unwrap()
+        };
+        let rounded = corners(CornerShape::Round);
+        assert_eq!(rounded.pixel(0, 0).unwrap().alpha(), 0);
+        for (x, y) in [(39, 0), (39, 19), (0, 19)] {
+            assert_eq!(rounded.pixel(x, y).unwrap().alpha(), 255);
+        }
+
+        // Squircles bulge out further towards the tip
+        let squircle = corners(CornerShape::Squircle);
+        assert_eq!(squircle.pixel(0, 0).unwrap().alpha(), 0);
+        assert!(rounded.pixel(2, 2).unwrap().alpha() < 255);
+        assert_eq!(squircle.pixel(2, 2).unwrap().alpha(), 255);
+    }
+
+    #[test]
+    fn rows_blend_by_their_coverage() {
+        let mut frame = [10, 20, 30, 255].repeat(3);
+        let source = [[200, 0, 0, 255], [0, 200, 0, 0], [0, 0, 200, 255]].concat();
+
+        // Transparent pixels leave the frame alone
+        blend_row(&mut frame, &source, None);
+        assert_eq!(frame, [200, 0, 0, 255, 10, 20, 30, 255, 0, 0, 200, 255]);
+
+        // Pixels outside of rounded corners are left out
+        let mut frame = [0, 0, 0, 255].repeat(3);
+        blend_row(&mut frame, &source, Some(&[255, 255, 0]));
+        assert_eq!(frame, [200, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255]);
     }
 }
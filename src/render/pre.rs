@@ -1,11 +1,66 @@
-use crate::ui::widget::{canvas::Canvas, container::Container, Widget, WidgetI};
-use std::rc::Rc;
+use crate::trace::widget_span;
+use crate::ui::{
+    layout::{address, Arranged, Dimension, Layout},
+    semantics::Role,
+    theme::Theme,
+    widget::{button::Button, container::Container, list::ListView, BaseWidget, WidgetI},
+};
+use std::{collections::HashSet, rc::Rc};
 
 #[derive(Debug, Default, Clone, Copy)]
-pub(crate) struct PreRenderer;
+pub(crate) struct PreRenderer {
+    theme: Option<Theme>,
+    /// Bumped on every theme change so widgets pick it up again
+    generation: u64,
+}
 impl PreRenderer {
     pub(crate) fn new() -> Self {
-        Self {}
+        PreRenderer::default()
+    }
+    pub(crate) fn theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+    /// Restyles every widget with `theme` on the next adjustment
+    ///
+    /// Returns the theme that was active before
+    pub(crate) fn set_theme(&mut self, theme: Theme) -> Option<Theme> {
+        self.generation += 1;
+        self.theme.replace(theme)
+    }
+    /// Resolve the style fields of widgets not set by
+    /// the user from the theme
+    ///
+    /// Widgets are only restyled once per theme so changes made
+    /// by actions in the meantime stick
+    fn adjust_theme(&self, widget: &Rc<dyn WidgetI>) {
+        let Some(theme) = &self.theme else {
+            return;
+        };
+        let apply = |base: &mut BaseWidget, role| {
+            if base.styled.theme != self.generation {
+                theme.apply(base, role);
+                base.styled.theme = self.generation;
+            }
+        };
+
+        let role = widget.role();
+        apply(&mut widget.base_mut(), role);
+
+        if let Some(button) = widget.downcast::<Button>() {
+            button.shade.set(theme.shade());
+        }
+
+        // Scrollbars are not children so they are styled here
+        if let Some(container) = widget.downcast::<Container>() {
+            if let Some((x, y)) = &container.scrollbar {
+                for scrollbar in [x, y] {
+                    apply(&mut scrollbar.base.borrow_mut(), Role::ScrollBar);
+                }
+            }
+        }
+        if let Some(list) = widget.downcast::<ListView>() {
+            apply(&mut list.scrollbar.base.borrow_mut(), Role::ScrollBar);
+        }
     }
     /// Adjust text layout of widgets based on
     /// user settings
@@ -13,11 +68,22 @@ impl PreRenderer {
         let mut widget_base = widget.base_mut();
 
         if !widget_base.text.label.is_empty() {
+            // Shorten text too wide for the widget
+            if widget_base.layout.w > 0.0 {
+                let width = if widget_base.text.halign {
+                    widget_base.layout.w
+                } else {
+                    widget_base.layout.w - widget_base.text.pos.x
+                };
+                widget_base.text.fit(width);
+            }
             // Center text horizontally
             if widget_base.text.halign {
+                // Text wider than the widget starts at its left edge
                 let new_x = widget_base
                     .layout
-                    .horizontal_center(widget_base.text.get_true_dimensions().x);
+                    .horizontal_center(widget_base.text.get_visible_dimensions().x)
+                    .max(0.0);
                 widget_base.text.pos.x = new_x;
             }
             // Center text vertically
@@ -36,101 +102,181 @@ impl PreRenderer {
             }
         }
     }
-    /// Adjust layout of widgets based on
-    /// user settings
-    fn adjust_layout(&self, widget: &Container) {
-        // Adjust spacing layout
-        match widget.flex {
-            crate::ui::layout::FlexLayout::None => widget.create_normal_layout(),
-            crate::ui::layout::FlexLayout::Col => widget.create_flex_col_layout(),
-            crate::ui::layout::FlexLayout::Grid(cols) => widget.create_flex_grid_layout(cols),
-        }
-    }
-    /// Adjust scrollbars
-    fn adjust_scrolling(&self, widget: &Container) {
-        assert!(widget.children.len() > 0, "on_scroll() can not be used on an empty Container");
-
-        if let Some(scrollbar) = &widget.scrollbar {
-            let (x, y) = scrollbar;
-            let widget_base = widget.base();
-
-            let mut x_base = x.base_mut();
-            x_base.layout.y = (widget_base.layout.h + widget_base.layout.y) - x_base.layout.h;
-            if x_base.layout.x == 0.0 {
-                x_base.layout.x = widget_base.layout.x;
-            }
-            // Create scrollbar to be balanced based on max amount of overflow
-            // occuring..otherwise its not seen if no overflow occurs
-            let container_width = widget_base.layout.w + widget_base.layout.x;
-            let overflow_x = widget
-                .children
-                .iter()
-                .fold(container_width, |acc, child| child.base().layout.w.max(acc));
-            let amount_to_take = container_width / overflow_x;
-            // Basically makes x scrollbar visible
-            if amount_to_take < 1.0 {
-                x_base.layout.w = amount_to_take * widget_base.layout.w;
-            }
-
-            let mut y_base = y.base_mut();
-            y_base.layout.x = (widget_base.layout.w + widget_base.layout.x) - y_base.layout.w;
+    /// Resolve relative width and height of the widget
+    /// against its `parent`
+    ///
+    /// Units depending on the widget position are only resolved
+    /// when `placed` since the parent positions its children later
+    fn adjust_dimensions(&self, widget: &Rc<dyn WidgetI>, parent: Layout, placed: bool) {
+        let mut widget_base = widget.base_mut();
+        let size = widget_base.size;
+        let layout = widget_base.layout;
 
-            // This check prevents the scrollbar from being stucked
-            // when redraws occur
-            if y_base.layout.y == 0.0 {
-                y_base.layout.y = widget_base.layout.y;
-            }
-            // Create scrollbar to be balanced based on max amount of overflow
-            // occuring..otherwise its not seen if no overflow occurs
-            let container_height = widget_base.layout.h + widget_base.layout.y;
-            let last_child = &widget.children[widget.children.len() - 1];
-            let last_child_base = last_child.base();
-            let overflow_y = last_child_base.layout.y + last_child_base.layout.h;
-            let amount_to_take = container_height / overflow_y;
-            // Basically makes y scrollbar visible
-            if amount_to_take < 1.0 {
-                y_base.layout.h = amount_to_take * widget_base.layout.h;
+        let resolve = |dimension: Dimension, start: f64, len: f64, pos: f64| {
+            if dimension == Dimension::Fill && !placed {
+                return None;
             }
+            dimension.resolve(start, len, pos)
+        };
+        if let Some(w) = resolve(size.width, parent.x, parent.w, layout.x) {
+            widget_base.layout.w = w;
+        }
+        if let Some(h) = resolve(size.height, parent.y, parent.h, layout.y) {
+            widget_base.layout.h = h;
         }
     }
-    /// Make all adjustments
-    /// that must propagate first
-    fn adjust_children(&self, widget: &Rc<dyn WidgetI>) {
+    /// Resolve the style and size of the widget before its
+    /// parent arranges it
+    fn measure(&self, widget: &Rc<dyn WidgetI>, parent: Layout, placed: bool) {
+        self.adjust_theme(widget);
+        self.adjust_dimensions(widget, parent, placed);
         self.adjust_text_layout(widget);
-
-        if let Some(widget) = widget.as_any().downcast_ref::<Container>() {
-            // Propagate changes down to children
-            for child in &widget.children {
-                self.adjust_children(child);
-            }
+    }
+    /// Returns whether nothing the layout of `widget` itself
+    /// depends on changed since it was last laid out in `parent`
+    fn is_arranged(
+        &self,
+        widget: &Rc<dyn WidgetI>,
+        parent: Layout,
+        children: &[Rc<dyn WidgetI>],
+    ) -> bool {
+        if widget.needs_layout() {
+            return false;
         }
+        let base = widget.base();
+        if self.theme.is_some() && base.styled.theme != self.generation {
+            return false;
+        }
+        matches!(&base.arranged, Some(arranged) if arranged.matches(&base, parent, children))
+    }
+    /// Collects the addresses of widgets with anything changed
+    /// in their subtree since it was last laid out
+    ///
+    /// Returns whether `widget` is one of them
+    fn collect_dirty(
+        &self,
+        widget: &Rc<dyn WidgetI>,
+        parent: Layout,
+        dirty: &mut HashSet<usize>,
+    ) -> bool {
+        let children = widget.children();
+        let mut changed = !self.is_arranged(widget, parent, &children);
+        let layout = widget.base().layout;
+        for child in &children {
+            changed |= self.collect_dirty(child, layout, dirty);
+        }
+        if changed {
+            dirty.insert(address(widget));
+        }
+        changed
     }
     /// Make all adjustments for widgets that do NOT
     /// need to be propagated to children
     ///
     /// Some actions user selects could trigger mutation
     /// of surrounding widgets or attributes
-    pub(crate) fn adjust(&self, widget: &Rc<dyn WidgetI>) {
-        self.adjust_children(widget);
+    ///
+    /// The `parent` is the region the widget is laid out in.
+    /// Subtrees left unchanged since they were last laid out
+    /// in the same region are skipped
+    ///
+    /// Returns whether anything was laid out again
+    pub(crate) fn adjust(&self, widget: &Rc<dyn WidgetI>, parent: Layout) -> bool {
+        let mut dirty = HashSet::new();
+        if !self.collect_dirty(widget, parent, &mut dirty) {
+            return false;
+        }
+        self.arrange(widget, parent, &dirty);
+        true
+    }
+    /// Lays out the subtrees marked `dirty` or moved by
+    /// their parent in the meantime
+    fn arrange(&self, widget: &Rc<dyn WidgetI>, parent: Layout, dirty: &HashSet<usize>) {
+        let children = widget.children();
+        if !dirty.contains(&address(widget)) && self.is_arranged(widget, parent, &children) {
+            return;
+        }
+        widget_span!("adjust", widget);
+        self.measure(widget, parent, true);
 
-        if let Some(widget) = widget.as_any().downcast_ref::<Container>() {
-            self.adjust_layout(widget);
-            self.adjust_scrolling(widget);
+        // Children are sized before they are arranged
+        let layout = widget.base().layout;
+        for child in &children {
+            self.measure(child, layout, false);
+        }
 
-            // Propagate changes down to children
-            for child in &widget.children {
-                self.adjust(child);
-            }
-        } else if let Some(widget) = widget.as_any().downcast_ref::<Canvas>() {
-            if let Some(grid) = &mut *widget.grid.borrow_mut() {
-                let widget_base = widget.base();
-                grid.resize(
-                    widget_base.layout.x,
-                    widget_base.layout.y,
-                    widget_base.layout.h,
-                    widget_base.layout.w,
-                );
-            }
+        widget.custom_layout();
+
+        // Propagate changes down to children
+        let layout = widget.base().layout;
+        let children = widget.children();
+        for child in &children {
+            self.arrange(child, layout, dirty);
         }
+
+        let mut base = widget.base_mut();
+        base.arranged = Some(Box::new(Arranged::new(&base, parent, &children)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, rc::Rc};
+
+    use crate::ui::{
+        layout::{FlexLayout, Layout},
+        widget::{container::Container, label::Label, Widget, WidgetI},
+    };
+
+    use super::PreRenderer;
+
+    fn is_arranged(pre: &PreRenderer, root: &Rc<dyn WidgetI>, viewport: Layout) -> bool {
+        !pre.collect_dirty(root, viewport, &mut HashSet::new())
+    }
+
+    #[test]
+    fn skips_laying_out_unchanged_widgets() {
+        let mut container = Container::new()
+            .set_width(100.0)
+            .set_height(100.0)
+            .set_flex_layout(FlexLayout::Col);
+        container.add_widget(Label::new().set_label("Open"));
+        container.add_widget(Label::new().set_label("Save"));
+        let root: Rc<dyn WidgetI> = Rc::new(container);
+        let viewport = Layout {
+            w: 800.0,
+            h: 600.0,
+            ..Default::default()
+        };
+        let pre = PreRenderer::new();
+
+        assert!(pre.adjust(&root, viewport));
+        assert!(is_arranged(&pre, &root, viewport));
+        assert!(!pre.adjust(&root, viewport));
+        let second = root.children()[1].clone();
+        let y = second.base().layout.y;
+
+        // Any change deep down lays the whole branch out again
+        root.children()[0].base_mut().text.label = "Open recent".into();
+        assert!(!is_arranged(&pre, &root, viewport));
+        second.base_mut().layout.y = 500.0;
+        assert!(pre.adjust(&root, viewport));
+        assert_eq!(second.base().layout.y, y);
+        assert!(is_arranged(&pre, &root, viewport));
+
+        // Scrolled content moved on screen as well
+        second.base_mut().offset.y = -20.0;
+        assert!(!is_arranged(&pre, &root, viewport));
+        assert!(pre.adjust(&root, viewport));
+
+        // A resized window too
+        assert!(!is_arranged(
+            &pre,
+            &root,
+            Layout {
+                w: 640.0,
+                ..viewport
+            }
+        ));
     }
 }
@@ -0,0 +1,152 @@
+//! Keeps the rasterized bodies and text of widgets between frames.
+//!
+//! Rounded corners and gradients are shaded by skia and text is drawn
+//! glyph by glyph which is too slow to repeat on every frame for widgets
+//! that did not change. Every widget keeps the pixmaps last drawn for it
+//! along with what they were drawn from so unchanged widgets are only
+//! blit onto the frame.
+
+use std::collections::HashMap;
+
+use tiny_skia::Pixmap;
+
+use crate::{
+    error::Error,
+    ui::{
+        color::{Color, Fill},
+        style::{Border, CornerShape, Radius},
+        text::Text,
+    },
+};
+
+/// Widgets not drawn for this many frames let go of their pixmaps
+const MAX_IDLE_FRAMES: u64 = 600;
+
+/// The `Body` struct holds everything the body of a widget
+/// is rasterized from
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Body {
+    /// How far into its first pixel the body sits
+    pub(crate) x: f32,
+    pub(crate) y: f32,
+    pub(crate) w: f64,
+    pub(crate) h: f64,
+    pub(crate) radius: Radius,
+    pub(crate) corner_shape: CornerShape,
+    pub(crate) color: Color,
+    pub(crate) fill: Option<Fill>,
+    pub(crate) border: Option<Border>,
+}
+
+#[derive(Debug, Default)]
+struct Entry {
+    body: Option<(Body, Option<Pixmap>)>,
+    text: Option<(Text, Pixmap)>,
+    /// The frame the widget was last drawn in
+    used: u64,
+}
+
+/// The `RasterCache` struct holds the pixmaps last drawn
+/// for every widget by the address of the widget
+#[derive(Debug, Default)]
+pub(crate) struct RasterCache {
+    entries: HashMap<usize, Entry>,
+    frame: u64,
+}
+impl RasterCache {
+    pub(crate) fn new() -> Self {
+        RasterCache::default()
+    }
+    fn entry(&mut self, id: usize) -> &mut Entry {
+        let entry = self.entries.entry(id).or_default();
+        entry.used = self.frame;
+        entry
+    }
+    /// Returns the body of the widget `id` drawn from `body`
+    ///
+    /// The body is only drawn again with `draw` once it changed
+    /// and drawn anew next time when `draw` failed
+    pub(crate) fn body<F>(
+        &mut self,
+        id: usize,
+        body: Body,
+        draw: F,
+    ) -> Result<Option<&Pixmap>, Error>
+    where
+        F: FnOnce(&Body) -> Result<Option<Pixmap>, Error>,
+    {
+        let entry = self.entry(id);
+        if !matches!(&entry.body, Some((cached, _)) if *cached == body) {
+            let pixmap = draw(&body)?;
+            entry.body = Some((body, pixmap));
+        }
+        Ok(entry.body.as_ref().and_then(|(_, pixmap)| pixmap.as_ref()))
+    }
+    /// Returns the `text` of the widget `id`
+    ///
+    /// The text is only drawn again with `draw` once it changed
+    pub(crate) fn text<F>(&mut self, id: usize, text: &Text, draw: F) -> Result<&Pixmap, Error>
+    where
+        F: FnOnce(&Text) -> Result<Pixmap, Error>,
+    {
+        let entry = self.entry(id);
+        if !matches!(&entry.text, Some((cached, _)) if cached == text) {
+            entry.text = Some((text.clone(), draw(text)?));
+        }
+        Ok(&entry.text.as_ref().unwrap().1)
+    }
+    /// Ends the frame letting go of the pixmaps of widgets
+    /// not drawn for a while
+    pub(crate) fn end_frame(&mut self) {
+        let frame = self.frame;
+        self.entries
+            .retain(|_, entry| frame - entry.used < MAX_IDLE_FRAMES);
+        self.frame += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tiny_skia::Pixmap;
+
+    use crate::ui::{
+        color::BLACK,
+        style::{CornerShape, Radius},
+    };
+
+    use super::{Body, RasterCache, MAX_IDLE_FRAMES};
+
+    #[test]
+    fn redraws_only_changed_bodies() {
+        let body = |w| Body {
+            x: 0.0,
+            y: 0.0,
+            w,
+            h: 10.0,
+            radius: Radius::all(2),
+            corner_shape: CornerShape::Round,
+            color: BLACK,
+            fill: None,
+            border: None,
+        };
+        let mut cache = RasterCache::new();
+        let mut drawn = 0;
+        let mut draw = |body: &Body| {
+            drawn += 1;
+            Ok(Pixmap::new(body.w as u32, body.h as u32))
+        };
+
+        cache.body(1, body(10.0), &mut draw).unwrap();
+        cache.body(1, body(10.0), &mut draw).unwrap();
+        let pixmap = cache.body(1, body(20.0), &mut draw).unwrap();
+        assert_eq!(pixmap.unwrap().width(), 20);
+        cache.body(2, body(20.0), &mut draw).unwrap();
+        assert_eq!(drawn, 3);
+
+        // Widgets gone for a while are dropped
+        for _ in 0..=MAX_IDLE_FRAMES {
+            cache.end_frame();
+        }
+        assert!(cache.entries.is_empty());
+    }
+}
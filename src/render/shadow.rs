@@ -0,0 +1,197 @@
+//! Caches blurred drop shadows so static widgets skip blurring.
+//!
+//! Blurring touches every pixel of the shadow several times which is
+//! too slow to repeat on every frame. Shadows only depend on the size
+//! and corners of the widget casting them so they are keyed by those
+//! and drawn from the cache until the widget changes shape.
+
+use std::collections::HashMap;
+
+use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, Rect, Transform};
+
+use crate::ui::{
+    color::Color,
+    style::{CornerShape, Radius, Shadow},
+};
+
+use super::pixels_backend::PixelsRenderer;
+
+/// Cached shadows are dropped past this many so resizing
+/// widgets cannot grow the cache forever
+const MAX_SHADOWS: usize = 256;
+/// A blur is approximated by this many box blurs in a row
+const BLUR_PASSES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ShadowKey {
+    width: u32,
+    height: u32,
+    radius: Radius,
+    shape: CornerShape,
+    blur: u64,
+    color: Color,
+}
+
+/// The `ShadowCache` struct holds the blurred shadows
+/// of the widgets drawn
+#[derive(Debug, Default, Clone)]
+pub(crate) struct ShadowCache {
+    shadows: HashMap<ShadowKey, Pixmap>,
+}
+impl ShadowCache {
+    pub(crate) fn new() -> Self {
+        ShadowCache::default()
+    }
+    /// Returns the `shadow` of a `width` x `height` widget with
+    /// corners rounded by `radius` in `shape`
+    ///
+    /// The shadow reaches past the widget by the blur so it must
+    /// be drawn that far up and to the left of the widget
+    pub(crate) fn rasterize(
+        &mut self,
+        width: f64,
+        height: f64,
+        (radius, shape): (Radius, CornerShape),
+        shadow: &Shadow,
+    ) -> Option<&Pixmap> {
+        let key = ShadowKey {
+            width: width.round() as u32,
+            height: height.round() as u32,
+            radius,
+            shape,
+            blur: shadow.blur.max(0.0).to_bits(),
+            color: shadow.color,
+        };
+
+        if !self.shadows.contains_key(&key) {
+            if self.shadows.len() >= MAX_SHADOWS {
+                self.shadows.clear();
+            }
+            let pixmap = ShadowCache::cast(key)?;
+            self.shadows.insert(key, pixmap);
+        }
+        self.shadows.get(&key)
+    }
+    fn cast(key: ShadowKey) -> Option<Pixmap> {
+        let spread = f64::from_bits(key.blur).ceil() as u32;
+        let mut pixmap = Pixmap::new(key.width + spread * 2, key.height + spread * 2)?;
+
+        let mut paint = Paint::default();
+        paint.set_color(key.color.into());
+        let (x, y) = (spread as f32, spread as f32);
+        let (w, h) = (key.width as f32, key.height as f32);
+        let path = if key.radius.is_zero() {
+            PathBuilder::from_rect(Rect::from_xywh(x, y, w, h)?)
+        } else {
+            PixelsRenderer::rounded_rect_path(x, y, w, h, key.radius, key.shape)?
+        };
+        pixmap.fill_path(
+            &path,
+            &paint,
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+
+        // Each pass spreads the shadow a third of the blur
+        let reach = (spread as usize).div_ceil(BLUR_PASSES);
+        if reach > 0 {
+            let (width, height) = (pixmap.width() as usize, pixmap.height() as usize);
+            for _ in 0..BLUR_PASSES {
+                box_blur(pixmap.data_mut(), width, height, reach, true);
+                box_blur(pixmap.data_mut(), width, height, reach, false);
+            }
+        }
+
+        Some(pixmap)
+    }
+}
+
+/// Averages every pixel of the `width` x `height` RGBA `data` with
+/// its neighbours up to `reach` pixels away along rows when
+/// `horizontal` or along columns otherwise
+///
+/// Works on premultiplied colors so all channels blur alike
+fn box_blur(data: &mut [u8], width: usize, height: usize, reach: usize, horizontal: bool) {
+    let (lines, len) = if horizontal {
+        (height, width)
+    } else {
+        (width, height)
+    };
+    let index = |line: usize, i: usize| {
+        if horizontal {
+            (line * width + i) * 4
+        } else {
+            (i * width + line) * 4
+        }
+    };
+    let window = (reach * 2 + 1) as u32;
+
+    let mut source = vec![0u8; len * 4];
+    for line in 0..lines {
+        for i in 0..len {
+            let idx = index(line, i);
+            source[i * 4..i * 4 + 4].copy_from_slice(&data[idx..idx + 4]);
+        }
+
+        // A running sum over the window slides along the line
+        // with pixels past the ends counting as transparent
+        let mut sum = [0u32; 4];
+        for i in 0..reach.min(len) {
+            for c in 0..4 {
+                sum[c] += u32::from(source[i * 4 + c]);
+            }
+        }
+        for i in 0..len {
+            if i + reach < len {
+                for c in 0..4 {
+                    sum[c] += u32::from(source[(i + reach) * 4 + c]);
+                }
+            }
+            let idx = index(line, i);
+            for c in 0..4 {
+                data[idx + c] = (sum[c] / window) as u8;
+            }
+            if i >= reach {
+                for c in 0..4 {
+                    sum[c] -= u32::from(source[(i - reach) * 4 + c]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::{
+        color::Color,
+        layout::Point,
+        style::{CornerShape, Radius, Shadow},
+    };
+
+    use super::*;
+
+    #[test]
+    fn blurs_past_the_widget_and_reuses_shadows() {
+        let shadow = Shadow::new(Point::new(2.0, 2.0), 6.0, Color::RGBA(0, 0, 0, 255));
+        let mut cache = ShadowCache::new();
+
+        let pixmap = cache
+            .rasterize(40.0, 30.0, (Radius::all(0), CornerShape::Round), &shadow)
+            .unwrap()
+            .clone();
+        assert_eq!((pixmap.width(), pixmap.height()), (52, 42));
+        // Solid in the middle fading out towards the edges
+        let middle = pixmap.pixel(26, 21).unwrap().alpha();
+        let edge = pixmap.pixel(6, 21).unwrap().alpha();
+        let outside = pixmap.pixel(0, 21).unwrap().alpha();
+        assert_eq!(middle, 255);
+        assert!(edge > outside && edge < middle);
+
+        // Moving a widget keeps its shadow
+        cache.rasterize(40.0, 30.0, (Radius::all(0), CornerShape::Round), &shadow);
+        assert_eq!(cache.shadows.len(), 1);
+        cache.rasterize(40.0, 30.0, (Radius::all(4), CornerShape::Round), &shadow);
+        assert_eq!(cache.shadows.len(), 2);
+    }
+}
@@ -0,0 +1,179 @@
+//! Shared application state widgets can react to.
+//!
+//! A `Store` owns a value behind a lock so it can be read and changed
+//! from the UI thread as well as from emitters. Widgets subscribe with a
+//! selector picking the part of the state they show, and get a callback
+//! on the UI thread whenever that part changes.
+
+use std::{
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, RwLock, RwLockReadGuard,
+    },
+};
+
+use crate::ui::{sync::Trigger, widget::WidgetI};
+
+type Watcher<T> = Box<dyn FnMut(&T) + Send>;
+
+/// A handle to a subscription made on a `Store`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SubscriptionId(usize);
+
+struct Inner<T> {
+    state: RwLock<T>,
+    watchers: Mutex<Vec<(SubscriptionId, Watcher<T>)>>,
+    next_id: AtomicUsize,
+}
+
+/// The `Store` struct wraps state shared across the app
+///
+/// Cloning a `Store` is cheap and every clone sees the same state,
+/// so it can be captured by actions and handed to emitters.
+///
+/// ## Example
+/// ```ignore
+/// let store = Store::new(Palette::default());
+/// store.subscribe(&swatch.trigger(), |p| p.color, |widget, color| {
+///     widget.base_mut().style.color = (*color).into();
+/// });
+/// store.update(|p| p.color = RED);
+/// ```
+pub struct Store<T> {
+    inner: Arc<Inner<T>>,
+}
+impl<T> Clone for Store<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+impl<T: Send + Sync + 'static> Store<T> {
+    /// Create a new `Store` holding `state`
+    pub fn new(state: T) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                state: RwLock::new(state),
+                watchers: Mutex::new(Vec::new()),
+                next_id: AtomicUsize::new(0),
+            }),
+        }
+    }
+    /// Returns read access to the state
+    ///
+    /// The store can not be changed while the guard is held
+    pub fn read(&self) -> RwLockReadGuard<'_, T> {
+        self.inner.state.read().unwrap()
+    }
+    /// Returns a copy of the state
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.read().clone()
+    }
+    /// Replaces the state notifying subscribers
+    pub fn set(&self, state: T) {
+        self.update(|current| *current = state);
+    }
+    /// Changes the state with `f` notifying subscribers
+    /// whose selected part changed
+    pub fn update<F: FnOnce(&mut T)>(&self, f: F) {
+        f(&mut self.inner.state.write().unwrap());
+
+        let state = self.read();
+        for (_, watcher) in self.inner.watchers.lock().unwrap().iter_mut() {
+            watcher(&state);
+        }
+    }
+    /// Calls `callback` with the part of the state `selector` picks
+    /// every time that part changes
+    ///
+    /// `callback` runs right inside `update` so it MUST not
+    /// change the store itself
+    pub fn watch<S, Sel, F>(&self, selector: Sel, callback: F) -> SubscriptionId
+    where
+        S: PartialEq + Send + 'static,
+        Sel: Fn(&T) -> S + Send + 'static,
+        F: Fn(&S) + Send + 'static,
+    {
+        let mut last = selector(&self.read());
+        let watcher: Watcher<T> = Box::new(move |state| {
+            let selected = selector(state);
+            if selected != last {
+                callback(&selected);
+                last = selected;
+            }
+        });
+
+        let id = SubscriptionId(self.inner.next_id.fetch_add(1, Ordering::Relaxed));
+        self.inner.watchers.lock().unwrap().push((id, watcher));
+        id
+    }
+    /// Subscribes the widget of `trigger` to the part of the state
+    /// `selector` picks
+    ///
+    /// `callback` runs on the UI thread with the widget and the newly
+    /// selected value, after which the widget is redrawn
+    pub fn subscribe<S, Sel, F>(
+        &self,
+        trigger: &Trigger,
+        selector: Sel,
+        callback: F,
+    ) -> SubscriptionId
+    where
+        S: PartialEq + Clone + Send + Sync + 'static,
+        Sel: Fn(&T) -> S + Send + 'static,
+        F: Fn(Rc<dyn WidgetI>, &S) + Send + Sync + 'static,
+    {
+        let trigger = trigger.clone();
+        let callback = Arc::new(callback);
+        self.watch(selector, move |selected: &S| {
+            let callback = callback.clone();
+            let selected = selected.clone();
+            trigger.update_callback(move |widget| callback(widget, &selected));
+        })
+    }
+    /// Stops the subscription behind `id`
+    pub fn unsubscribe(&self, id: SubscriptionId) {
+        self.inner
+            .watchers
+            .lock()
+            .unwrap()
+            .retain(|(watcher, _)| *watcher != id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::Store;
+
+    #[derive(Clone, Default)]
+    struct Palette {
+        color: u8,
+        size: u8,
+    }
+
+    #[test]
+    fn test_watchers_only_fire_when_selection_changes() {
+        let store = Store::new(Palette::default());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+
+        let log = seen.clone();
+        let id = store.watch(|p| p.color, move |color| log.lock().unwrap().push(*color));
+
+        store.update(|p| p.size = 4);
+        store.update(|p| p.color = 1);
+        store.update(|p| p.color = 1);
+        store.set(Palette { color: 2, size: 0 });
+        store.unsubscribe(id);
+        store.update(|p| p.color = 3);
+
+        assert_eq!(*seen.lock().unwrap(), vec![1, 2]);
+        assert_eq!(store.get().color, 3);
+    }
+}
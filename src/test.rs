@@ -0,0 +1,212 @@
+//! Golden image testing for widget trees.
+//!
+//! [`assert_snapshot!`](crate::assert_snapshot) draws a widget tree with
+//! the headless renderer and compares it against a PNG stored under
+//! `tests/snapshots` of the crate being tested. The first run records the
+//! snapshot and later runs fail once any pixel drifts further than the
+//! tolerance, leaving the new image and a diff next to the snapshot.
+//!
+//! Set `GEMINI_UPDATE_SNAPSHOTS` to record every snapshot again after an
+//! intended change.
+//!
+//! ## Example
+//! ```ignore
+//! #[test]
+//! fn button_looks_the_same() {
+//!     let button = Button::new().set_label("Ok").set_radius(6);
+//!     gemini::assert_snapshot!(button, "button");
+//! }
+//! ```
+
+use std::{fs, path::Path, rc::Rc};
+
+use tiny_skia::{ColorU8, IntSize, Pixmap, PremultipliedColorU8};
+
+use crate::ui::{dom::DOM, widget::WidgetI};
+
+/// The screen snapshots are laid out on
+pub const VIEWPORT: (u32, u32) = (1024, 768);
+/// How far any channel of a pixel may drift from the snapshot
+pub const TOLERANCE: u8 = 2;
+/// Records every snapshot again when set
+const UPDATE_VAR: &str = "GEMINI_UPDATE_SNAPSHOTS";
+
+/// Asserts the widget tree looks like the snapshot `name`
+///
+/// The widget tree is any widget or a tree already built into
+/// an `Rc<dyn WidgetI>`.
+///
+/// ## Example
+/// ```ignore
+/// assert_snapshot!(Container::new().add_widget(Label::new()), "empty_label");
+/// ```
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($tree:expr, $name:expr) => {
+        $crate::test::assert_snapshot(
+            $crate::test::IntoWidgetTree::into_tree($tree),
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("snapshots"),
+            $name,
+        )
+    };
+}
+
+/// A trait for whatever can be drawn as a snapshot
+pub trait IntoWidgetTree {
+    fn into_tree(self) -> Rc<dyn WidgetI>;
+}
+impl<W: WidgetI + 'static> IntoWidgetTree for W {
+    fn into_tree(self) -> Rc<dyn WidgetI> {
+        Rc::new(self)
+    }
+}
+impl IntoWidgetTree for Rc<dyn WidgetI> {
+    fn into_tree(self) -> Rc<dyn WidgetI> {
+        self
+    }
+}
+
+/// Asserts `tree` looks like the snapshot `name` stored in `dir`
+///
+/// Prefer [`assert_snapshot!`](crate::assert_snapshot) which finds
+/// the snapshots of the crate being tested
+///
+/// # Panics
+///
+/// This method will panic if the tree differs from the snapshot,
+/// can not be drawn or the snapshot can not be read or written
+pub fn assert_snapshot(tree: Rc<dyn WidgetI>, dir: &Path, name: &str) {
+    let actual = render(&tree);
+    let path = dir.join(format!("{name}.png"));
+    let new_path = dir.join(format!("{name}.new.png"));
+    let diff_path = dir.join(format!("{name}.diff.png"));
+
+    if !path.exists() || std::env::var_os(UPDATE_VAR).is_some() {
+        fs::create_dir_all(dir).expect("snapshot directory should be writable");
+        actual.save_png(&path).expect("snapshot should be writable");
+        // Leftovers of failed runs are out of date
+        let _ = fs::remove_file(new_path);
+        let _ = fs::remove_file(diff_path);
+        return;
+    }
+
+    let expected = Pixmap::load_png(&path).expect("snapshot should be a readable PNG");
+    match compare(&expected, &actual) {
+        Some(diff) => {
+            actual
+                .save_png(&new_path)
+                .expect("snapshot should be writable");
+            diff.save_png(&diff_path)
+                .expect("snapshot diff should be writable");
+            panic!(
+                "snapshot `{name}` changed, see {} and {}\nset {UPDATE_VAR} to accept the change",
+                new_path.display(),
+                diff_path.display()
+            );
+        }
+        None => {
+            let _ = fs::remove_file(new_path);
+            let _ = fs::remove_file(diff_path);
+        }
+    }
+}
+
+/// Draws `tree` cut down to the space it takes up
+fn render(tree: &Rc<dyn WidgetI>) -> Pixmap {
+    let frame = DOM::render_once_to_buffer(std::slice::from_ref(tree), VIEWPORT.0, VIEWPORT.1)
+        .expect("tree should be drawn");
+    let (width, height) = {
+        let base = tree.base();
+        (
+            ((base.layout.x + base.layout.w).ceil().max(1.0) as u32).min(VIEWPORT.0),
+            ((base.layout.y + base.layout.h).ceil().max(1.0) as u32).min(VIEWPORT.1),
+        )
+    };
+
+    // Rows of the frame are cut off at the width of the tree
+    let row = width as usize * 4;
+    let data = frame
+        .frame()
+        .chunks_exact(VIEWPORT.0 as usize * 4)
+        .take(height as usize)
+        .flat_map(|pixels| {
+            pixels[..row].chunks_exact(4).flat_map(|pixel| {
+                let color = ColorU8::from_rgba(pixel[0], pixel[1], pixel[2], pixel[3]);
+                let color = color.premultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+        })
+        .collect();
+    Pixmap::from_vec(data, IntSize::from_wh(width, height).unwrap()).unwrap()
+}
+
+/// Compares two images returning a diff highlighting the pixels
+/// drifting further than the tolerance or `None` when there are none
+fn compare(expected: &Pixmap, actual: &Pixmap) -> Option<Pixmap> {
+    if expected.width() != actual.width() || expected.height() != actual.height() {
+        // Nothing lines up so everything the two cover is marked
+        let mut diff = Pixmap::new(
+            expected.width().max(actual.width()),
+            expected.height().max(actual.height()),
+        )?;
+        diff.fill(tiny_skia::Color::from_rgba8(255, 0, 0, 255));
+        return Some(diff);
+    }
+
+    let mut diff = Pixmap::new(actual.width(), actual.height())?;
+    let mut changed = false;
+    for ((expected, actual), out) in expected
+        .pixels()
+        .iter()
+        .zip(actual.pixels())
+        .zip(diff.pixels_mut())
+    {
+        let drift = [
+            expected.red().abs_diff(actual.red()),
+            expected.green().abs_diff(actual.green()),
+            expected.blue().abs_diff(actual.blue()),
+            expected.alpha().abs_diff(actual.alpha()),
+        ];
+        *out = if drift.iter().any(|drift| *drift > TOLERANCE) {
+            changed = true;
+            PremultipliedColorU8::from_rgba(255, 0, 0, 255).unwrap()
+        } else {
+            // Unchanged pixels are faded to make the changes stand out
+            let demultiplied = actual.demultiply();
+            let gray = ((u16::from(demultiplied.red())
+                + u16::from(demultiplied.green())
+                + u16::from(demultiplied.blue()))
+                / 3) as u8;
+            let alpha = demultiplied.alpha() / 4;
+            ColorU8::from_rgba(gray, gray, gray, alpha).premultiply()
+        };
+    }
+    changed.then_some(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use tiny_skia::{Color, Pixmap, PremultipliedColorU8};
+
+    use super::{compare, TOLERANCE};
+
+    #[test]
+    fn drift_within_the_tolerance_passes() {
+        let mut expected = Pixmap::new(4, 4).unwrap();
+        expected.fill(Color::from_rgba8(100, 100, 100, 255));
+        let mut actual = expected.clone();
+        actual.fill(Color::from_rgba8(100 + TOLERANCE, 100, 100, 255));
+        assert!(compare(&expected, &actual).is_none());
+
+        // Only the pixel that drifted too far is marked
+        actual.pixels_mut()[5] = PremultipliedColorU8::from_rgba(0, 0, 0, 255).unwrap();
+        let diff = compare(&expected, &actual).unwrap();
+        assert_eq!(diff.pixel(1, 1).unwrap().red(), 255);
+        assert_ne!(diff.pixel(0, 0).unwrap().red(), 255);
+
+        // Resized trees never match
+        assert!(compare(&expected, &Pixmap::new(4, 5).unwrap()).is_some());
+    }
+}
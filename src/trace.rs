@@ -0,0 +1,69 @@
+//! Instrumentation of the frame loop.
+//!
+//! With the `tracing` feature enabled every frame is recorded as a
+//! `tracing` span holding the layout and render phases, which in turn
+//! hold a span for every widget laid out, drawn or handed an event.
+//! Widget spans carry the `id` and `type` of their widget so any
+//! `tracing` subscriber can break frames down by widget.
+//!
+//! Without the feature spans compile to nothing and events are
+//! logged through `log` as before.
+//!
+//! ## Example
+//! ```ignore
+//! // Cargo.toml: gemini = { features = ["tracing"] }
+//! tracing_subscriber::fmt()
+//!     .with_max_level(tracing::Level::DEBUG)
+//!     .with_span_events(FmtSpan::CLOSE)
+//!     .init();
+//! DOM::new(800, 600).run()?;
+//! ```
+
+#[cfg(feature = "tracing")]
+use std::rc::Rc;
+
+#[cfg(feature = "tracing")]
+use crate::ui::widget::WidgetI;
+
+/// Logs a debug event within the spans entered
+macro_rules! debug {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+        #[cfg(not(feature = "tracing"))]
+        log::debug!($($arg)*);
+    }};
+}
+pub(crate) use debug;
+
+/// Enters a span named `name` until the end of the
+/// enclosing scope
+///
+/// Fields are written as for `tracing::debug_span!` and are
+/// never evaluated without the `tracing` feature
+macro_rules! span {
+    ($name:literal $(, $($fields:tt)*)?) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($name $(, $($fields)*)?).entered();
+    };
+}
+pub(crate) use span;
+
+/// Enters a span named `name` for the `widget` handed in
+/// as `&Rc<dyn WidgetI>` until the end of the enclosing scope
+macro_rules! widget_span {
+    ($name:literal, $widget:expr $(, $($fields:tt)*)?) => {
+        $crate::trace::span!(
+            $name,
+            id = %$widget.base().id,
+            r#type = $crate::trace::type_name($widget)
+            $(, $($fields)*)?
+        );
+    };
+}
+pub(crate) use widget_span;
+
+#[cfg(feature = "tracing")]
+pub(crate) fn type_name(widget: &Rc<dyn WidgetI>) -> &'static str {
+    widget.type_name()
+}
@@ -0,0 +1,213 @@
+//! Exposes the widget tree to platform screen readers through AccessKit.
+//!
+//! The `DOM` mirrors every widget into an AccessKit node keyed by its
+//! `WidgetId`. Updates are pushed whenever layout or widget state
+//! changes, and requests coming back from assistive technologies
+//! (focus, click) are routed to the UI thread as `Signal`s.
+
+use std::rc::Rc;
+
+use accesskit::{
+    Action as AccessAction, ActionRequest, Checked, Node, NodeBuilder, NodeClassSet, NodeId, Rect,
+    Role, Tree, TreeUpdate,
+};
+use accesskit_winit::{ActionRequestEvent, Adapter};
+use winit::{
+    dpi::PhysicalPosition,
+    event::{DeviceId, ElementState, Event, MouseButton, WindowEvent},
+    event_loop::EventLoopProxy,
+    window::{Window, WindowId},
+};
+
+use super::{arena::WidgetId, semantics, sync::Signal, widget::WidgetI};
+
+/// The node representing the application window which
+/// every top-level widget hangs from
+const WINDOW_NODE: NodeId = NodeId(u64::MAX);
+
+impl From<ActionRequestEvent> for Signal {
+    fn from(value: ActionRequestEvent) -> Self {
+        Signal::Accessibility(value.request)
+    }
+}
+
+impl From<semantics::Role> for Role {
+    fn from(value: semantics::Role) -> Self {
+        match value {
+            semantics::Role::Generic => Role::GenericContainer,
+            semantics::Role::Group => Role::Group,
+            semantics::Role::Button => Role::Button,
+            semantics::Role::CheckBox => Role::CheckBox,
+            semantics::Role::Heading => Role::Heading,
+            semantics::Role::Label => Role::StaticText,
+            semantics::Role::Canvas => Role::Canvas,
+            semantics::Role::Cell => Role::Cell,
+            semantics::Role::ScrollBar => Role::ScrollBar,
+            semantics::Role::Tab => Role::Tab,
+            semantics::Role::TabList => Role::TabList,
+            semantics::Role::Tree => Role::Tree,
+            semantics::Role::TreeItem => Role::TreeItem,
+            semantics::Role::ProgressBar => Role::ProgressIndicator,
+            semantics::Role::Separator => Role::Splitter,
+            semantics::Role::Graphic => Role::GraphicsObject,
+        }
+    }
+}
+
+/// The `Accessibility` struct owns the AccessKit platform adapter
+/// of a window and keeps it in sync with the widget tree
+pub(crate) struct Accessibility {
+    adapter: Adapter,
+    title: String,
+}
+impl Accessibility {
+    /// Create the adapter for the `window`
+    ///
+    /// The window MUST not have been shown yet
+    pub(crate) fn new(window: &Window, proxy: EventLoopProxy<Signal>) -> Self {
+        let title = window.title();
+        let initial_title = title.clone();
+        let adapter = Adapter::new(
+            window,
+            move || {
+                // No widgets are attached yet when the window opens
+                let mut classes = NodeClassSet::new();
+                let mut root = NodeBuilder::new(Role::Window);
+                root.set_name(initial_title);
+                TreeUpdate {
+                    nodes: vec![(WINDOW_NODE, root.build(&mut classes))],
+                    tree: Some(Tree::new(WINDOW_NODE)),
+                    focus: WINDOW_NODE,
+                }
+            },
+            proxy,
+        );
+
+        Self { adapter, title }
+    }
+    /// Lets the adapter react to window events before the
+    /// application handles them
+    pub(crate) fn process_event(&self, window: &Window, event: &WindowEvent) {
+        self.adapter.process_event(window, event);
+    }
+    /// Pushes the current state of the widget tree and the
+    /// `focus` to assistive technologies (if any are listening)
+    pub(crate) fn update(&self, nodes: &[Rc<dyn WidgetI>], focus: Option<WidgetId>) {
+        self.adapter.update_if_active(|| {
+            let mut classes = NodeClassSet::new();
+            let mut update = Vec::new();
+
+            let mut root = NodeBuilder::new(Role::Window);
+            root.set_name(self.title.clone());
+            for node in nodes {
+                root.push_child(Accessibility::build_node(node, &mut classes, &mut update));
+            }
+            update.push((WINDOW_NODE, root.build(&mut classes)));
+
+            TreeUpdate {
+                nodes: update,
+                tree: None,
+                focus: focus.map(node_id).unwrap_or(WINDOW_NODE),
+            }
+        });
+    }
+    /// Creates the node for `widget` and all of its children
+    fn build_node(
+        widget: &Rc<dyn WidgetI>,
+        classes: &mut NodeClassSet,
+        update: &mut Vec<(NodeId, Node)>,
+    ) -> NodeId {
+        let id = node_id(widget.trigger().id());
+        let children = widget.children();
+
+        let mut builder = {
+            let base = widget.base();
+
+            let mut builder = NodeBuilder::new(widget.role().into());
+            builder.set_bounds(Rect {
+                x0: base.offset.x + base.layout.x,
+                y0: base.offset.y + base.layout.y,
+                x1: base.offset.x + base.layout.x + base.layout.w,
+                y1: base.offset.y + base.layout.y + base.layout.h,
+            });
+            let name = widget.accessible_name();
+            if !name.is_empty() {
+                builder.set_name(name);
+            }
+            if let Some(description) = &base.semantics.description {
+                builder.set_description(description.clone());
+            }
+            if let Some(checked) = widget.checked() {
+                builder.set_checked(if checked {
+                    Checked::True
+                } else {
+                    Checked::False
+                });
+            }
+            if let Some(value) = widget.value() {
+                builder.set_min_numeric_value(0.0);
+                builder.set_max_numeric_value(1.0);
+                builder.set_numeric_value(f64::from(value));
+            }
+            builder.add_action(AccessAction::Focus);
+            if !widget.action().is_empty() {
+                builder.add_action(AccessAction::Default);
+            }
+            builder
+        };
+
+        for child in &children {
+            builder.push_child(Accessibility::build_node(child, classes, update));
+        }
+        update.push((id, builder.build(classes)));
+
+        id
+    }
+}
+
+/// Maps a widget handle to its AccessKit node
+fn node_id(id: WidgetId) -> NodeId {
+    NodeId(id.to_raw())
+}
+
+/// Maps an AccessKit request back to the targeted widget handle
+pub(crate) fn request_target(request: &ActionRequest) -> Option<WidgetId> {
+    if request.target == WINDOW_NODE {
+        None
+    } else {
+        Some(WidgetId::from_raw(request.target.0))
+    }
+}
+
+/// Synthesizes the pointer events of a left click on the center
+/// of `widget` so its actions respond as if a user clicked it
+pub(crate) fn click_events(window_id: WindowId, widget: &Rc<dyn WidgetI>) -> Vec<Event<Signal>> {
+    let base = widget.base();
+    let position = PhysicalPosition::new(
+        base.offset.x + base.layout.x + base.layout.w / 2.0,
+        base.offset.y + base.layout.y + base.layout.h / 2.0,
+    );
+    // SAFETY: the dummy device is only used to tag events
+    // that never reach winit
+    let device_id = unsafe { DeviceId::dummy() };
+
+    [
+        WindowEvent::CursorMoved {
+            device_id,
+            position,
+        },
+        WindowEvent::MouseInput {
+            device_id,
+            state: ElementState::Pressed,
+            button: MouseButton::Left,
+        },
+        WindowEvent::MouseInput {
+            device_id,
+            state: ElementState::Released,
+            button: MouseButton::Left,
+        },
+    ]
+    .into_iter()
+    .map(|event| Event::WindowEvent { window_id, event })
+    .collect()
+}
@@ -0,0 +1,276 @@
+//! Tweens widget properties over time.
+//!
+//! An `Animation` moves the layout or color of a widget from wherever
+//! it currently is to a target value. Every frame is a tick of an
+//! interval timer on the UI thread which redraws the widget, and the
+//! timer is cleared once the animation finishes.
+//!
+//! ## Example
+//! ```ignore
+//! widget
+//!     .animate()
+//!     .to_x(200.0)
+//!     .duration_ms(300)
+//!     .easing(Easing::EaseOut)
+//!     .start();
+//! ```
+
+use std::sync::{Arc, Mutex, OnceLock};
+
+use web_time::{Duration, Instant};
+
+use super::{color::Color, sync::Trigger, timer::TimerId, widget::BaseWidget};
+
+/// The time between two frames of an animation
+pub(crate) const FRAME: Duration = Duration::from_millis(16);
+
+/// The `Easing` enum shapes how an animation
+/// progresses over its duration
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Easing {
+    /// Constant speed
+    Linear,
+    /// Starts slow and speeds up
+    EaseIn,
+    /// Starts fast and slows down
+    EaseOut,
+    /// Slow at both ends
+    #[default]
+    EaseInOut,
+    /// Slow at both ends with a sharper middle
+    Cubic,
+}
+impl Easing {
+    /// Maps the linear progress `t` in `0.0..=1.0`
+    /// onto the eased progress
+    pub fn apply(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    -1.0 + (4.0 - 2.0 * t) * t
+                }
+            }
+            Easing::Cubic => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// A property of a widget that can be animated
+/// holding its target value
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+enum Property {
+    X(f64),
+    Y(f64),
+    Width(f64),
+    Height(f64),
+    Color(Color),
+    Opacity(f32),
+}
+impl Property {
+    /// Returns the same property holding the
+    /// current value of the widget
+    fn current(&self, base: &BaseWidget) -> Property {
+        match self {
+            Property::X(_) => Property::X(base.layout.x),
+            Property::Y(_) => Property::Y(base.layout.y),
+            Property::Width(_) => Property::Width(base.layout.w),
+            Property::Height(_) => Property::Height(base.layout.h),
+            Property::Color(_) => Property::Color(base.style.color.color()),
+            Property::Opacity(_) => Property::Opacity(base.style.opacity),
+        }
+    }
+    /// Sets the widget to the value `t` of the way
+    /// from `from` to this property
+    fn tween(&self, from: &Property, t: f64, base: &mut BaseWidget) {
+        let lerp = |from: f64, to: f64| from + (to - from) * t;
+        match (from, self) {
+            (Property::X(from), Property::X(to)) => base.layout.x = lerp(*from, *to),
+            (Property::Y(from), Property::Y(to)) => base.layout.y = lerp(*from, *to),
+            (Property::Width(from), Property::Width(to)) => base.layout.w = lerp(*from, *to),
+            (Property::Height(from), Property::Height(to)) => base.layout.h = lerp(*from, *to),
+            (Property::Color(from), Property::Color(to)) => {
+                base.style.color.set_color(from.lerp(*to, t))
+            }
+            (Property::Opacity(from), Property::Opacity(to)) => {
+                base.style.opacity = lerp(f64::from(*from), f64::from(*to)).clamp(0.0, 1.0) as f32
+            }
+            _ => (),
+        }
+    }
+}
+
+/// The progress of a running animation
+#[derive(Default)]
+struct Tween {
+    started: Option<Instant>,
+    from: Vec<Property>,
+}
+
+/// A builder for an animation of a single widget
+///
+/// Nothing happens until `start` is called. Properties laid out
+/// by a flex `Container` are overridden by the container, so
+/// positions should only be animated on freely placed widgets.
+pub struct Animation {
+    trigger: Trigger,
+    properties: Vec<Property>,
+    duration: Duration,
+    easing: Easing,
+}
+impl Animation {
+    pub(crate) fn new(trigger: Trigger) -> Self {
+        Self {
+            trigger,
+            properties: Vec::new(),
+            duration: Duration::from_millis(250),
+            easing: Easing::default(),
+        }
+    }
+    /// Animate the x-axis position to `x`
+    pub fn to_x(mut self, x: f64) -> Self {
+        self.properties.push(Property::X(x));
+        self
+    }
+    /// Animate the y-axis position to `y`
+    pub fn to_y(mut self, y: f64) -> Self {
+        self.properties.push(Property::Y(y));
+        self
+    }
+    /// Animate the width to `width`
+    pub fn to_width(mut self, width: f64) -> Self {
+        self.properties.push(Property::Width(width));
+        self
+    }
+    /// Animate the height to `height`
+    pub fn to_height(mut self, height: f64) -> Self {
+        self.properties.push(Property::Height(height));
+        self
+    }
+    /// Animate the background color to `color`
+    pub fn to_color(mut self, color: Color) -> Self {
+        self.properties.push(Property::Color(color));
+        self
+    }
+    /// Animate the opacity to `opacity` such as fading
+    /// out with `0.0`
+    pub fn to_opacity(mut self, opacity: f32) -> Self {
+        self.properties
+            .push(Property::Opacity(opacity.clamp(0.0, 1.0)));
+        self
+    }
+    /// Set how long the animation runs
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+    /// Set how long the animation runs in milliseconds
+    pub fn duration_ms(self, ms: u64) -> Self {
+        self.duration(Duration::from_millis(ms))
+    }
+    /// Set how the animation progresses over its duration
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+    /// Starts the animation from the current values of
+    /// the widget
+    ///
+    /// Returns the timer driving the animation which can be
+    /// cleared to stop it early
+    pub fn start(self) -> TimerId {
+        let Animation {
+            trigger,
+            properties,
+            duration,
+            easing,
+        } = self;
+        let tween = Mutex::new(Tween::default());
+        let timer: Arc<OnceLock<TimerId>> = Arc::default();
+
+        let id = trigger.set_interval(FRAME, {
+            let timer = timer.clone();
+            move |widget| {
+                let mut tween = tween.lock().unwrap();
+                let now = Instant::now();
+
+                // The starting values are only known once the
+                // widget is reached on the UI thread
+                let started = *tween.started.get_or_insert(now);
+                if tween.from.is_empty() {
+                    let base = widget.base();
+                    tween.from = properties.iter().map(|p| p.current(&base)).collect();
+                }
+
+                let t = if duration.is_zero() {
+                    1.0
+                } else {
+                    (now - started).as_secs_f64() / duration.as_secs_f64()
+                };
+                let eased = easing.apply(t);
+                {
+                    let mut base = widget.base_mut();
+                    for (property, from) in properties.iter().zip(&tween.from) {
+                        property.tween(from, eased, &mut base);
+                    }
+                }
+
+                if t >= 1.0 {
+                    if let Some(id) = timer.get() {
+                        widget.trigger().clear_timer(*id);
+                    }
+                }
+            }
+        });
+        let _ = timer.set(id);
+
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::widget::BaseWidget;
+
+    use super::{Easing, Property};
+
+    #[test]
+    fn test_easings_start_and_end_in_place() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+            Easing::Cubic,
+        ] {
+            assert!(easing.apply(0.0) == 0.0);
+            assert!((easing.apply(1.0) - 1.0).abs() < f64::EPSILON);
+            // Overshooting the duration holds the end value
+            assert!((easing.apply(1.5) - 1.0).abs() < f64::EPSILON);
+        }
+        assert!(Easing::EaseIn.apply(0.5) < 0.5);
+        assert!(Easing::EaseOut.apply(0.5) > 0.5);
+    }
+
+    #[test]
+    fn test_opacity_fades_from_the_current_value() {
+        let mut base = BaseWidget::default();
+        let target = Property::Opacity(0.0);
+        let from = target.current(&base);
+        assert_eq!(from, Property::Opacity(1.0));
+
+        target.tween(&from, 0.25, &mut base);
+        assert_eq!(base.style.opacity, 0.75);
+    }
+}
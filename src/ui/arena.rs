@@ -0,0 +1,183 @@
+//! A generational arena used to store the widget tree.
+//!
+//! Widgets added to a `DOM` are stored as `Node`s referenced by typed
+//! `WidgetId` handles rather than raw pointers or random numbers. Each
+//! handle carries the generation of the slot it was issued for, so a
+//! handle to a removed widget can never resolve to a widget inserted
+//! later into the same slot.
+//!
+//! Every node links to its parent and children by handle, so the
+//! `DOM` walks and edits the tree without borrowing any widget.
+//! Widgets themselves still live behind `Rc` together with their
+//! `BaseWidget`, which the builders hand around before a widget is
+//! ever added to a `DOM`.
+
+use std::rc::Rc;
+
+use super::widget::WidgetI;
+
+/// A typed handle to a widget stored in the DOM.
+///
+/// Handles are cheap to copy and safe to send across threads, which
+/// makes them the preferred way to refer to a widget outside of the
+/// UI thread.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WidgetId {
+    index: u32,
+    generation: u32,
+}
+impl WidgetId {
+    /// Packs the handle into a single integer
+    #[cfg(feature = "accesskit")]
+    pub(crate) fn to_raw(self) -> u64 {
+        ((self.generation as u64) << 32) | self.index as u64
+    }
+    /// Unpacks a handle made by `to_raw`
+    #[cfg(feature = "accesskit")]
+    pub(crate) fn from_raw(raw: u64) -> Self {
+        Self {
+            index: raw as u32,
+            generation: (raw >> 32) as u32,
+        }
+    }
+}
+
+/// A widget stored in the arena and its links to the
+/// rest of the tree
+pub(crate) struct Node {
+    pub(crate) widget: Rc<dyn WidgetI>,
+    /// Missing for top-level widgets
+    pub(crate) parent: Option<WidgetId>,
+    /// Hidden children included, in the order the widget holds them
+    pub(crate) children: Vec<WidgetId>,
+}
+
+#[derive(Debug, Clone)]
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+/// A slotmap style container handing out `WidgetId`s for every
+/// value inserted
+#[derive(Debug, Clone)]
+pub(crate) struct Arena<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    len: usize,
+}
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self {
+            slots: Vec::default(),
+            free: Vec::default(),
+            len: 0,
+        }
+    }
+}
+impl<T> Arena<T> {
+    pub(crate) fn new() -> Self {
+        Arena::default()
+    }
+    /// Stores the `value` and returns the handle to reach it
+    ///
+    /// Slots of removed values get reused with a bumped generation
+    pub(crate) fn insert(&mut self, value: T) -> WidgetId {
+        self.len += 1;
+
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.value = Some(value);
+            WidgetId {
+                index,
+                generation: slot.generation,
+            }
+        } else {
+            self.slots.push(Slot {
+                generation: 0,
+                value: Some(value),
+            });
+            WidgetId {
+                index: (self.slots.len() - 1) as u32,
+                generation: 0,
+            }
+        }
+    }
+    /// Removes the value behind `id` returning it if the
+    /// handle was still valid
+    pub(crate) fn remove(&mut self, id: WidgetId) -> Option<T> {
+        let slot = self.slots.get_mut(id.index as usize)?;
+        if slot.generation != id.generation {
+            return None;
+        }
+
+        let value = slot.value.take()?;
+        // Invalidate every handle issued for this slot
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(id.index);
+        self.len -= 1;
+
+        Some(value)
+    }
+    /// Returns the value behind `id` if the handle is still valid
+    pub(crate) fn get(&self, id: WidgetId) -> Option<&T> {
+        self.slots
+            .get(id.index as usize)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.value.as_ref())
+    }
+    /// Returns the value behind `id` mutably if the handle is still valid
+    pub(crate) fn get_mut(&mut self, id: WidgetId) -> Option<&mut T> {
+        self.slots
+            .get_mut(id.index as usize)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.value.as_mut())
+    }
+    /// Iterates over every live value and its handle
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (WidgetId, &T)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.value.as_ref().map(|value| {
+                (
+                    WidgetId {
+                        index: index as u32,
+                        generation: slot.generation,
+                    },
+                    value,
+                )
+            })
+        })
+    }
+    /// The amount of live values
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+}
+impl Arena<Node> {
+    /// Returns the widget behind `id` if the handle is still valid
+    pub(crate) fn widget(&self, id: WidgetId) -> Option<&Rc<dyn WidgetI>> {
+        self.get(id).map(|node| &node.widget)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Arena;
+
+    #[test]
+    fn test_stale_handles_do_not_resolve() {
+        let mut arena = Arena::new();
+        let a = arena.insert("a");
+        let b = arena.insert("b");
+
+        assert!(arena.remove(a) == Some("a"));
+        assert!(arena.get(a).is_none());
+        assert!(arena.remove(a).is_none());
+
+        // Reuses the slot of `a` without reviving its handle
+        let c = arena.insert("c");
+        assert!(arena.get(a).is_none());
+        assert!(arena.get(c) == Some(&"c"));
+        assert!(arena.get(b) == Some(&"b"));
+        assert!(arena.len() == 2);
+    }
+}
@@ -0,0 +1,402 @@
+//! Pixel data backing a `Canvas` grid.
+//!
+//! The `CanvasBuffer` keeps one `Color` per grid cell apart from the
+//! cell widgets so editors can read and paint pixels directly. Every
+//! pixel that changes is remembered until the canvas redraws it.
+
+use std::collections::BTreeSet;
+
+use tiny_skia::Pixmap;
+
+use super::color::Color;
+
+/// The `CanvasBuffer` struct is a 2D array of colors
+///
+/// Pixels are addressed by `x` (column) and `y` (row) starting
+/// from the top-left corner
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CanvasBuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+    dirty: BTreeSet<(usize, usize)>,
+}
+impl CanvasBuffer {
+    /// Create a new `CanvasBuffer` of `width x height` pixels
+    /// all set to `color`
+    pub fn new(width: usize, height: usize, color: Color) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![color; width * height],
+            dirty: BTreeSet::new(),
+        }
+    }
+    /// Decodes the bytes of a PNG into a buffer of its pixels
+    ///
+    /// Returns `None` if `png` is not a valid PNG
+    pub fn from_png(png: &[u8]) -> Option<Self> {
+        let pixmap = Pixmap::decode_png(png).ok()?;
+        let pixels = pixmap
+            .pixels()
+            .iter()
+            .map(|pixel| {
+                let color = pixel.demultiply();
+                Color::RGBA(color.red(), color.green(), color.blue(), color.alpha())
+            })
+            .collect();
+        Some(Self {
+            width: pixmap.width() as usize,
+            height: pixmap.height() as usize,
+            pixels,
+            dirty: BTreeSet::new(),
+        })
+    }
+    /// Builds a buffer of `width x height` pixels out of their
+    /// RGBA bytes laid out row by row
+    ///
+    /// Returns `None` if `rgba` holds another amount of pixels
+    pub fn from_rgba(width: usize, height: usize, rgba: &[u8]) -> Option<Self> {
+        if rgba.len() != width * height * 4 {
+            return None;
+        }
+
+        let pixels = rgba
+            .chunks_exact(4)
+            .map(|pixel| Color::RGBA(pixel[0], pixel[1], pixel[2], pixel[3]))
+            .collect();
+        Some(Self {
+            width,
+            height,
+            pixels,
+            dirty: BTreeSet::new(),
+        })
+    }
+    /// Returns the RGBA bytes of every pixel laid out row by row
+    pub fn to_rgba(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|pixel| <[u8; 4]>::from(*pixel))
+            .collect()
+    }
+    /// Returns the amount of columns
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    /// Returns the amount of rows
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    /// Returns the color at `(x, y)` or `None` when
+    /// out of bounds
+    pub fn get_pixel(&self, x: usize, y: usize) -> Option<Color> {
+        self.index(x, y).map(|i| self.pixels[i])
+    }
+    /// Set the color at `(x, y)`
+    ///
+    /// NoOp if `(x, y)` is out of bounds
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: Color) {
+        if let Some(i) = self.index(x, y) {
+            if self.pixels[i] != color {
+                self.pixels[i] = color;
+                self.dirty.insert((x, y));
+            }
+        }
+    }
+    /// Set every pixel to `color`
+    pub fn fill(&mut self, color: Color) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                self.set_pixel(x, y, color);
+            }
+        }
+    }
+    /// Set every pixel on the straight line from `from` to `to`
+    /// to `color`
+    ///
+    /// Ends may lie outside the buffer where the line is cut off
+    pub fn draw_line(&mut self, from: (isize, isize), to: (isize, isize), color: Color) {
+        // Bresenham steps one pixel at a time along the longer axis
+        let (dx, dy) = ((to.0 - from.0).abs(), -(to.1 - from.1).abs());
+        let (sx, sy) = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+        let (mut x, mut y) = from;
+        let mut error = dx + dy;
+        loop {
+            self.set_signed(x, y, color);
+            if (x, y) == to {
+                break;
+            }
+            let doubled = error * 2;
+            if doubled >= dy {
+                error += dy;
+                x += sx;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += sy;
+            }
+        }
+    }
+    /// Set the pixels along the edges of the rectangle with
+    /// the opposite corners `from` and `to` to `color`
+    ///
+    /// Corners may lie outside the buffer where the rectangle
+    /// is cut off
+    pub fn draw_rect(&mut self, from: (isize, isize), to: (isize, isize), color: Color) {
+        self.draw_line(from, (to.0, from.1), color);
+        self.draw_line((to.0, from.1), to, color);
+        self.draw_line(to, (from.0, to.1), color);
+        self.draw_line((from.0, to.1), from, color);
+    }
+    /// Set the pixels along the ellipse fitting the rectangle with
+    /// the opposite corners `from` and `to` to `color`
+    ///
+    /// Corners may lie outside the buffer where the ellipse
+    /// is cut off
+    pub fn draw_ellipse(&mut self, from: (isize, isize), to: (isize, isize), color: Color) {
+        let (left, right) = (from.0.min(to.0), from.0.max(to.0));
+        let (top, bottom) = (from.1.min(to.1), from.1.max(to.1));
+        let (cx, cy) = ((left + right) as f64 / 2.0, (top + bottom) as f64 / 2.0);
+        let (rx, ry) = ((right - left) as f64 / 2.0, (bottom - top) as f64 / 2.0);
+        if rx == 0.0 || ry == 0.0 {
+            return self.draw_rect(from, to, color);
+        }
+
+        // Sweeping along both axes leaves no gaps where
+        // the outline runs steep or flat
+        let reach = |offset: f64, radius: f64, other: f64| {
+            (other * (1.0 - (offset / radius).powi(2)).max(0.0).sqrt()).round() as isize
+        };
+        for x in left..=right {
+            let dy = reach(x as f64 - cx, rx, ry);
+            for y in [cy.floor() as isize - dy, cy.ceil() as isize + dy] {
+                self.set_signed(x, y, color);
+            }
+        }
+        for y in top..=bottom {
+            let dx = reach(y as f64 - cy, ry, rx);
+            for x in [cx.floor() as isize - dx, cx.ceil() as isize + dx] {
+                self.set_signed(x, y, color);
+            }
+        }
+    }
+    /// Replace the region of same colored pixels connected to `(x, y)`
+    /// horizontally or vertically with `color`
+    ///
+    /// NoOp if `(x, y)` is out of bounds
+    pub fn flood_fill(&mut self, x: usize, y: usize, color: Color) {
+        let Some(target) = self.get_pixel(x, y) else {
+            return;
+        };
+        if target == color {
+            return;
+        }
+
+        let mut stack = vec![(x, y)];
+        while let Some((x, y)) = stack.pop() {
+            if self.get_pixel(x, y) != Some(target) {
+                continue;
+            }
+            self.set_pixel(x, y, color);
+
+            if x > 0 {
+                stack.push((x - 1, y));
+            }
+            if y > 0 {
+                stack.push((x, y - 1));
+            }
+            stack.push((x + 1, y));
+            stack.push((x, y + 1));
+        }
+    }
+    /// Set every pixel of the `width x height` rectangle starting
+    /// at `(x, y)` to `color`
+    ///
+    /// Pixels past the edges of the buffer are left out
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Color) {
+        for y in y..(y + height).min(self.height) {
+            for x in x..(x + width).min(self.width) {
+                self.set_pixel(x, y, color);
+            }
+        }
+    }
+    /// Returns a copy of the `width x height` pixels starting
+    /// at `(x, y)`
+    ///
+    /// Pixels past the edges of the buffer are left out
+    pub fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> CanvasBuffer {
+        let width = width.min(self.width.saturating_sub(x));
+        let height = height.min(self.height.saturating_sub(y));
+        let pixels = (y..y + height)
+            .flat_map(|y| (x..x + width).filter_map(move |x| self.get_pixel(x, y)))
+            .collect();
+        CanvasBuffer {
+            width,
+            height,
+            pixels,
+            dirty: BTreeSet::new(),
+        }
+    }
+    /// Set the pixels starting at `(x, y)` to the colors
+    /// of `other`
+    ///
+    /// Pixels landing past the edges of the buffer are cut off
+    pub fn blit(&mut self, other: &CanvasBuffer, x: usize, y: usize) {
+        for oy in 0..other.height {
+            for ox in 0..other.width {
+                if let Some(color) = other.get_pixel(ox, oy) {
+                    self.set_pixel(x + ox, y + oy, color);
+                }
+            }
+        }
+    }
+    /// Sets every pixel to the color it has in `other`
+    /// of the same size
+    pub(crate) fn restore(&mut self, other: &CanvasBuffer) {
+        for y in 0..self.height.min(other.height) {
+            for x in 0..self.width.min(other.width) {
+                if let Some(color) = other.get_pixel(x, y) {
+                    self.set_pixel(x, y, color);
+                }
+            }
+        }
+    }
+    /// Takes the positions of the pixels changed since
+    /// the last call
+    pub(crate) fn take_dirty(&mut self) -> Vec<(usize, usize)> {
+        std::mem::take(&mut self.dirty).into_iter().collect()
+    }
+    /// Set the color at `(x, y)` which may lie before
+    /// the buffer
+    fn set_signed(&mut self, x: isize, y: isize, color: Color) {
+        if let (Ok(x), Ok(y)) = (usize::try_from(x), usize::try_from(y)) {
+            self.set_pixel(x, y, color);
+        }
+    }
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::color::{BLACK, RED, TRANSPARENT, WHITE};
+
+    use super::CanvasBuffer;
+
+    #[test]
+    fn test_set_pixel_marks_only_changes_dirty() {
+        let mut buffer = CanvasBuffer::new(2, 2, WHITE);
+
+        buffer.set_pixel(1, 0, RED);
+        buffer.set_pixel(0, 1, WHITE);
+        buffer.set_pixel(5, 5, RED);
+
+        assert_eq!(buffer.get_pixel(1, 0), Some(RED));
+        assert_eq!(buffer.get_pixel(5, 5), None);
+        assert_eq!(buffer.take_dirty(), vec![(1, 0)]);
+        assert!(buffer.take_dirty().is_empty());
+    }
+
+    #[test]
+    fn test_rgba_bytes_round_trip() {
+        let mut buffer = CanvasBuffer::new(2, 1, WHITE);
+        buffer.set_pixel(1, 0, TRANSPARENT);
+
+        let rgba = buffer.to_rgba();
+        assert_eq!(rgba, [255, 255, 255, 255, 0, 0, 0, 0]);
+        assert_eq!(
+            CanvasBuffer::from_rgba(2, 1, &rgba).map(|copy| copy.pixels),
+            Some(buffer.pixels)
+        );
+
+        // The size has to match the bytes
+        assert!(CanvasBuffer::from_rgba(1, 1, &rgba).is_none());
+    }
+
+    #[test]
+    fn test_draw_line_leaves_no_gaps() {
+        let mut buffer = CanvasBuffer::new(5, 3, WHITE);
+
+        buffer.draw_line((-1, 0), (4, 2), RED);
+
+        let mut dirty = buffer.take_dirty();
+        dirty.sort_by_key(|(x, _)| *x);
+        assert_eq!(dirty, vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]);
+    }
+
+    #[test]
+    fn test_draw_ellipse_is_closed() {
+        let mut buffer = CanvasBuffer::new(7, 5, WHITE);
+
+        buffer.draw_ellipse((6, 4), (0, 0), RED);
+
+        for (x, y) in [(0, 2), (6, 2), (3, 0), (3, 4)] {
+            assert_eq!(buffer.get_pixel(x, y), Some(RED));
+        }
+        assert_eq!(buffer.get_pixel(3, 2), Some(WHITE));
+        assert_eq!(buffer.get_pixel(0, 0), Some(WHITE));
+        // Every pixel of the outline touches the next one
+        for (x, y) in buffer.take_dirty() {
+            let neighbors = (x.saturating_sub(1)..=x + 1)
+                .flat_map(|nx| (y.saturating_sub(1)..=y + 1).map(move |ny| (nx, ny)))
+                .filter(|&(nx, ny)| (nx, ny) != (x, y) && buffer.get_pixel(nx, ny) == Some(RED))
+                .count();
+            assert!(neighbors >= 2);
+        }
+    }
+
+    #[test]
+    fn test_flood_fill_stops_at_borders() {
+        // W B W
+        // W B W
+        // W W B
+        let mut buffer = CanvasBuffer::new(3, 3, WHITE);
+        buffer.set_pixel(1, 0, BLACK);
+        buffer.set_pixel(1, 1, BLACK);
+        buffer.set_pixel(2, 2, BLACK);
+        buffer.take_dirty();
+
+        buffer.flood_fill(0, 0, RED);
+
+        assert_eq!(buffer.get_pixel(0, 0), Some(RED));
+        assert_eq!(buffer.get_pixel(0, 2), Some(RED));
+        assert_eq!(buffer.get_pixel(1, 2), Some(RED));
+        assert_eq!(buffer.get_pixel(2, 0), Some(WHITE));
+        assert_eq!(buffer.get_pixel(2, 2), Some(BLACK));
+        assert_eq!(buffer.take_dirty(), vec![(0, 0), (0, 1), (0, 2), (1, 2)]);
+    }
+
+    #[test]
+    fn test_crop_and_blit_are_cut_off_at_the_edges() {
+        let mut buffer = CanvasBuffer::new(3, 3, WHITE);
+        buffer.fill_rect(1, 1, 5, 5, RED);
+        assert_eq!(buffer.get_pixel(0, 0), Some(WHITE));
+        assert_eq!(buffer.get_pixel(2, 2), Some(RED));
+
+        let cropped = buffer.crop(1, 0, 4, 2);
+        assert_eq!((cropped.width(), cropped.height()), (2, 2));
+        assert_eq!(cropped.get_pixel(0, 0), Some(WHITE));
+        assert_eq!(cropped.get_pixel(1, 1), Some(RED));
+        assert_eq!(buffer.crop(3, 0, 1, 1).width(), 0);
+
+        let mut target = CanvasBuffer::new(3, 3, BLACK);
+        target.blit(&cropped, 2, 1);
+        assert_eq!(target.get_pixel(2, 1), Some(WHITE));
+        assert_eq!(target.get_pixel(2, 2), Some(RED));
+        assert_eq!(target.get_pixel(1, 1), Some(BLACK));
+    }
+
+    #[test]
+    fn test_png_pixels_are_decoded() {
+        let mut pixmap = tiny_skia::Pixmap::new(2, 1).unwrap();
+        pixmap.pixels_mut()[0] = tiny_skia::ColorU8::from_rgba(255, 0, 0, 255).premultiply();
+        let png = pixmap.encode_png().unwrap();
+
+        let buffer = CanvasBuffer::from_png(&png).unwrap();
+        assert_eq!((buffer.width(), buffer.height()), (2, 1));
+        assert_eq!(buffer.get_pixel(0, 0), Some(RED));
+        assert_eq!(buffer.get_pixel(1, 0), Some(TRANSPARENT));
+        assert!(CanvasBuffer::from_png(b"not a png").is_none());
+    }
+}
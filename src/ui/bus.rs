@@ -0,0 +1,180 @@
+//! A typed message bus between app logic and widgets.
+//!
+//! Background threads send their own domain messages through an
+//! [`EventBus`] handed out by the `DOM` while widgets subscribe to the
+//! types of messages they care about. Messages are queued until the
+//! `DOM` drains the bus on the UI thread and hands every message to
+//! the handlers subscribed to its type.
+//!
+//! ## Example
+//! ```ignore
+//! enum AppMsg {
+//!     DataLoaded(Vec<String>),
+//! }
+//!
+//! let mut dom = DOM::new(800, 600);
+//! let status = dom.add_widget(Label::new());
+//! dom.subscribe(status, |widget, msg: &AppMsg| match msg {
+//!     AppMsg::DataLoaded(rows) => {
+//!         widget.base_mut().text.label = format!("{} rows", rows.len());
+//!     }
+//! });
+//!
+//! let bus = dom.event_bus();
+//! std::thread::spawn(move || bus.send(AppMsg::DataLoaded(load())));
+//! ```
+
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    rc::Rc,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+};
+
+use super::{
+    arena::WidgetId,
+    sync::{Proxy, Signal},
+    widget::WidgetI,
+};
+
+type Message = Box<dyn Any + Send>;
+type Handler = Rc<dyn Fn(Rc<dyn WidgetI>, &dyn Any)>;
+
+/// The `EventBus` struct sends messages from any thread
+/// to the widgets subscribed to their type
+#[derive(Clone)]
+pub struct EventBus {
+    queue: Sender<Message>,
+    proxy: Arc<Mutex<Proxy>>,
+}
+impl EventBus {
+    /// Queues `msg` for the widgets subscribed to its type
+    ///
+    /// Messages nobody subscribed to are dropped once drained
+    pub fn send<M: Any + Send>(&self, msg: M) {
+        if self.queue.send(Box::new(msg)).is_ok() {
+            // Wakes the UI thread to drain the bus
+            let _ = self.proxy.lock().unwrap().send_event(Signal::Bus);
+        }
+    }
+}
+
+/// The `Subscriptions` struct holds the handlers of every
+/// widget by the type of message they subscribed to
+pub(crate) struct Subscriptions {
+    bus: Sender<Message>,
+    queue: Receiver<Message>,
+    handlers: HashMap<TypeId, Vec<(WidgetId, Handler)>>,
+}
+impl Subscriptions {
+    pub(crate) fn new() -> Self {
+        let (bus, queue) = mpsc::channel();
+        Self {
+            bus,
+            queue,
+            handlers: HashMap::new(),
+        }
+    }
+    /// Returns a bus sending messages into the queue
+    pub(crate) fn bus(&self, proxy: Arc<Mutex<Proxy>>) -> EventBus {
+        EventBus {
+            queue: self.bus.clone(),
+            proxy,
+        }
+    }
+    pub(crate) fn subscribe<M, F>(&mut self, id: WidgetId, handler: F)
+    where
+        M: Any,
+        F: Fn(Rc<dyn WidgetI>, &M) + 'static,
+    {
+        let handler: Handler = Rc::new(move |widget, msg| {
+            if let Some(msg) = msg.downcast_ref::<M>() {
+                handler(widget, msg);
+            }
+        });
+        self.handlers
+            .entry(TypeId::of::<M>())
+            .or_default()
+            .push((id, handler));
+    }
+    /// Removes every handler of the widget behind `id`
+    pub(crate) fn unsubscribe(&mut self, id: WidgetId) {
+        for handlers in self.handlers.values_mut() {
+            handlers.retain(|(widget, _)| *widget != id);
+        }
+        self.handlers.retain(|_, handlers| !handlers.is_empty());
+    }
+    /// Hands every queued message to the handlers subscribed to
+    /// its type along with their widget found by `widget`
+    ///
+    /// Returns the widgets that handled a message
+    pub(crate) fn drain<F>(&mut self, widget: F) -> Vec<Rc<dyn WidgetI>>
+    where
+        F: Fn(WidgetId) -> Option<Rc<dyn WidgetI>>,
+    {
+        let mut handled = Vec::new();
+        while let Ok(msg) = self.queue.try_recv() {
+            // Handlers may subscribe more widgets while running
+            let Some(handlers) = self.handlers.get(&(*msg).type_id()).cloned() else {
+                continue;
+            };
+            for (id, handler) in handlers {
+                if let Some(widget) = widget(id) {
+                    handler(widget.clone(), &*msg);
+                    handled.push(widget);
+                }
+            }
+        }
+        handled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        rc::Rc,
+        sync::{mpsc, Arc, Mutex},
+    };
+
+    use crate::ui::{
+        arena::Arena,
+        sync::Proxy,
+        widget::{label::Label, WidgetI},
+    };
+
+    use super::Subscriptions;
+
+    enum AppMsg {
+        Loaded(usize),
+    }
+
+    #[test]
+    fn messages_reach_subscribers_of_their_type() {
+        let mut arena = Arena::new();
+        let label: Rc<dyn WidgetI> = Rc::new(Label::new());
+        let id = arena.insert(label);
+
+        let (sender, _) = mpsc::channel();
+        let mut subscriptions = Subscriptions::new();
+        let bus = subscriptions.bus(Arc::new(Mutex::new(Proxy::Queue(sender))));
+        subscriptions.subscribe(id, |widget, msg: &AppMsg| match msg {
+            AppMsg::Loaded(rows) => widget.base_mut().text.label = format!("{rows} rows"),
+        });
+
+        std::thread::spawn(move || {
+            bus.send(AppMsg::Loaded(3));
+            bus.send("nobody listens");
+        })
+        .join()
+        .unwrap();
+        let handled = subscriptions.drain(|id| arena.get(id).cloned());
+        assert_eq!(handled.len(), 1);
+        assert_eq!(handled[0].base().text.label, "3 rows");
+
+        subscriptions.unsubscribe(id);
+        assert!(subscriptions.handlers.is_empty());
+    }
+}
@@ -1,3 +1,5 @@
+use super::theme::ThemeColor;
+
 pub const RED: Color = Color::RGBA(255, 0, 0, 255);
 pub const GREEN: Color = Color::RGBA(0, 255, 0, 255);
 pub const BLUE: Color = Color::RGBA(0, 0, 255, 255);
@@ -10,11 +12,15 @@ pub const TRANSPARENT: Color = Color::RGBA(0, 0, 0, 0);
 /// Represents the current color settings used for rendering.
 ///
 /// `ColorState` defines the base color and the mode
-/// in which it should be applied.
+/// in which it should be applied. A themed color takes its
+/// base color from the active `Theme`.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColorState {
     color: Color,
     mode: ColorMode,
+    #[cfg_attr(feature = "serde", serde(default))]
+    themed: Option<ThemeColor>,
 }
 impl ColorState {
     /// Create a new solid `Color`
@@ -24,10 +30,22 @@ impl ColorState {
             ..Default::default()
         }
     }
+    /// Returns the base color
+    pub fn color(&self) -> Color {
+        self.color
+    }
     /// Change the base color
     pub fn set_color(&mut self, color: Color) {
         self.color = color
     }
+    /// Returns the theme key the color follows if any
+    pub fn themed(&self) -> Option<ThemeColor> {
+        self.themed
+    }
+    /// Returns how the color is visually shown
+    pub(crate) fn mode(&self) -> ColorMode {
+        self.mode
+    }
     /// Change how to visually show the color
     pub(crate) fn set_mode(&mut self, mode: ColorMode) {
         self.mode = mode
@@ -52,6 +70,7 @@ impl From<Color> for ColorState {
 
 /// Determines how color is applied during rendering.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) enum ColorMode {
     #[default]
     /// Renders the color directly.
@@ -62,6 +81,63 @@ pub(crate) enum ColorMode {
     None,
 }
 
+/// A fill shading a widget with more than one color
+///
+/// Every stop places a color at an offset from `0.0` at the
+/// start to `1.0` at the end of the gradient
+///
+/// ## Example
+/// ```ignore
+/// Button::new().set_fill(Fill::LinearGradient {
+///     stops: vec![(0.0, RED), (1.0, BLUE)],
+///     angle: 90.0,
+/// });
+/// ```
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Fill {
+    /// Blends the stops along a line through the center of the
+    /// widget `angle` degrees clockwise from pointing up, so `0.0`
+    /// runs bottom to top and `90.0` left to right
+    LinearGradient { stops: Vec<(f32, Color)>, angle: f32 },
+    /// Blends the stops outwards from the center of the widget
+    /// to its corners
+    RadialGradient { stops: Vec<(f32, Color)> },
+}
+impl Fill {
+    /// Returns the color stops of the gradient
+    pub fn stops(&self) -> &[(f32, Color)] {
+        match self {
+            Fill::LinearGradient { stops, .. } | Fill::RadialGradient { stops } => stops,
+        }
+    }
+    /// Returns the same gradient with every stop color run through `f`
+    pub(crate) fn map_colors(&self, f: impl Fn(Color) -> Color) -> Fill {
+        let stops = self
+            .stops()
+            .iter()
+            .map(|(offset, color)| (*offset, f(*color)))
+            .collect();
+        match self {
+            Fill::LinearGradient { angle, .. } => Fill::LinearGradient {
+                stops,
+                angle: *angle,
+            },
+            Fill::RadialGradient { .. } => Fill::RadialGradient { stops },
+        }
+    }
+}
+
+/// Makes a color follow the `key` of the active theme
+///
+/// The color is transparent until a theme is set
+pub fn themed(key: ThemeColor) -> ColorState {
+    ColorState {
+        themed: Some(key),
+        ..Default::default()
+    }
+}
+
 /// Makes a color nonexistent useful for when a color
 /// need to show but needs a way to be ignored logically
 /// for some reason
@@ -73,6 +149,7 @@ pub fn none(color: Color) -> ColorState {
 
 /// Represents an RGB color using 8-bit red, green, and blue components.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     /// An RGB color in the form (red, green, blue).
     RGBA(u8, u8, u8, u8),
@@ -114,6 +191,35 @@ impl Color {
 
         [out_r, out_g, out_b, 255]
     }
+    /// Returns either black or white based on the perceived brightness of this color.
+    ///
+    /// This function calculates the luminance of the color using the
+    /// standard formula for relative luminance:
+    /// `luminance = 0.299 * R + 0.587 * G + 0.114 * B`
+    ///
+    pub(crate) fn contrast(&self) -> Color {
+        let (r, g, b): (u8, u8, u8) = (*self).into();
+        // Detect luminance
+        if 0.299 * (r as f32 / 255.0) + 0.587 * (g as f32 / 255.0) + 0.114 * (b as f32 / 255.0)
+            > 0.5
+        {
+            BLACK
+        } else {
+            WHITE
+        }
+    }
+    /// Mixes this color towards `other` where a `t` of `0.0` is
+    /// this color and `1.0` is `other`
+    pub(crate) fn lerp(&self, other: Color, t: f64) -> Color {
+        let (r, g, b, a): (u8, u8, u8, u8) = (*self).into();
+        let (or, og, ob, oa): (u8, u8, u8, u8) = other.into();
+        let mix = |from: u8, to: u8| -> u8 {
+            (from as f64 + (to as f64 - from as f64) * t)
+                .round()
+                .clamp(0.0, 255.0) as u8
+        };
+        Color::RGBA(mix(r, or), mix(g, og), mix(b, ob), mix(a, oa))
+    }
     /// Performs alpha blending of two RGBA colors.
     /// `top` is drawn over `bottom`.
     pub(crate) fn blend(bottom: Color, top: Color) -> Color {
@@ -138,6 +244,119 @@ impl Color {
             (out_a * 255.0).round().clamp(0.0, 255.0) as u8,
         )
     }
+    /// Create an opaque color from its hue in degrees along
+    /// with its saturation and value from `0.0` to `1.0`
+    pub fn from_hsv(h: f64, s: f64, v: f64) -> Color {
+        let (s, v) = (s.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+        let chroma = v * s;
+        Color::from_chroma(h, chroma, v - chroma)
+    }
+    /// Create an opaque color from its hue in degrees along
+    /// with its saturation and lightness from `0.0` to `1.0`
+    pub fn from_hsl(h: f64, s: f64, l: f64) -> Color {
+        let (s, l) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        Color::from_chroma(h, chroma, l - chroma / 2.0)
+    }
+    /// Parses a color written as `#RRGGBB` or `#RRGGBBAA`
+    ///
+    /// The leading `#` may be left out
+    ///
+    /// Returns `None` if `hex` is written any other way
+    pub fn from_hex(hex: &str) -> Option<Color> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| {
+            hex.get(i * 2..i * 2 + 2)
+                .map(|digits| u8::from_str_radix(digits, 16).ok())
+        };
+        Some(Color::RGBA(
+            channel(0)??,
+            channel(1)??,
+            channel(2)??,
+            channel(3).unwrap_or(Some(255))?,
+        ))
+    }
+    /// Returns the hue of the color in degrees along with
+    /// its saturation and value from `0.0` to `1.0`
+    pub fn to_hsv(&self) -> (f64, f64, f64) {
+        let (hue, max, min) = self.hue();
+        let saturation = if max > 0.0 { (max - min) / max } else { 0.0 };
+        (hue, saturation, max)
+    }
+    /// Returns the hue of the color in degrees along with
+    /// its saturation and lightness from `0.0` to `1.0`
+    pub fn to_hsl(&self) -> (f64, f64, f64) {
+        let (hue, max, min) = self.hue();
+        let lightness = (max + min) / 2.0;
+        let saturation = if max > min {
+            (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+        } else {
+            0.0
+        };
+        (hue, saturation, lightness)
+    }
+    /// Returns the color with its hue turned `degrees` around
+    /// the color wheel keeping its alpha
+    pub fn rotate_hue(&self, degrees: f64) -> Color {
+        let (h, s, l) = self.to_hsl();
+        Color::from_hsl(h + degrees, s, l).with_alpha_of(*self)
+    }
+    /// Returns the color made lighter by `amount` from `0.0`
+    /// to `1.0` keeping its hue and alpha
+    pub fn lighten(&self, amount: f64) -> Color {
+        let (h, s, l) = self.to_hsl();
+        Color::from_hsl(h, s, l + amount).with_alpha_of(*self)
+    }
+    /// Returns the color made darker by `amount` from `0.0`
+    /// to `1.0` keeping its hue and alpha
+    pub fn darken(&self, amount: f64) -> Color {
+        self.lighten(-amount)
+    }
+    /// Create an opaque color from its hue in degrees, its chroma
+    /// and the amount `m` added to every channel
+    fn from_chroma(h: f64, chroma: f64, m: f64) -> Color {
+        let h = h.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u8 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let channel = |c: f64| ((c + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+        Color::RGBA(channel(r), channel(g), channel(b), 255)
+    }
+    /// Returns the hue of the color in degrees along with its
+    /// largest and smallest channel from `0.0` to `1.0`
+    fn hue(&self) -> (f64, f64, f64) {
+        let (r, g, b): (u8, u8, u8) = (*self).into();
+        let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+
+        let hue = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        (hue, max, min)
+    }
+    /// Returns the color with the alpha of `other`
+    fn with_alpha_of(&self, other: Color) -> Color {
+        let (r, g, b): (u8, u8, u8) = (*self).into();
+        let (.., a): (u8, u8, u8, u8) = other.into();
+        Color::RGBA(r, g, b, a)
+    }
 }
 impl Default for Color {
     fn default() -> Self {
@@ -185,3 +404,41 @@ impl From<Color> for tiny_skia::Color {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Color, BLUE, GREEN, RED, WHITE};
+
+    #[test]
+    fn hsv_and_hsl_round_trip() {
+        let orange = Color::RGBA(255, 128, 0, 255);
+        let (h, s, v) = orange.to_hsv();
+        assert_eq!(Color::from_hsv(h, s, v), orange);
+        let (h, s, l) = orange.to_hsl();
+        assert_eq!(Color::from_hsl(h, s, l), orange);
+
+        assert_eq!(Color::from_hsv(120.0, 1.0, 1.0), GREEN);
+        assert_eq!(Color::from_hsl(240.0, 1.0, 0.5), BLUE);
+        assert_eq!(WHITE.to_hsl(), (0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn adjustments_keep_the_alpha() {
+        let red = Color::RGBA(255, 0, 0, 100);
+        assert_eq!(red.rotate_hue(120.0), Color::RGBA(0, 255, 0, 100));
+        assert_eq!(red.rotate_hue(-120.0), Color::RGBA(0, 0, 255, 100));
+        assert_eq!(red.lighten(0.5), Color::RGBA(255, 255, 255, 100));
+        assert_eq!(RED.darken(0.25), Color::RGBA(128, 0, 0, 255));
+    }
+
+    #[test]
+    fn hex_colors_are_parsed() {
+        let orange = Color::RGBA(255, 128, 0, 255);
+        assert_eq!(Color::from_hex("#ff8000"), Some(orange));
+        let translucent = Color::RGBA(255, 128, 0, 128);
+        assert_eq!(Color::from_hex("FF800080"), Some(translucent));
+        assert_eq!(Color::from_hex("#ff80"), None);
+        assert_eq!(Color::from_hex("#gg8000"), None);
+        assert_eq!(Color::from_hex("#ff80é0"), None);
+    }
+}
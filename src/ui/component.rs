@@ -0,0 +1,194 @@
+//! A module for composing reusable compound widgets.
+//!
+//! Applications often repeat the same small arrangements of widgets
+//! (a labeled input, a titled card, a palette row). The `Component`
+//! trait lets such an arrangement live behind a single struct that
+//! builds and owns its internal widget subtree while exposing its own
+//! typed API to the rest of the application.
+
+use std::{
+    any::Any,
+    cell::{Ref, RefMut},
+    rc::Rc,
+    sync::Arc,
+};
+
+use crate::{action::Action, render::Painter};
+
+use super::{
+    layout::Layout,
+    semantics::Role,
+    sync::{Thread, Trigger},
+    widget::{BaseWidget, Widget, WidgetHooks, WidgetI, WidgetInternal},
+};
+
+/// A trait representing a reusable compound widget.
+///
+/// Types that implement `Component` own a `root` widget which holds
+/// the whole internal subtree. Every `Component` is a `Widget` itself,
+/// so it can be passed to `DOM::add_widget` or `Container::add_widget`
+/// like any other widget, while the struct is free to expose its own
+/// methods on top.
+///
+/// ## Example
+/// ```ignore
+/// struct Card {
+///     root: Container,
+/// }
+/// impl Card {
+///     pub fn new(title: &str) -> Self {
+///         let mut root = Container::new().set_flex_layout(FlexLayout::Col);
+///         root.add_widget(Heading::new().set_small_heading().set_label(title));
+///         Self { root }
+///     }
+/// }
+/// impl Component for Card {
+///     type Root = Container;
+///     fn root(&self) -> &Container {
+///         &self.root
+///     }
+///     fn root_mut(&mut self) -> &mut Container {
+///         &mut self.root
+///     }
+/// }
+///
+/// dom.add_widget(Card::new("Palette").set_width(200.0));
+/// ```
+pub trait Component: 'static {
+    /// The widget sitting at the top of the component subtree
+    type Root: WidgetI + Default;
+    /// Returns an immutable reference to the root widget
+    fn root(&self) -> &Self::Root;
+    /// Returns a mutable reference to the root widget
+    fn root_mut(&mut self) -> &mut Self::Root;
+}
+
+impl<C: Component> Widget for C {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn action(&self) -> Ref<'_, Vec<Action>> {
+        self.root().action()
+    }
+    fn action_mut(&self) -> RefMut<'_, Vec<Action>> {
+        self.root().action_mut()
+    }
+    fn base(&self) -> Ref<'_, BaseWidget> {
+        self.root().base()
+    }
+    fn base_mut(&self) -> RefMut<'_, BaseWidget> {
+        self.root().base_mut()
+    }
+    fn emitter(&self) -> Option<&Arc<dyn Thread>> {
+        self.root().emitter()
+    }
+    fn connect<T: Thread + 'static>(mut self, emitter: T) -> Self {
+        let root = std::mem::take(self.root_mut());
+        *self.root_mut() = root.connect(emitter);
+        self
+    }
+}
+impl<C: Component> WidgetInternal for C {
+    fn internal_trigger(&self) -> Option<Rc<Trigger>> {
+        self.root().internal_trigger()
+    }
+    fn internal_trigger_mut(&self) -> RefMut<'_, Option<Rc<Trigger>>> {
+        self.root().internal_trigger_mut()
+    }
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<C>()
+    }
+}
+impl<C: Component> WidgetHooks for C {
+    fn children(&self) -> Vec<Rc<dyn WidgetI>> {
+        self.root().children()
+    }
+    fn hidden_children(&self) -> Vec<Rc<dyn WidgetI>> {
+        self.root().hidden_children()
+    }
+    fn removed_children(&self) -> Vec<Rc<dyn WidgetI>> {
+        self.root().removed_children()
+    }
+    fn custom_layout(&self) {
+        self.root().custom_layout()
+    }
+    fn custom_draw(&self, painter: &mut dyn Painter) {
+        self.root().custom_draw(painter)
+    }
+    fn custom_draw_over(&self, painter: &mut dyn Painter) {
+        self.root().custom_draw_over(painter)
+    }
+    fn children_clip(&self) -> Option<Layout> {
+        self.root().children_clip()
+    }
+    fn covers_children(&self, x: f64, y: f64) -> bool {
+        self.root().covers_children(x, y)
+    }
+    fn default_role(&self) -> Role {
+        self.root().default_role()
+    }
+    fn checked(&self) -> Option<bool> {
+        self.root().checked()
+    }
+    fn needs_layout(&self) -> bool {
+        self.root().needs_layout()
+    }
+    fn value(&self) -> Option<f32> {
+        self.root().value()
+    }
+    fn inner(&self) -> Option<&(dyn WidgetI + 'static)> {
+        Some(self.root())
+    }
+}
+impl<C: Component> WidgetI for C {}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::{
+        dom::DOM,
+        widget::{container::Container, label::Label, widget_id, Widget},
+    };
+
+    use super::Component;
+
+    struct Card {
+        root: Container,
+    }
+    impl Card {
+        fn new(title: &str) -> Self {
+            let mut root = Container::new();
+            root.add_widget(Label::new().set_id("title").set_label(title));
+            Self { root }
+        }
+        fn title(&self) -> String {
+            self.root.children.borrow()[0].base().text.label.clone()
+        }
+    }
+    impl Component for Card {
+        type Root = Container;
+        fn root(&self) -> &Container {
+            &self.root
+        }
+        fn root_mut(&mut self) -> &mut Container {
+            &mut self.root
+        }
+    }
+
+    #[test]
+    fn components_are_widgets_of_their_own_type() {
+        let mut dom = DOM::headless(200, 100);
+        let id = dom.add_widget(Card::new("Palette"));
+        let widget = dom.widget(id).unwrap();
+
+        let card = widget.downcast::<Card>().unwrap();
+        assert_eq!(card.title(), "Palette");
+        // The root is still reachable for what only it knows
+        assert!(widget.downcast::<Container>().is_some());
+        assert!(widget.downcast::<Label>().is_none());
+
+        // The tree goes on through the children of the root
+        let title = widget_id(&dom.get_widget_by_id("title").unwrap());
+        assert_eq!(dom.children(id), title.as_slice());
+        assert_eq!(title.and_then(|title| dom.parent(title)), Some(id));
+    }
+}
@@ -0,0 +1,320 @@
+//! Overlays for diagnosing widget trees.
+//!
+//! The frame overlay shows how long the last frame took to lay out and
+//! draw, how many frames were drawn over the last second and how much
+//! work went into them. Every region repainted by the last frame is
+//! outlined so widgets redrawing more than they should stand out. The
+//! overlay is toggled with F12 or `DOM::enable_debug_overlay`.
+//!
+//! The inspector started with `DOM::enable_inspector` works like the
+//! element picker of browser devtools. The widget under the pointer is
+//! highlighted along with the region its text sits in and the region it
+//! is clipped to. Clicking a widget logs what makes it up instead of
+//! clicking it.
+
+use std::{collections::VecDeque, fmt::Write, rc::Rc};
+
+use web_time::{Duration, Instant};
+
+use crate::render::Painter;
+
+use super::{
+    color::{Color, WHITE},
+    layout::{Layout, Point},
+    widget::{label::Label, widget_id, Widget, WidgetI},
+};
+
+/// The color the repainted regions are outlined in
+const OUTLINE: Color = Color::RGBA(255, 0, 255, 255);
+/// The color of the panels text is shown on
+const PANEL: Color = Color::RGBA(0, 0, 0, 200);
+/// The color inspected widgets are covered in
+const BOUNDS: Color = Color::RGBA(147, 196, 125, 120);
+/// The color the text of inspected widgets is covered in
+const CONTENT: Color = Color::RGBA(111, 168, 220, 140);
+/// The color the clip of inspected widgets is outlined in
+const CLIP: Color = Color::RGBA(255, 153, 0, 255);
+/// Where the frame statistics are shown
+const STATS_POSITION: Point = Point { x: 8.0, y: 8.0 };
+const LINE_HEIGHT: f64 = 16.0;
+const PADDING: f64 = 6.0;
+
+/// The `FrameStats` struct holds the work that
+/// went into drawing a frame
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FrameStats {
+    /// How long laying out and drawing the frame took
+    pub(crate) time: Duration,
+    /// How many times a top-level widget was drawn
+    pub(crate) draw_calls: usize,
+    /// How many widgets are part of the tree
+    pub(crate) widgets: usize,
+    /// The regions repainted or `None` when the whole
+    /// screen was
+    pub(crate) damaged: Option<Vec<Layout>>,
+}
+
+/// The `DebugOverlay` struct draws the frame statistics
+/// over the UI
+#[derive(Debug, Default)]
+pub(crate) struct DebugOverlay {
+    /// When the frames of the last second were drawn
+    frames: VecDeque<Instant>,
+    /// The regions drawn over by the last frame
+    drawn: Vec<Layout>,
+}
+impl DebugOverlay {
+    pub(crate) fn new() -> Self {
+        DebugOverlay::default()
+    }
+    /// Counts a frame drawn `at`
+    ///
+    /// Returns how many frames were drawn over the last second
+    pub(crate) fn record(&mut self, at: Instant) -> usize {
+        while self
+            .frames
+            .front()
+            .is_some_and(|frame| at.duration_since(*frame) >= Duration::from_secs(1))
+        {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(at);
+        self.frames.len()
+    }
+    /// Takes the regions the overlay drew over which have to
+    /// be repainted before it is drawn again
+    pub(crate) fn take_drawn(&mut self) -> Vec<Layout> {
+        std::mem::take(&mut self.drawn)
+    }
+    /// Draws the statistics of the frame drawn `at`
+    pub(crate) fn draw(&mut self, painter: &mut dyn Painter, stats: &FrameStats, at: Instant) {
+        let fps = self.record(at);
+
+        for rect in stats.damaged.iter().flatten() {
+            outline(painter, *rect, OUTLINE);
+            self.drawn.push(outline_rect(*rect));
+        }
+
+        let lines = [
+            format!("{fps} fps"),
+            format!("{:.2} ms", stats.time.as_secs_f64() * 1000.0),
+            format!("{} draw calls", stats.draw_calls),
+            format!("{} widgets", stats.widgets),
+        ];
+        self.drawn.push(panel(painter, STATS_POSITION, &lines));
+    }
+}
+
+/// The `Inspector` struct highlights the widget under
+/// the pointer
+#[derive(Default)]
+pub(crate) struct Inspector {
+    /// The widget under the pointer along with the
+    /// region it is clipped to
+    hovered: Option<(Rc<dyn WidgetI>, Option<Layout>)>,
+    /// The regions drawn over by the last frame
+    drawn: Vec<Layout>,
+}
+impl Inspector {
+    pub(crate) fn new() -> Self {
+        Inspector::default()
+    }
+    /// Returns the widget under the pointer
+    pub(crate) fn hovered(&self) -> Option<&Rc<dyn WidgetI>> {
+        self.hovered.as_ref().map(|(widget, _)| widget)
+    }
+    /// Highlights `widget` clipped to `clip` from now on
+    ///
+    /// Returns `true` if another widget was highlighted before
+    pub(crate) fn hover(&mut self, widget: Option<Rc<dyn WidgetI>>, clip: Option<Layout>) -> bool {
+        let changed = match (self.hovered(), &widget) {
+            (Some(hovered), Some(widget)) => !Rc::ptr_eq(hovered, widget),
+            (hovered, widget) => hovered.is_some() != widget.is_some(),
+        };
+        self.hovered = widget.map(|widget| (widget, clip));
+        changed
+    }
+    /// Takes the regions the inspector drew over which have
+    /// to be repainted before it is drawn again
+    pub(crate) fn take_drawn(&mut self) -> Vec<Layout> {
+        std::mem::take(&mut self.drawn)
+    }
+    /// Draws the highlight of the hovered widget
+    pub(crate) fn draw(&mut self, painter: &mut dyn Painter) {
+        let Some((widget, clip)) = &self.hovered else {
+            return;
+        };
+        let base = widget.base();
+        let bounds = Layout {
+            x: base.offset.x + base.layout.x,
+            y: base.offset.y + base.layout.y,
+            ..base.layout
+        };
+        painter.fill_rect(bounds, BOUNDS);
+        self.drawn.push(bounds);
+
+        // Text is laid out inside the widget by its position
+        if !base.text.label.is_empty() {
+            let size = base.text.get_visible_dimensions();
+            let content = Layout {
+                x: bounds.x + base.text.pos.x,
+                y: bounds.y + base.text.pos.y,
+                w: size.x,
+                h: size.y,
+            };
+            painter.fill_rect(content, CONTENT);
+            self.drawn.push(content);
+        }
+        if let Some(clip) = clip {
+            outline(painter, *clip, CLIP);
+            self.drawn.push(outline_rect(*clip));
+        }
+
+        // The name tag sits above the widget unless there is
+        // no room left
+        let name = format!(
+            "{} {}x{}",
+            widget.type_name(),
+            base.layout.w.round(),
+            base.layout.h.round()
+        );
+        let above = bounds.y - LINE_HEIGHT - PADDING * 2.0;
+        let at = Point::new(
+            bounds.x,
+            if above < 0.0 {
+                bounds.y + bounds.h
+            } else {
+                above
+            },
+        );
+        self.drawn.push(panel(painter, at, &[name]));
+    }
+}
+
+/// Describes `widget` the way the inspector logs it
+pub(crate) fn describe(widget: &Rc<dyn WidgetI>) -> String {
+    let base = widget.base();
+    let actions: Vec<_> = widget.action().iter().map(|action| action.name()).collect();
+
+    let mut description = widget.type_name().to_string();
+    if !base.id.is_empty() {
+        let _ = write!(description, " #{}", base.id);
+    }
+    if let Some(id) = widget_id(widget) {
+        let _ = write!(description, " ({id:?})");
+    }
+    let _ = write!(
+        description,
+        "\n  layout: {:?}\n  offset: {:?}\n  style: {:?}\n  state: {:?}\n  actions: {:?}",
+        base.layout, base.offset, base.style, base.state, actions
+    );
+    if !base.text.label.is_empty() {
+        let _ = write!(description, "\n  text: {:?}", base.text.label);
+    }
+    description
+}
+
+/// Draws `lines` of white text on a dark panel at `at`
+///
+/// Returns the region the panel covers
+fn panel(painter: &mut dyn Painter, at: Point, lines: &[String]) -> Layout {
+    let labels: Vec<_> = lines
+        .iter()
+        .map(|line| {
+            Label::new()
+                .set_label(line)
+                .set_label_color(WHITE)
+                .set_label_size(12.0)
+        })
+        .collect();
+    let width = labels
+        .iter()
+        .map(|label| label.base().text.get_true_dimensions().x)
+        .fold(0.0, f64::max);
+    let rect = Layout {
+        x: at.x,
+        y: at.y,
+        w: width + PADDING * 2.0,
+        h: lines.len() as f64 * LINE_HEIGHT + PADDING * 2.0,
+    };
+
+    painter.fill_rect(rect, PANEL);
+    for (i, label) in labels.into_iter().enumerate() {
+        let label = label
+            .set_x(rect.x + PADDING)
+            .set_y(rect.y + PADDING + i as f64 * LINE_HEIGHT)
+            .set_width(width)
+            .set_height(LINE_HEIGHT);
+        painter.draw_widget(&label);
+    }
+    rect
+}
+
+/// Strokes the edges of `rect` in `color`
+fn outline(painter: &mut dyn Painter, rect: Layout, color: Color) {
+    let corners = [
+        Point::new(rect.x, rect.y),
+        Point::new(rect.x + rect.w, rect.y),
+        Point::new(rect.x + rect.w, rect.y + rect.h),
+        Point::new(rect.x, rect.y + rect.h),
+    ];
+    for (i, from) in corners.iter().enumerate() {
+        painter.stroke_line(*from, corners[(i + 1) % 4], 1.0, color);
+    }
+}
+
+/// Returns the region the outline of `rect` covers
+fn outline_rect(rect: Layout) -> Layout {
+    // The stroke reaches beyond the edges
+    Layout {
+        x: rect.x - 1.0,
+        y: rect.y - 1.0,
+        w: rect.w + 2.0,
+        h: rect.h + 2.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use web_time::{Duration, Instant};
+
+    use crate::ui::{
+        dom::DOM,
+        widget::{checkbox::Checkbox, Widget},
+    };
+
+    use super::{describe, DebugOverlay};
+
+    #[test]
+    fn only_frames_of_the_last_second_are_counted() {
+        let mut overlay = DebugOverlay::new();
+        let start = Instant::now();
+        for i in 0..30 {
+            overlay.record(start + Duration::from_millis(i * 50));
+        }
+        // Frames up to 0.5s fell out of the window
+        assert_eq!(overlay.record(start + Duration::from_millis(1500)), 20);
+    }
+
+    #[test]
+    fn inspected_widgets_are_logged_instead_of_clicked() {
+        let mut dom = DOM::headless(100, 100);
+        let id = dom.add_widget(Checkbox::new().set_id("agree").set_x(10.0).set_y(10.0));
+        let widget = dom.widget(id).unwrap();
+        let is_checked = || {
+            let checkbox = widget.as_any().downcast_ref::<Checkbox>().unwrap();
+            checkbox.is_checked()
+        };
+
+        dom.enable_inspector();
+        dom.click(15.0, 15.0);
+        assert!(!is_checked());
+        let description = describe(&widget);
+        assert!(description.starts_with("Checkbox #agree"));
+        assert!(description.contains(r#"actions: ["Click", "Key"]"#));
+
+        dom.disable_inspector();
+        dom.click(15.0, 15.0);
+        assert!(is_checked());
+    }
+}
@@ -1,24 +1,67 @@
+#[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+use std::path::Path;
 use std::{
+    any::Any,
     collections::HashMap,
     rc::Rc,
-    sync::{Arc, Mutex},
+    sync::{
+        mpsc::{self, Receiver},
+        Arc, Mutex,
+    },
 };
 
-use log::debug;
 use pixels::{Pixels, SurfaceTexture};
-use rand::Rng as _;
+use web_time::{Duration, Instant};
 use winit::{
-    dpi::{LogicalSize, PhysicalPosition},
-    event::{Event, WindowEvent},
-    event_loop::{EventLoop, EventLoopBuilder, EventLoopProxy},
-    window::{Window, WindowBuilder},
+    dpi::{PhysicalPosition, PhysicalSize},
+    event::{
+        DeviceId, ElementState, Event, Ime, KeyEvent, MouseButton, MouseScrollDelta, WindowEvent,
+    },
+    event_loop::{ControlFlow, EventLoop, EventLoopBuilder, EventLoopWindowTarget},
+    keyboard::{Key, ModifiersState, NamedKey},
+    window::{CursorIcon, Fullscreen, UserAttentionType, Window, WindowId},
 };
 
-use crate::render::{pixels_backend::PixelsRenderer, pre::PreRenderer, Renderer};
+use crate::{
+    action::{
+        pointer::{self, Phase, PointerEvent},
+        scroll::DEFAULT_SCROLL_STEP,
+        Action,
+    },
+    app::Program,
+    clipboard::Clipboard,
+    error::{Error, ErrorHook},
+    render::{
+        damage::Damage, headless::HeadlessRenderer, pixels_backend::PixelsRenderer,
+        pre::PreRenderer, Renderer,
+    },
+    trace::{debug, span, widget_span},
+};
+
+#[cfg(target_os = "android")]
+use winit::platform::android::activity::AndroidApp;
 
+#[cfg(feature = "accesskit")]
+use super::accessibility::{self, Accessibility};
+#[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+use super::reload::{LoadError, UiFile};
 use super::{
-    sync::{Signal, Trigger, UID},
-    widget::{canvas::Canvas, container::Container, Widget, WidgetI},
+    arena::{Arena, Node, WidgetId},
+    bus::{EventBus, Subscriptions},
+    debug::{self, DebugOverlay, FrameStats, Inspector},
+    focus::FocusManager,
+    hit::HitIndex,
+    layout::{Insets, Layout, Point},
+    mobile::{self, TouchPointer},
+    reconcile::{self, Changes},
+    select::SelectionManager,
+    sync::{Emitter, Proxy, Signal, Thread, Trigger, WidgetCallback},
+    text::TextOverflow,
+    theme::Theme,
+    timer::{TimerId, TimerRequest, Timers},
+    tree::WidgetNode,
+    widget::{container::Container, list::ListView, widget_id, z_ordered, WidgetI},
+    window::{DOMBuilder, WindowRequest},
 };
 
 /// The main entry point for building and managing the UI tree.
@@ -28,78 +71,457 @@ use super::{
 /// - Handling input events (e.g., mouse movement)
 /// - Triggering redraws and layout updates
 pub struct DOM {
-    renderer: PixelsRenderer,
+    /// Missing while the app is suspended and has no surface to draw on
+    renderer: Option<Box<dyn Renderer>>,
     pre_renderer: PreRenderer,
-    window: Window,
-    event_loop: EventLoop<Signal>,
-    proxy: Arc<Mutex<EventLoopProxy<Signal>>>,
+    damage: Damage,
+    /// Missing for headless DOMs
+    window: Option<Window>,
+    /// The size of the screen of headless DOMs
+    size: PhysicalSize<u32>,
+    event_loop: Option<EventLoop<Signal>>,
+    proxy: Arc<Mutex<Proxy>>,
+    /// Signals sent to headless DOMs
+    queue: Option<Receiver<Signal>>,
     cursor_position: PhysicalPosition<f64>,
+    cursor_icon: CursorIcon,
+    touch: TouchPointer,
+    safe_area: Insets,
+    focus: FocusManager,
+    subscriptions: Subscriptions,
+    /// The app built with `App` updating the tree
+    program: Option<Box<dyn Program>>,
+    hits: HitIndex,
+    /// The widgets the last pointer event was sent to so the
+    /// ones the pointer left get to see it leave
+    pointed: Vec<Rc<dyn WidgetI>>,
+    /// The widgets under the pointer when the left button got
+    /// pressed which keep getting pointer events until released
+    captured: Vec<Rc<dyn WidgetI>>,
+    /// Shows the frame statistics over the UI while set
+    debug: Option<DebugOverlay>,
+    /// Picks widgets in place of the pointer while set
+    inspector: Option<Inspector>,
+    /// Whether widgets are drawn where they sit within
+    /// a pixel instead of on the nearest one
+    subpixel: bool,
+    /// Handed whatever went wrong while the event loop runs
+    errors: ErrorHook,
+    selection: SelectionManager,
+    modifiers: ModifiersState,
+    timers: Timers,
+    /// The theme `toggle_theme` switches back to
+    inactive_theme: Option<Theme>,
     nodes: Vec<Rc<dyn WidgetI>>,
-    nodes_ref: HashMap<usize, Rc<dyn WidgetI>>,
+    nodes_ref: Arena<Node>,
+    /// Maps the user given id of widgets to their handle
+    ids: HashMap<String, WidgetId>,
+    /// The file the tree is reloaded from when it changes
+    #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+    ui_file: Option<UiFile>,
+    /// Actions attached again by widget id whenever the tree reloads
+    #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+    bindings: HashMap<String, Vec<Rc<dyn Fn() -> Action>>>,
+    /// Missing for headless DOMs
+    #[cfg(feature = "accesskit")]
+    accessibility: Option<Accessibility>,
+    #[cfg(target_os = "android")]
+    android_app: Option<AndroidApp>,
 }
 impl DOM {
+    /// Creates a native window of `width` x `height` to host the UI
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the window can not be created,
+    /// see `DOM::try_new`
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
     pub fn new(width: u32, height: u32) -> Self {
+        DOM::builder().size(width, height).build()
+    }
+    /// Creates a native window of `width` x `height` to host the UI
+    ///
+    /// Returns an error if the platform refused to create
+    /// the window or a surface to draw on
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+    pub fn try_new(width: u32, height: u32) -> Result<Self, Error> {
+        DOM::builder().size(width, height).try_build()
+    }
+    /// Starts describing the native window hosting the UI
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let dom = DOM::builder()
+    ///     .title("Notes")
+    ///     .maximized()
+    ///     .decorations(false)
+    ///     .build();
+    /// ```
+    pub fn builder() -> DOMBuilder {
+        DOMBuilder::new()
+    }
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+    pub(crate) fn from_builder(builder: &DOMBuilder) -> Result<Self, Error> {
+        let event_loop = EventLoopBuilder::<Signal>::with_user_event().build()?;
+        let window = DOM::create_window(&event_loop, builder)?;
+        let renderer = DOM::create_renderer(&window)?;
+
+        Ok(DOM::from_parts(event_loop, window, Some(renderer)))
+    }
+    /// Creates the UI of the Android `app`
+    ///
+    /// The UI fills the whole screen and only starts drawing
+    /// once the activity is resumed
+    #[cfg(target_os = "android")]
+    pub fn new_android(app: AndroidApp) -> Result<Self, Error> {
+        use winit::platform::android::EventLoopBuilderExtAndroid;
+
         let event_loop = EventLoopBuilder::<Signal>::with_user_event()
-            .build()
-            .unwrap();
+            .with_android_app(app.clone())
+            .build()?;
+        let window = DOM::create_window(&event_loop, &DOMBuilder::new())?;
 
-        // Allow other threads to send info to
-        // main UI thread
-        let proxy = event_loop.create_proxy();
+        let mut dom = DOM::from_parts(event_loop, window, None);
+        dom.android_app = Some(app);
+        Ok(dom)
+    }
+    /// Creates a `width` x `height` canvas to host the UI inside
+    /// the web page
+    ///
+    /// The canvas is appended to the element with the `parent` id or
+    /// to the document body when no such element exists
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if called outside of a web page
+    #[cfg(target_arch = "wasm32")]
+    pub async fn new_web(width: u32, height: u32, parent: &str) -> Result<Self, Error> {
+        use winit::platform::web::WindowExtWebSys;
 
-        // Window to contain the application
-        let window = WindowBuilder::new()
-            .with_title("Gemini - UI Framework")
-            .with_inner_size(LogicalSize::new(width, height))
-            .build(&event_loop)
-            .unwrap();
+        let event_loop = EventLoopBuilder::<Signal>::with_user_event().build()?;
+        let window = DOM::create_window(&event_loop, &DOMBuilder::new().size(width, height))?;
+
+        // The canvas must be part of the page before the
+        // WebGL context can be requested
+        let canvas = window
+            .canvas()
+            .expect("window should be backed by a canvas");
+        let document = web_sys::window()
+            .and_then(|win| win.document())
+            .expect("should run inside a browser document");
+        document
+            .get_element_by_id(parent)
+            .map(|element| element.into())
+            .or_else(|| document.body().map(|body| body.into()))
+            .map(|element: web_sys::Element| element.append_child(&canvas))
+            .expect("should have an element to attach the canvas to")
+            .expect("canvas should be attached to the page");
 
         // Backend to render ui drawings
         let size = window.inner_size();
         let surface_texture = SurfaceTexture::new(size.width, size.height, &window);
-        let pixels = Pixels::new(size.width, size.height, surface_texture).unwrap();
+        let pixels = Pixels::new_async(size.width, size.height, surface_texture).await?;
+
+        Ok(DOM::from_parts(
+            event_loop,
+            window,
+            Some(PixelsRenderer::new(pixels)),
+        ))
+    }
+    fn create_window(
+        event_loop: &EventLoop<Signal>,
+        builder: &DOMBuilder,
+    ) -> Result<Window, Error> {
+        let window = builder
+            .window()
+            // Assistive technologies must be hooked up before
+            // the window is shown for the first time
+            .with_visible(false)
+            .build(event_loop)?;
+        Ok(window)
+    }
+    /// Backend to render ui drawings
+    #[cfg(not(target_arch = "wasm32"))]
+    fn create_renderer(window: &Window) -> Result<PixelsRenderer, Error> {
+        let size = window.inner_size();
+        let surface_texture = SurfaceTexture::new(size.width, size.height, window);
+        let pixels = Pixels::new(size.width, size.height, surface_texture)?;
+        Ok(PixelsRenderer::new(pixels))
+    }
+    /// Creates a DOM without a window which draws into memory
+    /// and is driven by `simulate` in place of a user
+    ///
+    /// Meant for testing how widgets react to input where no
+    /// display is available
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let mut dom = DOM::headless(800, 600);
+    /// let id = dom.add_widget(Checkbox::new());
+    /// dom.click(5.0, 5.0);
+    /// assert_eq!(dom.focused(), Some(id));
+    /// ```
+    pub fn headless(width: u32, height: u32) -> Self {
+        let (sender, queue) = mpsc::channel();
+        let renderer = HeadlessRenderer::new(width, height);
+
+        let mut dom = DOM::with_proxy(Proxy::Queue(sender), Some(Box::new(renderer)));
+        dom.size = PhysicalSize::new(width, height);
+        dom.queue = Some(queue);
+        dom
+    }
+    fn from_parts(
+        event_loop: EventLoop<Signal>,
+        window: Window,
+        renderer: Option<PixelsRenderer>,
+    ) -> Self {
+        // Allow other threads to send info to
+        // main UI thread
+        let proxy = event_loop.create_proxy();
+        let renderer = renderer.map(|renderer| Box::new(renderer) as Box<dyn Renderer>);
+        let mut dom = DOM::with_proxy(Proxy::EventLoop(proxy.clone()), renderer);
+
+        #[cfg(feature = "accesskit")]
+        {
+            dom.accessibility = Some(Accessibility::new(&window, proxy));
+        }
 
+        window.set_visible(true);
+        dom.window = Some(window);
+        dom.event_loop = Some(event_loop);
+        dom
+    }
+    fn with_proxy(proxy: Proxy, renderer: Option<Box<dyn Renderer>>) -> Self {
         Self {
             pre_renderer: PreRenderer::new(),
-            renderer: PixelsRenderer::new(pixels),
-            window,
+            damage: Damage::new(),
+            renderer,
+            window: None,
+            size: PhysicalSize::default(),
             nodes: Vec::default(),
-            event_loop,
+            event_loop: None,
             proxy: Arc::new(Mutex::new(proxy)),
+            queue: None,
             cursor_position: PhysicalPosition::default(),
-            nodes_ref: HashMap::default(),
+            cursor_icon: CursorIcon::Default,
+            touch: TouchPointer::default(),
+            safe_area: Insets::default(),
+            focus: FocusManager::new(),
+            subscriptions: Subscriptions::new(),
+            program: None,
+            hits: HitIndex::new(),
+            pointed: Vec::new(),
+            captured: Vec::new(),
+            debug: None,
+            subpixel: false,
+            inspector: None,
+            errors: ErrorHook::default(),
+            selection: SelectionManager::new(),
+            modifiers: ModifiersState::empty(),
+            timers: Timers::new(),
+            inactive_theme: None,
+            nodes_ref: Arena::new(),
+            ids: HashMap::new(),
+            #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+            ui_file: None,
+            #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+            bindings: HashMap::new(),
+            #[cfg(feature = "accesskit")]
+            accessibility: None,
+            #[cfg(target_os = "android")]
+            android_app: None,
+        }
+    }
+    /// Set the title shown in the title bar of the window
+    pub fn set_title(&self, title: &str) {
+        if let Some(window) = &self.window {
+            window.set_title(title);
+        }
+    }
+    /// Returns `true` if the window covers the whole screen
+    pub fn is_fullscreen(&self) -> bool {
+        self.window
+            .as_ref()
+            .is_some_and(|window| window.fullscreen().is_some())
+    }
+    /// Makes the window cover the whole screen it is on
+    /// or puts it back the way it was
+    pub fn set_fullscreen(&self, fullscreen: bool) {
+        if let Some(window) = &self.window {
+            window.set_fullscreen(fullscreen.then_some(Fullscreen::Borderless(None)));
+        }
+    }
+    /// Switches the window in and out of fullscreen
+    pub fn toggle_fullscreen(&self) {
+        self.set_fullscreen(!self.is_fullscreen());
+    }
+    /// Asks the platform to draw the attention of the user
+    /// to the window such as by flashing it in the taskbar
+    ///
+    /// Does nothing while the window is already focused
+    pub fn request_attention(&self) {
+        if let Some(window) = &self.window {
+            window.request_user_attention(Some(UserAttentionType::Informational));
+        }
+    }
+    /// Returns the theme widgets are styled with if any
+    pub fn theme(&self) -> Option<&Theme> {
+        self.pre_renderer.theme()
+    }
+    /// Restyles every widget with `theme`
+    ///
+    /// Only style fields never set on a widget are taken from
+    /// the theme
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.inactive_theme = self.pre_renderer.set_theme(theme);
+        self.damage.add_full();
+        self.request_redraw();
+    }
+    /// Swaps the active theme for the one active before it
+    ///
+    /// Switches between the built-in light and dark themes when
+    /// no other theme was set before
+    pub fn toggle_theme(&mut self) {
+        let theme = self.inactive_theme.unwrap_or_else(|| {
+            if self.theme().is_some_and(|theme| theme.is_dark()) {
+                Theme::light()
+            } else {
+                Theme::dark()
+            }
+        });
+        self.set_theme(theme);
+    }
+    /// Shows how long frames take to draw, the frame rate and
+    /// the regions every frame repainted over the UI
+    ///
+    /// Pressing F12 shows or hides the overlay as well
+    pub fn enable_debug_overlay(&mut self) {
+        if self.debug.is_none() {
+            self.debug = Some(DebugOverlay::new());
+            self.request_redraw();
+        }
+    }
+    /// Hides the overlay shown by `enable_debug_overlay`
+    pub fn disable_debug_overlay(&mut self) {
+        if self.debug.take().is_some() {
+            self.damage.add_full();
+            self.request_redraw();
+        }
+    }
+    /// Draws widgets where they sit within a pixel with
+    /// anti aliased edges
+    ///
+    /// Widgets are otherwise drawn from the nearest pixel which
+    /// makes them jitter while animating or scrolling by less
+    /// than a pixel every frame
+    pub fn enable_subpixel_rendering(&mut self) {
+        self.set_subpixel(true);
+    }
+    /// Draws widgets from the nearest pixel again
+    pub fn disable_subpixel_rendering(&mut self) {
+        self.set_subpixel(false);
+    }
+    fn set_subpixel(&mut self, subpixel: bool) {
+        if self.subpixel == subpixel {
+            return;
+        }
+        self.subpixel = subpixel;
+        if let Some(renderer) = &mut self.renderer {
+            renderer.set_subpixel(subpixel);
+        }
+        self.damage.add_full();
+        self.request_redraw();
+    }
+    /// Picks widgets with the pointer like the element picker
+    /// of browser devtools
+    ///
+    /// The widget under the pointer is highlighted along with the
+    /// region it is clipped to. Clicking a widget logs its id, type,
+    /// layout, style and actions instead of clicking it.
+    pub fn enable_inspector(&mut self) {
+        if self.inspector.is_none() {
+            self.inspector = Some(Inspector::new());
+        }
+    }
+    /// Hands the pointer back to the widgets
+    pub fn disable_inspector(&mut self) {
+        if self.inspector.take().is_some() {
+            self.damage.add_full();
+            self.request_redraw();
+        }
+    }
+    /// Hands every error the UI recovers from while running to
+    /// `hook` in place of logging it
+    ///
+    /// Frames that failed to draw are drawn anew on the next
+    /// redraw so apps may simply count or report them
+    ///
+    /// ## Example
+    /// ```ignore
+    /// dom.on_error(|err| eprintln!("ui: {err}"));
+    /// ```
+    pub fn on_error<F: FnMut(Error) + 'static>(&mut self, hook: F) {
+        self.errors = ErrorHook::new(hook);
+    }
+    /// Returns the space kept clear around the edges of the screen
+    pub fn safe_area(&self) -> Insets {
+        self.safe_area
+    }
+    /// Keeps widgets clear of the `insets` around the edges of
+    /// the screen
+    ///
+    /// The safe area is measured automatically on iOS. Other
+    /// platforms which draw below system bars must set it
+    /// themselves.
+    pub fn set_safe_area(&mut self, insets: Insets) {
+        let dx = insets.left - self.safe_area.left;
+        let dy = insets.top - self.safe_area.top;
+        self.safe_area = insets;
+
+        // Top-level widgets are positioned on the screen so
+        // moving them moves the whole tree into the safe area
+        for node in &self.nodes {
+            let mut base = node.base_mut();
+            base.layout.x += dx;
+            base.layout.y += dy;
         }
+        self.damage.add_full();
+        self.request_redraw();
     }
     /// Act on the widget apperance and behaviours based on the
     /// actions they subscribed to and only triggering action based
     /// on the actions logic
+    ///
+    /// The `hits` resolve what dragged widgets are dropped onto
     fn apply_actions(
         node: &Rc<dyn WidgetI>,
         event: Event<Signal>,
         cursor_pos: PhysicalPosition<f64>,
+        hits: &HitIndex,
     ) {
-        let mut actions = node.action_mut();
-        for action in actions.iter_mut() {
-            action.apply_action(node.trigger(), node, event.clone(), cursor_pos);
-        }
+        DOM::apply_widget_actions(node, event.clone(), cursor_pos, hits);
 
         // Child nodes are possible and must invoke any events as well
-        if let Some(canvas) = node.as_any().downcast_ref::<Canvas>() {
-            // Handle all grid cells of canvas
-            let grid = &*canvas.grid.borrow();
-            if let Some(grid) = grid {
-                grid.on_cell(|_, cell| {
-                    let mut actions = cell.action_mut();
-                    let cell: Rc<dyn WidgetI> = cell.clone();
-                    for action in actions.iter_mut() {
-                        action.apply_action(cell.trigger(), &cell, event.clone(), cursor_pos);
-                    }
-                });
-            }
-        } else if let Some(container) = node.as_any().downcast_ref::<Container>() {
-            for child in &container.children {
-                DOM::apply_actions(child, event.clone(), cursor_pos);
-            }
+        for child in node.children() {
+            DOM::apply_actions(&child, event.clone(), cursor_pos, hits);
+        }
+    }
+    /// Act on the actions of `node` alone leaving
+    /// its children untouched
+    fn apply_widget_actions(
+        node: &Rc<dyn WidgetI>,
+        event: Event<Signal>,
+        cursor_pos: PhysicalPosition<f64>,
+        hits: &HitIndex,
+    ) {
+        let mut actions = node.action_mut();
+        if actions.is_empty() {
+            return;
+        }
+        widget_span!("actions", node);
+        for action in actions.iter_mut() {
+            span!("action", name = action.name());
+            action.apply_action(node.trigger(), node, event.clone(), cursor_pos, hits);
         }
     }
     /// Widgets may need ui changes off thread
@@ -112,110 +534,1766 @@ impl DOM {
             emit.start(Trigger::new(self.proxy.clone(), widget.trigger().uid));
         }
 
-        if let Some(container) = widget.as_any().downcast_ref::<Container>() {
-            for child in &container.children {
-                self.apply_emitters(child);
+        for child in widget.children() {
+            self.apply_emitters(&child);
+        }
+    }
+    /// Starts the event loop and shows the UI
+    ///
+    /// Returns an error for headless DOMs or when the event
+    /// loop could not be run
+    pub fn run(mut self) -> Result<(), Error> {
+        let event_loop = self.event_loop.take().ok_or(Error::Headless)?;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        event_loop.run(move |event, target| self.handle_event(event, Some(target)))?;
+
+        // Browsers own the event loop so it can only be
+        // handed over without blocking
+        #[cfg(target_arch = "wasm32")]
+        {
+            use winit::platform::web::EventLoopExtWebSys as _;
+            event_loop.spawn(move |event, target| self.handle_event(event, Some(target)));
+        }
+        Ok(())
+    }
+    /// Lays out and draws the top-level `widgets` once into a
+    /// `width` x `height` buffer without opening a window
+    ///
+    /// Meant for testing how widgets look where no display
+    /// is available
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let button: Rc<dyn WidgetI> = Rc::new(Button::new().set_color(RED));
+    /// let frame = DOM::render_once_to_buffer(&[button], 200, 100)?;
+    /// assert_eq!(frame.pixel(5, 5), Some(RED));
+    /// ```
+    pub fn render_once_to_buffer(
+        widgets: &[Rc<dyn WidgetI>],
+        width: u32,
+        height: u32,
+    ) -> Result<HeadlessRenderer, Error> {
+        let viewport = Layout {
+            x: 0.0,
+            y: 0.0,
+            w: f64::from(width),
+            h: f64::from(height),
+        };
+        let pre_renderer = PreRenderer::new();
+        for widget in widgets {
+            pre_renderer.adjust(widget, viewport);
+        }
+
+        // Layers are drawn bottom to top
+        let mut renderer = HeadlessRenderer::new(width, height);
+        renderer.clear();
+        for widget in z_ordered(widgets) {
+            renderer.draw(&widget)?;
+        }
+        renderer.present()?;
+        Ok(renderer)
+    }
+    /// Handles `event` as if the window received it and lays out
+    /// and draws whatever it changed
+    ///
+    /// Meant for testing how widgets react to input together with
+    /// `DOM::headless`. Signals sent by triggers of a headless DOM
+    /// are handled before returning. Key presses can not be built
+    /// outside of winit so text is typed with `Ime` events instead.
+    ///
+    /// ## Example
+    /// ```ignore
+    /// dom.simulate(WindowEvent::Ime(Ime::Commit("hello".into())));
+    /// ```
+    pub fn simulate(&mut self, event: WindowEvent) {
+        // The tree must be laid out before anything can be hit
+        self.handle_event(self.simulated(WindowEvent::RedrawRequested), None);
+        self.handle_event(self.simulated(event), None);
+
+        if let Some(queue) = &self.queue {
+            let signals: Vec<_> = queue.try_iter().collect();
+            for signal in signals {
+                self.handle_event(Event::UserEvent(signal), None);
             }
         }
+        self.handle_event(self.simulated(WindowEvent::RedrawRequested), None);
     }
-    pub fn run(mut self) {
-        self.event_loop
-            .run(|event, target| {
-                // Handles core events that are always moinitored
-                // for functionality
-                match event {
-                    Event::WindowEvent { ref event, .. } => match event {
-                        // Updating and tracking cursor position
-                        WindowEvent::CursorMoved { position, .. } => {
-                            self.cursor_position = *position;
+    /// Moves the cursor to `x`, `y` as if the mouse moved there
+    pub fn move_cursor(&mut self, x: f64, y: f64) {
+        self.simulate(WindowEvent::CursorMoved {
+            device_id: DOM::simulated_device(),
+            position: PhysicalPosition::new(x, y),
+        });
+    }
+    /// Clicks the left mouse button at `x`, `y`
+    pub fn click(&mut self, x: f64, y: f64) {
+        self.move_cursor(x, y);
+        self.left_button(ElementState::Pressed);
+        self.left_button(ElementState::Released);
+    }
+    /// Drags with the left mouse button held from `from`
+    /// to `to` in a single movement
+    pub fn drag(&mut self, from: Point, to: Point) {
+        self.move_cursor(from.x, from.y);
+        self.left_button(ElementState::Pressed);
+        self.move_cursor(to.x, to.y);
+        self.left_button(ElementState::Released);
+    }
+    /// Presses or releases the left mouse button where the cursor is
+    pub(crate) fn left_button(&mut self, state: ElementState) {
+        self.simulate(WindowEvent::MouseInput {
+            device_id: DOM::simulated_device(),
+            state,
+            button: MouseButton::Left,
+        });
+    }
+    /// Turns the mouse wheel `lines` down where the cursor is
+    ///
+    /// Negative `lines` scroll back up
+    pub fn scroll(&mut self, lines: f32) {
+        self.simulate(WindowEvent::MouseWheel {
+            device_id: DOM::simulated_device(),
+            delta: MouseScrollDelta::LineDelta(0.0, -lines),
+            phase: winit::event::TouchPhase::Moved,
+        });
+    }
+    /// Types `text` into the focused widget
+    pub fn type_text(&mut self, text: &str) {
+        self.simulate(WindowEvent::Ime(Ime::Commit(text.to_string())));
+    }
+    /// Wraps `event` as if the window received it
+    fn simulated(&self, event: WindowEvent) -> Event<Signal> {
+        Event::WindowEvent {
+            window_id: self.window_id(),
+            event,
+        }
+    }
+    fn window_id(&self) -> WindowId {
+        match &self.window {
+            Some(window) => window.id(),
+            // SAFETY: the dummy window is only used to tag events
+            // that never reach winit
+            None => unsafe { WindowId::dummy() },
+        }
+    }
+    fn simulated_device() -> DeviceId {
+        // SAFETY: the dummy device is only used to tag events
+        // that never reach winit
+        unsafe { DeviceId::dummy() }
+    }
+    fn handle_event(
+        &mut self,
+        event: Event<Signal>,
+        target: Option<&EventLoopWindowTarget<Signal>>,
+    ) {
+        // Handles core events that are always moinitored
+        // for functionality
+        match event {
+            // The drawing surface only exists while the app is in
+            // the foreground on phones
+            Event::Resumed => {
+                #[cfg(not(target_arch = "wasm32"))]
+                if let (None, Some(window)) = (&self.renderer, &self.window) {
+                    match DOM::create_renderer(window) {
+                        Ok(mut renderer) => {
+                            renderer.set_subpixel(self.subpixel);
+                            self.renderer = Some(Box::new(renderer));
                         }
-                        // Handle for closing window
-                        WindowEvent::CloseRequested => target.exit(),
-                        // Draw all nodes on the display
-                        WindowEvent::RedrawRequested => {
-                            self.renderer.clear();
+                        Err(err) => self.errors.report(Err(err)),
+                    }
+                }
+                self.damage.add_full();
+                if let Some(insets) = self.window.as_ref().and_then(mobile::safe_area) {
+                    self.set_safe_area(insets);
+                }
+                self.request_redraw();
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Event::Suspended => self.renderer = None,
+            Event::WindowEvent {
+                window_id,
+                ref event,
+            } => {
+                #[cfg(feature = "accesskit")]
+                if let (Some(accessibility), Some(window)) = (&self.accessibility, &self.window) {
+                    accessibility.process_event(window, event);
+                }
+                // The pointer picks widgets while inspecting
+                if self.inspector.is_some() && self.inspect(event) {
+                    return;
+                }
 
+                match event {
+                    // Updating and tracking cursor position
+                    WindowEvent::CursorMoved { position, .. } => {
+                        self.cursor_position = *position;
+                    }
+                    // Widgets only know the mouse so touches
+                    // get replayed as pointer events
+                    WindowEvent::Touch(touch) => {
+                        for event in self.touch.pointer_events(window_id, touch) {
+                            self.handle_event(event, target);
+                        }
+                    }
+                    WindowEvent::ModifiersChanged(modifiers) => {
+                        self.modifiers = modifiers.state();
+                    }
+                    // Clicking moves focus to whatever focusable
+                    // widget is under the cursor
+                    WindowEvent::MouseInput {
+                        state: ElementState::Pressed,
+                        button: MouseButton::Left,
+                        ..
+                    } => {
+                        let widget = self
+                            .hit_test()
+                            .into_iter()
+                            .rev()
+                            .find(|widget| widget.is_focusable());
+                        self.set_focus(widget);
+                    }
+                    WindowEvent::KeyboardInput { event: key, .. }
+                        if key.state == ElementState::Pressed
+                            && key.logical_key == Key::Named(NamedKey::F12) =>
+                    {
+                        match self.debug {
+                            Some(_) => self.disable_debug_overlay(),
+                            None => self.enable_debug_overlay(),
+                        }
+                        return;
+                    }
+                    // Tab is reserved for moving focus and never
+                    // reaches the widgets
+                    WindowEvent::KeyboardInput { event: key, .. }
+                        if key.state == ElementState::Pressed
+                            && key.logical_key == Key::Named(NamedKey::Tab) =>
+                    {
+                        self.traverse_focus(self.modifiers.shift_key());
+                        return;
+                    }
+                    // Copies the selected text while the focused
+                    // widget still gets the shortcut
+                    WindowEvent::KeyboardInput { event: key, .. } if self.is_shortcut(key, "c") => {
+                        if let Some(text) = self.selection.selected_text() {
+                            Clipboard::set_text(&text);
+                        }
+                    }
+                    // Phones resize when rotated
+                    WindowEvent::Resized(size) => {
+                        if let Some(renderer) = &mut self.renderer {
+                            self.errors.report(renderer.resize(size.width, size.height));
+                        }
+                        self.size = *size;
+                        self.damage.add_full();
+                        if let Some(insets) = self.window.as_ref().and_then(mobile::safe_area) {
+                            self.set_safe_area(insets);
+                        }
+                    }
+                    // Handle for closing window
+                    WindowEvent::CloseRequested => {
+                        if let Some(target) = target {
+                            target.exit();
+                        }
+                    }
+                    // Draw all nodes on the display
+                    WindowEvent::RedrawRequested => {
+                        span!("frame");
+                        let started = Instant::now();
+                        let viewport = self.viewport();
+                        if self.renderer.is_some() {
+                            span!("layout");
+                            let mut arranged = false;
                             for node in &self.nodes {
-                                self.pre_renderer.adjust(node);
-                                self.renderer.draw(node);
+                                arranged |= self.pre_renderer.adjust(node, viewport);
+                            }
+                            // Only a new layout moves widgets around
+                            if arranged {
+                                self.hits.invalidate();
+                            }
+                            // Children built while laid out join the tree
+                            for node in self.nodes.clone() {
+                                self.sync_children(&node);
                             }
-
-                            self.renderer.present();
                         }
-                        _ => (),
-                    },
-                    Event::UserEvent(ref signal) => match signal {
-                        Signal::Update(id) => {
-                            // We need to route the signals in a way to denote what
-                            // widget to target
-                            let widget = self.nodes_ref.get(id).unwrap();
+                        if let Some(renderer) = &mut self.renderer {
+                            span!("render");
+                            // What the overlays drew over is painted again
+                            if let Some(debug) = &mut self.debug {
+                                for rect in debug.take_drawn() {
+                                    self.damage.add_alongside(rect);
+                                }
+                            }
+                            if let Some(inspector) = &mut self.inspector {
+                                for rect in inspector.take_drawn() {
+                                    self.damage.add_alongside(rect);
+                                }
+                            }
+
+                            // Layers are drawn bottom to top
+                            let nodes = z_ordered(&self.nodes);
+                            let damaged = self.damage.take();
+                            let mut drawn = Ok(());
+                            match &damaged {
+                                // To save on performance we only need to
+                                // clean and redraw whats damaged
+                                Some(rects) => {
+                                    for rect in rects {
+                                        let (x, y, h, w) = (*rect).into();
+                                        renderer.dirty_clear(x, y, h, w);
 
-                            // To save on performance we only need to clean whats
-                            // targeted
-                            let (x, y, h, w) = widget.base().layout.into();
-                            self.renderer.dirty_clear(x, y, h, w);
+                                        for node in &nodes {
+                                            drawn = drawn.and(renderer.draw_region(node, *rect));
+                                        }
+                                    }
+                                }
+                                None => {
+                                    renderer.clear();
 
-                            self.renderer.draw(widget);
+                                    for node in &nodes {
+                                        drawn = drawn.and(renderer.draw(node));
+                                    }
+                                }
+                            }
 
-                            self.renderer.present();
+                            if let Some(inspector) = &mut self.inspector {
+                                drawn = drawn.and(
+                                    renderer.draw_overlay(&mut |painter| inspector.draw(painter)),
+                                );
+                            }
+                            if let Some(debug) = &mut self.debug {
+                                let stats = FrameStats {
+                                    time: started.elapsed(),
+                                    draw_calls: nodes.len()
+                                        * damaged.as_ref().map_or(1, |rects| rects.len()),
+                                    widgets: self.nodes_ref.len(),
+                                    damaged,
+                                };
+                                drawn = drawn.and(renderer.draw_overlay(&mut |painter| {
+                                    debug.draw(painter, &stats, started)
+                                }));
+                            }
 
-                            debug!("redrawing widget: {}", &widget.base().id);
+                            // Whatever was left out is drawn on the next frame
+                            let drawn = drawn.and(renderer.present());
+                            if drawn.is_err() {
+                                self.damage.add_full();
+                            }
+                            self.errors.report(drawn);
                         }
-                        Signal::Callback(sig) => {
-                            let (id, func) = sig;
-                            let widget = self.nodes_ref.get(id).unwrap();
+                        // Text being composed may have moved the caret
+                        self.place_ime_cursor();
 
-                            func(widget.clone());
+                        #[cfg(feature = "accesskit")]
+                        if let Some(accessibility) = &self.accessibility {
+                            accessibility.update(&self.nodes, self.focus.focused());
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            Event::UserEvent(ref signal) => match signal {
+                Signal::Update(id) => {
+                    // We need to route the signals in a way to denote what
+                    // widget to target
+                    // Triggers of removed widgets may still fire
+                    if let Some(widget) = self.nodes_ref.widget(*id) {
+                        self.damage.add(DOM::screen_rect(widget));
+                        self.request_redraw();
 
-                            let (x, y, h, w) = widget.base().layout.into();
-                            self.renderer.dirty_clear(x, y, h, w);
+                        debug!("redrawing widget: {}", &widget.base().id);
+                    }
+                }
+                Signal::Invalidate(rect) => {
+                    self.damage.add(*rect);
+                    self.request_redraw();
+                }
+                Signal::Callback(sig) => {
+                    let (id, func) = sig;
+                    if let Some(widget) = self.nodes_ref.widget(*id).cloned() {
+                        self.run_callback(&widget, func.as_ref());
+                    }
+                }
+                Signal::CallbackById((id, func)) => {
+                    if let Some(widget) = self.get_widget_by_id(id) {
+                        self.run_callback(&widget, func.as_ref());
+                    }
+                }
+                #[cfg(feature = "accesskit")]
+                Signal::Accessibility(request) => {
+                    let widget = accessibility::request_target(request)
+                        .and_then(|id| self.nodes_ref.widget(id).cloned());
 
-                            self.renderer.draw(widget);
+                    if let Some(widget) = widget {
+                        match request.action {
+                            accesskit::Action::Focus if widget.is_focusable() => {
+                                self.set_focus(Some(widget.clone()))
+                            }
+                            accesskit::Action::Default => {
+                                // The request targets the widget even
+                                // when something else covers it
+                                self.hits.mark(std::slice::from_ref(&widget));
+                                for event in accessibility::click_events(self.window_id(), &widget)
+                                {
+                                    DOM::apply_widget_actions(
+                                        &widget,
+                                        event,
+                                        self.cursor_position,
+                                        &self.hits,
+                                    );
+                                }
+                            }
+                            _ => (),
+                        }
 
-                            self.renderer.present();
+                        debug!(
+                            "assistive request {:?} for widget: {}",
+                            request.action,
+                            &widget.base().id
+                        );
+                    }
+                }
+                Signal::SoftKeyboard(visible) => {
+                    #[cfg(target_os = "android")]
+                    if let Some(app) = &self.android_app {
+                        if *visible {
+                            app.show_soft_input(true);
+                        } else {
+                            app.hide_soft_input(false);
+                        }
+                    }
 
-                            debug!("callback then redrawing widget: {}", &widget.base().id);
+                    if let Some(window) = &self.window {
+                        window.set_ime_allowed(*visible);
+                    }
+                }
+                Signal::SetTimer(request) => self.timers.add(request.clone()),
+                Signal::ClearTimer(id) => self.timers.remove(*id),
+                // Pastes are handed straight to the focused widget
+                Signal::Paste(_) => (),
+                // The app runs once the event was handled
+                Signal::App => (),
+                #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+                Signal::Reload => self.reload(),
+                Signal::Bus => {
+                    let nodes_ref = &self.nodes_ref;
+                    let handled = self.subscriptions.drain(|id| nodes_ref.widget(id).cloned());
+                    for widget in &handled {
+                        self.damage.add(DOM::screen_rect(widget));
+                    }
+                    if !handled.is_empty() {
+                        self.request_redraw();
+                    }
+                }
+                Signal::Window(WindowRequest::Close) => {
+                    if let Some(target) = target {
+                        target.exit();
+                    }
+                }
+                Signal::Window(request) => {
+                    if let Some(window) = &self.window {
+                        match request {
+                            WindowRequest::Drag => {
+                                // Fails once the button was already released
+                                let _ = window.drag_window();
+                            }
+                            WindowRequest::Minimize => window.set_minimized(true),
+                            WindowRequest::ToggleMaximize => {
+                                window.set_maximized(!window.is_maximized())
+                            }
+                            // Closing ends the event loop above
+                            WindowRequest::Close => (),
+                        }
+                    }
+                }
+            },
+            // Timers run once all pending events were handled
+            // and put the loop to sleep until the next one is due
+            Event::AboutToWait => {
+                let nodes_ref = &self.nodes_ref;
+                let damage = &mut self.damage;
+                let window = &self.window;
+                self.timers.run_due(
+                    |id| nodes_ref.widget(id).cloned(),
+                    |widget| {
+                        damage.add(DOM::screen_rect(widget));
+                        if let Some(window) = window {
+                            window.request_redraw();
                         }
                     },
+                );
+
+                if let Some(target) = target {
+                    target.set_control_flow(match self.timers.next_deadline() {
+                        Some(deadline) => ControlFlow::WaitUntil(deadline),
+                        None => ControlFlow::Wait,
+                    });
+                }
+            }
+            _ => (),
+        }
+
+        match event {
+            // Keyboard input only goes to the focused widget
+            Event::WindowEvent {
+                event: ref input @ (WindowEvent::KeyboardInput { .. } | WindowEvent::Ime(_)),
+                ..
+            } => {
+                if let Some(widget) = self
+                    .focus
+                    .focused()
+                    .and_then(|id| self.nodes_ref.widget(id))
+                {
+                    let paste = match input {
+                        WindowEvent::KeyboardInput { event: key, .. }
+                            if self.is_shortcut(key, "v") =>
+                        {
+                            Clipboard::get_text()
+                        }
+                        _ => None,
+                    };
+                    DOM::apply_widget_actions(widget, event, self.cursor_position, &self.hits);
+
+                    // The pasted text follows the shortcut itself
+                    if let Some(text) = paste {
+                        let event = Event::UserEvent(Signal::Paste(text));
+                        DOM::apply_widget_actions(widget, event, self.cursor_position, &self.hits);
+                    }
+                }
+            }
+            // Pointer events are only meant for the topmost widget
+            // under the cursor which may have moved since scrolling
+            Event::WindowEvent {
+                event:
+                    ref pointer @ (WindowEvent::CursorMoved { .. }
+                    | WindowEvent::MouseInput { .. }
+                    | WindowEvent::MouseWheel { .. }),
+                ..
+            } => {
+                let path = self.hit_test();
+                self.propagate(&path, pointer);
+                match pointer {
+                    WindowEvent::CursorMoved { .. } => {
+                        self.update_cursor_icon(&path);
+                        self.selection.drag(self.cursor_position.x);
+                    }
+                    WindowEvent::MouseInput {
+                        state,
+                        button: MouseButton::Left,
+                        ..
+                    } => match state {
+                        ElementState::Pressed => {
+                            self.selection.press(&path, self.cursor_position.x)
+                        }
+                        ElementState::Released => self.selection.release(),
+                    },
+                    WindowEvent::MouseWheel { delta, .. } => {
+                        DOM::scroll_text(&path, delta);
+                        DOM::scroll_innermost(&path, delta);
+                    }
                     _ => (),
                 }
 
+                let released = matches!(
+                    pointer,
+                    WindowEvent::MouseInput {
+                        state: ElementState::Released,
+                        button: MouseButton::Left,
+                        ..
+                    }
+                );
+                if let WindowEvent::MouseInput {
+                    state: ElementState::Pressed,
+                    button: MouseButton::Left,
+                    ..
+                } = pointer
+                {
+                    self.captured = path.clone();
+                }
+                for widget in &self.pointer_targets(path) {
+                    DOM::apply_widget_actions(
+                        widget,
+                        event.clone(),
+                        self.cursor_position,
+                        &self.hits,
+                    );
+                }
+                if released {
+                    self.captured.clear();
+                }
+            }
+            _ => {
                 for node in &self.nodes {
-                    DOM::apply_actions(node, event.clone(), self.cursor_position);
+                    DOM::apply_actions(node, event.clone(), self.cursor_position, &self.hits);
                 }
-            })
-            .unwrap();
+            }
+        }
+
+        // Messages dispatched while handling the event update the app
+        if let Some(mut program) = self.program.take() {
+            program.process(self);
+            self.program = Some(program);
+        }
+    }
+    /// Runs `callback` on the widget behind `id` once
+    /// after `delay`
+    pub fn set_timeout<F: WidgetCallback>(
+        &mut self,
+        id: WidgetId,
+        delay: Duration,
+        callback: F,
+    ) -> TimerId {
+        self.set_timer(id, delay, false, callback)
+    }
+    /// Runs `callback` on the widget behind `id` every
+    /// `interval` until cleared
+    ///
+    /// Intervals shorter than `MIN_INTERVAL` are stretched to it
+    pub fn set_interval<F: WidgetCallback>(
+        &mut self,
+        id: WidgetId,
+        interval: Duration,
+        callback: F,
+    ) -> TimerId {
+        self.set_timer(id, interval, true, callback)
+    }
+    /// Cancels the timer behind `id`
+    pub fn clear_timer(&mut self, id: TimerId) {
+        self.timers.remove(id);
     }
-    fn add_widgets(&mut self, widget: Rc<dyn WidgetI>) {
+    fn set_timer<F: WidgetCallback>(
+        &mut self,
+        id: WidgetId,
+        delay: Duration,
+        repeat: bool,
+        callback: F,
+    ) -> TimerId {
+        let timer = TimerId::next();
+        self.timers.add(TimerRequest {
+            id: timer,
+            target: id,
+            delay,
+            repeat,
+            callback: Arc::new(callback),
+        });
+        timer
+    }
+    /// Runs `func` on `widget` then redraws it
+    fn run_callback(&mut self, widget: &Rc<dyn WidgetI>, func: &dyn WidgetCallback) {
+        // The callback may move the widget so both where it
+        // was and where it ends up are damaged
+        self.damage.add(DOM::screen_rect(widget));
+        func(widget.clone());
+        self.damage.add(DOM::screen_rect(widget));
+        self.request_redraw();
+
+        debug!("callback then redrawing widget: {}", &widget.base().id);
+    }
+    /// Whether `key` is pressed together with the control key,
+    /// or the command key on macOS, to form the shortcut `c`
+    fn is_shortcut(&self, key: &KeyEvent, c: &str) -> bool {
+        key.state == ElementState::Pressed
+            && (self.modifiers.control_key() || self.modifiers.super_key())
+            && matches!(&key.logical_key, Key::Character(k) if k.eq_ignore_ascii_case(c))
+    }
+    /// Highlights the widget under the pointer and logs the
+    /// widgets clicked while inspecting
+    ///
+    /// Returns `true` if the event was taken by the inspector
+    fn inspect(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::CursorMoved { position, .. } => {
+                self.cursor_position = *position;
+                let widget = self
+                    .hits
+                    .hit_path(&self.nodes, position.x, position.y)
+                    .pop();
+                let clip = widget.as_ref().and_then(|widget| self.hits.clip(widget));
+                if self
+                    .inspector
+                    .as_mut()
+                    .is_some_and(|inspector| inspector.hover(widget, clip))
+                {
+                    self.request_redraw();
+                }
+            }
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+                ..
+            } => {
+                if let Some(widget) = self.inspector.as_ref().and_then(Inspector::hovered) {
+                    log::info!("{}", debug::describe(widget));
+                }
+            }
+            WindowEvent::MouseInput { .. } | WindowEvent::MouseWheel { .. } => (),
+            _ => return false,
+        }
+        true
+    }
+    /// Resolves the topmost widget under the cursor and marks
+    /// which widgets pointer events are meant for
+    ///
+    /// Returns the widgets hit from the top-level one down
+    fn hit_test(&mut self) -> Vec<Rc<dyn WidgetI>> {
+        let (x, y) = (self.cursor_position.x, self.cursor_position.y);
+        let path = self.hits.hit_path(&self.nodes, x, y);
+        self.hits.mark(&path);
+        path
+    }
+    /// Returns the widgets a pointer event hitting `path` is
+    /// dispatched to, remembering them for the next one
+    ///
+    /// Only the widgets under the pointer get the event along with
+    /// the ones it just left and the ones holding the pressed button.
+    /// The widgets left come first so they see the pointer leave
+    /// before the others see it enter.
+    fn pointer_targets(&mut self, path: Vec<Rc<dyn WidgetI>>) -> Vec<Rc<dyn WidgetI>> {
+        let previous = std::mem::replace(&mut self.pointed, path.clone());
+        let contains = |widgets: &[Rc<dyn WidgetI>], widget: &Rc<dyn WidgetI>| {
+            widgets.iter().any(|other| Rc::ptr_eq(other, widget))
+        };
+
+        let mut targets: Vec<_> = previous
+            .into_iter()
+            .filter(|widget| !contains(&path, widget))
+            .collect();
+        targets.extend(path);
+        for widget in &self.captured {
+            if !contains(&targets, widget) {
+                targets.push(widget.clone());
+            }
+        }
+        targets
+    }
+    /// Sends the pointer `event` through the hit `path` and
+    /// narrows down the widgets the built-in actions react in
+    /// to the ones the event reached
+    fn propagate(&mut self, path: &[Rc<dyn WidgetI>], event: &WindowEvent) {
+        let Some(target) = path.last().and_then(widget_id) else {
+            return;
+        };
+        let position = Point::new(self.cursor_position.x, self.cursor_position.y);
+        let mut pointer = PointerEvent::new(event.clone(), position, target);
+
+        match pointer::propagate(path, &mut pointer) {
+            _ if pointer.is_default_prevented() => self.hits.mark(&[]),
+            // Stopped on the way down so the event ends there
+            Some((Phase::Capture, i)) => self.hits.mark(&path[..=i]),
+            // Stopped on the way up so it reaches no parent
+            Some((_, i)) => {
+                for widget in &path[..i] {
+                    widget.base_mut().state.hit = false;
+                }
+            }
+            None => (),
+        }
+    }
+    /// Moves the text of the widget under the cursor sideways
+    /// when it overflows with `TextOverflow::Scroll`
+    fn scroll_text(path: &[Rc<dyn WidgetI>], delta: &MouseScrollDelta) {
+        let Some(widget) = path.last() else {
+            return;
+        };
+        let mut base = widget.base_mut();
+        // The hit is cleared when a handler prevented the default
+        if !base.state.hit || base.text.overflow != TextOverflow::Scroll {
+            return;
+        }
+
+        let (dx, dy) = match delta {
+            MouseScrollDelta::LineDelta(x, y) => (
+                *x as f64 * DEFAULT_SCROLL_STEP,
+                *y as f64 * DEFAULT_SCROLL_STEP,
+            ),
+            MouseScrollDelta::PixelDelta(pos) => (pos.x, pos.y),
+        };
+        // Plain mouse wheels only scroll vertically
+        let amount = if dx != 0.0 { -dx } else { -dy };
+        let width = base.layout.w - base.text.pos.x;
+        let scroll = base.text.scroll;
+        base.text.scroll_by(amount, width);
+
+        if base.text.scroll != scroll {
+            drop(base);
+            widget.trigger().update();
+        }
+    }
+    /// Scrolls the innermost `ListView` or scrolling `Container`
+    /// under the cursor leaving the ones around it in place
+    fn scroll_innermost(path: &[Rc<dyn WidgetI>], delta: &MouseScrollDelta) {
+        for widget in path.iter().rev() {
+            if let Some(list) = widget.downcast::<ListView>() {
+                let amount = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => *y as f64 * DEFAULT_SCROLL_STEP,
+                    MouseScrollDelta::PixelDelta(pos) => pos.y,
+                };
+                list.scroll_by(-amount);
+                return;
+            }
+            if let Some(container) = widget.downcast::<Container>() {
+                let mut actions = widget.action_mut();
+                if let Some(scroll) = actions.iter_mut().find_map(Action::scroll_mut) {
+                    scroll.wheel(&widget.trigger(), container, delta);
+                    return;
+                }
+            }
+        }
+    }
+    /// Shows the cursor of the topmost widget in the hit `path`
+    fn update_cursor_icon(&mut self, path: &[Rc<dyn WidgetI>]) {
+        let (x, y) = (self.cursor_position.x, self.cursor_position.y);
+        // Scrollbars are drawn over the content of their container
+        let on_scrollbar = path
+            .last()
+            .is_some_and(|widget| widget.covers_children(x, y));
+        let icon = if on_scrollbar {
+            CursorIcon::Grab
+        } else {
+            path.iter()
+                .rev()
+                .find_map(|widget| widget.cursor_icon())
+                .unwrap_or_default()
+        };
+
+        if icon != self.cursor_icon {
+            self.cursor_icon = icon;
+            if let Some(window) = &self.window {
+                window.set_cursor_icon(icon);
+            }
+        }
+    }
+    /// Asks the window to be redrawn once the pending
+    /// events were handled
+    fn request_redraw(&self) {
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+    /// Returns the region of the window inside the safe
+    /// area top-level widgets are laid out in
+    fn viewport(&self) -> Layout {
+        let size = self.window.as_ref().map_or(self.size, Window::inner_size);
+        Layout {
+            x: self.safe_area.left,
+            y: self.safe_area.top,
+            w: size.width as f64 - self.safe_area.left - self.safe_area.right,
+            h: size.height as f64 - self.safe_area.top - self.safe_area.bottom,
+        }
+    }
+    /// Returns the rect `widget` covers on the screen
+    ///
+    /// Includes the shadow cast by the widget
+    fn screen_rect(widget: &Rc<dyn WidgetI>) -> Layout {
+        let base = widget.base();
+        let rect = Layout {
+            x: base.offset.x + base.layout.x,
+            y: base.offset.y + base.layout.y,
+            ..base.layout
+        };
+        let Some(shadow) = base.style.shadow else {
+            return rect;
+        };
+
+        let spread = shadow.spread();
+        let (left, top) = (
+            rect.x.min(rect.x + shadow.offset.x - spread),
+            rect.y.min(rect.y + shadow.offset.y - spread),
+        );
+        let (right, bottom) = (
+            (rect.x + rect.w).max(rect.x + rect.w + shadow.offset.x + spread),
+            (rect.y + rect.h).max(rect.y + rect.h + shadow.offset.y + spread),
+        );
+        Layout {
+            x: left,
+            y: top,
+            w: right - left,
+            h: bottom - top,
+        }
+    }
+    fn set_focus(&mut self, widget: Option<Rc<dyn WidgetI>>) {
+        let previous = self
+            .focus
+            .focused()
+            .and_then(|id| self.nodes_ref.widget(id).cloned());
+        // Text left composing is dropped along with the focus
+        if let Some(previous) = &previous {
+            if previous.base_mut().text.preedit.take().is_some() {
+                self.damage.add(DOM::screen_rect(previous));
+                self.request_redraw();
+            }
+        }
+        self.focus.set(previous, widget);
+
+        // Input methods are only on while a widget taking text is focused
+        if let Some(window) = &self.window {
+            let accepts_text = self
+                .focus
+                .focused()
+                .and_then(|id| self.nodes_ref.widget(id))
+                .is_some_and(|widget| widget.action().iter().any(Action::accepts_text));
+            window.set_ime_allowed(accepts_text);
+        }
+        self.place_ime_cursor();
+
+        #[cfg(feature = "accesskit")]
+        if let Some(accessibility) = &self.accessibility {
+            accessibility.update(&self.nodes, self.focus.focused());
+        }
+    }
+    /// Opens the candidate window of the input method
+    /// next to the caret of the focused widget
+    fn place_ime_cursor(&self) {
+        let Some(window) = &self.window else {
+            return;
+        };
+        let Some(widget) = self
+            .focus
+            .focused()
+            .and_then(|id| self.nodes_ref.widget(id))
+        else {
+            return;
+        };
+        if !widget.action().iter().any(Action::accepts_text) {
+            return;
+        }
+
+        let base = widget.base();
+        let scroll = match base.text.overflow {
+            TextOverflow::Scroll => base.text.scroll,
+            _ => 0.0,
+        };
+        let x =
+            base.offset.x + base.layout.x + base.text.pos.x - scroll + base.text.preedit_caret();
+        let y = base.offset.y + base.layout.y + base.text.pos.y;
+        window.set_ime_cursor_area(
+            PhysicalPosition::new(x, y),
+            PhysicalSize::new(1.0, base.text.get_true_dimensions().y),
+        );
+    }
+    /// Returns the handle of the widget receiving
+    /// keyboard input
+    pub fn focused(&self) -> Option<WidgetId> {
+        self.focus.focused()
+    }
+    /// Moves keyboard focus onto the widget behind `id`
+    ///
+    /// Does nothing if the widget is gone or not focusable
+    pub fn focus(&mut self, id: WidgetId) {
+        let widget = self.widget(id).filter(|widget| widget.is_focusable());
+        if widget.is_some() {
+            self.set_focus(widget);
+        }
+    }
+    /// Moves focus to the next focusable widget like Tab does,
+    /// or to the previous one like Shift-Tab when `backwards`
+    pub(crate) fn traverse_focus(&mut self, backwards: bool) {
+        let widget = self.focus.traverse(&self.nodes, backwards);
+        self.set_focus(widget);
+    }
+    fn add_widgets(&mut self, widget: Rc<dyn WidgetI>, parent: Option<WidgetId>) -> WidgetId {
+        self.hits.invalidate();
         // Attach trigger to allow user to trigger redraws on this widget
         // later
-        let uid: UID = rand::thread_rng().gen();
+        let uid = self.nodes_ref.insert(Node {
+            widget: widget.clone(),
+            parent,
+            children: Vec::new(),
+        });
         *widget.internal_trigger_mut() = Some(Rc::new(Trigger::new(self.proxy.clone(), uid)));
+        widget.mounted(&Rc::downgrade(&widget));
 
-        self.nodes_ref.insert(uid, widget.clone());
-        self.nodes.push(widget.clone());
+        let id = widget.base().id.clone();
+        if !id.is_empty() {
+            self.ids.entry(id).or_insert(uid);
+        }
+
+        let children = widget
+            .children()
+            .into_iter()
+            .chain(widget.hidden_children())
+            .map(|child| self.add_widgets(child, Some(uid)))
+            .collect();
+        if let Some(node) = self.nodes_ref.get_mut(uid) {
+            node.children = children;
+        }
+
+        uid
+    }
+    /// Links `widget` to the children it holds right now
+    fn link(&mut self, widget: &Rc<dyn WidgetI>) {
+        let Some(id) = widget_id(widget) else {
+            return;
+        };
 
-        if let Some(canvas) = widget.as_any().downcast_ref::<Canvas>() {
-            // Handle all grid cells of canvas
-            let grid = &*canvas.grid.borrow();
-            if let Some(grid) = grid {
-                grid.on_cell(|_, cell| {
-                    let cell: Rc<dyn WidgetI> = cell.clone();
-                    let uid: UID = rand::thread_rng().gen();
-                    *cell.internal_trigger_mut() =
-                        Some(Rc::new(Trigger::new(self.proxy.clone(), uid)));
-                    self.nodes_ref.insert(uid, cell);
-                });
+        let children: Vec<WidgetId> = widget
+            .children()
+            .iter()
+            .chain(&widget.hidden_children())
+            .filter_map(widget_id)
+            .collect();
+        for child in &children {
+            if let Some(node) = self.nodes_ref.get_mut(*child) {
+                node.parent = Some(id);
             }
-        } else if let Some(container) = widget.as_any().downcast_ref::<Container>() {
-            for child in &container.children {
-                self.add_widgets(child.clone());
+        }
+        if let Some(node) = self.nodes_ref.get_mut(id) {
+            node.children = children;
+        }
+    }
+    /// Links `widget` and everything below it again
+    fn relink(&mut self, widget: &Rc<dyn WidgetI>) {
+        self.link(widget);
+        for child in widget.children().iter().chain(&widget.hidden_children()) {
+            self.relink(child);
+        }
+    }
+    /// Adds a widget and all of its children to the tree
+    ///
+    /// Returns the handle of the added widget
+    pub fn add_widget<T: WidgetI + 'static>(&mut self, widget: T) -> WidgetId {
+        self.add_root(Rc::new(widget))
+    }
+    /// Builds the widget tree `node` describes and adds it
+    ///
+    /// Returns the handle of the root widget
+    pub fn add_node(&mut self, node: &WidgetNode) -> WidgetId {
+        self.add_root(node.build())
+    }
+    /// Describes every top-level widget and its children
+    ///
+    /// Widgets of kinds that can not be described are left out
+    pub fn nodes(&self) -> Vec<WidgetNode> {
+        self.nodes
+            .iter()
+            .filter_map(WidgetNode::from_widget)
+            .map(|mut node| {
+                // Added nodes get moved into the safe area again
+                node.base.layout.x -= self.safe_area.left;
+                node.base.layout.y -= self.safe_area.top;
+                node
+            })
+            .collect()
+    }
+    /// Attaches `action` to the widget whose `BaseWidget::id` is `id`
+    ///
+    /// Returns `false` if no such widget exists
+    pub fn attach_action(&mut self, id: &str, action: Action) -> bool {
+        let Some(widget) = self.get_widget_by_id(id) else {
+            return false;
+        };
+        widget.action_mut().push(action);
+        true
+    }
+    /// Starts `emitter` for the widget whose `BaseWidget::id` is `id`
+    ///
+    /// Returns `false` if no such widget exists
+    pub fn attach_emitter<E: Emitter>(&mut self, id: &str, emitter: E) -> bool {
+        let Some(widget) = self.get_widget_by_id(id) else {
+            return false;
+        };
+        Arc::new(emitter).start(Trigger::new(self.proxy.clone(), widget.trigger().uid));
+        true
+    }
+    pub(crate) fn proxy(&self) -> Arc<Mutex<Proxy>> {
+        self.proxy.clone()
+    }
+    pub(crate) fn set_program(&mut self, program: Box<dyn Program>) {
+        self.program = Some(program);
+    }
+    /// Returns a bus any thread can send messages through
+    /// to the widgets subscribed to their type
+    pub fn event_bus(&self) -> EventBus {
+        self.subscriptions.bus(self.proxy.clone())
+    }
+    /// Runs `handler` on the widget behind `id` for every message
+    /// of type `M` sent through the `EventBus`
+    ///
+    /// The widget is redrawn after handling the message and
+    /// unsubscribed once removed
+    ///
+    /// ## Example
+    /// ```ignore
+    /// dom.subscribe(id, |widget, msg: &AppMsg| match msg {
+    ///     AppMsg::Saved => widget.base_mut().text.label = "Saved".into(),
+    /// });
+    /// ```
+    pub fn subscribe<M, F>(&mut self, id: WidgetId, handler: F)
+    where
+        M: Any,
+        F: Fn(Rc<dyn WidgetI>, &M) + 'static,
+    {
+        self.subscriptions.subscribe(id, handler);
+    }
+    /// Replaces the whole tree with the widgets described by the
+    /// JSON file at `path` and rebuilds it every time the file changes
+    ///
+    /// A file that fails to load on a change is reported and the
+    /// current tree is kept. Actions bound with `bind_action` are
+    /// attached again after every reload.
+    #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+    pub fn load_from_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), LoadError> {
+        let file = UiFile::watch(path, self.proxy())?;
+        let nodes = file.load()?;
+        self.ui_file = Some(file);
+        self.rebuild(&nodes);
+        Ok(())
+    }
+    /// Attaches the action `factory` creates to the widget whose
+    /// `BaseWidget::id` is `id`, now and after every reload of
+    /// the UI file
+    #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+    pub fn bind_action<F: Fn() -> Action + 'static>(&mut self, id: &str, factory: F) {
+        let factory: Rc<dyn Fn() -> Action> = Rc::new(factory);
+        self.attach_action(id, factory());
+        self.bindings
+            .entry(id.to_string())
+            .or_default()
+            .push(factory);
+    }
+    #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+    fn reload(&mut self) {
+        let Some(file) = &self.ui_file else {
+            return;
+        };
+        match file.load() {
+            Ok(nodes) => {
+                debug!("reloading ui file");
+                self.rebuild(&nodes);
             }
+            Err(err) => log::error!("{}", err),
+        }
+    }
+    /// Swaps every top-level widget for the `nodes`
+    #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+    fn rebuild(&mut self, nodes: &[WidgetNode]) {
+        // Focus follows the id across rebuilds
+        let focused = self
+            .focus
+            .focused()
+            .and_then(|id| self.nodes_ref.widget(id))
+            .map(|widget| widget.base().id.clone());
+
+        self.reconcile(nodes.iter().map(WidgetNode::build).collect());
+
+        let bindings: Vec<(String, Rc<dyn Fn() -> Action>)> = self
+            .bindings
+            .iter()
+            .flat_map(|(id, factories)| factories.iter().map(|f| (id.clone(), f.clone())))
+            .collect();
+        for (id, factory) in bindings {
+            self.attach_action(&id, factory());
+        }
+
+        if let Some(id) = focused.filter(|id| !id.is_empty()) {
+            let widget = self.get_widget_by_id(&id);
+            self.set_focus(widget.filter(|widget| widget.is_focusable()));
         }
+
+        self.damage.add_full();
+        self.request_redraw();
+    }
+    /// Swaps the top-level widgets for `roots` reusing the widgets
+    /// they were built the same as
+    ///
+    /// Rebuilt widgets are paired with the mounted widgets by id or
+    /// otherwise by their place among their siblings. Paired widgets
+    /// of the same type take on the look and actions of the rebuilt
+    /// ones while keeping their handle, focus and scroll position.
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let view = |items: &[&str]| {
+    ///     let mut list = Container::new().set_vertical();
+    ///     for item in items {
+    ///         list.add_widget(Label::new().set_id(item).set_label(item));
+    ///     }
+    ///     vec![Rc::new(list) as Rc<dyn WidgetI>]
+    /// };
+    /// dom.reconcile(view(&["milk", "eggs"]));
+    /// // Only the label of "bread" is built
+    /// dom.reconcile(view(&["milk", "bread", "eggs"]));
+    /// ```
+    pub fn reconcile(&mut self, roots: Vec<Rc<dyn WidgetI>>) {
+        let mut changes = Changes::default();
+        let roots = reconcile::reconcile(&self.nodes, roots, &mut changes);
+        // Patched roots were laid out anew
+        for root in &roots {
+            let mut base = root.base_mut();
+            base.layout.x += self.safe_area.left;
+            base.layout.y += self.safe_area.top;
+        }
+        self.nodes = roots;
+        self.mount(changes);
+        for node in self.nodes.clone() {
+            self.relink(&node);
+        }
+    }
+    /// Patches the widget behind `id` to look and act like `widget`
+    /// or replaces it when they are not the same widget
+    ///
+    /// Returns the widget left in the tree
+    pub(crate) fn reconcile_rc(
+        &mut self,
+        id: WidgetId,
+        widget: Rc<dyn WidgetI>,
+    ) -> Option<Rc<dyn WidgetI>> {
+        let old = self.nodes_ref.widget(id)?.clone();
+        let mut changes = Changes::default();
+        if !reconcile::patch(&old, &widget, &mut changes) {
+            self.replace_rc(id, widget.clone()).ok()?;
+            return Some(widget);
+        }
+
+        if self.nodes.iter().any(|node| Rc::ptr_eq(node, &old)) {
+            let mut base = old.base_mut();
+            base.layout.x += self.safe_area.left;
+            base.layout.y += self.safe_area.top;
+        }
+        self.mount(changes);
+        self.relink(&old);
+        Some(old)
     }
-    pub fn add_widget<T: WidgetI + 'static>(&mut self, widget: T) {
-        let widget: Rc<dyn WidgetI> = Rc::new(widget);
-        self.add_widgets(widget.clone());
+    /// Registers the widgets reconciling added and
+    /// unregisters the ones it removed
+    ///
+    /// Added widgets are left unlinked from their parent
+    /// until the patched tree gets relinked
+    fn mount(&mut self, changes: Changes) {
+        for widget in changes.removed {
+            if let Some(id) = widget_id(&widget) {
+                self.remove_widgets(id);
+            }
+        }
+        for widget in changes.added {
+            self.add_widgets(widget.clone(), None);
+            self.apply_emitters(&widget);
+        }
+
+        self.hits.invalidate();
+        self.damage.add_full();
+        self.request_redraw();
+    }
+    pub(crate) fn add_root(&mut self, widget: Rc<dyn WidgetI>) -> WidgetId {
+        let id = self.add_widgets(widget.clone(), None);
+
+        {
+            let mut base = widget.base_mut();
+            base.layout.x += self.safe_area.left;
+            base.layout.y += self.safe_area.top;
+        }
+
+        self.nodes.push(widget.clone());
+        self.apply_emitters(&widget);
+        id
+    }
+    /// Returns the widget behind the `id` handle if it
+    /// is still part of the tree
+    pub fn widget(&self, id: WidgetId) -> Option<Rc<dyn WidgetI>> {
+        self.nodes_ref.widget(id).cloned()
+    }
+    /// Returns the widget whose `BaseWidget::id` is `id`
+    ///
+    /// The first widget added wins when ids are shared
+    pub fn get_widget_by_id(&self, id: &str) -> Option<Rc<dyn WidgetI>> {
+        let indexed = self
+            .ids
+            .get(id)
+            .and_then(|uid| self.nodes_ref.widget(*uid))
+            .filter(|widget| widget.base().id == id)
+            .cloned();
+
+        // Ids may have been changed after the widget was added
+        indexed.or_else(|| {
+            self.query_all(|widget| widget.base().id == id)
+                .into_iter()
+                .next()
+        })
+    }
+    /// Returns every widget in the tree matching `predicate`
+    pub fn query_all<P: Fn(&Rc<dyn WidgetI>) -> bool>(&self, predicate: P) -> Vec<Rc<dyn WidgetI>> {
+        self.nodes_ref
+            .iter()
+            .map(|(_, node)| &node.widget)
+            .filter(|widget| predicate(widget))
+            .cloned()
+            .collect()
+    }
+    /// Removes the widget behind `id` and all of its children
+    /// from the tree
+    ///
+    /// Returns the removed widget or an error if the handle
+    /// went stale or the parent can not let go of the widget
+    pub fn remove_widget(&mut self, id: WidgetId) -> Result<Rc<dyn WidgetI>, Error> {
+        let node = self.nodes_ref.get(id).ok_or(Error::StaleWidget(id))?;
+        let widget = node.widget.clone();
+
+        if let Some(parent) = node.parent {
+            // Only containers hold children that can be taken out
+            self.nodes_ref
+                .widget(parent)
+                .ok_or(Error::StaleWidget(id))?
+                .downcast::<Container>()
+                .ok_or(Error::Fixed(id))?
+                .remove_child(id);
+        } else {
+            self.nodes.retain(|node| !Rc::ptr_eq(node, &widget));
+        }
+
+        self.damage.add(DOM::screen_rect(&widget));
+        self.remove_widgets(id);
+        self.request_redraw();
+
+        debug!("removed widget: {}", widget.base().id);
+        Ok(widget)
+    }
+    /// Swaps the widget behind `id` for `widget` keeping its
+    /// place in the tree
+    ///
+    /// Returns the handle of the new widget or an error if the
+    /// handle went stale or the parent can not let go of the widget
+    pub fn replace_widget<T: WidgetI + 'static>(
+        &mut self,
+        id: WidgetId,
+        widget: T,
+    ) -> Result<WidgetId, Error> {
+        self.replace_rc(id, Rc::new(widget))
+    }
+    pub(crate) fn replace_rc(
+        &mut self,
+        id: WidgetId,
+        widget: Rc<dyn WidgetI>,
+    ) -> Result<WidgetId, Error> {
+        let node = self.nodes_ref.get(id).ok_or(Error::StaleWidget(id))?;
+        let old = node.widget.clone();
+        let parent = node.parent;
+
+        if let Some(parent) = parent {
+            self.nodes_ref
+                .widget(parent)
+                .ok_or(Error::StaleWidget(id))?
+                .downcast::<Container>()
+                .ok_or(Error::Fixed(id))?
+                .replace_child(id, widget.clone());
+        } else if let Some(idx) = self.nodes.iter().position(|node| Rc::ptr_eq(node, &old)) {
+            {
+                let mut base = widget.base_mut();
+                base.layout.x += self.safe_area.left;
+                base.layout.y += self.safe_area.top;
+            }
+            self.nodes[idx] = widget.clone();
+        }
+
+        self.damage.add(DOM::screen_rect(&old));
+        self.remove_widgets(id);
+        let new_id = self.add_widgets(widget.clone(), parent);
+        // Keeps the new widget where the old one sat
+        if let Some(parent) = parent.and_then(|parent| self.nodes_ref.widget(parent).cloned()) {
+            self.link(&parent);
+        }
         self.apply_emitters(&widget);
+        self.request_redraw();
+
+        debug!("replaced widget: {}", old.base().id);
+        Ok(new_id)
+    }
+    /// Registers the children `widget` built since the last layout
+    /// and unregisters the ones it let go of
+    fn sync_children(&mut self, widget: &Rc<dyn WidgetI>) {
+        let mut changed = false;
+        for child in widget.removed_children() {
+            if let Some(id) = widget_id(&child) {
+                self.remove_widgets(id);
+                changed = true;
+            }
+        }
+        for child in widget.children() {
+            if child.internal_trigger().is_none() {
+                self.add_widgets(child, widget_id(widget));
+                changed = true;
+            } else {
+                self.sync_children(&child);
+            }
+        }
+
+        if changed {
+            self.link(widget);
+        }
+    }
+    /// Unregisters the widget behind `id` and all of its children
+    fn remove_widgets(&mut self, id: WidgetId) {
+        let Some(node) = self.nodes_ref.remove(id) else {
+            return;
+        };
+
+        self.hits.invalidate();
+        for child in node.children {
+            self.remove_widgets(child);
+        }
+        if let Some(parent) = node
+            .parent
+            .and_then(|parent| self.nodes_ref.get_mut(parent))
+        {
+            parent.children.retain(|child| *child != id);
+        }
+
+        // Removed widgets get no more pointer events
+        self.pointed
+            .retain(|other| !Rc::ptr_eq(other, &node.widget));
+        self.captured
+            .retain(|other| !Rc::ptr_eq(other, &node.widget));
+        self.ids.retain(|_, uid| *uid != id);
+        self.subscriptions.unsubscribe(id);
+        if self.focus.focused() == Some(id) {
+            self.set_focus(None);
+        }
+    }
+    /// Returns the handle of the widget holding the
+    /// widget behind `id`
+    ///
+    /// Top-level widgets and stale handles have none
+    pub fn parent(&self, id: WidgetId) -> Option<WidgetId> {
+        self.nodes_ref.get(id)?.parent
+    }
+    /// Returns the handles of the widgets the widget behind
+    /// `id` holds, hidden ones included
+    pub fn children(&self, id: WidgetId) -> &[WidgetId] {
+        self.nodes_ref
+            .get(id)
+            .map_or(&[], |node| node.children.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use winit::event::{ElementState, Event, Ime, WindowEvent};
+
+    use crate::{
+        action::{hover::Hover, key::KeyPress, Action, ActionHandler},
+        error::Error,
+        ui::{
+            color::{Color, RED, WHITE},
+            layout::{HitShape, Point},
+            sync::{Signal, Trigger},
+            widget::{
+                checkbox::Checkbox, container::Container, label::Label, widget_id, Widget, WidgetI,
+            },
+        },
+    };
+
+    use super::DOM;
+
+    /// Counts the pointer events reaching its widget
+    #[derive(Clone)]
+    struct Pointed(Rc<Cell<usize>>);
+    impl ActionHandler for Pointed {
+        fn apply(&mut self, _: Rc<Trigger>, _: &Rc<dyn WidgetI>, e: Event<Signal>, _: Point) {
+            if let Event::WindowEvent {
+                event: WindowEvent::CursorMoved { .. } | WindowEvent::MouseInput { .. },
+                ..
+            } = e
+            {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+    }
+
+    /// Appends whatever gets typed to the label
+    fn typing() -> Action {
+        let typing = KeyPress::new(()).on_text(|_, trigger, widget, text| {
+            widget.base_mut().text.label.push_str(text);
+            trigger.update();
+        });
+        Action::Key(Box::new(typing))
+    }
+
+    #[test]
+    fn simulated_clicks_reach_the_widget_under_the_cursor() {
+        let mut dom = DOM::headless(200, 100);
+        let id = dom.add_widget(Checkbox::new().set_x(50.0).set_y(20.0));
+        let checked = |dom: &DOM| {
+            let widget = dom.widget(id).unwrap();
+            let checkbox = widget.downcast::<Checkbox>().unwrap();
+            checkbox.is_checked()
+        };
+
+        dom.click(55.0, 25.0);
+        assert!(checked(&dom));
+        assert_eq!(dom.focused(), Some(id));
+
+        // Clicks next to the checkbox miss it
+        dom.click(5.0, 5.0);
+        assert!(checked(&dom));
+        dom.click(60.0, 30.0);
+        assert!(!checked(&dom));
+    }
+
+    #[test]
+    fn composed_text_is_shown_until_committed() {
+        let mut dom = DOM::headless(200, 100);
+        let id = dom.add_widget(
+            Label::new()
+                .set_label("a")
+                .set_focusable()
+                .on_action(typing()),
+        );
+        dom.focus(id);
+        let text = |dom: &DOM| dom.widget(id).unwrap().base().text.clone();
+
+        dom.simulate(WindowEvent::Ime(Ime::Preedit("bc".into(), Some((1, 1)))));
+        let spans = text(&dom).resolved_spans();
+        assert_eq!(spans[1].text, "bc");
+        assert!(spans[1].underline);
+        assert!(text(&dom).preedit_caret() < text(&dom).get_true_dimensions().x);
+
+        dom.type_text("bc");
+        assert_eq!(text(&dom).label, "abc");
+        assert_eq!(text(&dom).preedit, None);
+        assert_eq!(
+            text(&dom).preedit_caret(),
+            text(&dom).get_true_dimensions().x
+        );
+    }
+
+    #[test]
+    fn stale_handles_are_reported() {
+        let mut dom = DOM::headless(200, 100);
+        let id = dom.add_widget(Label::new());
+        assert!(dom.remove_widget(id).is_ok());
+
+        assert!(matches!(dom.remove_widget(id), Err(Error::StaleWidget(stale)) if stale == id));
+        assert!(matches!(
+            dom.replace_widget(id, Label::new()),
+            Err(Error::StaleWidget(_))
+        ));
+        assert!(matches!(dom.run(), Err(Error::Headless)));
+    }
+
+    #[test]
+    fn widgets_are_linked_to_their_parent_and_children() {
+        let mut dom = DOM::headless(200, 100);
+        let mut container = Container::new();
+        container.add_widget(Label::new().set_id("first"));
+        container.add_widget(Label::new().set_id("second"));
+        let parent = dom.add_widget(container);
+        let child = |dom: &DOM, id| widget_id(&dom.get_widget_by_id(id).unwrap()).unwrap();
+        let (first, second) = (child(&dom, "first"), child(&dom, "second"));
+
+        assert_eq!(dom.parent(parent), None);
+        assert_eq!(dom.parent(first), Some(parent));
+        assert_eq!(dom.children(parent), [first, second]);
+
+        // Replacements take the place of the old widget
+        let third = dom.replace_widget(first, Label::new()).unwrap();
+        assert_eq!(dom.parent(third), Some(parent));
+        assert_eq!(dom.children(parent), [third, second]);
+
+        // Removed widgets are detached from their parent
+        dom.remove_widget(second).unwrap();
+        assert_eq!(dom.children(parent), [third]);
+        dom.remove_widget(parent).unwrap();
+        assert!(dom.children(parent).is_empty());
+        assert!(dom.widget(third).is_none());
+    }
+
+    #[test]
+    fn tab_moves_focus_in_document_order_and_wraps_around() {
+        let mut dom = DOM::headless(200, 100);
+        let first = dom.add_widget(Label::new().set_label("first").set_focusable());
+        let mut container = Container::new();
+        container.add_widget(Label::new().set_id("nested").set_focusable());
+        dom.add_widget(container);
+        let last = dom.add_widget(Checkbox::new());
+        let nested = widget_id(&dom.get_widget_by_id("nested").unwrap());
+
+        // Children come right after their parent
+        dom.traverse_focus(false);
+        assert_eq!(dom.focused(), Some(first));
+        dom.traverse_focus(false);
+        assert_eq!(dom.focused(), nested);
+        dom.traverse_focus(false);
+        assert_eq!(dom.focused(), Some(last));
+        dom.traverse_focus(false);
+        assert_eq!(dom.focused(), Some(first));
+
+        // Shift-Tab walks back the same way
+        dom.traverse_focus(true);
+        assert_eq!(dom.focused(), Some(last));
+        dom.traverse_focus(true);
+        assert_eq!(dom.focused(), nested);
+    }
+
+    #[test]
+    fn tab_skips_widgets_that_can_not_take_focus() {
+        let mut dom = DOM::headless(200, 100);
+        dom.add_widget(Label::new().set_label("plain"));
+        let id = dom.add_widget(Label::new().set_label("focusable").set_focusable());
+        dom.add_widget(Label::new().set_label("plain"));
+
+        for backwards in [false, false, true, true] {
+            dom.traverse_focus(backwards);
+            assert_eq!(dom.focused(), Some(id));
+        }
+
+        // Nothing is focused when nothing can be
+        let mut dom = DOM::headless(200, 100);
+        let id = dom.add_widget(Label::new().set_label("plain"));
+        dom.traverse_focus(false);
+        assert_eq!(dom.focused(), None);
+        dom.focus(id);
+        assert_eq!(dom.focused(), None);
+    }
+
+    #[test]
+    fn clicks_focus_the_focusable_widget_under_the_cursor() {
+        let mut dom = DOM::headless(200, 100);
+        let id = dom.add_widget(
+            Label::new()
+                .set_width(50.0)
+                .set_height(20.0)
+                .set_focusable(),
+        );
+        dom.add_widget(Label::new().set_y(50.0).set_width(50.0).set_height(20.0));
+
+        dom.click(10.0, 10.0);
+        assert_eq!(dom.focused(), Some(id));
+
+        // Clicking anything else takes the focus away
+        dom.click(10.0, 60.0);
+        assert_eq!(dom.focused(), None);
+        dom.click(10.0, 10.0);
+        dom.click(150.0, 90.0);
+        assert_eq!(dom.focused(), None);
+    }
+
+    #[test]
+    fn key_input_only_reaches_the_focused_widget() {
+        let mut dom = DOM::headless(200, 100);
+        let first = dom.add_widget(Label::new().set_focusable().on_action(typing()));
+        let second = dom.add_widget(Label::new().set_focusable().on_action(typing()));
+        let label = |dom: &DOM, id| dom.widget(id).unwrap().base().text.label.clone();
+
+        // Nothing takes the input without focus
+        dom.type_text("a");
+        assert_eq!(label(&dom, first), "");
+        assert_eq!(label(&dom, second), "");
+
+        dom.traverse_focus(false);
+        dom.type_text("b");
+        dom.traverse_focus(false);
+        dom.type_text("c");
+        assert_eq!(label(&dom, first), "b");
+        assert_eq!(label(&dom, second), "c");
+    }
+
+    #[test]
+    fn disabled_widgets_ignore_input_and_are_drawn_dimmed() {
+        let mut dom = DOM::headless(200, 100);
+        let id = dom.add_widget(
+            Checkbox::new()
+                .set_x(50.0)
+                .set_y(20.0)
+                .set_disabled(true)
+                .on_action(Action::Hover(Hover::new(RED))),
+        );
+        let next = dom.add_widget(Label::new().set_label("next").set_y(60.0).set_focusable());
+        let checkbox = dom.widget(id).unwrap();
+
+        dom.click(55.0, 25.0);
+        let checked = checkbox.downcast::<Checkbox>().unwrap().is_checked();
+        assert!(!checked);
+        assert!(!checkbox.base().state.hovered);
+        assert_eq!(dom.focused(), None);
+
+        // Tab passes over it both ways
+        dom.traverse_focus(false);
+        assert_eq!(dom.focused(), Some(next));
+        dom.traverse_focus(true);
+        assert_eq!(dom.focused(), Some(next));
+
+        let overlay = Color::RGBA(0, 0, 0, 128);
+        let widgets: [Rc<dyn WidgetI>; 2] = [
+            Rc::new(
+                Label::new()
+                    .set_width(20.0)
+                    .set_height(20.0)
+                    .set_color(WHITE),
+            ),
+            Rc::new(
+                Label::new()
+                    .set_x(20.0)
+                    .set_width(20.0)
+                    .set_height(20.0)
+                    .set_color(WHITE)
+                    .set_disabled(true)
+                    .set_disabled_overlay(overlay),
+            ),
+        ];
+        let frame = DOM::render_once_to_buffer(&widgets, 40, 20).unwrap();
+        assert_eq!(frame.pixel(10, 10), Some(WHITE));
+        assert_eq!(frame.pixel(30, 10), Some(Color::blend(WHITE, overlay)));
+    }
+
+    #[test]
+    fn pointer_events_only_reach_widgets_along_the_hit_path() {
+        let mut dom = DOM::headless(200, 100);
+        let (a, b) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+        let counted = |x: f64, count: &Rc<Cell<usize>>| {
+            Label::new()
+                .set_x(x)
+                .set_width(20.0)
+                .set_height(20.0)
+                .on_action(Action::CursorMove(Box::new(Pointed(count.clone()))))
+        };
+        dom.add_widget(counted(0.0, &a));
+        dom.add_widget(counted(50.0, &b));
+
+        dom.move_cursor(5.0, 5.0);
+        assert_eq!((a.get(), b.get()), (1, 0));
+        // Leaving a widget is the last event it sees
+        dom.move_cursor(55.0, 5.0);
+        assert_eq!((a.get(), b.get()), (2, 1));
+        dom.move_cursor(150.0, 50.0);
+        dom.move_cursor(150.0, 60.0);
+        assert_eq!((a.get(), b.get()), (2, 2));
+
+        // Pressing a widget keeps it informed until released
+        dom.move_cursor(55.0, 5.0);
+        dom.left_button(ElementState::Pressed);
+        dom.move_cursor(150.0, 50.0);
+        dom.move_cursor(150.0, 60.0);
+        dom.left_button(ElementState::Released);
+        dom.move_cursor(150.0, 70.0);
+        assert_eq!((a.get(), b.get()), (2, 7));
+    }
+
+    #[test]
+    fn hit_index_is_only_rebuilt_after_a_new_layout() {
+        let mut dom = DOM::headless(200, 100);
+        let id = dom.add_widget(
+            Label::new()
+                .set_width(40.0)
+                .set_height(40.0)
+                .on_action(Action::Hover(Hover::new(RED))),
+        );
+        let label = dom.widget(id).unwrap();
+        dom.move_cursor(20.0, 20.0);
+        assert!(label.base().state.hovered);
+
+        // Repainting the hovered widget keeps the index as it was
+        label.base_mut().hit_shape = HitShape::Circle;
+        dom.move_cursor(2.0, 2.0);
+        assert!(label.base().state.hovered);
+
+        // Moving it lays it out again
+        label.base_mut().layout.x = 100.0;
+        dom.move_cursor(2.0, 2.0);
+        assert!(!label.base().state.hovered);
+        dom.move_cursor(102.0, 2.0);
+        assert!(!label.base().state.hovered);
+        dom.move_cursor(120.0, 20.0);
+        assert!(label.base().state.hovered);
     }
 }
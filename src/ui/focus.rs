@@ -0,0 +1,86 @@
+//! Tracks which widget receives keyboard input.
+//!
+//! Only widgets marked focusable take part. Focus moves in document
+//! order (the order widgets were added, parents before children) with
+//! Tab and Shift-Tab, or to a focusable widget when it gets clicked.
+
+use std::rc::Rc;
+
+use super::{arena::WidgetId, widget::WidgetI};
+
+/// The `FocusManager` struct owns the focus of a `DOM`
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct FocusManager {
+    focused: Option<WidgetId>,
+}
+impl FocusManager {
+    pub(crate) fn new() -> Self {
+        FocusManager::default()
+    }
+    /// Returns the handle of the focused widget
+    pub(crate) fn focused(&self) -> Option<WidgetId> {
+        self.focused
+    }
+    /// Moves focus onto `widget` or clears it on `None`
+    ///
+    /// Both the widget losing and the widget gaining focus
+    /// get redrawn to reflect their new state
+    pub(crate) fn set(
+        &mut self,
+        previous: Option<Rc<dyn WidgetI>>,
+        widget: Option<Rc<dyn WidgetI>>,
+    ) {
+        let id = widget.as_ref().map(|widget| widget.trigger().id());
+        if id == self.focused {
+            return;
+        }
+
+        if let Some(previous) = previous {
+            previous.base_mut().state.focused = false;
+            previous.trigger().update();
+        }
+        if let Some(widget) = &widget {
+            widget.base_mut().state.focused = true;
+            widget.trigger().update();
+        }
+        self.focused = id;
+    }
+    /// Returns the focusable widget after the focused one,
+    /// or before it when going `backwards`
+    ///
+    /// Wraps around at the ends of the tree
+    pub(crate) fn traverse(
+        &self,
+        roots: &[Rc<dyn WidgetI>],
+        backwards: bool,
+    ) -> Option<Rc<dyn WidgetI>> {
+        let mut order = Vec::new();
+        for root in roots {
+            focus_order(root, &mut order);
+        }
+        if backwards {
+            order.reverse();
+        }
+
+        let current = self.focused.and_then(|focused| {
+            order
+                .iter()
+                .position(|widget| widget.trigger().id() == focused)
+        });
+        match current {
+            Some(i) => order.into_iter().cycle().nth(i + 1),
+            None => order.into_iter().next(),
+        }
+    }
+}
+
+/// Collects the focusable widgets under `widget` in
+/// document order
+fn focus_order(widget: &Rc<dyn WidgetI>, order: &mut Vec<Rc<dyn WidgetI>>) {
+    if widget.is_focusable() {
+        order.push(widget.clone());
+    }
+    for child in widget.children() {
+        focus_order(&child, order);
+    }
+}
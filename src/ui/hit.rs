@@ -0,0 +1,414 @@
+//! Resolves which widget the pointer is over.
+//!
+//! Widgets overlap when layered with `z_index` or nested inside each
+//! other, yet only the topmost one under the pointer is hit. Children
+//! are drawn over their parent and higher layers over lower ones, while
+//! anything clipped away by a parent cannot be hit at all. Widgets set
+//! to bubble pass the hit on to their parent.
+//!
+//! Large trees such as canvas grids hold thousands of widgets so the
+//! screen is split into a uniform grid of buckets, each listing the
+//! widgets overlapping it. A hit only looks at the bucket under the
+//! pointer and the index gets rebuilt lazily once the layout changed.
+
+use std::{collections::HashMap, rc::Rc};
+
+use super::{
+    arena::WidgetId,
+    layout::{intersect_clips, HitShape, Layout},
+    style::{CornerShape, Radius},
+    widget::{widget_id, z_ordered, WidgetI},
+};
+
+/// The side length of the square buckets the screen is split into
+const BUCKET_SIZE: f64 = 64.0;
+
+/// A widget as it was laid out when the index was built
+struct Entry {
+    widget: Rc<dyn WidgetI>,
+    /// Missing for widgets never added to a `DOM`
+    id: Option<WidgetId>,
+    parent: Option<usize>,
+    rect: Layout,
+    /// The part of the rect that can be hit
+    shape: HitShape,
+    /// The corners of the widget as it is drawn
+    corners: (Radius, CornerShape),
+    /// Clipping region inherited from the parents
+    clip: Option<Layout>,
+    drop_target: bool,
+}
+impl Entry {
+    fn contains(&self, x: f64, y: f64) -> bool {
+        self.shape.contains(self.rect, self.corners, x, y)
+            && self.clip.is_none_or(|clip| clip.is_inbounds(x, y))
+    }
+}
+
+/// The `HitIndex` struct maps positions on the screen to the
+/// widgets drawn there
+///
+/// Widgets are stored in the order they are drawn so the
+/// topmost one under the pointer is the last one listed
+#[derive(Default)]
+pub(crate) struct HitIndex {
+    entries: Vec<Entry>,
+    buckets: HashMap<(i64, i64), Vec<usize>>,
+    stale: bool,
+    marked: Vec<Rc<dyn WidgetI>>,
+}
+impl HitIndex {
+    pub(crate) fn new() -> Self {
+        Self {
+            stale: true,
+            ..Default::default()
+        }
+    }
+    /// Marks the index for rebuilding before the next hit
+    ///
+    /// Must be called whenever widgets were moved, resized,
+    /// added or removed
+    pub(crate) fn invalidate(&mut self) {
+        self.stale = true;
+    }
+    /// Returns the widgets under the position `x`, `y` from the
+    /// top-level widget down to the topmost one hit
+    pub(crate) fn hit_path(
+        &mut self,
+        roots: &[Rc<dyn WidgetI>],
+        x: f64,
+        y: f64,
+    ) -> Vec<Rc<dyn WidgetI>> {
+        if self.stale {
+            self.rebuild(roots);
+        }
+
+        let Some(hit) = self.buckets.get(&bucket(x, y)).and_then(|bucket| {
+            bucket
+                .iter()
+                .rev()
+                .find(|&&i| self.entries[i].contains(x, y))
+        }) else {
+            return Vec::new();
+        };
+
+        let mut path = Vec::new();
+        let mut next = Some(*hit);
+        while let Some(i) = next {
+            path.push(i);
+            next = self.entries[i].parent;
+        }
+        path.reverse();
+
+        // Content drawn over the children hides them so the
+        // outermost widget covering the position is hit instead
+        if let Some(cover) = path[..path.len() - 1].iter().position(|&i| {
+            let entry = &self.entries[i];
+            entry.contains(x, y) && entry.widget.covers_children(x, y)
+        }) {
+            path.truncate(cover + 1);
+        }
+
+        path.into_iter()
+            .map(|i| self.entries[i].widget.clone())
+            .collect()
+    }
+    /// Marks the last widget of `path` as hit along with the
+    /// parents it bubbles up to, clearing the widgets marked
+    /// before
+    pub(crate) fn mark(&mut self, path: &[Rc<dyn WidgetI>]) {
+        for widget in self.marked.drain(..) {
+            widget.base_mut().state.hit = false;
+        }
+        for widget in path.iter().rev() {
+            let mut base = widget.base_mut();
+            base.state.hit = true;
+            self.marked.push(widget.clone());
+            if !base.bubbles {
+                break;
+            }
+        }
+    }
+    /// Returns the topmost drop target under the position `x`, `y`
+    /// leaving out the `dragged` widget and everything inside it
+    ///
+    /// Widgets are never borrowed so the dragged one may be
+    /// mutably borrowed meanwhile
+    pub(crate) fn drop_target(&self, x: f64, y: f64, dragged: WidgetId) -> Option<WidgetId> {
+        let bucket = self.buckets.get(&bucket(x, y))?;
+        bucket
+            .iter()
+            .rev()
+            .filter(|&&i| self.entries[i].drop_target && self.entries[i].contains(x, y))
+            .find(|&&i| !self.is_within(i, dragged))
+            .and_then(|&i| self.entries[i].id)
+    }
+    /// Returns whether the entry `i` is the widget behind `id`
+    /// or one of its children
+    fn is_within(&self, i: usize, id: WidgetId) -> bool {
+        let mut next = Some(i);
+        while let Some(i) = next {
+            if self.entries[i].id == Some(id) {
+                return true;
+            }
+            next = self.entries[i].parent;
+        }
+        false
+    }
+    /// Returns the region `widget` is clipped to when it was
+    /// drawn by the last frame
+    pub(crate) fn clip(&self, widget: &Rc<dyn WidgetI>) -> Option<Layout> {
+        self.entries
+            .iter()
+            .find(|entry| Rc::ptr_eq(&entry.widget, widget))
+            .and_then(|entry| entry.clip)
+    }
+    fn rebuild(&mut self, roots: &[Rc<dyn WidgetI>]) {
+        self.entries.clear();
+        self.buckets.clear();
+        for root in z_ordered(roots) {
+            self.insert(root, None, None);
+        }
+        self.stale = false;
+    }
+    fn insert(&mut self, widget: Rc<dyn WidgetI>, parent: Option<usize>, clip: Option<Layout>) {
+        let (rect, shape, corners, drop_target) = {
+            let base = widget.base();
+            let rect = Layout {
+                x: base.offset.x + base.layout.x,
+                y: base.offset.y + base.layout.y,
+                ..base.layout
+            };
+            let corners = (base.style.radius, base.style.corner_shape);
+            (rect, base.hit_shape.clone(), corners, base.drop_target)
+        };
+        // Only the visible part of the widget can be hit
+        let (mut left, mut top, mut right, mut bottom) =
+            (rect.x, rect.y, rect.x + rect.w, rect.y + rect.h);
+        if let Some(clip) = clip {
+            left = left.max(clip.x);
+            top = top.max(clip.y);
+            right = right.min(clip.x + clip.w);
+            bottom = bottom.min(clip.y + clip.h);
+        }
+
+        let i = self.entries.len();
+        if left <= right && top <= bottom {
+            let (min, max) = (bucket(left, top), bucket(right, bottom));
+            for bx in min.0..=max.0 {
+                for by in min.1..=max.1 {
+                    self.buckets.entry((bx, by)).or_default().push(i);
+                }
+            }
+        }
+        let children_clip = intersect_clips(widget.children_clip(), clip);
+        let children = widget.children();
+        self.entries.push(Entry {
+            id: widget_id(&widget),
+            widget,
+            parent,
+            rect,
+            shape,
+            corners,
+            clip,
+            drop_target,
+        });
+
+        // Children are drawn over their parent so they are listed after it
+        for child in z_ordered(&children) {
+            self.insert(child, Some(i), children_clip);
+        }
+    }
+}
+
+/// Returns the bucket holding the position `x`, `y`
+fn bucket(x: f64, y: f64) -> (i64, i64) {
+    (
+        (x / BUCKET_SIZE).floor() as i64,
+        (y / BUCKET_SIZE).floor() as i64,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::ui::{
+        layout::Point,
+        widget::{container::Container, label::Label, Widget, WidgetI},
+    };
+
+    use super::*;
+
+    fn label(x: f64, z_index: i32) -> Rc<dyn WidgetI> {
+        Rc::new(
+            Label::new()
+                .set_x(x)
+                .set_width(20.0)
+                .set_height(20.0)
+                .set_z_index(z_index),
+        )
+    }
+
+    #[test]
+    fn hits_topmost_layer() {
+        let lower = label(0.0, 1);
+        let upper = label(10.0, 2);
+        let roots = vec![upper.clone(), lower.clone()];
+        let mut index = HitIndex::new();
+
+        let path = index.hit_path(&roots, 15.0, 5.0);
+        assert_eq!(path.len(), 1);
+        assert!(Rc::ptr_eq(&path[0], &upper));
+
+        let path = index.hit_path(&roots, 5.0, 5.0);
+        assert!(Rc::ptr_eq(&path[0], &lower));
+        assert!(index.hit_path(&roots, 50.0, 5.0).is_empty());
+
+        // Moved widgets are only found again once invalidated
+        upper.base_mut().layout.x = 100.0;
+        assert!(Rc::ptr_eq(&index.hit_path(&roots, 15.0, 5.0)[0], &upper));
+        index.invalidate();
+        assert!(Rc::ptr_eq(&index.hit_path(&roots, 15.0, 5.0)[0], &lower));
+        assert!(Rc::ptr_eq(&index.hit_path(&roots, 110.0, 5.0)[0], &upper));
+    }
+
+    #[test]
+    fn bubbles_to_parents() {
+        let mut container = Container::new().set_width(50.0).set_height(50.0);
+        container.add_widget(Label::new().set_width(10.0).set_height(10.0));
+        let root: Rc<dyn WidgetI> = Rc::new(container);
+        let roots = vec![root.clone()];
+        let child = root.children()[0].clone();
+        let mut index = HitIndex::new();
+
+        let path = index.hit_path(&roots, 5.0, 5.0);
+        assert_eq!(path.len(), 2);
+        index.mark(&path);
+        assert!(child.base().state.hit);
+        assert!(!root.base().state.hit);
+
+        child.base_mut().bubbles = true;
+        index.mark(&path);
+        assert!(child.base().state.hit);
+        assert!(root.base().state.hit);
+
+        let path = index.hit_path(&roots, 30.0, 30.0);
+        index.mark(&path);
+        assert!(!child.base().state.hit);
+        assert!(root.base().state.hit);
+    }
+
+    #[test]
+    fn nested_scroll_containers_clip_hits() {
+        let mut inner = Container::new()
+            .set_x(100.0)
+            .set_y(100.0)
+            .set_width(100.0)
+            .set_height(100.0)
+            .on_scroll();
+        inner.add_widget(
+            Label::new()
+                .set_x(100.0)
+                .set_y(50.0)
+                .set_width(100.0)
+                .set_height(100.0),
+        );
+        let mut outer = Container::new()
+            .set_width(150.0)
+            .set_height(150.0)
+            .on_scroll();
+        outer.add_widget(inner);
+        let root: Rc<dyn WidgetI> = Rc::new(outer);
+        let roots = vec![root.clone()];
+        let mut index = HitIndex::new();
+
+        // Only the corner both containers show can be hit
+        assert_eq!(index.hit_path(&roots, 120.0, 120.0).len(), 3);
+        assert_eq!(index.hit_path(&roots, 160.0, 120.0).len(), 0);
+        assert_eq!(index.hit_path(&roots, 120.0, 160.0).len(), 0);
+        assert_eq!(index.hit_path(&roots, 120.0, 70.0).len(), 1);
+
+        // Scrolling the outer container moves the clip of the inner one
+        root.children()[0].base_mut().offset.y = -50.0;
+        index.invalidate();
+        let path = index.hit_path(&roots, 120.0, 70.0);
+        assert_eq!(path.len(), 3);
+        assert!(Rc::ptr_eq(&path[1], &root.children()[0]));
+    }
+
+    #[test]
+    fn rounded_off_corners_are_not_hit() {
+        let button: Rc<dyn WidgetI> = Rc::new(
+            Label::new()
+                .set_width(40.0)
+                .set_height(40.0)
+                .set_radius(Radius::new(20, 0, 0, 0)),
+        );
+        let roots = vec![button.clone()];
+        let mut index = HitIndex::new();
+
+        assert!(index.hit_path(&roots, 2.0, 2.0).is_empty());
+        assert_eq!(index.hit_path(&roots, 38.0, 2.0).len(), 1);
+        assert_eq!(index.hit_path(&roots, 20.0, 20.0).len(), 1);
+
+        // Squircles reach further into their corners
+        assert!(index.hit_path(&roots, 5.0, 5.0).is_empty());
+        button.base_mut().style.corner_shape = CornerShape::Squircle;
+        index.invalidate();
+        assert_eq!(index.hit_path(&roots, 5.0, 5.0).len(), 1);
+    }
+
+    #[test]
+    fn widgets_are_hit_within_their_shape() {
+        let widget: Rc<dyn WidgetI> = Rc::new(
+            Label::new()
+                .set_width(40.0)
+                .set_height(40.0)
+                .set_radius(20)
+                .set_hit_shape(HitShape::Rect),
+        );
+        let roots = vec![widget.clone()];
+        let mut index = HitIndex::new();
+        let hits = |index: &mut HitIndex, x, y| !index.hit_path(&roots, x, y).is_empty();
+
+        // Rounded corners are hit when asked to
+        assert!(hits(&mut index, 2.0, 2.0));
+
+        widget.base_mut().hit_shape = HitShape::Circle;
+        index.invalidate();
+        assert!(!hits(&mut index, 5.0, 5.0));
+        assert!(hits(&mut index, 20.0, 2.0));
+
+        // A triangle pointing down
+        widget.base_mut().hit_shape = HitShape::Path(vec![
+            Point::new(0.0, 0.0),
+            Point::new(40.0, 0.0),
+            Point::new(20.0, 40.0),
+        ]);
+        index.invalidate();
+        assert!(hits(&mut index, 20.0, 30.0));
+        assert!(!hits(&mut index, 5.0, 30.0));
+        assert!(!hits(&mut index, 35.0, 30.0));
+    }
+
+    #[test]
+    fn widgets_are_hit_in_every_bucket_they_overlap() {
+        let wide: Rc<dyn WidgetI> = Rc::new(
+            Label::new()
+                .set_x(-30.0)
+                .set_y(40.0)
+                .set_width(300.0)
+                .set_height(100.0),
+        );
+        let roots = vec![wide.clone()];
+        let mut index = HitIndex::new();
+
+        for (x, y) in [(-20.0, 50.0), (10.0, 70.0), (200.0, 130.0), (269.0, 139.0)] {
+            assert!(Rc::ptr_eq(&index.hit_path(&roots, x, y)[0], &wide));
+        }
+        assert!(index.hit_path(&roots, 275.0, 100.0).is_empty());
+        assert!(index.hit_path(&roots, 100.0, 145.0).is_empty());
+        assert!(index.hit_path(&roots, -40.0, 100.0).is_empty());
+    }
+}
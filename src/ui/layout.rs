@@ -1,8 +1,11 @@
 use std::{iter::repeat_with, rc::Rc};
 
-use crate::ui::widget::cell::Cell;
+use crate::ui::widget::{cell::Cell, BaseWidget, WidgetI};
 
-use super::color::ColorState;
+use super::{
+    style::{CornerShape, GridLines, Radius},
+    text::Text,
+};
 
 /// A struct representing the position and size of a UI element.
 ///
@@ -21,6 +24,7 @@ use super::color::ColorState;
 /// container.
 ///
 #[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Layout {
     pub x: f64,
     pub y: f64,
@@ -36,6 +40,46 @@ impl Layout {
             && my >= self.y as f64
             && my <= (self.y + self.h) as f64
     }
+    /// Determines if the layout covers no area
+    ///
+    /// Layouts of zero, negative or undefined size are empty
+    pub(crate) fn is_empty(&self) -> bool {
+        !(self.w > 0.0 && self.h > 0.0)
+    }
+    /// Determines if this layout overlaps the `other` layout
+    ///
+    /// Layouts merely sharing an edge do not overlap
+    pub(crate) fn intersects(&self, other: &Layout) -> bool {
+        self.x < other.x + other.w
+            && other.x < self.x + self.w
+            && self.y < other.y + other.h
+            && other.y < self.y + self.h
+    }
+    /// Determines the smallest layout containing both
+    /// this layout and the `other` layout
+    pub(crate) fn union(&self, other: &Layout) -> Layout {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Layout {
+            x,
+            y,
+            w: (self.x + self.w).max(other.x + other.w) - x,
+            h: (self.y + self.h).max(other.y + other.h) - y,
+        }
+    }
+    /// Determines the part this layout shares with the `other` layout
+    ///
+    /// Layouts not overlapping share an empty layout
+    pub(crate) fn intersect(&self, other: &Layout) -> Layout {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        Layout {
+            x,
+            y,
+            w: ((self.x + self.w).min(other.x + other.w) - x).max(0.0),
+            h: ((self.y + self.h).min(other.y + other.h) - y).max(0.0),
+        }
+    }
     /// Determines the center of the layout vertically
     /// with the `rhs` included in the layout
     pub(crate) fn vertical_center(&self, rhs: f64) -> f64 {
@@ -53,8 +97,155 @@ impl From<Layout> for (f64, f64, f64, f64) {
         (value.x, value.y, value.h, value.w)
     }
 }
+
+/// Narrows down two clipping regions to the part they share
+///
+/// Clipping regions are absolute rects on the screen and `None`
+/// leaves drawing unconfined
+pub(crate) fn intersect_clips(a: Option<Layout>, b: Option<Layout>) -> Option<Layout> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.intersect(&b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// The `Dimension` enum describes how a widget is sized
+/// along one axis
+///
+/// Relative units are resolved against the parent widget, or
+/// the window for top-level widgets, right before drawing.
+#[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Dimension {
+    /// Sized by its content or left as laid out
+    #[default]
+    Auto,
+    /// An absolute amount of pixels
+    Px(f64),
+    /// A percentage of the parent size
+    Percent(f64),
+    /// Stretches from its position to the far edge of
+    /// the parent
+    Fill,
+}
+impl From<f64> for Dimension {
+    fn from(value: f64) -> Self {
+        Dimension::Px(value)
+    }
+}
+impl Dimension {
+    /// Resolves a relative dimension against the parent
+    /// spanning `parent_len` from `parent_start`
+    ///
+    /// Returns `None` when the dimension is not relative
+    pub(crate) fn resolve(&self, parent_start: f64, parent_len: f64, start: f64) -> Option<f64> {
+        match self {
+            Dimension::Percent(percent) => Some((parent_len * percent / 100.0).max(0.0)),
+            Dimension::Fill => Some((parent_start + parent_len - start).max(0.0)),
+            Dimension::Auto | Dimension::Px(_) => None,
+        }
+    }
+}
+
+/// The `Size` struct holds the width and height a widget
+/// was asked to have
+#[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Size {
+    pub width: Dimension,
+    pub height: Dimension,
+}
+
+/// The `Flex` struct describes how a widget shares the space
+/// left over along a `Row` or `Col` layout
+///
+/// - `grow`: The share of the leftover space the widget takes
+///   compared to its siblings. Widgets with none keep their size
+/// - `shrink`: The share of the missing space the widget gives up
+///   when its siblings overflow, weighed by its size. Widgets with
+///   none keep their size
+/// - `basis`: The size of the widget before the layout last resized
+///   it so it starts from its own size on the next layout
+/// - `grown`: The size the layout last resized the widget to
+#[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Flex {
+    pub grow: f64,
+    pub shrink: f64,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) basis: Point,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) grown: Point,
+}
+
+/// The `Arranged` struct records what a widget was last laid out
+/// from so unchanged widgets can skip laying out again
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Arranged {
+    parent: Layout,
+    layout: Layout,
+    offset: Point,
+    size: Size,
+    text: Text,
+    flex: Flex,
+    dock: Dock,
+    position: Position,
+    origin: Point,
+    align_self: Option<Align>,
+    /// The addresses of the children laid out
+    children: Vec<usize>,
+}
+impl Arranged {
+    pub(crate) fn new(base: &BaseWidget, parent: Layout, children: &[Rc<dyn WidgetI>]) -> Self {
+        Arranged {
+            parent,
+            layout: base.layout,
+            offset: base.offset,
+            size: base.size,
+            text: base.text.clone(),
+            flex: base.flex,
+            dock: base.dock,
+            position: base.position,
+            origin: base.origin,
+            align_self: base.align_self,
+            children: children.iter().map(address).collect(),
+        }
+    }
+    /// Returns whether `base` is still laid out in `parent`
+    /// the way it was recorded
+    pub(crate) fn matches(
+        &self,
+        base: &BaseWidget,
+        parent: Layout,
+        children: &[Rc<dyn WidgetI>],
+    ) -> bool {
+        self.parent == parent
+            && self.layout == base.layout
+            && self.offset == base.offset
+            && self.size == base.size
+            && self.text == base.text
+            && self.flex == base.flex
+            && self.dock == base.dock
+            && self.position == base.position
+            && self.origin == base.origin
+            && self.align_self == base.align_self
+            && self.children.len() == children.len()
+            && self
+                .children
+                .iter()
+                .zip(children)
+                .all(|(a, b)| *a == address(b))
+    }
+}
+
+/// Identifies a widget by where it lives in memory
+pub(crate) fn address(widget: &Rc<dyn WidgetI>) -> usize {
+    Rc::as_ptr(widget) as *const () as usize
+}
+
 /// The `Point` struct defines a simple x and y coordinates
 #[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point {
     pub x: f64,
     pub y: f64,
@@ -73,6 +264,84 @@ impl From<ab_glyph::Point> for Point {
     }
 }
 
+/// A struct representing the space kept clear on each
+/// side of a region
+///
+/// On phones this is the safe area, the part of the screen not
+/// covered by notches, rounded corners or system bars.
+#[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Insets {
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+/// The `HitShape` enum describes the part of a widget
+/// the pointer hits
+///
+/// Nothing outside of the bounds of a widget is ever hit
+/// whatever its shape.
+#[derive(Default, Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HitShape {
+    /// The whole bounds even where corners are rounded off
+    Rect,
+    /// The bounds with the corners rounded off as the
+    /// widget is drawn
+    #[default]
+    RoundedRect,
+    /// The largest circle centered inside the bounds
+    Circle,
+    /// The ellipse filling the bounds
+    Ellipse,
+    /// The polygon through the points given relative to the
+    /// top-left of the widget
+    Path(Vec<Point>),
+}
+impl HitShape {
+    /// Returns `true` if the position `x`, `y` hits the shape
+    /// laid out over `rect` with `corners`
+    pub(crate) fn contains(
+        &self,
+        rect: Layout,
+        (radius, corner_shape): (Radius, CornerShape),
+        x: f64,
+        y: f64,
+    ) -> bool {
+        if !rect.is_inbounds(x, y) {
+            return false;
+        }
+        match self {
+            HitShape::Rect => true,
+            HitShape::RoundedRect => radius.covers(corner_shape, rect, x, y),
+            HitShape::Circle => {
+                let r = rect.w.min(rect.h) / 2.0;
+                let (dx, dy) = (x - rect.x - rect.w / 2.0, y - rect.y - rect.h / 2.0);
+                dx * dx + dy * dy <= r * r
+            }
+            HitShape::Ellipse => {
+                let (rx, ry) = (rect.w / 2.0, rect.h / 2.0);
+                let (dx, dy) = ((x - rect.x - rx) / rx, (y - rect.y - ry) / ry);
+                dx * dx + dy * dy <= 1.0
+            }
+            HitShape::Path(points) => {
+                // Counts the edges a ray cast to the right crosses
+                let (x, y) = (x - rect.x, y - rect.y);
+                let mut inside = false;
+                for (i, a) in points.iter().enumerate() {
+                    let b = points[(i + 1) % points.len()];
+                    if (a.y > y) != (b.y > y) && x < a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x) {
+                        inside = !inside;
+                    }
+                }
+                inside
+            }
+        }
+    }
+}
+
 /// A struct representing a grid layout for UI elements.
 ///
 /// The `Grid` struct is designed to manage a 2D grid of `Cell` elements,
@@ -90,13 +359,11 @@ impl From<ab_glyph::Point> for Point {
 pub struct Grid {
     pub(crate) size: Point,
     pub(crate) cells: Vec<Vec<Rc<Cell>>>,
-    pub(crate) thickness: f64,
-    pub(crate) color: ColorState,
 }
 impl Grid {
     /// Create a new `Grid` filling the `cells`
     /// with an empty widget with size `[size.y][size.x]`
-    pub(crate) fn new(size: Point, thickness: f64, color: ColorState) -> Self {
+    pub(crate) fn new(size: Point) -> Self {
         let mut cells: Vec<Vec<Rc<Cell>>> = Vec::new();
         for _ in 0..size.y as usize {
             cells.push(
@@ -105,52 +372,39 @@ impl Grid {
                     .collect(),
             );
         }
-        Self {
-            size,
-            cells,
-            thickness,
-            color,
-            ..Default::default()
-        }
+        Self { size, cells }
     }
     /// Resize grid to meet the dimensions of
     /// `height x width` also account for pos `x` and `y` offset
     ///
-    /// NOTE
-    ///
-    /// locked to only be called once until dirty render is implemented
-    pub(crate) fn resize(&mut self, x: f64, y: f64, height: f64, width: f64) {
+    /// Cells leave room for the `lines` in front of them
+    pub(crate) fn resize(
+        &mut self,
+        x: f64,
+        y: f64,
+        height: f64,
+        width: f64,
+        lines: Option<&GridLines>,
+    ) {
         let h_cell_size = height / self.size.y;
         let w_cell_size = width / self.size.x;
+        // The edges of the grid carry no line
+        let thickness = |index: f64| match lines {
+            Some(lines) if index > 0.0 => lines.line(index as usize).0.max(0.0),
+            _ => 0.0,
+        };
 
         self.on_cell(|pos, c| {
             let mut cbase = c.base.borrow_mut();
-            cbase.style.color = self.color;
             // Due to line thickness being at minimal 1 px we need to
             // account for that spacing that way we do not overlap
             // cells
-            let buffer_x = pos.x * w_cell_size;
-            let buffer_y = pos.y * h_cell_size;
-            cbase.layout.x = if buffer_x > 0.0 {
-                buffer_x + self.thickness
-            } else {
-                0.0
-            } + x;
-            cbase.layout.y = if buffer_y > 0.0 {
-                buffer_y + self.thickness
-            } else {
-                0.0
-            } + y;
-            cbase.layout.w = if buffer_x > 0.0 {
-                w_cell_size - self.thickness
-            } else {
-                w_cell_size
-            };
-            cbase.layout.h = if buffer_y > 0.0 {
-                h_cell_size - self.thickness
-            } else {
-                h_cell_size
-            };
+            let (line_x, line_y) = (thickness(pos.x), thickness(pos.y));
+            cbase.layout.x = pos.x * w_cell_size + line_x + x;
+            cbase.layout.y = pos.y * h_cell_size + line_y + y;
+            // Lines thicker than the cells leave nothing of them
+            cbase.layout.w = (w_cell_size - line_x).max(0.0);
+            cbase.layout.h = (h_cell_size - line_y).max(0.0);
         });
     }
     /// Callback function on every cell
@@ -178,7 +432,8 @@ pub type Col = usize;
 
 /// The `FlexLayout` provides a variety of ways to organize
 /// the container of widgets in a uniform way
-#[derive(Default, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FlexLayout {
     #[default]
     /// Default for `Container` widget
@@ -225,4 +480,120 @@ pub enum FlexLayout {
     /// -----                 
     /// ```
     Col,
+    /// Layout a container as a single row
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let mut toolbar = Container::new().set_flex_layout(FlexLayout::Row)
+    /// ```
+    ///
+    /// How the layout would look if 5 widgets
+    /// were stored in the container:
+    ///
+    /// ```text
+    /// ---------------------
+    /// | w | w | w | w | w |
+    /// ---------------------
+    /// ```
+    Row,
+    /// Layout a container as rows that wrap onto the next
+    /// line once the container width is used up
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let mut palette = Container::new().set_flex_layout(FlexLayout::RowWrap)
+    /// ```
+    ///
+    /// How the layout would look if 5 widgets
+    /// were stored in a container 3 widgets wide:
+    ///
+    /// ```text
+    /// -------------
+    /// | w | w | w |
+    /// | w | w |
+    /// -------------
+    /// ```
+    RowWrap,
+    /// Layout a container by pinning every child to an edge
+    /// of the space the children before it left over
+    ///
+    /// Children are docked in the order added and set where
+    /// they go with `set_dock`
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let mut shell = Container::new().set_flex_layout(FlexLayout::Dock);
+    /// shell.add_widget(toolbar.set_height(32.0).set_dock(Dock::Top));
+    /// shell.add_widget(sidebar.set_width(200.0).set_dock(Dock::Left));
+    /// shell.add_widget(content.set_dock(Dock::Fill));
+    /// ```
+    ///
+    /// How the layout would look:
+    ///
+    /// ```text
+    /// -----------------
+    /// |    toolbar    |
+    /// |---------------|
+    /// | side |content |
+    /// |      |        |
+    /// -----------------
+    /// ```
+    Dock,
+}
+
+/// The `Position` enum describes how a child is placed
+/// inside of a `Container`
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Position {
+    /// Placed by the layout of the container ignoring
+    /// its own `x` and `y`
+    #[default]
+    Static,
+    /// Placed by the layout of the container then moved
+    /// by its own `x` and `y`
+    Relative,
+    /// Kept at its own `x` and `y` on screen and left out of
+    /// the layout of the container
+    Absolute,
+}
+
+/// The `Dock` enum describes which edge of a `Dock` layout
+/// a child is pinned to
+///
+/// Children docked to an edge stretch along it and keep their
+/// own size across it
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Dock {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    /// Takes all of the space left over
+    #[default]
+    Fill,
+}
+
+/// The `Align` enum controls how children are distributed
+/// along an axis of a flex container
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Align {
+    /// Pack children at the start of the axis
+    #[default]
+    Start,
+    /// Pack children around the middle of the axis
+    Center,
+    /// Pack children at the end of the axis
+    End,
+    /// Spread children so the first and last touch the edges
+    /// with equal space in between
+    ///
+    /// Behaves as `Start` on the cross axis
+    SpaceBetween,
+    /// Stretch children across the whole cross axis
+    ///
+    /// Behaves as `Start` on the main axis
+    Stretch,
 }
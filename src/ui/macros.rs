@@ -0,0 +1,103 @@
+//! Declarative helpers for building widget trees.
+//!
+//! Nesting builder chains and `add_widget` calls quickly becomes hard
+//! to read for non-trivial screens. The `ui!` macro lets the same tree
+//! be written in the shape it will be displayed.
+
+/// Builds a widget tree declaratively and returns the root widget.
+///
+/// Every node is written as the widget type, followed by an optional
+/// list of attributes in parentheses, an optional list of builder calls
+/// in square brackets and an optional list of children in braces.
+///
+/// - Attributes are written `name: value` and call the `set_name` setter
+///   of the widget. Setters without arguments are written as `name`
+///   alone. Only plain widget names (no paths or generics) take
+///   attributes.
+/// - Builder calls are any fluent `set_*`/`on_*` methods of the widget
+///   (ids, styles, actions, ...).
+/// - Children are attached with the parent's `add_widget`.
+///
+/// ## Example
+/// ```ignore
+/// let root = ui! {
+///     Container(id: "root", flex_layout: FlexLayout::Col, gap: 8.0) {
+///         Heading(large_heading, label: "Settings"),
+///         Container(flex_layout: FlexLayout::Grid(2)) {
+///             Button(label: "Ok") [on_action(Action::Hover(Hover::new(RED)))],
+///             Button [set_label("Cancel")],
+///         },
+///         Label(label: "hi"),
+///     }
+/// };
+/// dom.add_widget(root);
+/// ```
+#[macro_export]
+macro_rules! ui {
+    // Attach every child node to the parent one at a time
+    (@children $parent:ident) => {};
+    // Tried first since a type would swallow the attributes
+    (@children $parent:ident
+        $ty:ident ( $($attrs:tt)* ) $([ $($methods:tt)* ])? $({ $($children:tt)* })?
+        $(, $($rest:tt)*)?
+    ) => {
+        $parent.add_widget($crate::ui!($ty ( $($attrs)* ) $([ $($methods)* ])? $({ $($children)* })?));
+        $crate::ui!(@children $parent $($($rest)*)?);
+    };
+    (@children $parent:ident
+        $ty:ty $([ $($attrs:tt)* ])? $({ $($children:tt)* })?
+        $(, $($rest:tt)*)?
+    ) => {
+        $parent.add_widget($crate::ui!($ty $([ $($attrs)* ])? $({ $($children)* })?));
+        $crate::ui!(@children $parent $($($rest)*)?);
+    };
+    // A single node with its attributes, builder calls and children
+    (
+        $ty:ident ( $($attr:ident $(: $value:expr)?),* $(,)? )
+        $([ $($method:ident ( $($arg:expr),* $(,)? )),* $(,)? ])?
+        $({ $($children:tt)* })?
+    ) => {{
+        #[allow(unused_mut)]
+        let mut widget = $crate::paste::paste! {
+            <$ty>::new() $(.[<set_ $attr>]($($value)?))*
+        } $($(.$method($($arg),*))*)?;
+        $($crate::ui!(@children widget $($children)*);)?
+        widget
+    }};
+    // A single node with its builder calls and children
+    (
+        $ty:ty
+        $([ $($method:ident ( $($arg:expr),* $(,)? )),* $(,)? ])?
+        $({ $($children:tt)* })?
+    ) => {{
+        #[allow(unused_mut)]
+        let mut widget = <$ty>::new() $($(.$method($($arg),*))*)?;
+        $($crate::ui!(@children widget $($children)*);)?
+        widget
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::{
+        layout::FlexLayout,
+        widget::{container::Container, label::Label, Widget},
+    };
+
+    #[test]
+    fn test_attributes_call_setters() {
+        let root = ui! {
+            Container(id: "root", flex_layout: FlexLayout::Col, gap: 8.0) {
+                Label(label: "hi", focusable),
+                Label [set_label("there")],
+            }
+        };
+
+        let children = root.children.borrow();
+        assert_eq!(root.base().id, "root");
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].base().text.label, "hi");
+        assert!(children[0].base().focusable);
+        assert_eq!(children[1].base().text.label, "there");
+    }
+}
@@ -0,0 +1,197 @@
+//! Support for running the same widget tree on touch-first phones.
+//!
+//! Widgets only understand pointer events, so touches are translated
+//! into the cursor and left button events a mouse would produce. The
+//! safe area of the screen is measured so the `DOM` can keep widgets
+//! clear of notches and system bars.
+
+use winit::{
+    dpi::PhysicalPosition,
+    event::{ElementState, Event, MouseButton, Touch, TouchPhase, WindowEvent},
+    window::{Window, WindowId},
+};
+
+use super::{layout::Insets, sync::Signal};
+
+/// A position outside of every widget so touch lifting
+/// off the screen does not leave anything hovered
+const NOWHERE: PhysicalPosition<f64> = PhysicalPosition::new(-1.0, -1.0);
+
+/// The `TouchPointer` struct tracks the primary finger on the
+/// screen and emulates a mouse with it
+///
+/// Additional fingers are ignored until the primary one lifts
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct TouchPointer {
+    finger: Option<u64>,
+}
+impl TouchPointer {
+    /// Converts the `touch` into the pointer events a
+    /// left button mouse would have produced
+    pub(crate) fn pointer_events(
+        &mut self,
+        window_id: WindowId,
+        touch: &Touch,
+    ) -> Vec<Event<Signal>> {
+        let device_id = touch.device_id;
+        let moved = |position| WindowEvent::CursorMoved {
+            device_id,
+            position,
+        };
+        let button = |state| WindowEvent::MouseInput {
+            device_id,
+            state,
+            button: MouseButton::Left,
+        };
+
+        let events = match (touch.phase, self.finger) {
+            (TouchPhase::Started, None) => {
+                self.finger = Some(touch.id);
+                vec![moved(touch.location), button(ElementState::Pressed)]
+            }
+            (TouchPhase::Moved, Some(finger)) if finger == touch.id => {
+                vec![moved(touch.location)]
+            }
+            (TouchPhase::Ended, Some(finger)) if finger == touch.id => {
+                self.finger = None;
+                vec![
+                    moved(touch.location),
+                    button(ElementState::Released),
+                    moved(NOWHERE),
+                ]
+            }
+            // Moving away first makes sure the release
+            // never counts as a click
+            (TouchPhase::Cancelled, Some(finger)) if finger == touch.id => {
+                self.finger = None;
+                vec![moved(NOWHERE), button(ElementState::Released)]
+            }
+            _ => Vec::new(),
+        };
+
+        events
+            .into_iter()
+            .map(|event| Event::WindowEvent { window_id, event })
+            .collect()
+    }
+}
+
+/// Measures the safe area of the `window`
+///
+/// Only iOS reports it, where the inner size of a window is its
+/// safe area while the surface covers the whole screen. Every other
+/// platform returns `None`.
+pub(crate) fn safe_area(window: &Window) -> Option<Insets> {
+    if !cfg!(target_os = "ios") {
+        return None;
+    }
+
+    let inner = window.inner_position().ok()?;
+    let outer = window.outer_position().ok()?;
+    let inner_size = window.inner_size();
+    let outer_size = window.outer_size();
+
+    let top = (inner.y - outer.y) as f64;
+    let left = (inner.x - outer.x) as f64;
+    Some(Insets {
+        top,
+        left,
+        bottom: outer_size.height as f64 - inner_size.height as f64 - top,
+        right: outer_size.width as f64 - inner_size.width as f64 - left,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::{
+        dpi::PhysicalPosition,
+        event::{DeviceId, Event, Touch, TouchPhase, WindowEvent},
+        window::WindowId,
+    };
+
+    use super::TouchPointer;
+
+    /// Feeds the touch of `finger` to `pointer` describing the
+    /// pointer events it turned into
+    fn touch(
+        pointer: &mut TouchPointer,
+        finger: u64,
+        phase: TouchPhase,
+        x: f64,
+        y: f64,
+    ) -> Vec<String> {
+        let touch = Touch {
+            device_id: unsafe { DeviceId::dummy() },
+            phase,
+            location: PhysicalPosition::new(x, y),
+            force: None,
+            id: finger,
+        };
+        pointer
+            .pointer_events(unsafe { WindowId::dummy() }, &touch)
+            .into_iter()
+            .map(|event| match event {
+                Event::WindowEvent {
+                    event: WindowEvent::CursorMoved { position, .. },
+                    ..
+                } => format!("move {},{}", position.x, position.y),
+                Event::WindowEvent {
+                    event: WindowEvent::MouseInput { state, button, .. },
+                    ..
+                } => format!("{button:?} {state:?}"),
+                _ => unreachable!("touches only move and press the pointer"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn a_touch_clicks_where_it_lifts_and_hovers_nothing_after() {
+        let mut pointer = TouchPointer::default();
+
+        assert_eq!(
+            touch(&mut pointer, 0, TouchPhase::Started, 10.0, 20.0),
+            ["move 10,20", "Left Pressed"]
+        );
+        assert_eq!(
+            touch(&mut pointer, 0, TouchPhase::Moved, 15.0, 25.0),
+            ["move 15,25"]
+        );
+        assert_eq!(
+            touch(&mut pointer, 0, TouchPhase::Ended, 15.0, 25.0),
+            ["move 15,25", "Left Released", "move -1,-1"]
+        );
+        assert!(touch(&mut pointer, 0, TouchPhase::Moved, 20.0, 30.0).is_empty());
+    }
+
+    #[test]
+    fn cancelled_touches_release_away_from_every_widget() {
+        let mut pointer = TouchPointer::default();
+        touch(&mut pointer, 0, TouchPhase::Started, 10.0, 20.0);
+
+        assert_eq!(
+            touch(&mut pointer, 0, TouchPhase::Cancelled, 10.0, 20.0),
+            ["move -1,-1", "Left Released"]
+        );
+        assert!(touch(&mut pointer, 0, TouchPhase::Ended, 10.0, 20.0).is_empty());
+    }
+
+    #[test]
+    fn other_fingers_are_ignored_until_the_first_lifts() {
+        let mut pointer = TouchPointer::default();
+        touch(&mut pointer, 0, TouchPhase::Started, 10.0, 20.0);
+
+        for phase in [TouchPhase::Started, TouchPhase::Moved, TouchPhase::Ended] {
+            assert!(touch(&mut pointer, 1, phase, 50.0, 50.0).is_empty());
+        }
+        assert_eq!(
+            touch(&mut pointer, 0, TouchPhase::Ended, 10.0, 20.0),
+            ["move 10,20", "Left Released", "move -1,-1"]
+        );
+
+        // Whichever finger touches next takes over
+        assert_eq!(
+            touch(&mut pointer, 1, TouchPhase::Started, 50.0, 50.0),
+            ["move 50,50", "Left Pressed"]
+        );
+    }
+}
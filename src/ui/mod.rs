@@ -11,11 +11,33 @@
 //! editors, or graphical tools.
 
 
+#[cfg(feature = "accesskit")]
+pub(crate) mod accessibility;
+pub mod animate;
+pub mod arena;
+pub mod buffer;
+pub mod bus;
 pub mod color;
+pub mod component;
+pub(crate) mod debug;
+pub(crate) mod focus;
+pub(crate) mod hit;
 pub mod layout;
+mod macros;
+pub(crate) mod mobile;
+pub(crate) mod reconcile;
+#[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+pub mod reload;
+pub mod runtime;
+pub(crate) mod select;
+pub mod semantics;
 pub mod style;
 pub mod text;
+pub mod theme;
+pub mod timer;
+pub mod tree;
 pub mod widget;
+pub mod window;
 pub mod dom;
 pub mod state;
 pub mod sync;
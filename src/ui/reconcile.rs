@@ -0,0 +1,206 @@
+//! Reconciles freshly built widget trees with the mounted one.
+//!
+//! Declarative code describes the whole UI again every time something
+//! changes. Swapping the mounted widgets for the rebuilt ones would lose
+//! everything the user did to them, so each rebuilt widget is paired
+//! with the mounted widget it takes the place of instead. Widgets with
+//! an id are paired by their id wherever they moved to while the others
+//! are paired in order. A pair of the same type and kind is patched in
+//! place: the mounted widget takes on the look and actions of the
+//! rebuilt one yet keeps its trigger, focus, hover and scroll state.
+//! Widgets left without a pair are mounted or unmounted.
+
+use std::{collections::HashMap, rc::Rc};
+
+use super::{
+    state::State,
+    tree::WidgetKind,
+    widget::{checkbox::Checkbox, container::Container, BaseWidget, WidgetI},
+};
+
+/// The `Changes` struct records the widgets reconciling
+/// added to or removed from the tree
+#[derive(Default)]
+pub(crate) struct Changes {
+    /// Rebuilt widgets mounted in place of nothing
+    pub(crate) added: Vec<Rc<dyn WidgetI>>,
+    /// Mounted widgets the rebuilt tree left out
+    pub(crate) removed: Vec<Rc<dyn WidgetI>>,
+}
+
+/// Reconciles the mounted widgets `old` with the rebuilt `new`
+///
+/// Returns the widgets to mount in place of `old`
+pub(crate) fn reconcile(
+    old: &[Rc<dyn WidgetI>],
+    new: Vec<Rc<dyn WidgetI>>,
+    changes: &mut Changes,
+) -> Vec<Rc<dyn WidgetI>> {
+    let pairs = pair(old, &new);
+    let mut kept = vec![false; old.len()];
+    let widgets = new
+        .into_iter()
+        .zip(pairs)
+        .map(|(widget, paired)| match paired {
+            Some(i) => {
+                update(&old[i], &widget, changes);
+                kept[i] = true;
+                old[i].clone()
+            }
+            None => {
+                changes.added.push(widget.clone());
+                widget
+            }
+        })
+        .collect();
+
+    changes.removed.extend(
+        old.iter()
+            .zip(kept)
+            .filter(|(_, kept)| !kept)
+            .map(|(widget, _)| widget.clone()),
+    );
+    widgets
+}
+
+/// Patches the mounted `old` to look and act like the rebuilt `new`
+///
+/// Returns `false` leaving `old` untouched when `new` is
+/// not the same widget
+pub(crate) fn patch(old: &Rc<dyn WidgetI>, new: &Rc<dyn WidgetI>, changes: &mut Changes) -> bool {
+    if !same_widget(old, new) {
+        return false;
+    }
+    update(old, new, changes);
+    true
+}
+
+/// Pairs every widget in `new` with the index of the widget
+/// in `old` it takes the place of
+fn pair(old: &[Rc<dyn WidgetI>], new: &[Rc<dyn WidgetI>]) -> Vec<Option<usize>> {
+    let mut keyed = HashMap::new();
+    let mut unkeyed = Vec::new();
+    for (i, widget) in old.iter().enumerate() {
+        let id = widget.base().id.clone();
+        if id.is_empty() {
+            unkeyed.push(i);
+        } else {
+            keyed.entry(id).or_insert(i);
+        }
+    }
+
+    let mut unkeyed = unkeyed.into_iter();
+    new.iter()
+        .map(|widget| {
+            let id = &widget.base().id;
+            // Widgets sharing an id are only paired once
+            let paired = if id.is_empty() {
+                unkeyed.next()
+            } else {
+                keyed.remove(id)
+            };
+            paired.filter(|i| same_widget(&old[*i], widget))
+        })
+        .collect()
+}
+
+/// Whether `new` can be patched into `old`
+///
+/// Settings widgets are built with can not change once mounted
+/// so they have to match along with the type and id
+fn same_widget(old: &Rc<dyn WidgetI>, new: &Rc<dyn WidgetI>) -> bool {
+    let settings = |widget| match WidgetKind::of(widget) {
+        // Whether a checkbox is checked is patched
+        Some(WidgetKind::Checkbox { check_color, .. }) => Some(WidgetKind::Checkbox {
+            checked: false,
+            check_color,
+        }),
+        kind => kind,
+    };
+    old.as_any().type_id() == new.as_any().type_id()
+        && old.base().id == new.base().id
+        && settings(old) == settings(new)
+}
+
+fn update(old: &Rc<dyn WidgetI>, new: &Rc<dyn WidgetI>, changes: &mut Changes) {
+    patch_base(&mut old.base_mut(), new.base().clone());
+
+    // Checkboxes share their state with the actions toggling them
+    if let (Some(old), Some(new)) = (old.downcast::<Checkbox>(), new.downcast::<Checkbox>()) {
+        old.adopt(new);
+    } else {
+        *old.action_mut() = std::mem::take(&mut *new.action_mut());
+    }
+
+    // Only containers hold children that can be swapped
+    if let (Some(old), Some(new)) = (old.downcast::<Container>(), new.downcast::<Container>()) {
+        let mounted = old.children.borrow().clone();
+        let children = reconcile(&mounted, new.children.take(), changes);
+        *old.children.borrow_mut() = children;
+    }
+}
+
+/// Replaces `base` with `new` leaving the state the
+/// user brought the widget into as it was
+fn patch_base(base: &mut BaseWidget, mut new: BaseWidget) {
+    new.offset = base.offset;
+    new.state = State {
+        disabled: new.state.disabled,
+        ..base.state.clone()
+    };
+    new.style.color.set_mode(base.style.color.mode());
+
+    // Positions in the text only hold while it reads the same
+    new.text.preedit = base.text.preedit.take();
+    if new.text.label == base.text.label {
+        new.text.scroll = base.text.scroll;
+        new.text.selection = base.text.selection;
+        new.text.truncated = base.text.truncated;
+    }
+    new.arranged = base.arranged.take();
+    *base = new;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::ui::{
+        dom::DOM,
+        layout::FlexLayout,
+        widget::{button::Button, container::Container, label::Label, widget_id, Widget, WidgetI},
+    };
+
+    fn view(label: &str, buttons: &[&str]) -> Vec<Rc<dyn WidgetI>> {
+        let mut container = Container::new()
+            .set_flex_layout(FlexLayout::Row)
+            .set_width(300.0)
+            .set_height(20.0);
+        container.add_widget(Label::new().set_label(label));
+        for id in buttons {
+            container.add_widget(Button::new().set_id(id).set_width(50.0).set_focusable());
+        }
+        vec![Rc::new(container)]
+    }
+
+    #[test]
+    fn mounted_widgets_keep_their_state() {
+        let mut dom = DOM::headless(300, 100);
+        dom.reconcile(view("first", &["a", "b"]));
+        let label = dom.query_all(|widget| widget.as_any().is::<Label>())[0].clone();
+        let b = dom.get_widget_by_id("b").unwrap();
+        let a = widget_id(&dom.get_widget_by_id("a").unwrap()).unwrap();
+        dom.focus(widget_id(&b).unwrap());
+
+        dom.reconcile(view("second", &["b", "c"]));
+        // The label was paired in order and the button by its id
+        let widgets = dom.query_all(|widget| widget.as_any().is::<Label>());
+        assert!(Rc::ptr_eq(&widgets[0], &label));
+        assert_eq!(label.base().text.label, "second");
+        assert!(Rc::ptr_eq(&dom.get_widget_by_id("b").unwrap(), &b));
+        assert_eq!(dom.focused(), widget_id(&b));
+
+        assert!(dom.widget(a).is_none());
+        assert!(dom.get_widget_by_id("c").is_some());
+    }
+}
@@ -0,0 +1,118 @@
+//! Hot reloading of UI definition files.
+//!
+//! A UI file holds the JSON list of `WidgetNode`s making up the top-level
+//! widgets of a `DOM`. The file is watched through the file system
+//! notifications of the platform and the tree gets rebuilt whenever it
+//! changes on disk, so layouts can be tweaked without restarting the app.
+//! An idle app is left asleep until the file changes.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::{
+    sync::{Proxy, Signal},
+    tree::WidgetNode,
+};
+
+/// The errors loading a UI file can run into
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    #[error("failed to read ui file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid ui file: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("failed to watch ui file: {0}")]
+    Watch(#[from] notify::Error),
+}
+
+/// The `UiFile` struct tracks a UI definition file on disk
+pub(crate) struct UiFile {
+    path: PathBuf,
+    /// Stops watching the file once dropped
+    _watcher: RecommendedWatcher,
+}
+impl UiFile {
+    /// Watches the file at `path` sending `Signal::Reload`
+    /// through `proxy` whenever it changes
+    ///
+    /// The folder of the file is watched rather than the file
+    /// itself since editors often save by replacing the file
+    pub(crate) fn watch<P: AsRef<Path>>(
+        path: P,
+        proxy: Arc<Mutex<Proxy>>,
+    ) -> Result<Self, LoadError> {
+        let path = path.as_ref().to_path_buf();
+        let name = path.file_name().map(ToOwned::to_owned);
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else {
+                    return;
+                };
+                let changed = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                    && event
+                        .paths
+                        .iter()
+                        .any(|changed| changed.file_name() == name.as_deref());
+                if changed {
+                    let _ = proxy.lock().unwrap().send_event(Signal::Reload);
+                }
+            })?;
+        let folder = match path.parent() {
+            Some(folder) if !folder.as_os_str().is_empty() => folder,
+            _ => Path::new("."),
+        };
+        watcher.watch(folder, RecursiveMode::NonRecursive)?;
+
+        Ok(Self {
+            path,
+            _watcher: watcher,
+        })
+    }
+    /// Reads and parses the widget nodes of the file
+    pub(crate) fn load(&self) -> Result<Vec<WidgetNode>, LoadError> {
+        let content = fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        sync::{mpsc, Arc, Mutex},
+        time::Duration,
+    };
+
+    use crate::ui::sync::{Proxy, Signal};
+
+    use super::UiFile;
+
+    #[test]
+    fn test_changes_are_signalled_and_parsed() {
+        let dir = std::env::temp_dir().join(format!("gemini-ui-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("ui.json");
+        fs::write(&path, "[]").unwrap();
+
+        let (sender, queue) = mpsc::channel();
+        let file = UiFile::watch(&path, Arc::new(Mutex::new(Proxy::Queue(sender)))).unwrap();
+        assert!(file.load().unwrap().is_empty());
+        // Other files in the folder are ignored
+        fs::write(dir.join("other.json"), "[]").unwrap();
+        assert!(queue.recv_timeout(Duration::from_millis(200)).is_err());
+
+        fs::write(&path, "not json").unwrap();
+        assert!(matches!(
+            queue.recv_timeout(Duration::from_secs(5)),
+            Ok(Signal::Reload)
+        ));
+        assert!(file.load().is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,219 @@
+//! A shared runtime for the futures of async emitters.
+//!
+//! Emitters connected with [`Widget::connect_async`] hand out a future
+//! instead of blocking a thread of their own. Their futures all run on
+//! one worker thread by default which suits tasks spending most of
+//! their time waiting such as network requests.
+//!
+//! Apps already running an async runtime hand the futures to it with
+//! [`set_spawner`] instead which is needed for futures relying on the
+//! reactor of that runtime.
+//!
+//! ## Example
+//! ```ignore
+//! let runtime = tokio::runtime::Runtime::new().unwrap();
+//! let handle = runtime.handle().clone();
+//! gemini::ui::runtime::set_spawner(move |task| {
+//!     handle.spawn(task);
+//! });
+//!
+//! let label = Label::new().connect_async(|trigger| async move {
+//!     let body = reqwest::get("https://example.com").await.unwrap().text().await.unwrap();
+//!     trigger.update_callback(move |widget| widget.base_mut().text.label = body.clone());
+//! });
+//! ```
+//!
+//! [`Widget::connect_async`]: crate::ui::widget::Widget::connect_async
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, OnceLock},
+};
+
+use super::sync::{Thread, Trigger};
+
+/// A future run by the runtime
+pub type Task = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// The `Spawner` trait hands the futures of async
+/// emitters to the runtime running them
+pub trait Spawner: Send + Sync + 'static {
+    fn spawn(&self, task: Task);
+}
+impl<F: Fn(Task) + Send + Sync + 'static> Spawner for F {
+    fn spawn(&self, task: Task) {
+        self(task)
+    }
+}
+
+static SPAWNER: OnceLock<Box<dyn Spawner>> = OnceLock::new();
+
+/// Runs the futures of async emitters on `spawner` in
+/// place of the shared worker thread
+///
+/// Must be called before any async emitter starts. Returns
+/// `false` when the runtime was already picked.
+pub fn set_spawner<S: Spawner>(spawner: S) -> bool {
+    SPAWNER.set(Box::new(spawner)).is_ok()
+}
+
+/// Runs `task` on the runtime picked for async emitters
+pub fn spawn<F: Future<Output = ()> + Send + 'static>(task: F) {
+    SPAWNER
+        .get_or_init(|| Box::new(executor::spawn))
+        .spawn(Box::pin(task));
+}
+
+/// The `AsyncEmitter` struct runs the future built by
+/// `task` for the widget it is connected to
+pub struct AsyncEmitter<F> {
+    task: F,
+}
+impl<F, Fut> AsyncEmitter<F>
+where
+    F: Fn(Trigger) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    pub fn new(task: F) -> Self {
+        Self { task }
+    }
+}
+impl<F, Fut> Thread for AsyncEmitter<F>
+where
+    F: Fn(Trigger) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    fn start(self: Arc<Self>, trigger: Trigger) {
+        spawn((self.task)(trigger));
+    }
+}
+
+/// The worker thread polling every future handed to it
+#[cfg(not(target_arch = "wasm32"))]
+mod executor {
+    use std::{
+        sync::{
+            mpsc::{self, Sender},
+            Arc, Mutex, OnceLock,
+        },
+        task::{Context, Wake, Waker},
+    };
+
+    use super::Task;
+
+    /// A future along with the queue it goes back
+    /// into once woken
+    struct Job {
+        task: Mutex<Option<Task>>,
+        queue: Sender<Arc<Job>>,
+    }
+    impl Wake for Job {
+        fn wake(self: Arc<Self>) {
+            let _ = self.queue.clone().send(self);
+        }
+    }
+
+    /// Returns the queue of the worker thread starting
+    /// it the first time
+    fn queue() -> &'static Sender<Arc<Job>> {
+        static QUEUE: OnceLock<Sender<Arc<Job>>> = OnceLock::new();
+        QUEUE.get_or_init(|| {
+            let (queue, jobs) = mpsc::channel::<Arc<Job>>();
+            std::thread::Builder::new()
+                .name("gemini-runtime".into())
+                .spawn(move || {
+                    for job in jobs {
+                        // Jobs woken again after finishing are left empty
+                        let mut slot = job.task.lock().unwrap();
+                        let Some(mut task) = slot.take() else {
+                            continue;
+                        };
+                        let waker = Waker::from(job.clone());
+                        if task
+                            .as_mut()
+                            .poll(&mut Context::from_waker(&waker))
+                            .is_pending()
+                        {
+                            *slot = Some(task);
+                        }
+                    }
+                })
+                .expect("runtime thread should start");
+            queue
+        })
+    }
+
+    pub(super) fn spawn(task: Task) {
+        let queue = queue();
+        let _ = queue.send(Arc::new(Job {
+            task: Mutex::new(Some(task)),
+            queue: queue.clone(),
+        }));
+    }
+}
+
+/// Browsers have no threads so the futures are queued
+/// onto the page event loop instead
+#[cfg(target_arch = "wasm32")]
+mod executor {
+    use super::Task;
+
+    pub(super) fn spawn(task: Task) {
+        wasm_bindgen_futures::spawn_local(task);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::Future,
+        pin::Pin,
+        sync::mpsc,
+        task::{Context, Poll},
+        time::Duration,
+    };
+
+    use crate::ui::sync::Trigger;
+
+    use super::spawn;
+
+    /// Waits for another thread to wake it once
+    struct Woken(bool);
+    impl Future for Woken {
+        type Output = ();
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                return Poll::Ready(());
+            }
+            self.0 = true;
+            let waker = cx.waker().clone();
+            std::thread::spawn(move || waker.wake());
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn pending_futures_run_once_woken() {
+        let (done, finished) = mpsc::channel();
+        for i in 0..3 {
+            let done = done.clone();
+            spawn(async move {
+                Woken(false).await;
+                done.send(i).unwrap();
+            });
+        }
+
+        let mut finished: Vec<_> = (0..3)
+            .map(|_| finished.recv_timeout(Duration::from_secs(5)).unwrap())
+            .collect();
+        finished.sort();
+        assert_eq!(finished, [0, 1, 2]);
+    }
+
+    #[test]
+    fn triggers_move_into_tasks() {
+        fn assert_send<T: Send + Sync>() {}
+        assert_send::<Trigger>();
+    }
+}
@@ -0,0 +1,85 @@
+//! Tracks the text selected with the mouse.
+//!
+//! Pressing on a selectable text places the caret and dragging
+//! extends the selection from there. Only one text is selected
+//! at a time so pressing anywhere else clears it.
+
+use std::rc::Rc;
+
+use super::{text::TextOverflow, widget::WidgetI};
+
+/// The `SelectionManager` struct owns the text selection of a `DOM`
+#[derive(Default)]
+pub(crate) struct SelectionManager {
+    selected: Option<Rc<dyn WidgetI>>,
+    dragging: bool,
+}
+impl SelectionManager {
+    pub(crate) fn new() -> Self {
+        SelectionManager::default()
+    }
+    /// Starts selecting the text of the last widget in the
+    /// hit `path` at `x` on the screen
+    ///
+    /// The previous selection is cleared either way
+    pub(crate) fn press(&mut self, path: &[Rc<dyn WidgetI>], x: f64) {
+        self.clear();
+
+        let Some(widget) = path.last() else {
+            return;
+        };
+        {
+            let mut base = widget.base_mut();
+            if !base.state.hit || !base.text.selectable || base.text.label.is_empty() {
+                return;
+            }
+            let index = base.text.caret_index(text_x(widget, x));
+            base.text.selection = Some((index, index));
+        }
+        self.selected = Some(widget.clone());
+        self.dragging = true;
+    }
+    /// Extends the selection up to `x` on the screen while
+    /// the mouse is held down
+    pub(crate) fn drag(&mut self, x: f64) {
+        let Some(widget) = self.selected.as_ref().filter(|_| self.dragging) else {
+            return;
+        };
+
+        let index = widget.base().text.caret_index(text_x(widget, x));
+        let mut base = widget.base_mut();
+        let Some((anchor, head)) = base.text.selection else {
+            return;
+        };
+        if head != index {
+            base.text.selection = Some((anchor, index));
+            drop(base);
+            widget.trigger().update();
+        }
+    }
+    /// Stops extending the selection
+    pub(crate) fn release(&mut self) {
+        self.dragging = false;
+    }
+    /// Returns the text that is selected
+    pub(crate) fn selected_text(&self) -> Option<String> {
+        self.selected.as_ref()?.base().text.selected_text()
+    }
+    fn clear(&mut self) {
+        self.dragging = false;
+        if let Some(widget) = self.selected.take() {
+            widget.base_mut().text.selection = None;
+            widget.trigger().update();
+        }
+    }
+}
+
+/// Returns how far `x` on the screen is into the text of `widget`
+fn text_x(widget: &Rc<dyn WidgetI>, x: f64) -> f64 {
+    let base = widget.base();
+    let scroll = match base.text.overflow {
+        TextOverflow::Scroll => base.text.scroll,
+        _ => 0.0,
+    };
+    x - (base.offset.x + base.layout.x + base.text.pos.x) + scroll
+}
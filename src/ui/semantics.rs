@@ -0,0 +1,105 @@
+//! Describes what widgets mean rather than how they look.
+//!
+//! Every widget has a `Role` and an accessible name that screen
+//! readers and test tooling go by. Widgets pick a fitting role by
+//! default and are named after their text label unless told otherwise.
+
+/// The purpose a widget serves in the user interface.
+///
+/// Roles describe the UI meaningfully to assistive technologies
+/// and test tooling independent of how a widget is drawn.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Role {
+    #[default]
+    /// A widget with no particular meaning
+    Generic,
+    /// A widget grouping other widgets
+    Group,
+    /// A widget that triggers an action when pressed
+    Button,
+    /// A widget toggled between checked and unchecked
+    CheckBox,
+    /// A title above a section of content
+    Heading,
+    /// Plain text content
+    Label,
+    /// A drawing surface
+    Canvas,
+    /// A single unit of a grid
+    Cell,
+    /// A control scrolling the content of a container
+    ScrollBar,
+    /// A header switching between panes of content
+    Tab,
+    /// A strip of tab headers
+    TabList,
+    /// A hierarchy of nodes that expand and collapse
+    Tree,
+    /// A single node of a tree
+    TreeItem,
+    /// A widget showing how far along some work is
+    ProgressBar,
+    /// A rule splitting content into sections
+    Separator,
+    /// A figure drawn from lines and curves
+    Graphic,
+}
+
+/// A struct representing how a widget is described to
+/// assistive technologies.
+///
+/// - `role`: The role explicitly set by the user. When `None` the
+///   widget falls back to its default role.
+/// - `name`: The accessible name. When `None` the text label is used.
+/// - `description`: Extra information about the widget.
+#[derive(Default, Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Semantics {
+    pub role: Option<Role>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::ui::widget::{
+        button::Button, checkbox::Checkbox, container::Container, label::Label, spacer::Spacer,
+        Widget, WidgetI,
+    };
+
+    use super::Role;
+
+    #[test]
+    fn widgets_fall_back_to_their_default_role() {
+        let widgets: [(Rc<dyn WidgetI>, Role); 5] = [
+            (Rc::new(Label::new()), Role::Label),
+            (Rc::new(Button::new()), Role::Button),
+            (Rc::new(Checkbox::new()), Role::CheckBox),
+            (Rc::new(Container::new()), Role::Group),
+            (Rc::new(Spacer::new()), Role::Generic),
+        ];
+        for (widget, role) in widgets {
+            assert_eq!(widget.role(), role);
+        }
+
+        // A role set explicitly wins over the default
+        let widget: Rc<dyn WidgetI> = Rc::new(Label::new().set_role(Role::Heading));
+        assert_eq!(widget.role(), Role::Heading);
+    }
+
+    #[test]
+    fn widgets_are_named_after_their_label_unless_named() {
+        let widget: Rc<dyn WidgetI> = Rc::new(Label::new().set_label("Save"));
+        assert_eq!(widget.accessible_name(), "Save");
+
+        let widget: Rc<dyn WidgetI> =
+            Rc::new(Label::new().set_label("💾").set_accessible_name("Save"));
+        assert_eq!(widget.accessible_name(), "Save");
+
+        let widget: Rc<dyn WidgetI> = Rc::new(Container::new());
+        assert_eq!(widget.accessible_name(), "");
+    }
+}
@@ -6,8 +6,19 @@
 ///  underlying widget data.
 ///
 /// - `hovered`: Indicating whether the mouse is currently over the widget.
+/// - `focused`: Indicating whether the widget receives keyboard input.
+/// - `hit`: Indicating whether pointer events are meant for the widget.
+/// - `disabled`: Indicating whether the widget ignores user interaction.
 #[derive(Default, Debug, Clone, PartialEq, PartialOrd)]
 pub struct State {
     /// Indicates whether the mouse is currently over the widget
     pub hovered: bool,
+    /// Indicates whether the widget receives keyboard input
+    pub focused: bool,
+    /// Indicates whether the widget is the topmost one under the
+    /// mouse or a parent it bubbled up to
+    pub(crate) hit: bool,
+    /// Indicates whether the widget ignores clicks, hovering
+    /// and focus
+    pub disabled: bool,
 }
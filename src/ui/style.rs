@@ -1,4 +1,7 @@
-use super::color::ColorState;
+use super::{
+    color::{Color, ColorState, Fill},
+    layout::{Insets, Layout, Point},
+};
 
 /// A struct representing the visual style of a UI element.
 ///
@@ -12,12 +15,345 @@ use super::color::ColorState;
 ///   background, text, or other visual components.
 /// - `radius`: Specifies the corner radius (rounded corners) for the UI
 ///   element. This value controls how rounded the corners of the element
-///   should be.
-/// - `grid`: Optionally defines a `Grid` layout for the element. If present,
-///   this field indicates that the element follows a grid-based structure
-///   (e.g., for a container widget with a grid of items or cells).
-#[derive(Default, Debug, Clone, PartialEq, PartialOrd)]
+///   should be, alike or for every corner on its own.
+/// - `corner_shape`: Whether the corners are rounded as circles or
+///   squircles.
+/// - `border`: Optionally strokes the edges of the element.
+/// - `disabled_overlay`: The color laid over the element while it is
+///   disabled. A translucent gray is used when not set.
+/// - `fill`: Optionally shades the element with a gradient in place of
+///   its flat color.
+/// - `shadow`: Optionally casts a blurred shadow beneath the element.
+/// - `opacity`: How opaque the element and its children are drawn from
+///   `0.0` (invisible) to `1.0` (solid).
+/// - `grid`: Optionally draws lines between the cells of an element laid
+///   out as a grid such as a `Canvas`.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Style {
     pub color: ColorState,
-    pub radius: u32,
+    pub radius: Radius,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub corner_shape: CornerShape,
+    pub border: Option<Border>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub disabled_overlay: Option<Color>,
+    #[cfg_attr(feature = "serde", serde(default = "default_opacity"))]
+    pub opacity: f32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fill: Option<Fill>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub shadow: Option<Shadow>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub grid: Option<GridLines>,
+}
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            color: Default::default(),
+            radius: Radius::default(),
+            corner_shape: CornerShape::default(),
+            border: None,
+            disabled_overlay: None,
+            opacity: default_opacity(),
+            fill: None,
+            shadow: None,
+            grid: None,
+        }
+    }
+}
+
+/// Widgets are solid unless told otherwise
+fn default_opacity() -> f32 {
+    1.0
+}
+
+/// Dims disabled widgets that have no overlay of their own
+pub(crate) const DISABLED_OVERLAY: Color = Color::RGBA(160, 160, 160, 160);
+
+/// A struct recording which style fields were set explicitly
+/// so a `Theme` leaves them alone
+///
+/// - `theme`: The generation of the theme last applied
+#[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Styled {
+    pub color: bool,
+    pub radius: bool,
+    pub font_size: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) theme: u64,
+}
+
+/// A struct representing the blurred shadow cast beneath
+/// a UI element.
+///
+/// - `offset`: How far the shadow is moved from the element
+/// - `blur`: How many pixels the edges of the shadow fade out over
+/// - `color`: The color of the shadow where it is solid
+#[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shadow {
+    pub offset: Point,
+    pub blur: f64,
+    pub color: Color,
+}
+impl Shadow {
+    pub fn new(offset: Point, blur: f64, color: Color) -> Self {
+        Self {
+            offset,
+            blur,
+            color,
+        }
+    }
+    /// Returns how far the shadow reaches past every side
+    /// of the element before the offset
+    pub(crate) fn spread(&self) -> f64 {
+        self.blur.max(0.0).ceil()
+    }
+}
+
+/// A struct representing the stroke drawn along the
+/// edges of a UI element.
+///
+/// The border is drawn inside the element bounds with
+/// its own width on every side.
+#[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Border {
+    pub color: Color,
+    pub width: Insets,
+}
+impl Border {
+    /// Create a border of the same `width` on every side
+    pub fn new(width: f64, color: Color) -> Self {
+        Self {
+            color,
+            width: Insets {
+                top: width,
+                right: width,
+                bottom: width,
+                left: width,
+            },
+        }
+    }
+    /// Create a border with a different width per side
+    ///
+    /// Sides with a zero width are not drawn
+    pub fn sides(width: Insets, color: Color) -> Self {
+        Self { color, width }
+    }
+}
+
+/// A struct representing the stroke drawn along the
+/// outline of a shape.
+///
+/// The stroke is centered on the outline so half of it
+/// reaches outside of the shape.
+#[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outline {
+    pub color: Color,
+    pub width: f64,
+}
+impl Outline {
+    pub fn new(width: f64, color: Color) -> Self {
+        Self { color, width }
+    }
+}
+
+/// A struct representing how far every corner of a UI
+/// element is rounded
+///
+/// Corners adding up to more than the side they share are
+/// scaled down alike until they fit.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Radius {
+    pub top_left: u32,
+    pub top_right: u32,
+    pub bottom_right: u32,
+    pub bottom_left: u32,
+}
+impl Radius {
+    /// Round every corner by `radius`
+    pub fn all(radius: u32) -> Self {
+        Radius::new(radius, radius, radius, radius)
+    }
+    /// Round every corner by its own radius starting at
+    /// the top-left going clockwise
+    pub fn new(top_left: u32, top_right: u32, bottom_right: u32, bottom_left: u32) -> Self {
+        Self {
+            top_left,
+            top_right,
+            bottom_right,
+            bottom_left,
+        }
+    }
+    /// Returns `true` if no corner is rounded
+    pub fn is_zero(&self) -> bool {
+        *self == Radius::default()
+    }
+    /// Returns the radius of every corner starting at the top-left
+    /// going clockwise scaled down to fit a `w` x `h` rect
+    pub(crate) fn fit(&self, w: f64, h: f64) -> [f64; 4] {
+        let [tl, tr, br, bl] = [
+            self.top_left,
+            self.top_right,
+            self.bottom_right,
+            self.bottom_left,
+        ]
+        .map(f64::from);
+        let scale = [(w, tl + tr), (h, tr + br), (w, br + bl), (h, bl + tl)]
+            .into_iter()
+            .filter(|(_, sum)| *sum > 0.0)
+            .map(|(side, sum)| side.max(0.0) / sum)
+            .fold(1.0, f64::min);
+        [tl, tr, br, bl].map(|r| r * scale)
+    }
+    /// Returns the radius of the corners inside of `border`
+    pub(crate) fn inset(&self, border: &Insets) -> Radius {
+        let shrink = |radius: u32, by: f64| (f64::from(radius) - by).max(0.0).round() as u32;
+        Radius {
+            top_left: shrink(self.top_left, border.top.max(border.left)),
+            top_right: shrink(self.top_right, border.top.max(border.right)),
+            bottom_right: shrink(self.bottom_right, border.bottom.max(border.right)),
+            bottom_left: shrink(self.bottom_left, border.bottom.max(border.left)),
+        }
+    }
+    /// Returns `true` if the position `x`, `y` lies inside `rect`
+    /// with its corners rounded off in `shape`
+    pub(crate) fn covers(&self, shape: CornerShape, rect: Layout, x: f64, y: f64) -> bool {
+        if !rect.is_inbounds(x, y) {
+            return false;
+        }
+        let [tl, tr, br, bl] = self.fit(rect.w, rect.h);
+        let (right, bottom) = (rect.x + rect.w, rect.y + rect.h);
+        // The center of every corner along with the direction
+        // its tip points towards
+        let corners = [
+            (tl, rect.x + tl, rect.y + tl, -1.0, -1.0),
+            (tr, right - tr, rect.y + tr, 1.0, -1.0),
+            (br, right - br, bottom - br, 1.0, 1.0),
+            (bl, rect.x + bl, bottom - bl, -1.0, 1.0),
+        ];
+        corners.into_iter().all(|(r, cx, cy, tip_x, tip_y)| {
+            // Only positions past the center towards the tip
+            // may be rounded off
+            let (dx, dy) = ((x - cx) * tip_x, (y - cy) * tip_y);
+            r <= 0.0 || dx <= 0.0 || dy <= 0.0 || shape.reaches(dx / r, dy / r)
+        })
+    }
+}
+impl From<u32> for Radius {
+    fn from(radius: u32) -> Self {
+        Radius::all(radius)
+    }
+}
+
+/// The `CornerShape` enum lists the curves rounded corners
+/// are drawn along
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CornerShape {
+    /// Quarter circles meeting the sides at a sharp bend
+    #[default]
+    Round,
+    /// Quarter superellipses easing into the sides
+    Squircle,
+}
+impl CornerShape {
+    /// The exponent of the superellipse squircles follow
+    pub(crate) const SQUIRCLE_EXPONENT: f64 = 4.0;
+
+    /// Returns `true` if the position `x`, `y` away from the
+    /// center of a corner of radius 1 lies inside the curve
+    fn reaches(&self, x: f64, y: f64) -> bool {
+        match self {
+            CornerShape::Round => x * x + y * y <= 1.0,
+            CornerShape::Squircle => {
+                x.powf(CornerShape::SQUIRCLE_EXPONENT) + y.powf(CornerShape::SQUIRCLE_EXPONENT)
+                    <= 1.0
+            }
+        }
+    }
+}
+
+/// A struct representing the lines drawn between the cells
+/// of a grid.
+///
+/// Lines take up room of their own so cells are laid out
+/// between them even while they are hidden.
+///
+/// - `color`: The color of the lines
+/// - `thickness`: How many pixels wide the lines are
+/// - `visible`: Whether the lines are drawn
+/// - `major`: Optionally draws every few lines in a style of their own
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GridLines {
+    pub color: Color,
+    pub thickness: f64,
+    #[cfg_attr(feature = "serde", serde(default = "default_visible"))]
+    pub visible: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub major: Option<MajorGridLines>,
+}
+impl GridLines {
+    pub fn new(thickness: f64, color: Color) -> Self {
+        Self {
+            color,
+            thickness,
+            visible: true,
+            major: None,
+        }
+    }
+    /// Draw every `every`th line `thickness` wide in `color`
+    /// counting from the first edge of the grid
+    ///
+    /// NoOp if `every` is 0
+    pub fn set_major(mut self, every: u32, thickness: f64, color: Color) -> Self {
+        if every > 0 {
+            self.major = Some(MajorGridLines {
+                every,
+                thickness,
+                color,
+            });
+        }
+        self
+    }
+    /// Returns whether the `index`th line along a row or
+    /// column is a major line
+    pub(crate) fn is_major(&self, index: usize) -> bool {
+        self.major
+            .is_some_and(|major| major.every > 0 && index.is_multiple_of(major.every as usize))
+    }
+    /// Returns how thick and in what color the `index`th line
+    /// along a row or column is drawn
+    pub(crate) fn line(&self, index: usize) -> (f64, Color) {
+        match self.major {
+            Some(major) if self.is_major(index) => (major.thickness, major.color),
+            _ => (self.thickness, self.color),
+        }
+    }
+}
+
+/// A struct representing the style of the major lines
+/// drawn every few cells of a grid.
+///
+/// - `every`: How many cells apart the major lines are
+/// - `thickness`: How many pixels wide the major lines are
+/// - `color`: The color of the major lines
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MajorGridLines {
+    pub every: u32,
+    pub thickness: f64,
+    pub color: Color,
+}
+
+/// Grid lines are shown unless told otherwise
+#[cfg(feature = "serde")]
+fn default_visible() -> bool {
+    true
 }
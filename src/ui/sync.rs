@@ -1,13 +1,20 @@
 use std::{
     rc::Rc,
-    sync::{Arc, Mutex},
-    thread,
+    sync::{mpsc::Sender, Arc, Mutex},
 };
+use web_time::Duration;
 use winit::event_loop::EventLoopProxy;
 
-use super::widget::WidgetI;
+use super::{
+    animate::Animation,
+    arena::WidgetId,
+    layout::Layout,
+    timer::{TimerId, TimerRequest},
+    widget::WidgetI,
+    window::WindowRequest,
+};
 
-pub(crate) type UID = usize;
+pub(crate) type UID = WidgetId;
 
 pub trait WidgetCallback: Fn(Rc<dyn WidgetI>) + Send + Sync + 'static {}
 impl<F: Fn(Rc<dyn WidgetI>) + Send + Sync + 'static> WidgetCallback for F {}
@@ -17,22 +24,70 @@ impl<F: Fn(Rc<dyn WidgetI>) + Send + Sync + 'static> WidgetCallback for F {}
 pub enum Signal {
     /// Redraw widget
     Update(UID),
+    /// Redraw a region of the screen
+    Invalidate(Layout),
     /// Callback to apply changes to a widget
     /// before redrawing
     Callback((UID, Arc<dyn WidgetCallback>)),
+    /// Callback to apply changes to the widget with
+    /// the user given id before redrawing
+    CallbackById((String, Arc<dyn WidgetCallback>)),
+    /// Request coming from assistive technologies
+    #[cfg(feature = "accesskit")]
+    Accessibility(accesskit::ActionRequest),
+    /// Show or hide the on-screen keyboard
+    SoftKeyboard(bool),
+    /// Schedule a timer on the UI thread
+    SetTimer(TimerRequest),
+    /// Cancel a scheduled timer
+    ClearTimer(TimerId),
+    /// Text pasted from the clipboard into the focused widget
+    Paste(String),
+    /// Move, resize or close the window hosting the UI
+    Window(WindowRequest),
+    /// Messages were sent through the `EventBus`
+    Bus,
+    /// Messages were dispatched to the `App`
+    App,
+    /// The UI file the tree was loaded from changed
+    #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+    Reload,
+}
+
+/// The `Proxy` enum describes where `Signal`s for the
+/// UI main thread are sent to
+pub(crate) enum Proxy {
+    /// The event loop of the window
+    EventLoop(EventLoopProxy<Signal>),
+    /// The queue a headless `DOM` handles signals from
+    Queue(Sender<Signal>),
+}
+impl Proxy {
+    /// Sends the `signal` handing it back when nothing
+    /// receives signals anymore
+    pub(crate) fn send_event(&self, signal: Signal) -> Result<(), Signal> {
+        match self {
+            Proxy::EventLoop(proxy) => proxy.send_event(signal).map_err(|closed| closed.0),
+            Proxy::Queue(queue) => queue.send(signal).map_err(|closed| closed.0),
+        }
+    }
 }
 
 /// The `Trigger` struct allows the user to trigger interactions
 /// with the widgets on the UI main thread
 #[derive(Clone)]
 pub struct Trigger {
-    proxy: Arc<Mutex<EventLoopProxy<Signal>>>,
+    proxy: Arc<Mutex<Proxy>>,
     pub(crate) uid: UID,
 }
 impl Trigger {
-    pub(crate) fn new(proxy: Arc<Mutex<EventLoopProxy<Signal>>>, uid: UID) -> Self {
+    pub(crate) fn new(proxy: Arc<Mutex<Proxy>>, uid: UID) -> Self {
         Self { proxy, uid }
     }
+    /// Returns the handle of the widget this trigger targets
+    pub fn id(&self) -> WidgetId {
+        self.uid
+    }
     /// Triggers update to widget
     pub fn update(&self) {
         let _ = self
@@ -41,6 +96,89 @@ impl Trigger {
             .unwrap()
             .send_event(Signal::Update(self.uid));
     }
+    /// Asks for the on-screen keyboard so the widget
+    /// can receive text on touch devices
+    ///
+    /// Typed text arrives as `WindowEvent::Ime` or
+    /// `WindowEvent::KeyboardInput` events
+    pub fn show_keyboard(&self) {
+        let _ = self
+            .proxy
+            .lock()
+            .unwrap()
+            .send_event(Signal::SoftKeyboard(true));
+    }
+    /// Dismisses the on-screen keyboard
+    pub fn hide_keyboard(&self) {
+        let _ = self
+            .proxy
+            .lock()
+            .unwrap()
+            .send_event(Signal::SoftKeyboard(false));
+    }
+    /// Asks the window hosting the UI to be moved,
+    /// minimized, maximized or closed
+    ///
+    /// Lets widgets stand in for the title bar of
+    /// undecorated windows
+    pub fn request_window(&self, request: WindowRequest) {
+        let _ = self
+            .proxy
+            .lock()
+            .unwrap()
+            .send_event(Signal::Window(request));
+    }
+    /// Marks the `rect` of the screen for redraw
+    ///
+    /// Useful to clear where a widget was before moving it
+    pub fn invalidate(&self, rect: Layout) {
+        let _ = self
+            .proxy
+            .lock()
+            .unwrap()
+            .send_event(Signal::Invalidate(rect));
+    }
+    /// Starts building an animation of the widget
+    pub fn animate(&self) -> Animation {
+        Animation::new(self.clone())
+    }
+    /// Runs `callback` on the widget once after `delay`
+    ///
+    /// The widget is redrawn after the callback ran
+    pub fn set_timeout<F: WidgetCallback>(&self, delay: Duration, callback: F) -> TimerId {
+        self.set_timer(delay, false, callback)
+    }
+    /// Runs `callback` on the widget every `interval`
+    /// until cleared
+    ///
+    /// The widget is redrawn after every run of the callback.
+    /// Intervals shorter than `MIN_INTERVAL` are stretched to it
+    pub fn set_interval<F: WidgetCallback>(&self, interval: Duration, callback: F) -> TimerId {
+        self.set_timer(interval, true, callback)
+    }
+    /// Cancels the timer behind `id`
+    pub fn clear_timer(&self, id: TimerId) {
+        let _ = self
+            .proxy
+            .lock()
+            .unwrap()
+            .send_event(Signal::ClearTimer(id));
+    }
+    fn set_timer<F: WidgetCallback>(&self, delay: Duration, repeat: bool, callback: F) -> TimerId {
+        let id = TimerId::next();
+        let _ = self
+            .proxy
+            .lock()
+            .unwrap()
+            .send_event(Signal::SetTimer(TimerRequest {
+                id,
+                target: self.uid,
+                delay,
+                repeat,
+                callback: Arc::new(callback),
+            }));
+        id
+    }
     /// Triggers callback on widget before
     /// updating
     pub fn update_callback<F: WidgetCallback>(&self, callback: F) {
@@ -50,6 +188,18 @@ impl Trigger {
             .unwrap()
             .send_event(Signal::Callback((self.uid, Arc::new(callback))));
     }
+    /// Triggers callback on the widget with the `id` given
+    /// by `set_id` before updating it
+    ///
+    /// Allows reaching any widget in the tree not only the
+    /// one this trigger targets
+    pub fn update_callback_by_id<F: WidgetCallback>(&self, id: &str, callback: F) {
+        let _ = self
+            .proxy
+            .lock()
+            .unwrap()
+            .send_event(Signal::CallbackById((id.to_string(), Arc::new(callback))));
+    }
 }
 
 /// The `Thread` defines anything that has the ability
@@ -67,15 +217,28 @@ pub trait Emitter: Send + Sync + 'static {
 }
 impl<E: Emitter> Thread for E {
     fn start(self: Arc<Self>, trigger: Trigger) {
-        let _ = thread::spawn(move || {
+        spawn(move || {
             self.run(trigger);
         });
     }
 }
 impl<E: Emitter> Thread for Arc<E> {
     fn start(self: Arc<Self>, trigger: Trigger) {
-        let _ = thread::spawn(move || {
+        spawn(move || {
             <Arc<E> as Clone>::clone(&self).run(trigger);
         });
     }
 }
+
+/// Runs `task` off the UI thread
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn(task: impl FnOnce() + Send + 'static) {
+    let _ = std::thread::spawn(task);
+}
+/// Browsers have no threads so the `task` gets queued onto
+/// the page event loop instead which means emitters MUST
+/// not block there
+#[cfg(target_arch = "wasm32")]
+fn spawn(task: impl FnOnce() + 'static) {
+    wasm_bindgen_futures::spawn_local(async move { task() });
+}
@@ -1,10 +1,21 @@
+use std::sync::OnceLock;
+
 use ab_glyph::{point, Font as _, FontRef, PxScale, ScaleFont as _};
 
-use super::layout::Point;
+use super::{
+    color::{Color, BLACK},
+    layout::Point,
+};
 
 pub(crate) const DEFAULT_FONT: &'static [u8; 146004] =
     include_bytes!("../../fonts/Roboto-Regular.ttf");
 
+/// Returns the default font parsed once for the whole app
+pub(crate) fn default_font() -> &'static FontRef<'static> {
+    static FONT: OnceLock<FontRef<'static>> = OnceLock::new();
+    FONT.get_or_init(|| FontRef::try_from_slice(DEFAULT_FONT).unwrap())
+}
+
 /// A struct representing the text content of a UI element.
 ///
 /// The `Text` struct is used to store and manage textual information
@@ -15,28 +26,208 @@ pub(crate) const DEFAULT_FONT: &'static [u8; 146004] =
 ///   displayed. If `None`, the element may not display any text, or a
 ///   default value may be used. If `Some`, the string is the label or text
 ///   shown on the element.
+/// - `color`: The color the text is drawn in
+/// - `spans`: Differently styled parts of the text which are drawn
+///   in place of the `label` when any are set
+/// - `overflow`: How text too wide for the widget is shown
+/// - `selectable`: Whether the text can be selected by dragging
+///   over it with the mouse
+/// - `preedit`: Text still being composed with an input method
+///   which is shown underlined after the text until committed
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Text {
     pub label: String,
     pub font_size: f32,
     pub pos: Point,
     pub(crate) valign: bool,
     pub(crate) halign: bool,
+    #[cfg_attr(feature = "serde", serde(default = "default_color"))]
+    pub color: Color,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub spans: Vec<Span>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub overflow: TextOverflow,
+    /// How many characters fit before the ellipsis
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) truncated: Option<usize>,
+    /// How far scrolling text was moved to the left
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) scroll: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub selectable: bool,
+    /// Where the selection was started and where it
+    /// extends to in characters
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) selection: Option<(usize, usize)>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) preedit: Option<Preedit>,
 }
 impl Text {
     /// Get the perfect display height and width for text
     /// based on the font style and kerning included
     pub(crate) fn get_true_dimensions(&self) -> Point {
-        let font = FontRef::try_from_slice(DEFAULT_FONT).unwrap();
-        let mut caret = point(0.0, self.font_size);
-        let scale = PxScale::from(self.font_size);
-        for c in self.label.chars() {
-            let glyph = font.glyph_id(c).with_scale_and_position(scale, caret);
-            caret.x += font.as_scaled(scale).h_advance(glyph.id);
+        Text::measure(&self.resolved_spans())
+    }
+    /// Get the display height and width of the part
+    /// of the text that is shown
+    pub(crate) fn get_visible_dimensions(&self) -> Point {
+        Text::measure(&self.visible_spans())
+    }
+    /// Measures the width of `spans` and the height of
+    /// the line they sit on
+    fn measure(spans: &[Span]) -> Point {
+        let font = default_font();
+        let mut caret = point(0.0, LineMetrics::of(spans).height());
+        for span in spans {
+            let scale = PxScale::from(span.font_size.unwrap_or_default());
+            for c in span.text.chars() {
+                let glyph = font.glyph_id(c).with_scale_and_position(scale, caret);
+                caret.x += font.as_scaled(scale).h_advance(glyph.id);
+            }
         }
 
         caret.into()
     }
+    /// Works out how much of the text fits into `width`
+    /// when the overflow is an ellipsis
+    pub(crate) fn fit(&mut self, width: f64) {
+        self.truncated = None;
+        if self.overflow != TextOverflow::Ellipsis || self.get_true_dimensions().x <= width {
+            return;
+        }
+
+        let font = default_font();
+        let spans = self.resolved_spans();
+        let ellipsis = {
+            let scale = PxScale::from(spans.last().and_then(|s| s.font_size).unwrap_or_default());
+            f64::from(font.as_scaled(scale).h_advance(font.glyph_id(ELLIPSIS)))
+        };
+        let mut caret = 0.0;
+        let mut fits = 0;
+        for span in &spans {
+            let scale = PxScale::from(span.font_size.unwrap_or_default());
+            for c in span.text.chars() {
+                caret += f64::from(font.as_scaled(scale).h_advance(font.glyph_id(c)));
+                if caret + ellipsis > width {
+                    self.truncated = Some(fits);
+                    return;
+                }
+                fits += 1;
+            }
+        }
+    }
+    /// Moves scrolling text by `amount` to the left keeping
+    /// it inside the `width` it is shown in
+    pub(crate) fn scroll_by(&mut self, amount: f64, width: f64) {
+        let max = (self.get_true_dimensions().x - width).max(0.0);
+        self.scroll = (self.scroll + amount).clamp(0.0, max);
+    }
+    /// Returns the spans that are shown with every
+    /// style resolved
+    ///
+    /// Truncated text ends in an ellipsis styled like
+    /// the last character kept
+    pub(crate) fn visible_spans(&self) -> Vec<Span> {
+        let spans = self.resolved_spans();
+        let Some(mut remaining) = self.truncated else {
+            return spans;
+        };
+
+        let mut visible = Vec::new();
+        for span in spans {
+            let count = span.text.chars().count();
+            if remaining <= count {
+                let mut text: String = span.text.chars().take(remaining).collect();
+                text.push(ELLIPSIS);
+                visible.push(Span { text, ..span });
+                break;
+            }
+            remaining -= count;
+            visible.push(span);
+        }
+        visible
+    }
+    /// Returns the character boundary closest to `x` pixels
+    /// into the text
+    pub(crate) fn caret_index(&self, x: f64) -> usize {
+        let font = default_font();
+        let mut caret = 0.0;
+        let mut index = 0;
+        for span in self.visible_spans() {
+            let scale = PxScale::from(span.font_size.unwrap_or_default());
+            for c in span.text.chars() {
+                let advance = f64::from(font.as_scaled(scale).h_advance(font.glyph_id(c)));
+                if x < caret + advance / 2.0 {
+                    return index;
+                }
+                caret += advance;
+                index += 1;
+            }
+        }
+        // The ellipsis stands in for the rest of the text
+        match self.truncated {
+            Some(fits) => fits,
+            None => index,
+        }
+    }
+    /// Returns how many pixels into the text the caret of
+    /// the text being composed sits
+    ///
+    /// Without any composition the caret sits at the end
+    pub(crate) fn preedit_caret(&self) -> f64 {
+        let mut spans = self.resolved_spans();
+        if let Some(Preedit { cursor: Some(cursor), .. }) = &self.preedit {
+            let composing = spans.last_mut();
+            if let Some(span) = composing.filter(|span| span.text.is_char_boundary(*cursor)) {
+                span.text.truncate(*cursor);
+            }
+        }
+        Text::measure(&spans).x
+    }
+    /// Returns the range of characters selected
+    /// from the first to the last
+    pub(crate) fn selected_range(&self) -> Option<(usize, usize)> {
+        self.selection
+            .map(|(anchor, head)| (anchor.min(head), anchor.max(head)))
+            .filter(|(start, end)| start != end)
+    }
+    /// Returns the characters that are selected
+    pub(crate) fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selected_range()?;
+        let text: String = self
+            .resolved_spans()
+            .iter()
+            .flat_map(|span| span.text.chars())
+            .skip(start)
+            .take(end - start)
+            .collect();
+        Some(text)
+    }
+    /// Returns the spans to draw with every style resolved
+    ///
+    /// A text without spans is drawn as a single span
+    /// of the `label`
+    ///
+    /// Text being composed follows as an underlined span
+    pub(crate) fn resolved_spans(&self) -> Vec<Span> {
+        let mut spans = if self.spans.is_empty() {
+            vec![Span::new(&self.label)]
+        } else {
+            self.spans.clone()
+        };
+        if let Some(preedit) = &self.preedit {
+            spans.push(Span::new(&preedit.text).set_underline());
+        }
+        spans
+            .into_iter()
+            .map(|span| Span {
+                color: Some(span.color.unwrap_or(self.color)),
+                font_size: Some(span.font_size.unwrap_or(self.font_size)),
+                ..span
+            })
+            .collect()
+    }
 }
 impl Default for Text {
     fn default() -> Self {
@@ -45,7 +236,240 @@ impl Default for Text {
             font_size: 12.0,
             pos: Default::default(),
             valign: false,
-            halign: false
+            halign: false,
+            color: default_color(),
+            spans: Vec::new(),
+            overflow: TextOverflow::default(),
+            truncated: None,
+            scroll: 0.0,
+            selectable: false,
+            selection: None,
+            preedit: None,
         }
     }
 }
+
+/// The `Preedit` struct holds text still being composed
+/// with an input method
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub(crate) struct Preedit {
+    pub(crate) text: String,
+    /// Where the cursor or the part being converted
+    /// starts in bytes into the text
+    pub(crate) cursor: Option<usize>,
+}
+
+/// The `LineMetrics` struct holds how far a line of
+/// text reaches around its baseline
+///
+/// - `ascent`: How far the tallest glyphs reach above the baseline
+/// - `descent`: How far glyphs like `g` or `y` reach below the baseline
+/// - `line_gap`: The space the font leaves between lines which is
+///   split evenly above and below the line
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct LineMetrics {
+    pub(crate) ascent: f32,
+    pub(crate) descent: f32,
+    pub(crate) line_gap: f32,
+}
+impl LineMetrics {
+    /// Returns the metrics of the line all `spans` sit on
+    ///
+    /// Spans shrunk down to nothing are left out
+    pub(crate) fn of(spans: &[Span]) -> Self {
+        let font = default_font();
+        spans
+            .iter()
+            .filter_map(|span| span.font_size)
+            .filter(|font_size| *font_size > 0.0)
+            .fold(LineMetrics::default(), |line, font_size| {
+                let font = font.as_scaled(PxScale::from(font_size));
+                LineMetrics {
+                    ascent: line.ascent.max(font.ascent()),
+                    descent: line.descent.max(-font.descent()),
+                    line_gap: line.line_gap.max(font.line_gap()),
+                }
+            })
+    }
+    /// Returns how tall the line is
+    pub(crate) fn height(&self) -> f32 {
+        self.ascent + self.descent + self.line_gap
+    }
+    /// Returns how far below the top of the line
+    /// the baseline sits
+    pub(crate) fn baseline(&self) -> f32 {
+        self.line_gap / 2.0 + self.ascent
+    }
+}
+
+/// Shown in place of the text cut off
+const ELLIPSIS: char = '\u{2026}';
+
+/// How text too wide for its widget is shown
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextOverflow {
+    /// Cut off at the edge of the widget
+    #[default]
+    Clip,
+    /// Cut off early enough to end in an ellipsis
+    Ellipsis,
+    /// Cut off at the edge of the widget while the mouse
+    /// wheel moves the text sideways
+    Scroll,
+}
+
+/// Text is black unless told otherwise
+fn default_color() -> Color {
+    BLACK
+}
+
+/// How thick the strokes of the text are drawn
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FontWeight {
+    #[default]
+    Regular,
+    Bold,
+}
+
+/// A part of a text drawn in its own style
+///
+/// Styles left as `None` are taken from the surrounding `Text`
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span {
+    pub text: String,
+    pub color: Option<Color>,
+    pub font_size: Option<f32>,
+    pub weight: FontWeight,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub underline: bool,
+}
+impl Span {
+    /// Create a new `Span` in the style of the surrounding text
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+    /// Set the color the span is drawn in
+    pub fn set_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+    /// Set the font size of the span
+    pub fn set_font_size(mut self, size: f32) -> Self {
+        self.font_size = Some(size);
+        self
+    }
+    /// Draw the span with thicker strokes
+    pub fn set_bold(mut self) -> Self {
+        self.weight = FontWeight::Bold;
+        self
+    }
+    /// Draw a line under the span
+    pub fn set_underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+}
+
+/// A text made of differently styled `Span`s
+///
+/// ```ignore
+/// let label = Label::new().set_rich_label(
+///     RichText::new()
+///         .push(Span::new("Status: "))
+///         .push(Span::new("online").set_color(GREEN).set_bold()),
+/// );
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct RichText {
+    spans: Vec<Span>,
+}
+impl RichText {
+    pub fn new() -> Self {
+        RichText::default()
+    }
+    /// Append the `span` to the end of the text
+    pub fn push(mut self, span: Span) -> Self {
+        self.spans.push(span);
+        self
+    }
+    /// Returns the unstyled text of all spans
+    pub fn plain(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+    pub(crate) fn into_spans(self) -> Vec<Span> {
+        self.spans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::color::RED;
+
+    use super::*;
+
+    #[test]
+    fn spans_inherit_the_text_style() {
+        let text = Text {
+            label: "ab".into(),
+            spans: RichText::new()
+                .push(Span::new("a"))
+                .push(Span::new("b").set_color(RED).set_font_size(24.0))
+                .into_spans(),
+            ..Default::default()
+        };
+
+        let spans = text.resolved_spans();
+        assert_eq!(spans[0].color, Some(BLACK));
+        assert_eq!(spans[0].font_size, Some(12.0));
+        assert_eq!(spans[1].color, Some(RED));
+        assert_eq!(text.get_true_dimensions().y, 24.0);
+    }
+
+    #[test]
+    fn ellipsis_fits_the_width() {
+        let mut text = Text {
+            label: "a long label".into(),
+            overflow: TextOverflow::Ellipsis,
+            ..Default::default()
+        };
+        let full = text.get_true_dimensions().x;
+
+        text.fit(full);
+        assert_eq!(text.truncated, None);
+
+        text.fit(full / 2.0);
+        let visible = text.visible_spans();
+        assert!(visible[0].text.ends_with(ELLIPSIS));
+        assert!(text.get_visible_dimensions().x <= full / 2.0);
+
+        text.overflow = TextOverflow::Clip;
+        text.fit(full / 2.0);
+        assert_eq!(text.visible_spans()[0].text, "a long label");
+    }
+
+    #[test]
+    fn selects_characters_under_the_caret() {
+        let mut text = Text {
+            label: "hello world".into(),
+            ..Default::default()
+        };
+        let full = text.get_true_dimensions().x;
+
+        assert_eq!(text.caret_index(-5.0), 0);
+        assert_eq!(text.caret_index(full + 5.0), 11);
+
+        let start = text.caret_index(0.0);
+        let end = text.caret_index(full / 2.0);
+        text.selection = Some((end, start));
+        assert_eq!(text.selected_text().as_deref(), Some(&text.label[..end]));
+
+        text.selection = Some((3, 3));
+        assert_eq!(text.selected_text(), None);
+    }
+}
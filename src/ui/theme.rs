@@ -0,0 +1,190 @@
+//! Global styling for every widget of a `DOM`.
+//!
+//! A `Theme` fills in the style of widgets that never had it set
+//! explicitly. Colors are picked by the role of the widget so buttons,
+//! containers and scrollbars each get a fitting color from the palette.
+
+use super::{
+    color::{Color, BLACK, WHITE},
+    semantics::Role,
+    widget::BaseWidget,
+};
+
+/// The semantic colors of a `Palette` widgets can follow
+/// through `color::themed`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThemeColor {
+    Background,
+    Surface,
+    Accent,
+    Scrollbar,
+}
+
+/// The colors a `Theme` styles widgets with
+///
+/// - `background`: Containers grouping other widgets
+/// - `surface`: Widgets drawn on top of the background (checkboxes, canvases)
+/// - `accent`: Widgets inviting interaction such as buttons
+/// - `scrollbar`: The thumbs of scrollbars and separators
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Palette {
+    pub background: Color,
+    pub surface: Color,
+    pub accent: Color,
+    pub scrollbar: Color,
+}
+
+/// The `Theme` struct describes the default look of widgets
+///
+/// - `palette`: The colors picked by widget role
+/// - `font_size`: The size of text without a label size set
+/// - `radius`: The corner radius of buttons and containers
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Theme {
+    pub palette: Palette,
+    pub font_size: f32,
+    pub radius: u32,
+}
+impl Theme {
+    /// A theme of dark text on bright surfaces
+    pub fn light() -> Self {
+        Self {
+            palette: Palette {
+                background: Color::RGBA(245, 245, 245, 255),
+                surface: Color::RGBA(255, 255, 255, 255),
+                accent: Color::RGBA(66, 133, 244, 255),
+                scrollbar: Color::RGBA(200, 200, 200, 255),
+            },
+            font_size: 12.0,
+            radius: 4,
+        }
+    }
+    /// A theme of bright text on dark surfaces
+    pub fn dark() -> Self {
+        Self {
+            palette: Palette {
+                background: Color::RGBA(32, 33, 36, 255),
+                surface: Color::RGBA(48, 49, 52, 255),
+                accent: Color::RGBA(138, 180, 248, 255),
+                scrollbar: Color::RGBA(95, 99, 104, 255),
+            },
+            font_size: 12.0,
+            radius: 4,
+        }
+    }
+    /// Returns the palette color behind `key`
+    pub fn color(&self, key: ThemeColor) -> Color {
+        match key {
+            ThemeColor::Background => self.palette.background,
+            ThemeColor::Surface => self.palette.surface,
+            ThemeColor::Accent => self.palette.accent,
+            ThemeColor::Scrollbar => self.palette.scrollbar,
+        }
+    }
+    /// Determines if the theme has a dark background
+    pub fn is_dark(&self) -> bool {
+        let (r, g, b): (u8, u8, u8) = self.palette.background.into();
+        // Perceived brightness
+        let brightness = 0.299 * f64::from(r) + 0.587 * f64::from(g) + 0.114 * f64::from(b);
+        brightness < 128.0
+    }
+    /// Returns the color hovered and pressed widgets are
+    /// shaded with
+    ///
+    /// Dark themes lighten widgets while light themes darken them
+    pub fn shade(&self) -> Color {
+        if self.is_dark() {
+            WHITE
+        } else {
+            BLACK
+        }
+    }
+    /// Returns the palette color of widgets with `role`
+    ///
+    /// Text, grid cells and shapes keep their own color
+    pub(crate) fn color_for(&self, role: Role) -> Option<Color> {
+        match role {
+            Role::Group | Role::Tree => Some(self.palette.background),
+            Role::Button => Some(self.palette.accent),
+            Role::CheckBox | Role::Canvas | Role::Tab => Some(self.palette.surface),
+            Role::ScrollBar | Role::Separator => Some(self.palette.scrollbar),
+            Role::Generic
+            | Role::Heading
+            | Role::Label
+            | Role::Cell
+            | Role::TabList
+            | Role::TreeItem
+            | Role::ProgressBar
+            | Role::Graphic => None,
+        }
+    }
+    /// Fills in every style field of `base` not set explicitly
+    ///
+    /// Themed colors are always resolved against this theme
+    pub(crate) fn apply(&self, base: &mut BaseWidget, role: Role) {
+        if let Some(key) = base.style.color.themed() {
+            base.style.color.set_color(self.color(key));
+        } else if !base.styled.color {
+            if let Some(color) = self.color_for(role) {
+                // Keeps hover overlays and the like intact
+                base.style.color.set_color(color);
+            }
+        }
+        if !base.styled.radius && matches!(role, Role::Button | Role::Group) {
+            base.style.radius = self.radius.into();
+        }
+        if !base.styled.font_size {
+            base.text.font_size = self.font_size;
+        }
+    }
+}
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::light()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::{
+        color::{themed, RED},
+        semantics::Role,
+        style::Radius,
+        widget::BaseWidget,
+    };
+
+    use super::{Theme, ThemeColor};
+
+    #[test]
+    fn test_apply_keeps_explicit_fields() {
+        let theme = Theme::dark();
+
+        let mut base = BaseWidget::default();
+        theme.apply(&mut base, Role::Button);
+        assert!(base.style.color.color() == theme.palette.accent);
+        assert!(base.style.radius == Radius::all(theme.radius));
+
+        let mut base = BaseWidget::default();
+        base.style.color = RED.into();
+        base.styled.color = true;
+        base.text.font_size = 30.0;
+        base.styled.font_size = true;
+        theme.apply(&mut base, Role::Button);
+        assert!(base.style.color.color() == RED);
+        assert!(base.text.font_size == 30.0);
+    }
+
+    #[test]
+    fn test_themed_colors_follow_the_theme() {
+        let mut base = BaseWidget::default();
+        base.style.color = themed(ThemeColor::Surface);
+        base.styled.color = true;
+
+        Theme::light().apply(&mut base, Role::Label);
+        assert!(base.style.color.color() == Theme::light().palette.surface);
+        Theme::dark().apply(&mut base, Role::Label);
+        assert!(base.style.color.color() == Theme::dark().palette.surface);
+        assert!(Theme::dark().is_dark() && !Theme::light().is_dark());
+    }
+}
@@ -0,0 +1,210 @@
+//! Schedules work on the UI thread.
+//!
+//! Timers run a callback against a widget once after a delay or
+//! repeatedly on an interval. The `DOM` sleeps the event loop until
+//! the next timer is due, so idle timers cost nothing in between.
+
+use std::{
+    rc::Rc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use web_time::{Duration, Instant};
+
+use super::{arena::WidgetId, sync::WidgetCallback, widget::WidgetI};
+
+/// The shortest interval repeating timers run at
+///
+/// Shorter ones would keep the event loop from ever sleeping
+pub const MIN_INTERVAL: Duration = Duration::from_millis(4);
+
+/// A handle to a scheduled timer used to cancel it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimerId(u64);
+impl TimerId {
+    /// Hands out a new id
+    ///
+    /// Ids are global so timers scheduled off the UI
+    /// thread can be identified right away
+    pub(crate) fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        TimerId(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// A request to run `callback` against the `target` widget
+/// after `delay`, repeating when `repeat` is set
+#[derive(Clone)]
+pub struct TimerRequest {
+    pub(crate) id: TimerId,
+    pub(crate) target: WidgetId,
+    pub(crate) delay: Duration,
+    pub(crate) repeat: bool,
+    pub(crate) callback: Arc<dyn WidgetCallback>,
+}
+
+struct Timer {
+    request: TimerRequest,
+    deadline: Instant,
+}
+
+/// The `Timers` struct keeps every pending timer of a `DOM`
+#[derive(Default)]
+pub(crate) struct Timers {
+    timers: Vec<Timer>,
+}
+impl Timers {
+    pub(crate) fn new() -> Self {
+        Timers::default()
+    }
+    /// Schedules the `request` counting from now
+    ///
+    /// Repeating timers run no more often than `MIN_INTERVAL`
+    pub(crate) fn add(&mut self, mut request: TimerRequest) {
+        if request.repeat {
+            request.delay = request.delay.max(MIN_INTERVAL);
+        }
+        self.timers.push(Timer {
+            deadline: Instant::now() + request.delay,
+            request,
+        });
+    }
+    /// Cancels the timer behind `id` if it is still pending
+    pub(crate) fn remove(&mut self, id: TimerId) {
+        self.timers.retain(|timer| timer.request.id != id);
+    }
+    /// Returns when the next timer is due
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        self.timers.iter().map(|timer| timer.deadline).min()
+    }
+    /// Runs the callbacks of every due timer against the widget
+    /// `lookup` finds for it
+    ///
+    /// The widget is handed to `touch` before and after its callback
+    /// so anything the callback moved can be redrawn. Timers of widgets
+    /// that no longer exist are dropped.
+    pub(crate) fn run_due<L, T>(&mut self, lookup: L, mut touch: T)
+    where
+        L: Fn(WidgetId) -> Option<Rc<dyn WidgetI>>,
+        T: FnMut(&Rc<dyn WidgetI>),
+    {
+        let now = Instant::now();
+
+        let mut due = Vec::new();
+        self.timers.retain(|timer| {
+            if timer.deadline <= now {
+                due.push(timer.request.clone());
+                false
+            } else {
+                true
+            }
+        });
+
+        for request in due {
+            let Some(widget) = lookup(request.target) else {
+                continue;
+            };
+            touch(&widget);
+            (request.callback)(widget.clone());
+            touch(&widget);
+
+            if request.repeat {
+                // Counting from now so waking up late never
+                // bursts callbacks to catch up
+                self.timers.push(Timer {
+                    deadline: now + request.delay,
+                    request,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        rc::Rc,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use web_time::Duration;
+
+    use crate::ui::{
+        arena::{Arena, WidgetId},
+        widget::{label::Label, WidgetI},
+    };
+
+    use super::{TimerId, TimerRequest, Timers, MIN_INTERVAL};
+
+    /// Schedules a timer on `target` counting its runs in `runs`
+    fn request(target: WidgetId, repeat: bool, runs: &Arc<AtomicUsize>) -> TimerRequest {
+        let runs = runs.clone();
+        TimerRequest {
+            id: TimerId::next(),
+            target,
+            delay: Duration::ZERO,
+            repeat,
+            callback: Arc::new(move |_| {
+                runs.fetch_add(1, Ordering::Relaxed);
+            }),
+        }
+    }
+
+    fn run(timers: &mut Timers, widgets: &Arena<Rc<dyn WidgetI>>) {
+        timers.run_due(|id| widgets.get(id).cloned(), |_| {});
+    }
+
+    #[test]
+    fn timeouts_run_once_and_intervals_until_cleared() {
+        let mut widgets: Arena<Rc<dyn WidgetI>> = Arena::new();
+        let id = widgets.insert(Rc::new(Label::new()));
+        let (once, every) = (Arc::default(), Arc::default());
+        let mut timers = Timers::new();
+        timers.add(request(id, false, &once));
+        let interval = request(id, true, &every);
+        let interval_id = interval.id;
+        timers.add(interval);
+
+        // Even a zero interval waits before running
+        run(&mut timers, &widgets);
+        assert_eq!(once.load(Ordering::Relaxed), 1);
+        assert_eq!(every.load(Ordering::Relaxed), 0);
+
+        std::thread::sleep(MIN_INTERVAL);
+        run(&mut timers, &widgets);
+        run(&mut timers, &widgets);
+        assert_eq!(once.load(Ordering::Relaxed), 1);
+        assert_eq!(every.load(Ordering::Relaxed), 1);
+
+        std::thread::sleep(MIN_INTERVAL);
+        run(&mut timers, &widgets);
+        assert_eq!(every.load(Ordering::Relaxed), 2);
+
+        timers.remove(interval_id);
+        std::thread::sleep(MIN_INTERVAL);
+        run(&mut timers, &widgets);
+        assert_eq!(every.load(Ordering::Relaxed), 2);
+        assert!(timers.next_deadline().is_none());
+    }
+
+    #[test]
+    fn timers_of_removed_widgets_are_dropped() {
+        let mut widgets: Arena<Rc<dyn WidgetI>> = Arena::new();
+        let id = widgets.insert(Rc::new(Label::new()));
+        let runs = Arc::default();
+        let mut timers = Timers::new();
+        timers.add(request(id, true, &runs));
+
+        widgets.remove(id);
+        std::thread::sleep(MIN_INTERVAL);
+        run(&mut timers, &widgets);
+        assert_eq!(runs.load(Ordering::Relaxed), 0);
+        assert!(timers.next_deadline().is_none());
+    }
+}
@@ -0,0 +1,246 @@
+//! A plain data description of a widget tree.
+//!
+//! Widgets hold actions, emitters and triggers which can not be saved,
+//! so a tree is first turned into `WidgetNode`s carrying only what
+//! describes its look and layout. With the `serde` feature enabled the
+//! nodes can be written to and read from any serde format (JSON, RON,
+//! ...) which allows saving layouts and building external design tools.
+//!
+//! Actions and emitters are attached again by id once a tree is
+//! loaded, see `DOM::attach_action` and `DOM::attach_emitter`.
+
+use std::rc::Rc;
+
+use super::{
+    color::Color,
+    layout::{Align, FlexLayout},
+    widget::{
+        button::Button,
+        canvas::Canvas,
+        checkbox::Checkbox,
+        container::Container,
+        heading::Heading,
+        icon::{Icon, IconName},
+        label::Label,
+        BaseWidget, WidgetI,
+    },
+};
+
+/// The kind of widget a `WidgetNode` describes along with the
+/// settings specific to it
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WidgetKind {
+    Container {
+        flex: FlexLayout,
+        gap: f64,
+        vertical: bool,
+        horizontal: bool,
+        main_align: Align,
+        cross_align: Align,
+        scrollable: bool,
+    },
+    Button,
+    Label,
+    Heading,
+    Checkbox {
+        checked: bool,
+        check_color: Color,
+    },
+    Canvas {
+        grid: Option<GridSpec>,
+    },
+    Icon {
+        name: IconName,
+        tint: Color,
+    },
+}
+impl WidgetKind {
+    /// Returns the kind of `widget` along with its settings
+    ///
+    /// Returns `None` for widgets of a kind that can not be described
+    pub(crate) fn of(widget: &Rc<dyn WidgetI>) -> Option<WidgetKind> {
+        let kind = if let Some(container) = widget.downcast::<Container>() {
+            WidgetKind::Container {
+                flex: container.flex.clone(),
+                gap: container.gap,
+                vertical: container.valign,
+                horizontal: container.halign,
+                main_align: container.main_align,
+                cross_align: container.cross_align,
+                scrollable: container.scrollbar.is_some(),
+            }
+        } else if widget.downcast::<Button>().is_some() {
+            WidgetKind::Button
+        } else if widget.downcast::<Label>().is_some() {
+            WidgetKind::Label
+        } else if widget.downcast::<Heading>().is_some() {
+            WidgetKind::Heading
+        } else if let Some(checkbox) = widget.downcast::<Checkbox>() {
+            WidgetKind::Checkbox {
+                checked: checkbox.is_checked(),
+                check_color: checkbox.check_color,
+            }
+        } else if let Some(canvas) = widget.downcast::<Canvas>() {
+            // Cells are generated by the grid so only the grid is kept
+            // while its lines are part of the style
+            WidgetKind::Canvas {
+                grid: canvas.grid.borrow().as_ref().map(|grid| GridSpec {
+                    cols: grid.size.x as u32,
+                    rows: grid.size.y as u32,
+                }),
+            }
+        } else if let Some(icon) = widget.downcast::<Icon>() {
+            WidgetKind::Icon {
+                name: icon.name(),
+                tint: icon.tint,
+            }
+        } else {
+            return None;
+        };
+        Some(kind)
+    }
+}
+
+/// The settings of a `Canvas` grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GridSpec {
+    pub cols: u32,
+    pub rows: u32,
+}
+
+/// The `WidgetNode` struct describes a widget and its children
+/// without any behavior attached
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WidgetNode {
+    pub kind: WidgetKind,
+    pub base: BaseWidget,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub children: Vec<WidgetNode>,
+}
+impl WidgetNode {
+    /// Describes `widget` and its children
+    ///
+    /// Returns `None` for widgets of a kind that can not be
+    /// described. Children of such kind are left out.
+    pub fn from_widget(widget: &Rc<dyn WidgetI>) -> Option<WidgetNode> {
+        let kind = WidgetKind::of(widget)?;
+        // Only containers hold children that can be described
+        let children = match widget.downcast::<Container>() {
+            Some(container) => container
+                .children
+                .borrow()
+                .iter()
+                .filter_map(WidgetNode::from_widget)
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Some(WidgetNode {
+            kind,
+            base: widget.base().clone(),
+            children,
+        })
+    }
+    /// Creates the widget tree this node describes
+    pub fn build(&self) -> Rc<dyn WidgetI> {
+        let widget: Rc<dyn WidgetI> = match &self.kind {
+            WidgetKind::Container {
+                flex,
+                gap,
+                vertical,
+                horizontal,
+                main_align,
+                cross_align,
+                scrollable,
+            } => {
+                let mut container = Container::new()
+                    .set_flex_layout(flex.clone())
+                    .set_gap(*gap)
+                    .set_main_align(*main_align)
+                    .set_cross_align(*cross_align);
+                if *vertical {
+                    container = container.set_vertical();
+                }
+                if *horizontal {
+                    container = container.set_horizontal();
+                }
+                if *scrollable {
+                    container = container.on_scroll();
+                }
+                container
+                    .children
+                    .get_mut()
+                    .extend(self.children.iter().map(WidgetNode::build));
+                Rc::new(container)
+            }
+            WidgetKind::Button => Rc::new(Button::new()),
+            WidgetKind::Label => Rc::new(Label::new()),
+            WidgetKind::Heading => Rc::new(Heading::new()),
+            WidgetKind::Checkbox {
+                checked,
+                check_color,
+            } => Rc::new(
+                Checkbox::new()
+                    .set_checked(*checked)
+                    .set_check_color(*check_color),
+            ),
+            WidgetKind::Canvas { grid } => {
+                let mut canvas = Canvas::new();
+                if let Some(grid) = grid {
+                    canvas = canvas.with_cells((grid.cols, grid.rows));
+                }
+                Rc::new(canvas)
+            }
+            WidgetKind::Icon { name, tint } => Rc::new(Icon::new(*name).set_tint(*tint)),
+        };
+
+        *widget.base_mut() = self.base.clone();
+        widget
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::ui::{
+        color::RED,
+        layout::FlexLayout,
+        widget::{button::Button, container::Container, label::Label, Widget, WidgetI},
+    };
+
+    use super::WidgetNode;
+
+    #[test]
+    fn test_build_restores_described_tree() {
+        let mut container = Container::new()
+            .set_id("root")
+            .set_gap(4.0)
+            .set_flex_layout(FlexLayout::Col);
+        container.add_widget(Button::new().set_id("ok").set_label("Ok").set_color(RED));
+        container.add_widget(Label::new().set_label("hi"));
+        let widget: Rc<dyn WidgetI> = Rc::new(container);
+
+        let node = WidgetNode::from_widget(&widget).unwrap();
+        let rebuilt = WidgetNode::from_widget(&node.build()).unwrap();
+
+        assert_eq!(node, rebuilt);
+        assert_eq!(rebuilt.children.len(), 2);
+        assert_eq!(rebuilt.children[0].base.id, "ok");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_round_trips_through_json() {
+        let widget: Rc<dyn WidgetI> = Rc::new(Label::new().set_id("title").set_label("hi"));
+        let node = WidgetNode::from_widget(&widget).unwrap();
+
+        let json = serde_json::to_string(&node).unwrap();
+        let loaded: WidgetNode = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(node, loaded);
+    }
+}
@@ -1,16 +1,38 @@
 use std::{
     any::Any,
-    cell::{Ref, RefCell, RefMut},
+    cell::{Cell, Ref, RefCell, RefMut},
     rc::Rc,
     sync::Arc,
 };
 
 use crate::{
-    action::Action,
-    ui::sync::{Thread, Trigger},
+    action::{press::Press, Action},
+    ui::{
+        color::{themed, Color, BLACK, TRANSPARENT},
+        semantics::Role,
+        sync::{Thread, Trigger},
+        theme::ThemeColor,
+    },
 };
 
-use super::{impl_widget, BaseWidget, Widget, WidgetI, WidgetInternal};
+use super::{impl_widget, BaseWidget, Widget, WidgetHooks, WidgetI, WidgetInternal};
+
+/// The `ButtonVariant` enum lists the looks of a `Button`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ButtonVariant {
+    /// Filled in the accent color of the theme for
+    /// the main action of a view
+    #[default]
+    Primary,
+    /// Filled in the surface color of the theme for
+    /// actions next to the main one
+    Secondary,
+    /// Only shaded while hovered or pressed for actions
+    /// that should not stand out such as in toolbars
+    Ghost,
+}
+
 /// A struct representing a button widget.
 ///
 /// The `Button` struct encapsulates a button UI element, typically used
@@ -20,16 +42,143 @@ use super::{impl_widget, BaseWidget, Widget, WidgetI, WidgetInternal};
 ///
 /// The `Button` can be used in graphical user interfaces or any context
 /// where a button-like interaction is needed.
-#[derive(Default, Clone)]
+///
+/// Buttons are shaded while hovered and pressed and dimmed while
+/// disabled out of the box.
+///
+/// ## Example
+/// ```ignore
+/// let save = Button::new()
+///     .set_label("Save")
+///     .set_variant(ButtonVariant::Secondary)
+///     .on_click(|_, _| println!("saved"));
+/// ```
+#[derive(Clone)]
 pub struct Button {
     pub base: RefCell<BaseWidget>,
     pub actions: RefCell<Vec<Action>>,
     emitter: Option<Arc<dyn Thread>>,
     trigger: RefCell<Option<Rc<Trigger>>>,
+    variant: ButtonVariant,
+    /// The color hovered and pressed buttons are shaded with
+    /// as picked by the theme
+    pub(crate) shade: Cell<Color>,
+}
+impl Default for Button {
+    fn default() -> Self {
+        Self {
+            base: RefCell::default(),
+            actions: RefCell::new(vec![Action::Press(Press::new())]),
+            emitter: None,
+            trigger: RefCell::default(),
+            variant: ButtonVariant::default(),
+            shade: Cell::new(BLACK),
+        }
+    }
 }
 impl Button {
     pub fn new() -> Self {
         Button::default()
     }
+    /// Set the look of the button
+    ///
+    /// Colors set on the button take precedence over the ones
+    /// of the variant
+    pub fn set_variant(mut self, variant: ButtonVariant) -> Self {
+        self.variant = variant;
+        let mut base = self.base_mut();
+        match variant {
+            ButtonVariant::Primary => {
+                base.style.color = Default::default();
+                base.styled.color = false;
+            }
+            ButtonVariant::Secondary => {
+                base.style.color = themed(ThemeColor::Surface);
+                base.styled.color = true;
+            }
+            ButtonVariant::Ghost => {
+                base.style.color = TRANSPARENT.into();
+                base.styled.color = true;
+            }
+        }
+        drop(base);
+        self
+    }
+    /// Returns the look of the button
+    pub fn variant(&self) -> ButtonVariant {
+        self.variant
+    }
 }
 impl_widget! {Button}
+impl WidgetHooks for Button {
+    fn default_role(&self) -> Role {
+        Role::Button
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use winit::event::ElementState;
+
+    use crate::action::{hover::Hover, Action};
+    use crate::ui::{
+        color::{Color, ColorMode, BLUE, RED},
+        dom::DOM,
+        widget::Widget,
+    };
+
+    use super::Button;
+
+    #[test]
+    fn buttons_are_shaded_while_hovered_and_pressed() {
+        let clicks = Rc::new(Cell::new(0));
+        let mut dom = DOM::headless(100, 100);
+        let id = dom.add_widget(
+            Button::new()
+                .set_width(50.0)
+                .set_height(20.0)
+                .set_color(RED)
+                .on_click({
+                    let clicks = clicks.clone();
+                    move |_, _| clicks.set(clicks.get() + 1)
+                }),
+        );
+        let button = dom.widget(id).unwrap();
+        let mode = || button.base().style.color.mode();
+
+        dom.move_cursor(10.0, 10.0);
+        let ColorMode::Overlay(hovered) = mode() else {
+            panic!("hovered buttons should be shaded");
+        };
+        dom.left_button(ElementState::Pressed);
+        let ColorMode::Overlay(pressed) = mode() else {
+            panic!("pressed buttons should be shaded");
+        };
+        let alpha = |color: Color| -> u8 {
+            let (_, _, _, a) = color.into();
+            a
+        };
+        assert!(alpha(pressed) > alpha(hovered));
+
+        dom.left_button(ElementState::Released);
+        assert_eq!(clicks.get(), 1);
+        assert_eq!(mode(), ColorMode::Overlay(hovered));
+        dom.move_cursor(80.0, 80.0);
+        assert_eq!(mode(), ColorMode::Solid);
+
+        // Hover actions of their own take precedence
+        let id = dom.add_widget(
+            Button::new()
+                .set_y(50.0)
+                .set_width(50.0)
+                .set_height(20.0)
+                .on_action(Action::Hover(Hover::new(BLUE))),
+        );
+        let button = dom.widget(id).unwrap();
+        dom.move_cursor(10.0, 60.0);
+        dom.move_cursor(12.0, 60.0);
+        assert_eq!(button.base().style.color.mode(), ColorMode::Overlay(BLUE));
+    }
+}
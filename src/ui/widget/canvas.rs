@@ -1,20 +1,81 @@
 use std::{
     any::Any,
-    cell::{Ref, RefCell, RefMut},
-    rc::Rc,
+    cell::{Cell, Ref, RefCell, RefMut},
+    ops::Range,
+    rc::{Rc, Weak},
     sync::Arc,
 };
 
+use web_time::Duration;
+
 use crate::{
-    action::Action,
+    action::{
+        paint::{commit, Tool},
+        Action,
+    },
+    clipboard::Clipboard,
+    history::History,
+    render::Painter,
     ui::{
-        color::Color,
-        layout::{Col, Grid, Point, Row},
+        buffer::CanvasBuffer,
+        color::{Color, BLACK, TRANSPARENT, WHITE},
+        layout::{Col, Grid, Layout, Point, Row},
+        semantics::Role,
+        style::GridLines,
         sync::{Thread, Trigger},
+        timer::TimerId,
     },
 };
 
-use super::{impl_widget, BaseWidget, Widget, WidgetI, WidgetInternal};
+use super::{cell, impl_widget, BaseWidget, Widget, WidgetHooks, WidgetI, WidgetInternal};
+
+/// How long the dashes outlining the selection are
+const DASH: f64 = 4.0;
+/// The time between two steps of the dashes outlining
+/// the selection
+const ANTS_STEP: Duration = Duration::from_millis(120);
+
+/// The `Selection` struct is a rectangle of cells selected
+/// on a `Canvas`
+///
+/// Cells are addressed like the pixels of a `CanvasBuffer`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Selection {
+    /// The column of the left edge
+    pub x: usize,
+    /// The row of the top edge
+    pub y: usize,
+    /// The amount of columns
+    pub width: usize,
+    /// The amount of rows
+    pub height: usize,
+}
+impl Selection {
+    /// Create a selection of `width x height` cells starting
+    /// at `(x, y)`
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+    /// Returns `true` if the cell at `(x, y)` is selected
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+}
+
+/// Pixels floating over a `Canvas` until painted onto it
+#[derive(Debug, Clone)]
+struct Paste {
+    pixels: CanvasBuffer,
+    /// The column of the left edge
+    x: usize,
+    /// The row of the top edge
+    y: usize,
+}
 
 /// A struct representing a canvas widget.
 ///
@@ -25,17 +86,71 @@ use super::{impl_widget, BaseWidget, Widget, WidgetI, WidgetInternal};
 ///
 /// The `Canvas` can be used as a drawing surface, allowing you to add
 /// elements like shapes, images, or other visual content.
+///
+/// A canvas with a grid keeps the color of every cell in a
+/// `CanvasBuffer` which is edited through `paint`. Cells start out
+/// blank showing the color of the canvas and the lines between them
+/// are drawn as the `grid` of its style.
+///
+/// A rectangle of cells may be selected to be filled, cleared or
+/// moved around through the `Clipboard`. The selection is outlined
+/// by dashes marching around it. Pasted pixels float over the
+/// canvas until they are confirmed, also by clicking outside of
+/// them with `Tool::Select`.
+///
+/// ## Example
+/// ```ignore
+/// let key = KeyPress::new(())
+///     .on(KeyCombo::char('c').ctrl(), |_, _, widget, _| {
+///         widget.downcast::<Canvas>().map(Canvas::copy_selection);
+///     })
+///     .on(KeyCombo::char('v').ctrl(), |_, _, widget, _| {
+///         if let Some(canvas) = widget.downcast::<Canvas>() {
+///             let (x, y) = canvas.selection().map_or((0, 0), |s| (s.x, s.y));
+///             canvas.paste_at(x, y);
+///         }
+///     })
+///     .on(KeyCombo::named(NamedKey::Enter), |_, _, widget, _| {
+///         widget.downcast::<Canvas>().map(Canvas::confirm_paste);
+///     })
+///     .on(KeyCombo::named(NamedKey::Escape), |_, _, widget, _| {
+///         widget.downcast::<Canvas>().map(Canvas::cancel_paste);
+///     });
+/// ```
 #[derive(Default, Clone)]
 pub struct Canvas {
     pub base: RefCell<BaseWidget>,
     pub actions: RefCell<Vec<Action>>,
     emitter: Option<Arc<dyn Thread>>,
     pub grid: RefCell<Option<Grid>>,
+    buffer: RefCell<Option<CanvasBuffer>>,
+    /// The color cells are painted in by tools
+    brush: Cell<Color>,
+    /// The tool cells are painted with
+    tool: Cell<Tool>,
+    /// Where strokes are recorded to be undone
+    history: Option<Rc<RefCell<History>>>,
+    /// The size and colors of the squares drawn beneath
+    /// the cells
+    checkerboard: Option<(f64, (Color, Color))>,
+    /// The rectangle of cells selected
+    selection: Cell<Option<Selection>>,
+    /// How far the dashes outlining the selection moved
+    ants: Cell<usize>,
+    /// The timer moving the dashes outlining the selection
+    ants_timer: Cell<Option<TimerId>>,
+    /// The pixels pasted floating over the canvas
+    pasting: RefCell<Option<Paste>>,
+    /// The canvas itself once added to a `DOM`
+    this: RefCell<Option<Weak<dyn WidgetI>>>,
     trigger: RefCell<Option<Rc<Trigger>>>,
 }
 impl Canvas {
     pub fn new() -> Self {
-        Canvas::default()
+        Canvas {
+            brush: Cell::new(BLACK),
+            ..Default::default()
+        }
     }
     /// Subdivides the canvas into a grid of equally sized `Cell` elements.
     ///
@@ -49,24 +164,13 @@ impl Canvas {
     /// | | | | |
     /// | | | | |
     /// ```
+    /// The lines between the cells are drawn `thickness` wide
+    /// in `color`
     /// # Panics
     ///
     /// This function will panic if `size` is 0
-    pub fn set_grid(mut self, size: u32, thickness: f64, color: Color) -> Self {
-        let base = self.base.borrow_mut();
-
-        self.grid = RefCell::new(Some(Grid::new(
-            Point {
-                x: size as f64,
-                y: size as f64,
-            },
-            thickness,
-            color.into(),
-        )));
-
-        drop(base);
-
-        self
+    pub fn set_grid(self, size: u32, thickness: f64, color: Color) -> Self {
+        self.set_grid_range((size, size), thickness, color)
     }
     /// Set the actions to be triggered on every cell in
     /// the canvas grid
@@ -120,6 +224,111 @@ impl Canvas {
 
         self
     }
+    /// Set an action to be triggered on every cell within
+    /// `rows` and `cols` of the canvas grid
+    ///
+    /// NoOp if `set_grid` was not called before
+    ///
+    /// Cells past the edges of the grid are left out
+    ///
+    /// ## Example
+    /// ```ignore
+    /// // The top-left quarter of an 8x8 grid
+    /// let canvas = Canvas::new()
+    ///     .set_grid(8, 1.0, GRAY)
+    ///     .on_cell_range_action(0..4, 0..4, Action::Hover(Hover::new(BLUE)));
+    /// ```
+    pub fn on_cell_range_action(self, rows: Range<Row>, cols: Range<Col>, action: Action) -> Self {
+        self.for_each_cell_in(rows, cols, |_, cell| {
+            cell.action_mut().push(action.clone());
+        });
+        self
+    }
+    /// Disables or enables every cell within `rows` and
+    /// `cols` of the canvas grid
+    ///
+    /// Disabled cells ignore the pointer and are left
+    /// untouched by `Action::Paint`
+    ///
+    /// NoOp if `set_grid` was not called before
+    ///
+    /// Cells past the edges of the grid are left out
+    pub fn set_cells_disabled(&self, rows: Range<Row>, cols: Range<Col>, disabled: bool) {
+        self.for_each_cell_in(rows, cols, |_, cell| {
+            if cell.base().state.disabled == disabled {
+                return;
+            }
+            cell.base_mut().state.disabled = disabled;
+            if let Some(trigger) = cell.internal_trigger() {
+                trigger.update();
+            }
+        });
+    }
+    /// Returns whether the cell at `pos` is disabled
+    ///
+    /// Returns `None` if `pos` is outside of the grid or
+    /// `set_grid` was not called before
+    pub fn is_cell_disabled(&self, pos: (Row, Col)) -> Option<bool> {
+        let disabled = self.cell(pos)?.base().state.disabled;
+        Some(disabled)
+    }
+    /// Returns the cell at `pos` of the canvas grid
+    ///
+    /// Returns `None` if `pos` is outside of the grid or
+    /// `set_grid` was not called before
+    pub fn cell(&self, pos: (Row, Col)) -> Option<Rc<cell::Cell>> {
+        let grid = self.grid.borrow();
+        grid.as_ref()?.cells.get(pos.0)?.get(pos.1).cloned()
+    }
+    /// Calls `f` with the position and the cell of every cell
+    /// in the canvas grid row by row
+    ///
+    /// NoOp if `set_grid` was not called before
+    ///
+    /// ## Example
+    /// ```ignore
+    /// // Name every cell after its position
+    /// canvas.for_each_cell(|(row, col), cell| {
+    ///     cell.base_mut().semantics.name = Some(format!("{row}, {col}"));
+    /// });
+    /// ```
+    pub fn for_each_cell<F: FnMut((Row, Col), &Rc<cell::Cell>)>(&self, f: F) {
+        self.for_each_cell_in(0..usize::MAX, 0..usize::MAX, f);
+    }
+    /// Returns the column and row of every disabled cell
+    pub(crate) fn disabled_cells(&self) -> Vec<(usize, usize)> {
+        let mut disabled = Vec::new();
+        self.for_each_cell(|(row, col), cell| {
+            if cell.base().state.disabled {
+                disabled.push((col, row));
+            }
+        });
+        disabled
+    }
+    /// Calls `f` with the position and the cell of every cell
+    /// within `rows` and `cols` of the canvas grid
+    fn for_each_cell_in<F: FnMut((Row, Col), &Rc<cell::Cell>)>(
+        &self,
+        rows: Range<Row>,
+        cols: Range<Col>,
+        mut f: F,
+    ) {
+        let grid = self.grid.borrow();
+        let Some(grid) = grid.as_ref() else {
+            return;
+        };
+        for (row, cells) in grid
+            .cells
+            .iter()
+            .enumerate()
+            .take(rows.end)
+            .skip(rows.start)
+        {
+            for (col, cell) in cells.iter().enumerate().take(cols.end).skip(cols.start) {
+                f((row, col), cell);
+            }
+        }
+    }
     /// Subdivides the canvas into a grid of equally sized `Cell` elements.
     ///
     /// This method generates a specific range grid of `[size.1][size.0]` cells,
@@ -132,33 +341,715 @@ impl Canvas {
     /// | | |
     /// | | |
     /// ```
+    /// The lines between the cells are drawn `thickness` wide
+    /// in `color`
     /// # Panics
     ///
     /// This function will panic if `size.0` or `size.1` is 0
-    pub fn set_grid_range(mut self, size: (u32, u32), thickness: f64, color: Color) -> Self {
-        let base = self.base.borrow_mut();
-
-        self.grid = RefCell::new(Some(Grid::new(
-            Point {
-                x: size.0 as f64,
-                y: size.1 as f64,
-            },
-            thickness,
-            color.into(),
+    pub fn set_grid_range(self, size: (u32, u32), thickness: f64, color: Color) -> Self {
+        self.with_cells(size)
+            .set_grid_lines(GridLines::new(thickness, color))
+    }
+    /// Subdivides the canvas into `[size.1][size.0]` blank cells
+    /// leaving the lines between them as they are
+    pub(crate) fn with_cells(mut self, size: (u32, u32)) -> Self {
+        self.grid = RefCell::new(Some(Grid::new(Point {
+            x: size.0 as f64,
+            y: size.1 as f64,
+        })));
+        self.buffer = RefCell::new(Some(CanvasBuffer::new(
+            size.0 as usize,
+            size.1 as usize,
+            TRANSPARENT,
         )));
-
+        self
+    }
+    /// Set the style of the lines drawn between the cells
+    /// of the canvas grid
+    ///
+    /// ## Example
+    /// ```ignore
+    /// // A line every cell with a thicker one every 8 cells
+    /// let canvas = Canvas::new().set_grid(32, 1.0, GRAY).set_grid_lines(
+    ///     GridLines::new(1.0, GRAY).set_major(8, 2.0, BLACK),
+    /// );
+    /// ```
+    pub fn set_grid_lines(self, lines: GridLines) -> Self {
+        self.base.borrow_mut().style.grid = Some(lines);
+        self
+    }
+    /// Draws a checkerboard of `size` wide squares alternating
+    /// between `colors` beneath the cells
+    ///
+    /// Transparent cells show the checkerboard through so they
+    /// stand apart from white ones. The squares keep their size
+    /// on screen however large the canvas is drawn and start at
+    /// its top-left corner so they move along with it.
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let canvas = Canvas::new()
+    ///     .set_grid(32, 1.0, GRAY)
+    ///     .set_checkerboard(8.0, (WHITE, LIGHT_GRAY));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `size` is not above 0
+    pub fn set_checkerboard(mut self, size: f64, colors: (Color, Color)) -> Self {
+        assert!(size > 0.0, "checkerboard squares must have a size");
+        self.checkerboard = Some((size, colors));
+        self
+    }
+    /// Draws the checkerboard beneath the cells over `rect`
+    fn draw_checkerboard(&self, painter: &mut dyn Painter, rect: Layout) {
+        let Some((size, (even, odd))) = self.checkerboard else {
+            return;
+        };
+        painter.fill_rect(rect, even);
+        let cols = (rect.w / size).ceil() as usize;
+        let rows = (rect.h / size).ceil() as usize;
+        for row in 0..rows {
+            // Only every other square is drawn over the first color
+            for col in ((row + 1) % 2..cols).step_by(2) {
+                let x = col as f64 * size;
+                let y = row as f64 * size;
+                painter.fill_rect(
+                    Layout {
+                        x: rect.x + x,
+                        y: rect.y + y,
+                        // Squares past the edges are cut off
+                        w: size.min(rect.w - x),
+                        h: size.min(rect.h - y),
+                    },
+                    odd,
+                );
+            }
+        }
+    }
+    /// Shows or hides the lines between the cells of the
+    /// canvas grid
+    ///
+    /// Cells keep their place while the lines are hidden
+    ///
+    /// NoOp if no lines were set before
+    pub fn set_grid_lines_visible(&self, visible: bool) {
+        let mut base = self.base_mut();
+        let Some(lines) = &mut base.style.grid else {
+            return;
+        };
+        if lines.visible == visible {
+            return;
+        }
+        lines.visible = visible;
         drop(base);
+        if let Some(trigger) = self.internal_trigger() {
+            trigger.update();
+        }
+    }
+    /// Returns whether the lines between the cells of the
+    /// canvas grid are shown
+    pub fn grid_lines_visible(&self) -> bool {
+        self.base().style.grid.is_some_and(|lines| lines.visible)
+    }
+    /// Edit the pixels of the canvas grid
+    ///
+    /// Only the cells whose color changed are redrawn
+    /// afterwards
+    ///
+    /// Returns `None` if `set_grid` was not called before
+    pub fn paint<R, F: FnOnce(&mut CanvasBuffer) -> R>(&self, f: F) -> Option<R> {
+        let mut buffer = self.buffer.borrow_mut();
+        let buffer = buffer.as_mut()?;
+        let result = f(buffer);
 
+        if let Some(grid) = &*self.grid.borrow() {
+            for (x, y) in buffer.take_dirty() {
+                let cell = &grid.cells[y][x];
+                if let Some(color) = buffer.get_pixel(x, y) {
+                    cell.base_mut().style.color = color.into();
+                }
+                if let Some(trigger) = cell.internal_trigger() {
+                    trigger.update();
+                }
+            }
+        }
+
+        Some(result)
+    }
+    /// Returns the pixels of the canvas grid
+    ///
+    /// Returns `None` if `set_grid` was not called before
+    pub fn buffer(&self) -> Option<Ref<'_, CanvasBuffer>> {
+        Ref::filter_map(self.buffer.borrow(), Option::as_ref).ok()
+    }
+    /// Returns the color cells are painted in by
+    /// `Action::Paint`
+    pub fn brush_color(&self) -> Color {
+        self.brush.get()
+    }
+    /// Set the color cells are painted in by `Action::Paint`
+    /// from now on
+    pub fn set_brush_color(&self, color: Color) {
+        self.brush.set(color);
+    }
+    /// Returns the tool cells are painted with by
+    /// `Action::Paint`
+    pub fn tool(&self) -> Tool {
+        self.tool.get()
+    }
+    /// Set the tool cells are painted with by `Action::Paint`
+    /// from now on
+    pub fn set_tool(&self, tool: Tool) {
+        self.tool.set(tool);
+    }
+    /// Record every stroke of `Action::Paint` in `history`
+    /// so it can be undone
+    ///
+    /// The history may be shared with the rest of the app
+    pub fn set_history(mut self, history: Rc<RefCell<History>>) -> Self {
+        self.history = Some(history);
         self
     }
+    /// Returns the history strokes are recorded in
+    pub(crate) fn history(&self) -> Option<&Rc<RefCell<History>>> {
+        self.history.as_ref()
+    }
+    /// Returns the rectangle of cells selected
+    pub fn selection(&self) -> Option<Selection> {
+        self.selection.get()
+    }
+    /// Selects the rectangle of cells `selection` or lets go
+    /// of the selection on `None`
+    ///
+    /// The selection is cut off at the edges of the grid
+    /// and let go of if nothing of it is left
+    pub fn set_selection(&self, selection: Option<Selection>) {
+        let selection = selection.and_then(|selection| {
+            let buffer = self.buffer()?;
+            let width = selection
+                .width
+                .min(buffer.width().saturating_sub(selection.x));
+            let height = selection
+                .height
+                .min(buffer.height().saturating_sub(selection.y));
+            (width > 0 && height > 0).then_some(Selection {
+                width,
+                height,
+                ..selection
+            })
+        });
+        if self.selection.replace(selection) == selection {
+            return;
+        }
+        let Some(trigger) = self.internal_trigger() else {
+            return;
+        };
+        if selection.is_none() {
+            if let Some(id) = self.ants_timer.take() {
+                trigger.clear_timer(id);
+            }
+        }
+        self.march_ants();
+        trigger.update();
+    }
+    /// Starts marching the dashes around the selection
+    ///
+    /// NoOp without a selection or until added to a `DOM`
+    fn march_ants(&self) {
+        if self.selection.get().is_none() || self.ants_timer.get().is_some() {
+            return;
+        }
+        if let Some(trigger) = self.internal_trigger() {
+            let id = trigger.set_interval(ANTS_STEP, |widget| {
+                if let Some(canvas) = widget.downcast::<Canvas>() {
+                    canvas.ants.set(canvas.ants.get().wrapping_add(1));
+                }
+            });
+            self.ants_timer.set(Some(id));
+        }
+    }
+    /// Selects the cells between the cells `from` and `to`
+    /// on screen which may lie past the edges of the grid
+    pub(crate) fn select_span(&self, from: (isize, isize), to: (isize, isize)) {
+        let Some((width, height)) = self
+            .buffer()
+            .map(|buffer| (buffer.width(), buffer.height()))
+        else {
+            return;
+        };
+        let clamp = |at: isize, size: usize| at.clamp(0, size as isize - 1) as usize;
+        let (left, right) = (
+            clamp(from.0.min(to.0), width),
+            clamp(from.0.max(to.0), width),
+        );
+        let (top, bottom) = (
+            clamp(from.1.min(to.1), height),
+            clamp(from.1.max(to.1), height),
+        );
+        self.set_selection(Some(Selection::new(
+            left,
+            top,
+            right - left + 1,
+            bottom - top + 1,
+        )));
+    }
+    /// Set every selected cell to `color`
+    ///
+    /// Recorded as a single step in the history of the
+    /// canvas if it has one
+    ///
+    /// NoOp without a selection
+    pub fn fill_selection(&self, color: Color) {
+        if let Some(selection) = self.selection.get() {
+            self.edit(|buffer| {
+                buffer.fill_rect(
+                    selection.x,
+                    selection.y,
+                    selection.width,
+                    selection.height,
+                    color,
+                )
+            });
+        }
+    }
+    /// Blanks every selected cell
+    ///
+    /// Recorded as a single step in the history of the
+    /// canvas if it has one
+    ///
+    /// NoOp without a selection
+    pub fn clear_selection(&self) {
+        self.fill_selection(TRANSPARENT);
+    }
+    /// Places the pixels of the selected cells on the `Clipboard`
+    ///
+    /// Returns `false` if nothing is selected
+    pub fn copy_selection(&self) -> bool {
+        let (Some(selection), Some(buffer)) = (self.selection.get(), self.buffer()) else {
+            return false;
+        };
+        Clipboard::set_pixels(buffer.crop(
+            selection.x,
+            selection.y,
+            selection.width,
+            selection.height,
+        ));
+        true
+    }
+    /// Places the pixels of the selected cells on the `Clipboard`
+    /// and blanks the cells
+    ///
+    /// Returns `false` if nothing is selected
+    pub fn cut_selection(&self) -> bool {
+        let copied = self.copy_selection();
+        if copied {
+            self.clear_selection();
+        }
+        copied
+    }
+    /// Floats the pixels on the `Clipboard` over the canvas with
+    /// their top-left corner at the cell `(x, y)` and selects them
+    ///
+    /// The pixels are only painted onto the canvas by `confirm_paste`.
+    /// Until then they may be moved with `move_paste` or dragged
+    /// around with `Tool::Select`. Pixels floating from an earlier
+    /// paste are painted first.
+    ///
+    /// Returns `false` if there are no pixels on the clipboard or
+    /// `set_grid` was not called before
+    pub fn paste_at(&self, x: usize, y: usize) -> bool {
+        let Some(pixels) = Clipboard::get_pixels() else {
+            return false;
+        };
+        if self.buffer().is_none() {
+            return false;
+        }
+        self.confirm_paste();
+        *self.pasting.borrow_mut() = Some(Paste { pixels, x: 0, y: 0 });
+        self.move_paste(x, y);
+        true
+    }
+    /// Returns where the pixels floating over the canvas lie
+    /// including those past the edges of the grid
+    pub fn pasting(&self) -> Option<Selection> {
+        let pasting = self.pasting.borrow();
+        let paste = pasting.as_ref()?;
+        Some(Selection::new(
+            paste.x,
+            paste.y,
+            paste.pixels.width(),
+            paste.pixels.height(),
+        ))
+    }
+    /// Moves the pixels floating over the canvas so their top-left
+    /// corner lies at the cell `(x, y)` and selects them
+    ///
+    /// The corner is kept within the grid
+    ///
+    /// NoOp if nothing is floating
+    pub fn move_paste(&self, x: usize, y: usize) {
+        let Some((width, height)) = self
+            .buffer()
+            .map(|buffer| (buffer.width(), buffer.height()))
+        else {
+            return;
+        };
+        let Some(selection) = self.pasting.borrow_mut().as_mut().map(|paste| {
+            paste.x = x.min(width.saturating_sub(1));
+            paste.y = y.min(height.saturating_sub(1));
+            Selection::new(
+                paste.x,
+                paste.y,
+                paste.pixels.width(),
+                paste.pixels.height(),
+            )
+        }) else {
+            return;
+        };
+        self.set_selection(Some(selection));
+        // The pixels move even when the selection is cut off the same
+        if let Some(trigger) = self.internal_trigger() {
+            trigger.update();
+        }
+    }
+    /// Moves the pixels floating over the canvas `by` cells
+    pub(crate) fn drag_paste(&self, by: (isize, isize)) {
+        if let Some(paste) = self.pasting() {
+            self.move_paste(
+                paste.x.saturating_add_signed(by.0),
+                paste.y.saturating_add_signed(by.1),
+            );
+        }
+    }
+    /// Paints the pixels floating over the canvas onto it
+    ///
+    /// Transparent pixels leave the cells beneath as they are.
+    /// Recorded as a single step in the history of the canvas if
+    /// it has one.
+    ///
+    /// Returns `false` if nothing was floating
+    pub fn confirm_paste(&self) -> bool {
+        let Some(paste) = self.pasting.borrow_mut().take() else {
+            return false;
+        };
+        self.edit(|buffer| {
+            for y in 0..paste.pixels.height() {
+                for x in 0..paste.pixels.width() {
+                    match paste.pixels.get_pixel(x, y) {
+                        Some(color) if color != TRANSPARENT => {
+                            buffer.set_pixel(paste.x + x, paste.y + y, color)
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        });
+        true
+    }
+    /// Drops the pixels floating over the canvas without
+    /// painting them and lets go of the selection
+    ///
+    /// Returns `false` if nothing was floating
+    pub fn cancel_paste(&self) -> bool {
+        if self.pasting.borrow_mut().take().is_none() {
+            return false;
+        }
+        self.set_selection(None);
+        true
+    }
+    /// Edits the pixels of the canvas grid through `paint`
+    /// recording the change as a single step in the history
+    ///
+    /// Returns `None` if `set_grid` was not called before
+    fn edit<F: FnOnce(&mut CanvasBuffer)>(&self, f: F) -> Option<()> {
+        let before = self.buffer()?.clone();
+        self.paint(f)?;
+        // Commands can only reach the canvas once added to a `DOM`
+        if let Some(this) = self.this.borrow().clone() {
+            commit(this, self, &before);
+        }
+        Some(())
+    }
+    /// Returns the column and row of the cell at `x`, `y`
+    /// on screen
+    ///
+    /// Positions outside of the canvas lie in cells past its
+    /// edges which may be negative
+    ///
+    /// Returns `None` if `set_grid` was not called before
+    pub(crate) fn cell_at(&self, x: f64, y: f64) -> Option<(isize, isize)> {
+        let grid = self.grid.borrow();
+        let grid = grid.as_ref()?;
+        let base = self.base();
+        let cell_w = base.layout.w / grid.size.x;
+        let cell_h = base.layout.h / grid.size.y;
+        if !(cell_w > 0.0 && cell_h > 0.0) {
+            return None;
+        }
+        let local = base.to_local(Point::new(x, y));
+        Some((
+            (local.x / cell_w).floor() as isize,
+            (local.y / cell_h).floor() as isize,
+        ))
+    }
 }
 impl_widget! {Canvas}
+impl WidgetHooks for Canvas {
+    fn default_role(&self) -> Role {
+        Role::Canvas
+    }
+    fn children(&self) -> Vec<Rc<dyn WidgetI>> {
+        let mut cells: Vec<Rc<dyn WidgetI>> = Vec::new();
+        if let Some(grid) = &*self.grid.borrow() {
+            grid.on_cell(|_, cell| cells.push(cell));
+        }
+        cells
+    }
+    fn mounted(&self, this: &Weak<dyn WidgetI>) {
+        *self.this.borrow_mut() = Some(this.clone());
+        // Selections made before being added start marching now
+        self.march_ants();
+    }
+    fn custom_layout(&self) {
+        if let Some(grid) = &mut *self.grid.borrow_mut() {
+            let base = self.base();
+            grid.resize(
+                base.layout.x,
+                base.layout.y,
+                base.layout.h,
+                base.layout.w,
+                base.style.grid.as_ref(),
+            );
+
+            // Cells take the color of their pixel
+            if let Some(buffer) = &*self.buffer.borrow() {
+                grid.on_cell(|pos, cell| {
+                    if let Some(color) = buffer.get_pixel(pos.x as usize, pos.y as usize) {
+                        cell.base_mut().style.color = color.into();
+                    }
+                });
+            }
+        }
+    }
+    /// # Note
+    ///
+    /// Round all floats to nearest
+    fn custom_draw(&self, painter: &mut dyn Painter) {
+        let base = self.base();
+        let x = base.offset.x + base.layout.x;
+        let y = base.offset.y + base.layout.y;
+        self.draw_checkerboard(
+            painter,
+            Layout {
+                x,
+                y,
+                ..base.layout
+            },
+        );
+
+        let Some(grid) = &*self.grid.borrow() else {
+            return;
+        };
+        let Some(lines) = base.style.grid.filter(|lines| lines.visible) else {
+            return;
+        };
+
+        let h_lines_spacing = base.layout.h / grid.size.y;
+        let w_lines_spacing = base.layout.w / grid.size.x;
+        // Major lines are drawn last to cross over the others
+        for major in [false, true] {
+            // Draw column gridlines
+            for col in (1..grid.size.x as usize).filter(|col| lines.is_major(*col) == major) {
+                let (thickness, color) = lines.line(col);
+                let spacing = w_lines_spacing * col as f64;
+                painter.fill_rect(
+                    Layout {
+                        x: x + spacing,
+                        y,
+                        w: thickness,
+                        h: base.layout.h,
+                    },
+                    color,
+                );
+            }
+            // Draw row gridlines
+            for row in (1..grid.size.y as usize).filter(|row| lines.is_major(*row) == major) {
+                let (thickness, color) = lines.line(row);
+                let spacing = h_lines_spacing * row as f64;
+                painter.fill_rect(
+                    Layout {
+                        x,
+                        y: y + spacing,
+                        w: base.layout.w,
+                        h: thickness,
+                    },
+                    color,
+                );
+            }
+        }
+    }
+    fn custom_draw_over(&self, painter: &mut dyn Painter) {
+        let Some(grid) = &*self.grid.borrow() else {
+            return;
+        };
+        let base = self.base();
+        let cell_w = base.layout.w / grid.size.x;
+        let cell_h = base.layout.h / grid.size.y;
+        let x = base.offset.x + base.layout.x;
+        let y = base.offset.y + base.layout.y;
+
+        if let Some(paste) = &*self.pasting.borrow() {
+            let (cols, rows) = (grid.size.x as usize, grid.size.y as usize);
+            for row in 0..paste.pixels.height().min(rows.saturating_sub(paste.y)) {
+                for col in 0..paste.pixels.width().min(cols.saturating_sub(paste.x)) {
+                    match paste.pixels.get_pixel(col, row) {
+                        Some(color) if color != TRANSPARENT => painter.fill_rect(
+                            Layout {
+                                x: x + (paste.x + col) as f64 * cell_w,
+                                y: y + (paste.y + row) as f64 * cell_h,
+                                w: cell_w,
+                                h: cell_h,
+                            },
+                            color,
+                        ),
+                        _ => (),
+                    }
+                }
+            }
+        }
+        if let Some(selection) = self.selection.get() {
+            let rect = Layout {
+                x: x + selection.x as f64 * cell_w,
+                y: y + selection.y as f64 * cell_h,
+                w: selection.width as f64 * cell_w,
+                h: selection.height as f64 * cell_h,
+            };
+            draw_ants(painter, rect, self.ants.get());
+        }
+    }
+}
+
+/// Outlines `rect` with black dashes on white shifted `phase`
+/// pixels around it
+fn draw_ants(painter: &mut dyn Painter, rect: Layout, phase: usize) {
+    // The edges run clockwise from the top-left corner
+    let edges = [rect.w, rect.h, rect.w, rect.h];
+    let piece = |edge: usize, at: f64, len: f64| match edge {
+        0 => Layout {
+            x: rect.x + at,
+            y: rect.y,
+            w: len,
+            h: 1.0,
+        },
+        1 => Layout {
+            x: rect.x + rect.w - 1.0,
+            y: rect.y + at,
+            w: 1.0,
+            h: len,
+        },
+        2 => Layout {
+            x: rect.x + rect.w - at - len,
+            y: rect.y + rect.h - 1.0,
+            w: len,
+            h: 1.0,
+        },
+        _ => Layout {
+            x: rect.x,
+            y: rect.y + rect.h - at - len,
+            w: 1.0,
+            h: len,
+        },
+    };
+
+    for (edge, length) in edges.into_iter().enumerate() {
+        painter.fill_rect(piece(edge, 0.0, length), WHITE);
+    }
+    let mut walked = phase as f64;
+    for (edge, length) in edges.into_iter().enumerate() {
+        let mut at = 0.0;
+        while at < length {
+            let along = walked + at;
+            let len = (DASH - along % DASH).min(length - at);
+            if ((along / DASH) as usize).is_multiple_of(2) {
+                painter.fill_rect(piece(edge, at, len), BLACK);
+            }
+            at += len;
+        }
+        walked += length;
+    }
+}
 
 #[cfg(test)]
 mod tests {
-    use crate::ui::{color::Color, layout::Layout, widget::Widget};
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        action::{
+            paint::{Paint, Tool},
+            Action,
+        },
+        history::History,
+        ui::{
+            color::{Color, BLACK, BLUE, LIGHT_GRAY, RED, TRANSPARENT, WHITE},
+            dom::DOM,
+            layout::{Layout, Point},
+            style::GridLines,
+            widget::{Widget, WidgetI},
+        },
+    };
+
+    use super::{Canvas, Selection};
+
+    #[test]
+    fn gridlines_are_drawn_in_their_own_style() {
+        let canvas = Rc::new(
+            Canvas::new()
+                .set_width(40.0)
+                .set_height(40.0)
+                .set_color(WHITE)
+                .set_grid(4, 1.0, RED)
+                .set_grid_lines(GridLines::new(1.0, RED).set_major(2, 2.0, BLUE)),
+        );
+        let widgets: [Rc<dyn WidgetI>; 1] = [canvas.clone()];
 
-    use super::Canvas;
+        let frame = DOM::render_once_to_buffer(&widgets, 40, 40).unwrap();
+        assert_eq!(frame.pixel(5, 5), Some(WHITE));
+        assert_eq!(frame.pixel(10, 5), Some(RED));
+        assert_eq!(frame.pixel(20, 5), Some(BLUE));
+        assert_eq!(frame.pixel(21, 5), Some(BLUE));
+        assert_eq!(frame.pixel(22, 5), Some(WHITE));
+        // Major lines cross over the others
+        assert_eq!(frame.pixel(10, 20), Some(BLUE));
+
+        canvas.set_grid_lines_visible(false);
+        let frame = DOM::render_once_to_buffer(&widgets, 40, 40).unwrap();
+        assert_eq!(frame.pixel(10, 5), Some(WHITE));
+        assert_eq!(frame.pixel(20, 5), Some(WHITE));
+    }
+
+    #[test]
+    fn checkerboard_shows_through_transparent_cells() {
+        let canvas = Canvas::new()
+            .set_x(5.0)
+            .set_width(40.0)
+            .set_height(40.0)
+            .set_grid(4, 0.0, TRANSPARENT)
+            .set_checkerboard(8.0, (WHITE, LIGHT_GRAY));
+        canvas.paint(|buffer| buffer.set_pixel(3, 3, RED));
+        let widgets: [Rc<dyn WidgetI>; 1] = [Rc::new(canvas)];
+
+        let frame = DOM::render_once_to_buffer(&widgets, 50, 40).unwrap();
+        // The squares start at the corner of the canvas
+        assert_eq!(frame.pixel(6, 1), Some(WHITE));
+        assert_eq!(frame.pixel(14, 1), Some(LIGHT_GRAY));
+        assert_eq!(frame.pixel(6, 9), Some(LIGHT_GRAY));
+        assert_eq!(frame.pixel(14, 9), Some(WHITE));
+        // Squares past the edge are cut off
+        assert_eq!(frame.pixel(46, 1), Some(TRANSPARENT));
+        // Painted cells cover the squares
+        assert_eq!(frame.pixel(40, 35), Some(RED));
+    }
 
     #[test]
     fn test_gridlines_are_spaced_correctly() {
@@ -169,7 +1060,7 @@ mod tests {
         );
 
         let mut grid = c.grid.borrow_mut().clone().unwrap();
-        grid.resize(0.0, 0.0, 16.0, 32.0);
+        grid.resize(0.0, 0.0, 16.0, 32.0, c.base().style.grid.as_ref());
 
         let cells = grid.cells;
         assert!(
@@ -317,4 +1208,113 @@ mod tests {
                 }
         );
     }
+
+    #[test]
+    fn selections_are_dragged_out_and_moved_through_the_clipboard() {
+        let mut dom = DOM::headless(100, 100);
+        let history = Rc::new(RefCell::new(History::new()));
+        let canvas = Canvas::new()
+            .set_width(80.0)
+            .set_height(80.0)
+            .set_grid(8, 0.0, TRANSPARENT)
+            .set_history(history.clone())
+            .on_action(Action::Paint(Paint::new()));
+        canvas.set_tool(Tool::Select);
+        canvas.paint(|buffer| buffer.set_pixel(1, 1, RED));
+        let id = dom.add_widget(canvas);
+        let widget = dom.widget(id).unwrap();
+        let canvas = widget.downcast::<Canvas>().unwrap();
+
+        // Dragging past the edge is cut off
+        dom.drag(Point::new(25.0, 15.0), Point::new(-20.0, 5.0));
+        assert_eq!(canvas.selection(), Some(Selection::new(0, 0, 3, 2)));
+
+        assert!(canvas.copy_selection());
+        assert!(canvas.paste_at(6, 5));
+        assert_eq!(canvas.selection(), Some(Selection::new(6, 5, 2, 2)));
+        assert_eq!(canvas.buffer().unwrap().get_pixel(7, 6), Some(TRANSPARENT));
+        assert!(canvas.confirm_paste());
+        assert_eq!(canvas.buffer().unwrap().get_pixel(7, 6), Some(RED));
+        assert!(history.borrow_mut().undo());
+        assert_eq!(canvas.buffer().unwrap().get_pixel(7, 6), Some(TRANSPARENT));
+
+        canvas.set_selection(Some(Selection::new(1, 1, 1, 1)));
+        assert!(canvas.cut_selection());
+        assert_eq!(canvas.buffer().unwrap().get_pixel(1, 1), Some(TRANSPARENT));
+        canvas.fill_selection(BLUE);
+        assert_eq!(canvas.buffer().unwrap().get_pixel(1, 1), Some(BLUE));
+
+        // Clicking lets go of the selection
+        dom.click(45.0, 45.0);
+        assert_eq!(canvas.selection(), None);
+        assert!(!canvas.copy_selection());
+    }
+
+    #[test]
+    fn pasted_pixels_float_until_confirmed() {
+        let mut dom = DOM::headless(100, 100);
+        let history = Rc::new(RefCell::new(History::new()));
+        let canvas = Canvas::new()
+            .set_width(80.0)
+            .set_height(80.0)
+            .set_grid(8, 0.0, TRANSPARENT)
+            .set_history(history.clone())
+            .on_action(Action::Paint(Paint::new()));
+        canvas.set_tool(Tool::Select);
+        canvas.paint(|buffer| {
+            buffer.set_pixel(1, 1, RED);
+            buffer.set_pixel(7, 5, BLUE);
+        });
+        let id = dom.add_widget(canvas);
+        let widget = dom.widget(id).unwrap();
+        let canvas = widget.downcast::<Canvas>().unwrap();
+
+        canvas.set_selection(Some(Selection::new(0, 0, 3, 2)));
+        assert!(canvas.copy_selection());
+        assert!(canvas.paste_at(4, 4));
+        assert_eq!(canvas.pasting(), Some(Selection::new(4, 4, 3, 2)));
+        assert_eq!(canvas.buffer().unwrap().get_pixel(5, 5), Some(TRANSPARENT));
+
+        // Dragging the pixels moves them along
+        dom.drag(Point::new(45.0, 45.0), Point::new(65.0, 55.0));
+        assert_eq!(canvas.pasting(), Some(Selection::new(6, 5, 3, 2)));
+        assert_eq!(canvas.selection(), Some(Selection::new(6, 5, 2, 2)));
+
+        // Clicking outside paints them leaving cells under
+        // transparent pixels as they were
+        dom.click(5.0, 75.0);
+        assert_eq!(canvas.pasting(), None);
+        assert_eq!(canvas.selection(), None);
+        let buffer = canvas.buffer().unwrap().clone();
+        assert_eq!(buffer.get_pixel(7, 6), Some(RED));
+        assert_eq!(buffer.get_pixel(7, 5), Some(BLUE));
+        assert!(history.borrow_mut().undo());
+        assert_eq!(canvas.buffer().unwrap().get_pixel(7, 6), Some(TRANSPARENT));
+
+        // Floating pixels are drawn over the cells
+        assert!(canvas.paste_at(4, 4));
+        let frame = DOM::render_once_to_buffer(std::slice::from_ref(&widget), 100, 100).unwrap();
+        assert_eq!(frame.pixel(55, 55), Some(RED));
+        assert!(canvas.cancel_paste());
+        assert_eq!(canvas.selection(), None);
+        assert_eq!(canvas.buffer().unwrap().get_pixel(5, 5), Some(TRANSPARENT));
+        assert!(!canvas.confirm_paste());
+    }
+
+    #[test]
+    fn selections_are_outlined_by_dashes() {
+        let canvas = Canvas::new()
+            .set_width(40.0)
+            .set_height(40.0)
+            .set_grid(4, 0.0, TRANSPARENT);
+        canvas.set_selection(Some(Selection::new(1, 1, 2, 2)));
+        let widgets: [Rc<dyn WidgetI>; 1] = [Rc::new(canvas)];
+
+        let frame = DOM::render_once_to_buffer(&widgets, 40, 40).unwrap();
+        assert_eq!(frame.pixel(10, 10), Some(BLACK));
+        assert_eq!(frame.pixel(14, 10), Some(WHITE));
+        assert_eq!(frame.pixel(18, 10), Some(BLACK));
+        assert_eq!(frame.pixel(29, 15), Some(BLACK));
+        assert_eq!(frame.pixel(20, 20), Some(TRANSPARENT));
+    }
 }
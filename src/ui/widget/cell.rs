@@ -7,10 +7,13 @@ use std::{
 
 use crate::{
     action::Action,
-    ui::sync::{Thread, Trigger},
+    ui::{
+        semantics::Role,
+        sync::{Thread, Trigger},
+    },
 };
 
-use super::{impl_widget, BaseWidget, Widget, WidgetI, WidgetInternal};
+use super::{impl_widget, BaseWidget, Widget, WidgetHooks, WidgetI, WidgetInternal};
 
 /// A struct representing a cell in a grid.
 ///
@@ -22,16 +25,78 @@ use super::{impl_widget, BaseWidget, Widget, WidgetI, WidgetInternal};
 /// This struct is typically used in a `Grid` layout where each `Cell`
 /// acts as an individual container
 /// for content or other widgets within that grid.
+///
+/// A cell may hold a value of any type such as the tile id of a
+/// tile map which action handlers read back with `data`.
 #[derive(Default, Clone)]
 pub struct Cell {
     pub base: RefCell<BaseWidget>,
     pub actions: RefCell<Vec<Action>>,
     emitter: Option<Arc<dyn Thread>>,
     trigger: RefCell<Option<Rc<Trigger>>>,
+    /// The value attached by the user
+    data: RefCell<Option<Rc<dyn Any>>>,
 }
 impl Cell {
     pub fn new() -> Self {
         Cell::default()
     }
+    /// Attach `value` to the cell replacing any value
+    /// attached before
+    ///
+    /// Values needing changes in place may be kept in a
+    /// `RefCell` of their own
+    ///
+    /// ## Example
+    /// ```ignore
+    /// canvas.for_each_cell(|_, cell| cell.set_data(TileId(0)));
+    /// let click = Click::new(()).on(MouseButton::LeftButton, |_, _, widget, _, _| {
+    ///     if let Some(tile) = widget.downcast::<Cell>().and_then(Cell::data::<TileId>) {
+    ///         println!("clicked tile {}", tile.0);
+    ///     }
+    /// });
+    /// ```
+    pub fn set_data<T: 'static>(&self, value: T) {
+        *self.data.borrow_mut() = Some(Rc::new(value));
+    }
+    /// Returns the value attached to the cell
+    ///
+    /// Returns `None` if no value was attached or the
+    /// value is of another type
+    pub fn data<T: 'static>(&self) -> Option<Rc<T>> {
+        let data = self.data.borrow().clone()?;
+        data.downcast().ok()
+    }
+    /// Removes the value attached to the cell
+    pub fn clear_data(&self) {
+        self.data.borrow_mut().take();
+    }
 }
 impl_widget! {Cell}
+impl WidgetHooks for Cell {
+    fn default_role(&self) -> Role {
+        Role::Cell
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cell;
+
+    #[test]
+    fn data_is_read_back_as_the_type_it_was_set_as() {
+        let cell = Cell::new();
+        assert_eq!(cell.data::<u32>(), None);
+
+        cell.set_data(7_u32);
+        assert_eq!(cell.data::<u32>().as_deref(), Some(&7));
+        assert_eq!(cell.data::<i64>(), None);
+
+        cell.set_data("grass");
+        assert_eq!(cell.data::<u32>(), None);
+        assert_eq!(cell.data::<&str>().as_deref(), Some(&"grass"));
+
+        cell.clear_data();
+        assert_eq!(cell.data::<&str>(), None);
+    }
+}
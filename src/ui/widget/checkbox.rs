@@ -0,0 +1,166 @@
+use std::{
+    any::Any,
+    cell::{Cell, Ref, RefCell, RefMut},
+    rc::Rc,
+    sync::Arc,
+};
+
+use winit::keyboard::NamedKey;
+
+use crate::{
+    action::{
+        click::{Click, MouseButton},
+        key::{KeyCombo, KeyPress},
+        Action,
+    },
+    render::Painter,
+    ui::{
+        color::{Color, ColorState, BLACK, WHITE},
+        layout::{Layout, Point},
+        semantics::Role,
+        style::{Border, Style},
+        sync::{Thread, Trigger},
+    },
+};
+
+use super::{impl_widget, BaseWidget, Widget, WidgetHooks, WidgetI, WidgetInternal};
+
+type ToggleCallback = Rc<dyn Fn(bool, Rc<Trigger>)>;
+
+/// The state shared between a `Checkbox` and the
+/// actions toggling it
+type ToggleState = (Rc<Cell<bool>>, Rc<RefCell<Vec<ToggleCallback>>>);
+/// How many actions toggling it every checkbox starts out with
+const TOGGLE_ACTIONS: usize = 2;
+
+/// A struct representing a checkbox widget.
+///
+/// The `Checkbox` struct is a box toggled between checked and
+/// unchecked by clicking it or pressing space while focused. A
+/// checkmark is drawn inside the box while checked.
+pub struct Checkbox {
+    pub base: RefCell<BaseWidget>,
+    pub actions: RefCell<Vec<Action>>,
+    emitter: Option<Arc<dyn Thread>>,
+    trigger: RefCell<Option<Rc<Trigger>>>,
+    checked: Rc<Cell<bool>>,
+    on_toggle: Rc<RefCell<Vec<ToggleCallback>>>,
+    pub(crate) check_color: Color,
+}
+impl Default for Checkbox {
+    fn default() -> Self {
+        let checked = Rc::new(Cell::new(false));
+        let on_toggle: Rc<RefCell<Vec<ToggleCallback>>> = Rc::default();
+        let state: ToggleState = (checked.clone(), on_toggle.clone());
+
+        let click = Click::new(state.clone())
+            .on(MouseButton::LeftButtonRelease, |state, trigger, _, _, _| {
+                Checkbox::toggle(state, trigger)
+            });
+        let key = KeyPress::new(state)
+            .on(KeyCombo::named(NamedKey::Space), |state, trigger, _, _| {
+                Checkbox::toggle(state, trigger)
+            });
+
+        Self {
+            base: RefCell::new(BaseWidget {
+                layout: Layout {
+                    w: 16.0,
+                    h: 16.0,
+                    ..Default::default()
+                },
+                style: Style {
+                    color: ColorState::new(WHITE),
+                    border: Some(Border::new(1.0, BLACK)),
+                    ..Default::default()
+                },
+                focusable: true,
+                ..Default::default()
+            }),
+            actions: RefCell::new(vec![
+                Action::Click(Box::new(click)),
+                Action::Key(Box::new(key)),
+            ]),
+            emitter: None,
+            trigger: RefCell::default(),
+            checked,
+            on_toggle,
+            check_color: BLACK,
+        }
+    }
+}
+impl Checkbox {
+    pub fn new() -> Self {
+        Checkbox::default()
+    }
+    /// Set whether the checkbox starts out checked
+    pub fn set_checked(self, checked: bool) -> Self {
+        self.checked.set(checked);
+        self
+    }
+    /// Set the color of the checkmark
+    pub fn set_check_color(mut self, color: Color) -> Self {
+        self.check_color = color;
+        self
+    }
+    /// Returns `true` if the checkbox is checked
+    pub fn is_checked(&self) -> bool {
+        self.checked.get()
+    }
+    /// Set a callback receiving the new checked state every
+    /// time the checkbox is toggled
+    pub fn on_toggle<F: Fn(bool, Rc<Trigger>) + 'static>(self, callback: F) -> Self {
+        self.on_toggle.borrow_mut().push(Rc::new(callback));
+        self
+    }
+    /// Takes on the checked state, callbacks and added actions of
+    /// `other` while keeping the actions toggling this checkbox
+    pub(crate) fn adopt(&self, other: &Checkbox) {
+        self.checked.set(other.is_checked());
+        *self.on_toggle.borrow_mut() = other.on_toggle.take();
+
+        let mut actions = self.action_mut();
+        actions.truncate(TOGGLE_ACTIONS);
+        actions.extend(other.action_mut().drain(TOGGLE_ACTIONS..));
+    }
+    fn toggle(state: &mut ToggleState, trigger: Rc<Trigger>) {
+        let (checked, on_toggle) = state;
+        checked.set(!checked.get());
+
+        for callback in on_toggle.borrow().iter() {
+            callback(checked.get(), trigger.clone());
+        }
+        trigger.update();
+    }
+}
+impl_widget! {Checkbox}
+impl WidgetHooks for Checkbox {
+    fn custom_draw(&self, painter: &mut dyn Painter) {
+        if !self.checked.get() {
+            return;
+        }
+
+        let (x, y, w, h) = {
+            let base = self.base();
+            (
+                base.offset.x + base.layout.x,
+                base.offset.y + base.layout.y,
+                base.layout.w,
+                base.layout.h,
+            )
+        };
+        // The checkmark scales with the box
+        let width = (w.min(h) * 0.12).max(1.5);
+        let start = Point::new(x + w * 0.22, y + h * 0.52);
+        let corner = Point::new(x + w * 0.42, y + h * 0.72);
+        let end = Point::new(x + w * 0.78, y + h * 0.3);
+        painter.stroke_line(start, corner, width, self.check_color);
+        painter.stroke_line(corner, end, width, self.check_color);
+    }
+    fn default_role(&self) -> Role {
+        Role::CheckBox
+    }
+    fn checked(&self) -> Option<bool> {
+        Some(self.checked.get())
+    }
+}
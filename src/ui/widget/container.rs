@@ -1,19 +1,29 @@
 use std::{
     any::Any,
-    cell::{Ref, RefCell, RefMut},
+    cell::{Cell, Ref, RefCell, RefMut},
     rc::Rc,
     sync::Arc,
 };
 
+use web_time::Instant;
+
 use crate::{
     action::{scroll::Scroll, Action},
+    render::Painter,
     ui::{
-        layout::{Col, FlexLayout},
+        arena::WidgetId,
+        layout::{Align, Col, Dock, FlexLayout, Layout, Point, Position},
+        semantics::Role,
         sync::{Thread, Trigger},
+        timer::TimerId,
     },
 };
 
-use super::{impl_widget, scrollbar::ScrollBar, BaseWidget, Widget, WidgetI, WidgetInternal};
+use super::{
+    impl_widget,
+    scrollbar::{ScrollBar, ScrollOptions},
+    widget_id, BaseWidget, Widget, WidgetHooks, WidgetI, WidgetInternal,
+};
 
 /// A struct representing a container widget.
 ///
@@ -28,12 +38,22 @@ pub struct Container {
     pub base: RefCell<BaseWidget>,
     pub actions: RefCell<Vec<Action>>,
     emitter: Option<Arc<dyn Thread>>,
-    pub children: Vec<Rc<dyn WidgetI>>,
+    pub children: RefCell<Vec<Rc<dyn WidgetI>>>,
     pub flex: FlexLayout,
-    valign: bool,
-    halign: bool,
-    gap: f64,
+    pub(crate) valign: bool,
+    pub(crate) halign: bool,
+    pub(crate) gap: f64,
+    pub(crate) main_align: Align,
+    pub(crate) cross_align: Align,
     pub(crate) scrollbar: Option<(ScrollBar, ScrollBar)>,
+    pub(crate) scroll_step: Option<f64>,
+    pub(crate) scroll_options: ScrollOptions,
+    /// When the scrollbars were last scrolled with
+    pub(crate) scroll_activity: Cell<Option<Instant>>,
+    /// The timer fading the scrollbars in or out
+    pub(crate) scroll_fade: Cell<Option<TimerId>>,
+    /// How thick the scrollbars were when last laid out
+    scrollbar_sizes: Cell<(f64, f64)>,
     trigger: RefCell<Option<Rc<Trigger>>>,
 }
 impl Container {
@@ -53,16 +73,56 @@ impl Container {
         self
     }
     /// Allows the container to be scrollable
-    pub fn on_scroll(mut self) -> Self {
-        self.scrollbar = Some((ScrollBar::new_x(), ScrollBar::new_y()));
+    pub fn on_scroll(self) -> Self {
+        self.on_scroll_with(ScrollOptions::default())
+    }
+    /// Allows the container to be scrollable with scrollbars
+    /// behaving as set in the `options`
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let container = Container::new().on_scroll_with(ScrollOptions {
+    ///     auto_hide: true,
+    ///     hover_thickness: 14.0,
+    ///     ..Default::default()
+    /// });
+    /// ```
+    pub fn on_scroll_with(mut self, options: ScrollOptions) -> Self {
+        let scrollbars = (ScrollBar::new_x(), ScrollBar::new_y());
+        if options.auto_hide {
+            scrollbars.0.base_mut().style.opacity = 0.0;
+            scrollbars.1.base_mut().style.opacity = 0.0;
+        }
+        self.scrollbar = Some(scrollbars);
+        self.scroll_options = options;
         self.action_mut().push(Action::Scroll(Scroll::new()));
         self
     }
+    /// Set how many pixels the content moves for every line
+    /// the mouse wheel turns
+    ///
+    /// Defaults to 20px
+    pub fn set_scroll_step(mut self, step: f64) -> Self {
+        self.scroll_step = Some(step);
+        self
+    }
     /// Set a gap size between every child in container
     pub fn set_gap(mut self, gap: f64) -> Self {
         self.gap = gap;
         self
     }
+    /// Set how children are distributed along the
+    /// direction of a `Row` or `RowWrap` layout
+    pub fn set_main_align(mut self, align: Align) -> Self {
+        self.main_align = align;
+        self
+    }
+    /// Set how children are placed across the
+    /// direction of a `Row` or `RowWrap` layout
+    pub fn set_cross_align(mut self, align: Align) -> Self {
+        self.cross_align = align;
+        self
+    }
     /// Set the type of flex layout to use
     ///
     /// # Note
@@ -78,7 +138,7 @@ impl Container {
     /// This will override x and y postions set internally
     /// for children widgets
     pub(crate) fn create_normal_layout(&self) {
-        for child in &self.children {
+        for child in self.flow_children().iter() {
             self.snap_to_parent(child);
 
             if self.halign {
@@ -104,7 +164,8 @@ impl Container {
     pub(crate) fn create_flex_grid_layout(&self, cols: Col) {
         assert!(cols > 0);
 
-        if self.children.is_empty() {
+        let children = self.flow_children();
+        if children.is_empty() {
             return;
         }
 
@@ -114,12 +175,12 @@ impl Container {
         let mut col = 0;
 
         let cols = cols as f64;
-        let rows = f64::max(self.children.len().div_ceil(cols as usize) as f64, 1.0);
+        let rows = f64::max(children.len().div_ceil(cols as usize) as f64, 1.0);
 
         let gaps_factor_col = self.gap * (rows - 1.0);
         let gaps_factor_row = self.gap * (cols - 1.0);
 
-        for child in self.children.iter().enumerate() {
+        for child in children.iter().enumerate() {
             let (idx, child) = child;
 
             self.snap_to_parent(child);
@@ -193,13 +254,21 @@ impl Container {
     /// This method will panic if no `add_widgets` call
     /// was made or children are zero
     pub(crate) fn create_flex_col_layout(&self) {
-        if self.children.is_empty() {
+        let children = self.flow_children();
+        if children.is_empty() {
             return;
         }
 
+        // Flexible children take up the height left over or give up
+        // the height missing
+        let container = self.base().layout;
+        let heights: f64 = children.iter().map(|child| child.base().layout.h).sum();
+        let gaps = self.gap * (children.len() - 1) as f64;
+        Container::flex_children(&children, container.h - heights - gaps, false);
+
         let mut prev: Option<&Rc<dyn WidgetI>> = None;
 
-        for child in self.children.iter() {
+        for child in children.iter() {
             self.snap_to_parent(child);
 
             ////////////
@@ -209,7 +278,20 @@ impl Container {
             ////////////
             /////// ALIGMENT
             ////
-            if self.halign {
+            let align_self = child.base().align_self;
+            if let Some(align) = align_self {
+                let mut child_base = child.base_mut();
+                let w = child_base.layout.w;
+                child_base.layout.x = match align {
+                    Align::Start | Align::SpaceBetween | Align::Stretch => container.x,
+                    Align::Center => container.x + (container.w - w) / 2.0,
+                    Align::End => container.x + container.w - w,
+                };
+                if align == Align::Stretch {
+                    let h = child_base.layout.h;
+                    Container::resize(&mut child_base, container.w, h);
+                }
+            } else if self.halign {
                 let new_x = {
                     let child_base = child.base();
                     self.base().layout.horizontal_center(child_base.layout.w)
@@ -236,6 +318,315 @@ impl Container {
             prev = Some(child);
         }
     }
+    /// Organize widgets in rows optionally wrapping onto a
+    /// new line when the container width is used up
+    ///
+    /// This will override x and y postions set internally
+    /// for children widgets
+    pub(crate) fn create_flex_row_layout(&self, wrap: bool) {
+        let children = self.flow_children();
+        if children.is_empty() {
+            return;
+        }
+
+        let container = self.base().layout;
+
+        ////////////
+        /////// LINE BREAKING
+        ////
+        let mut lines: Vec<&[Rc<dyn WidgetI>]> = Vec::new();
+        let mut start = 0;
+        let mut line_w = 0.0;
+        for (idx, child) in children.iter().enumerate() {
+            let w = child.base().layout.w;
+            // A line always takes at least one widget
+            if wrap && idx > start && line_w + self.gap + w > container.w {
+                lines.push(&children[start..idx]);
+                start = idx;
+                line_w = w;
+            } else if idx == start {
+                line_w = w;
+            } else {
+                line_w += self.gap + w;
+            }
+        }
+        lines.push(&children[start..]);
+
+        ////////////
+        /////// LAYOUT
+        ////
+        let mut y = container.y;
+        for line in lines {
+            let widths: f64 = line.iter().map(|child| child.base().layout.w).sum();
+            let gaps = self.gap * (line.len() - 1) as f64;
+            // Flexible children take up the width left over or give
+            // up the width missing
+            let free = Container::flex_children(line, container.w - widths - gaps, true);
+            // A single line spans the whole container height
+            let line_h = if wrap {
+                line.iter()
+                    .map(|child| child.base().layout.h)
+                    .fold(0.0, f64::max)
+            } else {
+                container.h
+            };
+
+            ////////////
+            /////// ALIGMENT
+            ////
+            let (mut x, gap) = match self.main_align {
+                Align::Start | Align::Stretch => (container.x, self.gap),
+                Align::Center => (container.x + free / 2.0, self.gap),
+                Align::End => (container.x + free, self.gap),
+                Align::SpaceBetween if line.len() > 1 && free > 0.0 => {
+                    (container.x, self.gap + free / (line.len() - 1) as f64)
+                }
+                Align::SpaceBetween => (container.x, self.gap),
+            };
+
+            for child in line {
+                let mut child_base = child.base_mut();
+                child_base.layout.x = x;
+                let align = child_base.align_self.unwrap_or(self.cross_align);
+                child_base.layout.y = match align {
+                    Align::Start | Align::SpaceBetween | Align::Stretch => y,
+                    Align::Center => y + (line_h - child_base.layout.h) / 2.0,
+                    Align::End => y + line_h - child_base.layout.h,
+                };
+                if align == Align::Stretch {
+                    let w = child_base.layout.w;
+                    Container::resize(&mut child_base, w, line_h);
+                }
+                x += child_base.layout.w + gap;
+            }
+
+            y += line_h + self.gap;
+        }
+    }
+    /// Organize widgets by pinning each to an edge of the
+    /// space left over by the ones before it
+    ///
+    /// This will override the positions and the size along
+    /// the docked edge set internally for children widgets
+    pub(crate) fn create_dock_layout(&self) {
+        let mut free = self.base().layout;
+        for child in self.flow_children().iter() {
+            let mut child_base = child.base_mut();
+            let dock = child_base.dock;
+            let (w, h) = match dock {
+                Dock::Top | Dock::Bottom => (free.w, child_base.layout.h.min(free.h)),
+                Dock::Left | Dock::Right => (child_base.layout.w.min(free.w), free.h),
+                Dock::Fill => (free.w, free.h),
+            };
+            child_base.layout = Layout {
+                x: match dock {
+                    Dock::Right => free.x + free.w - w,
+                    _ => free.x,
+                },
+                y: match dock {
+                    Dock::Bottom => free.y + free.h - h,
+                    _ => free.y,
+                },
+                w,
+                h,
+            };
+
+            let used_h = (h + self.gap).min(free.h);
+            let used_w = (w + self.gap).min(free.w);
+            match dock {
+                Dock::Top => {
+                    free.y += used_h;
+                    free.h -= used_h;
+                }
+                Dock::Bottom => free.h -= used_h,
+                Dock::Left => {
+                    free.x += used_w;
+                    free.w -= used_w;
+                }
+                Dock::Right => free.w -= used_w,
+                // Filling children share the space left
+                // over with the ones after them
+                Dock::Fill => (),
+            }
+        }
+    }
+    /// Returns the children placed by the layout of the
+    /// container leaving out `Absolute` ones
+    fn flow_children(&self) -> Vec<Rc<dyn WidgetI>> {
+        self.children
+            .borrow()
+            .iter()
+            .filter(|child| child.base().position != Position::Absolute)
+            .cloned()
+            .collect()
+    }
+    /// Moves `Relative` children away from where the layout
+    /// placed them by their own position
+    fn offset_relative_children(&self) {
+        for child in self.children.borrow().iter() {
+            let mut child_base = child.base_mut();
+            if child_base.position == Position::Relative {
+                child_base.layout.x += child_base.origin.x;
+                child_base.layout.y += child_base.origin.y;
+            }
+        }
+    }
+    /// Hands the `free` space along the main axis out to the
+    /// `children` in proportion to how much they grow or takes
+    /// the missing space from the ones shrinking when negative
+    ///
+    /// Returns the space still left over
+    pub(crate) fn flex_children(children: &[Rc<dyn WidgetI>], free: f64, horizontal: bool) -> f64 {
+        let len = |layout: &Layout| if horizontal { layout.w } else { layout.h };
+        // Shrinking is weighed by size as well so small
+        // children are not squashed away first
+        let weight = |base: &BaseWidget| {
+            if free > 0.0 {
+                base.flex.grow.max(0.0)
+            } else {
+                base.flex.shrink.max(0.0) * len(&base.layout)
+            }
+        };
+        let total: f64 = children.iter().map(|child| weight(&child.base())).sum();
+        if total <= 0.0 || free == 0.0 {
+            return free;
+        }
+
+        let mut left = free;
+        for child in children {
+            let mut child_base = child.base_mut();
+            let layout = child_base.layout;
+            // Children never shrink past nothing
+            let share = (free * weight(&child_base) / total).max(-len(&layout));
+            if horizontal {
+                Container::resize(&mut child_base, layout.w + share, layout.h);
+            } else {
+                Container::resize(&mut child_base, layout.w, layout.h + share);
+            }
+            left -= share;
+        }
+        left
+    }
+    /// Resizes a child layout remembering the size it had
+    /// of its own to return to on the next layout
+    fn resize(child_base: &mut BaseWidget, w: f64, h: f64) {
+        let layout = child_base.layout;
+        // Resized before in the same layout
+        if child_base.flex.grown != Point::new(layout.w, layout.h) {
+            child_base.flex.basis = Point::new(layout.w, layout.h);
+        }
+        child_base.layout.w = w;
+        child_base.layout.h = h;
+        child_base.flex.grown = Point::new(w, h);
+    }
+    /// Returns children to their own size before sharing
+    /// out the space left over again
+    ///
+    /// Children resized since they grew or shrank keep
+    /// their new size
+    fn reset_growth(&self) {
+        for child in self.children.borrow().iter() {
+            let mut child_base = child.base_mut();
+            let basis = std::mem::take(&mut child_base.flex.basis);
+            let grown = std::mem::take(&mut child_base.flex.grown);
+            if child_base.layout.w == grown.x {
+                child_base.layout.w = basis.x;
+            }
+            if child_base.layout.h == grown.y {
+                child_base.layout.h = basis.y;
+            }
+        }
+    }
+    /// Returns how thick the scrollbars are drawn
+    fn scrollbar_sizes(&self) -> (f64, f64) {
+        self.scrollbar
+            .as_ref()
+            .map_or((0.0, 0.0), |(x, y)| (x.base().layout.h, y.base().layout.w))
+    }
+    /// Adjust scrollbars to the overflow of the children
+    ///
+    /// Scrollbars of empty containers are hidden since
+    /// nothing overflows
+    pub(crate) fn adjust_scrolling(&self) {
+        if let Some(scrollbar) = &self.scrollbar {
+            let children = self.children.borrow();
+            let (x, y) = scrollbar;
+            if children.is_empty() {
+                let mut x_base = x.base_mut();
+                x_base.layout.x = 0.0;
+                x_base.layout.w = -1.0;
+                let mut y_base = y.base_mut();
+                y_base.layout.y = 0.0;
+                y_base.layout.h = -1.0;
+                return;
+            }
+
+            let widget_base = self.base();
+
+            let mut x_base = x.base_mut();
+            x_base.layout.y = (widget_base.layout.h + widget_base.layout.y) - x_base.layout.h;
+            if x_base.layout.x == 0.0 {
+                x_base.layout.x = widget_base.layout.x;
+            }
+            // Create scrollbar to be balanced based on max amount of overflow
+            // occuring..otherwise its not seen if no overflow occurs
+            let container_width = widget_base.layout.w + widget_base.layout.x;
+            let overflow_x = children
+                .iter()
+                .fold(container_width, |acc, child| child.base().layout.w.max(acc));
+            let amount_to_take = container_width / overflow_x;
+            // Basically makes x scrollbar visible
+            if amount_to_take < 1.0 {
+                x_base.layout.w = amount_to_take * widget_base.layout.w;
+            }
+
+            let mut y_base = y.base_mut();
+            y_base.layout.x = (widget_base.layout.w + widget_base.layout.x) - y_base.layout.w;
+
+            // This check prevents the scrollbar from being stucked
+            // when redraws occur
+            if y_base.layout.y == 0.0 {
+                y_base.layout.y = widget_base.layout.y;
+            }
+            // Create scrollbar to be balanced based on max amount of overflow
+            // occuring..otherwise its not seen if no overflow occurs
+            let container_height = widget_base.layout.h + widget_base.layout.y;
+            let last_child = &children[children.len() - 1];
+            let last_child_base = last_child.base();
+            let overflow_y = last_child_base.layout.y + last_child_base.layout.h;
+            let amount_to_take = container_height / overflow_y;
+            // Basically makes y scrollbar visible
+            if amount_to_take < 1.0 {
+                y_base.layout.h = amount_to_take * widget_base.layout.h;
+            }
+        }
+    }
+    /// Steps the opacity of auto hiding scrollbars one frame
+    /// towards being shown while in use or hidden otherwise
+    ///
+    /// Returns whether the scrollbars are still shown
+    pub(crate) fn fade_scrollbars(&self, step: f32) -> bool {
+        let Some((x, y)) = &self.scrollbar else {
+            return false;
+        };
+        let active = x.base().state.hovered
+            || y.base().state.hovered
+            || self
+                .scroll_activity
+                .get()
+                .is_some_and(|at| at.elapsed() < self.scroll_options.idle);
+
+        let mut shown = false;
+        for scrollbar in [x, y] {
+            let mut base = scrollbar.base_mut();
+            base.style.opacity = match active {
+                true => (base.style.opacity + step).min(1.0),
+                false => (base.style.opacity - step).max(0.0),
+            };
+            shown |= base.style.opacity > 0.0;
+        }
+        shown
+    }
     /// Pushs the layout of a child
     /// to be inside the parent
     pub(crate) fn snap_to_parent(&self, child: &Rc<dyn WidgetI>) {
@@ -244,7 +635,519 @@ impl Container {
         child_base.layout.y = self.base.borrow().layout.y;
     }
     pub fn add_widget<T: WidgetI + 'static>(&mut self, widget: T) {
-        self.children.push(Rc::new(widget));
+        self.children.get_mut().push(Rc::new(widget));
+    }
+    /// Removes the child behind `id` returning it if it
+    /// was part of the container
+    ///
+    /// The widget stays registered in the `DOM` so prefer
+    /// `DOM::remove_widget` once the container was added
+    pub fn remove_child(&self, id: WidgetId) -> Option<Rc<dyn WidgetI>> {
+        let mut children = self.children.borrow_mut();
+        let idx = children
+            .iter()
+            .position(|child| widget_id(child) == Some(id))?;
+        Some(children.remove(idx))
+    }
+    /// Returns how many pixels the content is scrolled
+    /// along the x and y axis
+    pub fn scroll_offset(&self) -> Point {
+        self.children
+            .borrow()
+            .first()
+            .map(|child| {
+                let offset = child.base().offset;
+                Point::new(-offset.x, -offset.y)
+            })
+            .unwrap_or_default()
+    }
+    /// Scrolls the content so it is moved `x`, `y` pixels
+    /// from where it starts
+    ///
+    /// The content stops at the ends just like when scrolled by
+    /// the user and the container is redrawn once part of a `DOM`
+    pub fn scroll_to(&self, x: f64, y: f64) {
+        if self.scrollbar.is_none() {
+            return;
+        }
+
+        let offset = self.scroll_offset();
+        Scroll::new().scroll_by(self, x - offset.x, y - offset.y);
+        if let Some(trigger) = self.internal_trigger() {
+            trigger.update();
+        }
+    }
+    /// Scrolls the least needed to show the whole child behind
+    /// `id` returning whether it is part of the container
+    ///
+    /// Children larger than the container are shown from their
+    /// top left corner
+    pub fn scroll_into_view(&self, id: WidgetId) -> bool {
+        let Some(child) = self
+            .children
+            .borrow()
+            .iter()
+            .find(|child| widget_id(child) == Some(id))
+            .cloned()
+        else {
+            return false;
+        };
+        let Some(view) = self.children_clip() else {
+            return true;
+        };
+
+        // Positions are compared as laid out before scrolling
+        let (layout, offset) = (child.base().layout, self.scroll_offset());
+        let origin = self.base().layout;
+        let reveal = |start: f64, len: f64, view_len: f64, scroll: f64| {
+            if start < scroll || len > view_len {
+                start
+            } else if start + len > scroll + view_len {
+                start + len - view_len
+            } else {
+                scroll
+            }
+        };
+        self.scroll_to(
+            reveal(layout.x - origin.x, layout.w, view.w, offset.x),
+            reveal(layout.y - origin.y, layout.h, view.h, offset.y),
+        );
+        true
+    }
+    /// Swaps the child behind `id` for `widget` returning the
+    /// old child if it was part of the container
+    pub(crate) fn replace_child(
+        &self,
+        id: WidgetId,
+        widget: Rc<dyn WidgetI>,
+    ) -> Option<Rc<dyn WidgetI>> {
+        let mut children = self.children.borrow_mut();
+        let child = children
+            .iter_mut()
+            .find(|child| widget_id(child) == Some(id))?;
+        Some(std::mem::replace(child, widget))
     }
 }
 impl_widget! {Container}
+impl WidgetHooks for Container {
+    fn children(&self) -> Vec<Rc<dyn WidgetI>> {
+        self.children.borrow().clone()
+    }
+    fn custom_layout(&self) {
+        self.reset_growth();
+        // Adjust spacing layout
+        match self.flex {
+            FlexLayout::None => self.create_normal_layout(),
+            FlexLayout::Col => self.create_flex_col_layout(),
+            FlexLayout::Grid(cols) => self.create_flex_grid_layout(cols),
+            FlexLayout::Row => self.create_flex_row_layout(false),
+            FlexLayout::RowWrap => self.create_flex_row_layout(true),
+            FlexLayout::Dock => self.create_dock_layout(),
+        }
+        self.offset_relative_children();
+        self.adjust_scrolling();
+        self.scrollbar_sizes.set(self.scrollbar_sizes());
+    }
+    fn needs_layout(&self) -> bool {
+        // Hovered scrollbars grow and must be lined up again
+        self.scrollbar_sizes.get() != self.scrollbar_sizes()
+    }
+    fn custom_draw_over(&self, painter: &mut dyn Painter) {
+        // Scrollbar must sit atop everything
+        if let Some((x, y)) = &self.scrollbar {
+            painter.draw_widget(x);
+            painter.draw_widget(y);
+        }
+    }
+    fn children_clip(&self) -> Option<Layout> {
+        let widget_base = self.base();
+
+        // Containers scrolled inside another are clipped
+        // where they were moved to
+        let bounds = Layout {
+            x: widget_base.offset.x + widget_base.layout.x,
+            y: widget_base.offset.y + widget_base.layout.y,
+            ..widget_base.layout
+        };
+
+        // Rounded containers keep children inside their corners
+        let Some((x, y)) = self.scrollbar.as_ref() else {
+            return (!widget_base.style.radius.is_zero()).then_some(bounds);
+        };
+
+        // When scrollbars are placed they take up space
+        // and we want to leave room for them while hovered
+        // scrollbars grow over the content
+        let x_buffer = if x.base().layout.w > 0.0 {
+            x.thickness
+        } else {
+            0.0
+        } + x.buffer;
+        let buffered_h = (bounds.h - x_buffer).max(0.0);
+        let y_buffer = if y.base().layout.h > 0.0 {
+            y.thickness
+        } else {
+            0.0
+        } + y.buffer;
+        let buffered_w = (bounds.w - y_buffer).max(0.0);
+
+        Some(Layout {
+            w: buffered_w,
+            h: buffered_h,
+            ..bounds
+        })
+    }
+    fn covers_children(&self, x: f64, y: f64) -> bool {
+        // Scrollbars are drawn over the children
+        self.scrollbar.as_ref().is_some_and(|(sx, sy)| {
+            sx.base().layout.is_inbounds(x, y) || sy.base().layout.is_inbounds(x, y)
+        })
+    }
+    fn default_role(&self) -> Role {
+        Role::Group
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::ui::{
+        dom::DOM,
+        layout::{intersect_clips, Align, Dock, FlexLayout, Layout, Point, Position},
+        widget::{
+            label::Label, scrollbar::ScrollOptions, spacer::Spacer, widget_id, Widget, WidgetHooks,
+            WidgetI,
+        },
+    };
+
+    use super::Container;
+
+    #[test]
+    fn test_row_wrap_breaks_lines_and_spreads_children() {
+        let mut c = Container::new()
+            .set_width(100.0)
+            .set_height(100.0)
+            .set_gap(10.0)
+            .set_flex_layout(FlexLayout::RowWrap)
+            .set_main_align(Align::SpaceBetween);
+        for _ in 0..3 {
+            c.add_widget(Label::new().set_width(40.0).set_height(20.0));
+        }
+
+        c.create_flex_row_layout(true);
+
+        let positions: Vec<(f64, f64)> = c
+            .children
+            .borrow()
+            .iter()
+            .map(|child| (child.base().layout.x, child.base().layout.y))
+            .collect();
+        assert!(positions == vec![(0.0, 0.0), (60.0, 0.0), (0.0, 30.0)]);
+    }
+
+    #[test]
+    fn spacers_share_the_leftover_space() {
+        let mut c = Container::new()
+            .set_width(100.0)
+            .set_height(20.0)
+            .set_flex_layout(FlexLayout::Row);
+        c.add_widget(Label::new().set_width(10.0).set_height(20.0));
+        c.add_widget(Spacer::new());
+        c.add_widget(Label::new().set_width(10.0).set_height(20.0));
+        c.add_widget(Spacer::with_grow(3.0));
+
+        // Laying out again starts over from the spacers own size
+        c.custom_layout();
+        c.custom_layout();
+        let layouts: Vec<(f64, f64)> = c
+            .children
+            .borrow()
+            .iter()
+            .map(|child| (child.base().layout.x, child.base().layout.w))
+            .collect();
+        assert_eq!(
+            layouts,
+            vec![(0.0, 10.0), (10.0, 20.0), (30.0, 10.0), (40.0, 60.0)]
+        );
+    }
+
+    #[test]
+    fn overflowing_columns_shrink_by_size() {
+        let mut c = Container::new()
+            .set_width(50.0)
+            .set_height(100.0)
+            .set_flex_layout(FlexLayout::Col);
+        c.add_widget(Label::new().set_height(80.0).set_flex_shrink(1.0));
+        c.add_widget(Label::new().set_height(40.0).set_flex_shrink(1.0));
+        c.add_widget(Label::new().set_height(10.0));
+
+        c.custom_layout();
+        let heights: Vec<f64> = c
+            .children
+            .borrow()
+            .iter()
+            .map(|child| child.base().layout.h)
+            .collect();
+        assert!((heights[0] - 60.0).abs() < 1e-9);
+        assert!((heights[1] - 30.0).abs() < 1e-9);
+        assert_eq!(heights[2], 10.0);
+
+        // Room to spare lets them back to their own size
+        let c = c.set_height(200.0);
+        c.custom_layout();
+        assert_eq!(c.children.borrow()[0].base().layout.h, 80.0);
+    }
+
+    #[test]
+    fn docks_children_around_the_space_left_over() {
+        let mut c = Container::new()
+            .set_width(300.0)
+            .set_height(200.0)
+            .set_flex_layout(FlexLayout::Dock);
+        c.add_widget(Label::new().set_height(30.0).set_dock(Dock::Top));
+        c.add_widget(Label::new().set_height(20.0).set_dock(Dock::Bottom));
+        c.add_widget(Label::new().set_width(80.0).set_dock(Dock::Left));
+        c.add_widget(Label::new().set_dock(Dock::Fill));
+
+        c.custom_layout();
+        let layouts: Vec<Layout> = c
+            .children
+            .borrow()
+            .iter()
+            .map(|child| child.base().layout)
+            .collect();
+        let rect = |x, y, w, h| Layout { x, y, w, h };
+        assert_eq!(
+            layouts,
+            vec![
+                rect(0.0, 0.0, 300.0, 30.0),
+                rect(0.0, 180.0, 300.0, 20.0),
+                rect(0.0, 30.0, 80.0, 150.0),
+                rect(80.0, 30.0, 220.0, 150.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn positioned_children_keep_their_coordinates() {
+        let mut c = Container::new()
+            .set_x(10.0)
+            .set_y(10.0)
+            .set_width(100.0)
+            .set_height(100.0)
+            .set_flex_layout(FlexLayout::Col);
+        c.add_widget(Label::new().set_x(40.0).set_height(20.0));
+        c.add_widget(
+            Label::new()
+                .set_x(5.0)
+                .set_y(3.0)
+                .set_height(20.0)
+                .set_position(Position::Relative),
+        );
+        c.add_widget(
+            Label::new()
+                .set_x(200.0)
+                .set_y(300.0)
+                .set_height(20.0)
+                .set_position(Position::Absolute),
+        );
+        c.add_widget(Label::new().set_height(20.0));
+
+        // Offsets do not pile up over layouts
+        c.custom_layout();
+        c.custom_layout();
+        let positions: Vec<(f64, f64)> = c
+            .children
+            .borrow()
+            .iter()
+            .map(|child| (child.base().layout.x, child.base().layout.y))
+            .collect();
+        assert_eq!(
+            positions,
+            vec![(10.0, 10.0), (15.0, 33.0), (200.0, 300.0), (10.0, 50.0)]
+        );
+    }
+
+    #[test]
+    fn children_align_themselves_across_rows() {
+        let mut c = Container::new()
+            .set_width(100.0)
+            .set_height(40.0)
+            .set_flex_layout(FlexLayout::Row)
+            .set_cross_align(Align::Center);
+        c.add_widget(Label::new().set_width(10.0).set_height(10.0));
+        c.add_widget(
+            Label::new()
+                .set_width(10.0)
+                .set_height(10.0)
+                .set_align_self(Align::End),
+        );
+        c.add_widget(
+            Label::new()
+                .set_width(10.0)
+                .set_height(10.0)
+                .set_align_self(Align::Stretch),
+        );
+
+        c.custom_layout();
+        c.custom_layout();
+        let rows: Vec<(f64, f64)> = c
+            .children
+            .borrow()
+            .iter()
+            .map(|child| (child.base().layout.y, child.base().layout.h))
+            .collect();
+        assert_eq!(rows, vec![(15.0, 10.0), (30.0, 10.0), (0.0, 40.0)]);
+    }
+
+    #[test]
+    fn scrolls_to_a_position_like_the_user_would() {
+        let mut c = Container::new()
+            .set_width(100.0)
+            .set_height(100.0)
+            .on_scroll();
+        c.add_widget(Label::new().set_width(100.0).set_height(300.0));
+        c.custom_layout();
+
+        c.scroll_to(30.0, 50.0);
+        let offset = c.scroll_offset();
+        // Nothing overflows sideways to scroll to
+        assert_eq!(offset.x, 0.0);
+        assert!((offset.y - 50.0).abs() < 1e-9);
+
+        c.scroll_to(0.0, 0.0);
+        assert_eq!(c.scroll_offset().y, 0.0);
+    }
+
+    #[test]
+    fn auto_hidden_scrollbars_fade_while_in_use() {
+        let c = Container::new().on_scroll_with(ScrollOptions {
+            auto_hide: true,
+            ..Default::default()
+        });
+        let opacity = |c: &Container| c.scrollbar.as_ref().unwrap().1.base().style.opacity;
+        assert_eq!(opacity(&c), 0.0);
+        assert!(!c.fade_scrollbars(0.5));
+
+        c.scroll_activity.set(Some(web_time::Instant::now()));
+        assert!(c.fade_scrollbars(0.5));
+        assert!(c.fade_scrollbars(0.5));
+        assert_eq!(opacity(&c), 1.0);
+
+        // Idle scrollbars fade out until hidden
+        c.scroll_activity.set(None);
+        assert!(c.fade_scrollbars(0.5));
+        assert!(!c.fade_scrollbars(0.5));
+        assert_eq!(opacity(&c), 0.0);
+    }
+
+    #[test]
+    fn nested_scroll_containers_clip_where_they_are_scrolled_to() {
+        let outer = Container::new()
+            .set_width(200.0)
+            .set_height(120.0)
+            .on_scroll();
+        let inner = Container::new()
+            .set_x(20.0)
+            .set_y(60.0)
+            .set_width(100.0)
+            .set_height(100.0)
+            .on_scroll();
+        // Scrolling the outer container moves the inner one up
+        inner.base_mut().offset.y = -30.0;
+
+        // Both leave room for their scrollbars
+        let outer_clip = outer.children_clip();
+        let inner_clip = inner.children_clip();
+        assert_eq!(
+            inner_clip,
+            Some(Layout {
+                x: 20.0,
+                y: 30.0,
+                w: 95.0,
+                h: 95.0
+            })
+        );
+        // The inner container reaches below the outer one
+        assert_eq!(
+            intersect_clips(inner_clip, outer_clip),
+            Some(Layout {
+                x: 20.0,
+                y: 30.0,
+                w: 95.0,
+                h: 85.0
+            })
+        );
+    }
+
+    #[test]
+    fn scrollbars_hide_once_the_last_child_is_removed() {
+        let mut dom = DOM::headless(200, 200);
+        let mut c = Container::new()
+            .set_width(100.0)
+            .set_height(100.0)
+            .on_scroll();
+        c.add_widget(
+            Label::new()
+                .set_id("tall")
+                .set_width(100.0)
+                .set_height(300.0),
+        );
+        let id = dom.add_widget(c);
+        let container = dom.widget(id).unwrap();
+        let child = dom.get_widget_by_id("tall").unwrap();
+        dom.move_cursor(10.0, 10.0);
+
+        dom.remove_widget(widget_id(&child).unwrap()).unwrap();
+        // Laid out again without any children to scroll
+        dom.move_cursor(20.0, 20.0);
+        let c = container.downcast::<Container>().unwrap();
+        assert!(c.children.borrow().is_empty());
+        let (x, y) = c.scrollbar.as_ref().unwrap();
+        assert!(x.base().layout.w < 0.0);
+        assert!(y.base().layout.h < 0.0);
+        assert_eq!(c.scroll_offset(), Point::default());
+    }
+
+    #[test]
+    fn only_the_innermost_container_takes_the_wheel() {
+        let mut dom = DOM::headless(200, 200);
+        let mut inner = Container::new()
+            .set_id("inner")
+            .set_width(100.0)
+            .set_height(100.0)
+            .on_scroll();
+        inner.add_widget(Label::new().set_width(100.0).set_height(300.0));
+        let mut outer = Container::new()
+            .set_width(200.0)
+            .set_height(100.0)
+            .set_flex_layout(FlexLayout::Col)
+            .on_scroll();
+        outer.add_widget(inner);
+        outer.add_widget(Label::new().set_width(100.0).set_height(200.0));
+        let id = dom.add_widget(outer);
+        let outer = dom.widget(id).unwrap();
+        let inner = dom.get_widget_by_id("inner").unwrap();
+        let offsets = || {
+            let offset = |widget: &Rc<dyn WidgetI>| {
+                widget.downcast::<Container>().unwrap().scroll_offset().y
+            };
+            (offset(&outer), offset(&inner))
+        };
+
+        dom.move_cursor(50.0, 50.0);
+        dom.scroll(1.0);
+        assert_eq!(offsets(), (0.0, 20.0));
+
+        // Next to the inner container the outer one scrolls
+        dom.move_cursor(150.0, 50.0);
+        dom.scroll(1.0);
+        assert_eq!(offsets(), (20.0, 20.0));
+
+        // The inner container is found where it was scrolled to
+        dom.move_cursor(50.0, 70.0);
+        dom.scroll(1.0);
+        assert_eq!(offsets(), (20.0, 40.0));
+    }
+}
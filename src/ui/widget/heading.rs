@@ -7,10 +7,13 @@ use std::{
 
 use crate::{
     action::Action,
-    ui::sync::{Thread, Trigger},
+    ui::{
+        semantics::Role,
+        sync::{Thread, Trigger},
+    },
 };
 
-use super::{impl_widget, BaseWidget, Widget, WidgetI, WidgetInternal};
+use super::{impl_widget, BaseWidget, Widget, WidgetHooks, WidgetI, WidgetInternal};
 
 /// A struct representing a heading widget.
 ///
@@ -30,17 +33,25 @@ impl Heading {
     pub fn new() -> Self {
         Heading::default()
     }
-    pub fn set_large_heading(self) -> Self {
-        self.base.borrow_mut().text.font_size = 32.0;
+    /// Lets the text be selected by dragging over it
+    /// and copied with Ctrl+C
+    pub fn set_selectable(self) -> Self {
+        self.base.borrow_mut().text.selectable = true;
         self
     }
+    pub fn set_large_heading(self) -> Self {
+        self.set_label_size(32.0)
+    }
     pub fn set_medium_heading(self) -> Self {
-        self.base.borrow_mut().text.font_size = 24.0;
-        self
+        self.set_label_size(24.0)
     }
     pub fn set_small_heading(self) -> Self {
-        self.base.borrow_mut().text.font_size = 18.0;
-        self
+        self.set_label_size(18.0)
     }
 }
 impl_widget! {Heading}
+impl WidgetHooks for Heading {
+    fn default_role(&self) -> Role {
+        Role::Heading
+    }
+}
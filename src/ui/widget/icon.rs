@@ -0,0 +1,327 @@
+use std::{
+    any::Any,
+    cell::{Cell, Ref, RefCell, RefMut},
+    rc::Rc,
+    sync::Arc,
+};
+
+use tiny_skia::{Path, PathBuilder, Transform};
+
+use crate::{
+    action::Action,
+    render::Painter,
+    ui::{
+        color::{Color, BLACK},
+        layout::Layout,
+        semantics::Role,
+        style::Outline,
+        sync::{Thread, Trigger},
+    },
+};
+
+use super::{impl_widget, BaseWidget, Widget, WidgetHooks, WidgetI, WidgetInternal};
+
+/// The size of the grid icons are drawn on
+const GRID: f32 = 24.0;
+/// How wide the strokes of icons are on the grid
+const STROKE: f32 = 2.0;
+/// The size of icons unless set otherwise
+const DEFAULT_SIZE: f64 = 24.0;
+
+/// The `IconName` enum lists the icons built into the crate
+///
+/// Icons are outlined on a 24 by 24 grid and scaled to
+/// the size of the widget showing them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IconName {
+    #[default]
+    Save,
+    Open,
+    Undo,
+    Redo,
+    ZoomIn,
+    ZoomOut,
+    Search,
+    Edit,
+    Delete,
+    Close,
+    Check,
+    Plus,
+    Minus,
+    Menu,
+    Play,
+    Pause,
+    ArrowLeft,
+    ArrowRight,
+    ArrowUp,
+    ArrowDown,
+}
+impl IconName {
+    /// Returns the name of the icon as read out
+    /// by assistive technologies
+    pub fn label(self) -> &'static str {
+        match self {
+            IconName::Save => "Save",
+            IconName::Open => "Open",
+            IconName::Undo => "Undo",
+            IconName::Redo => "Redo",
+            IconName::ZoomIn => "Zoom in",
+            IconName::ZoomOut => "Zoom out",
+            IconName::Search => "Search",
+            IconName::Edit => "Edit",
+            IconName::Delete => "Delete",
+            IconName::Close => "Close",
+            IconName::Check => "Check",
+            IconName::Plus => "Plus",
+            IconName::Minus => "Minus",
+            IconName::Menu => "Menu",
+            IconName::Play => "Play",
+            IconName::Pause => "Pause",
+            IconName::ArrowLeft => "Arrow left",
+            IconName::ArrowRight => "Arrow right",
+            IconName::ArrowUp => "Arrow up",
+            IconName::ArrowDown => "Arrow down",
+        }
+    }
+    /// Returns the outline of the icon on the grid
+    fn path(self) -> Option<Path> {
+        let mut pb = PathBuilder::new();
+        let mut lines = |points: &[(f32, f32)]| {
+            if let Some(((x, y), rest)) = points.split_first() {
+                pb.move_to(*x, *y);
+                for (x, y) in rest {
+                    pb.line_to(*x, *y);
+                }
+            }
+        };
+        match self {
+            IconName::Save => {
+                lines(&[(3.0, 3.0), (17.0, 3.0), (21.0, 7.0), (21.0, 21.0)]);
+                lines(&[(21.0, 21.0), (3.0, 21.0), (3.0, 3.0)]);
+                lines(&[(7.0, 3.0), (7.0, 8.0), (15.0, 8.0), (15.0, 3.0)]);
+                lines(&[(7.0, 21.0), (7.0, 14.0), (17.0, 14.0), (17.0, 21.0)]);
+            }
+            IconName::Open => {
+                lines(&[(3.0, 5.0), (9.0, 5.0), (11.0, 8.0), (21.0, 8.0)]);
+                lines(&[(21.0, 8.0), (21.0, 19.0), (3.0, 19.0), (3.0, 5.0)]);
+            }
+            IconName::Undo => {
+                lines(&[(9.0, 14.0), (4.0, 9.0), (9.0, 4.0)]);
+                lines(&[(4.0, 9.0), (15.0, 9.0)]);
+                pb.quad_to(20.0, 9.0, 20.0, 14.0);
+                pb.quad_to(20.0, 19.0, 15.0, 19.0);
+                pb.line_to(11.0, 19.0);
+            }
+            IconName::Redo => {
+                lines(&[(15.0, 14.0), (20.0, 9.0), (15.0, 4.0)]);
+                lines(&[(20.0, 9.0), (9.0, 9.0)]);
+                pb.quad_to(4.0, 9.0, 4.0, 14.0);
+                pb.quad_to(4.0, 19.0, 9.0, 19.0);
+                pb.line_to(13.0, 19.0);
+            }
+            IconName::ZoomIn | IconName::ZoomOut | IconName::Search => {
+                lines(&[(21.0, 21.0), (16.0, 16.0)]);
+                if self != IconName::Search {
+                    lines(&[(7.0, 11.0), (15.0, 11.0)]);
+                }
+                if self == IconName::ZoomIn {
+                    lines(&[(11.0, 7.0), (11.0, 15.0)]);
+                }
+                pb.push_circle(11.0, 11.0, 7.0);
+            }
+            IconName::Edit => {
+                lines(&[(16.0, 3.0), (21.0, 8.0), (8.0, 21.0), (3.0, 21.0)]);
+                lines(&[(3.0, 21.0), (3.0, 16.0), (16.0, 3.0)]);
+                lines(&[(13.0, 6.0), (18.0, 11.0)]);
+            }
+            IconName::Delete => {
+                lines(&[(3.0, 6.0), (21.0, 6.0)]);
+                lines(&[(9.0, 6.0), (9.0, 3.0), (15.0, 3.0), (15.0, 6.0)]);
+                lines(&[(5.0, 6.0), (6.0, 21.0), (18.0, 21.0), (19.0, 6.0)]);
+            }
+            IconName::Close => {
+                lines(&[(6.0, 6.0), (18.0, 18.0)]);
+                lines(&[(18.0, 6.0), (6.0, 18.0)]);
+            }
+            IconName::Check => lines(&[(4.0, 12.0), (9.0, 17.0), (20.0, 6.0)]),
+            IconName::Plus => {
+                lines(&[(12.0, 5.0), (12.0, 19.0)]);
+                lines(&[(5.0, 12.0), (19.0, 12.0)]);
+            }
+            IconName::Minus => lines(&[(5.0, 12.0), (19.0, 12.0)]),
+            IconName::Menu => {
+                lines(&[(4.0, 6.0), (20.0, 6.0)]);
+                lines(&[(4.0, 12.0), (20.0, 12.0)]);
+                lines(&[(4.0, 18.0), (20.0, 18.0)]);
+            }
+            IconName::Play => {
+                lines(&[(6.0, 4.0), (20.0, 12.0), (6.0, 20.0)]);
+                pb.close();
+            }
+            IconName::Pause => {
+                lines(&[(9.0, 5.0), (9.0, 19.0)]);
+                lines(&[(15.0, 5.0), (15.0, 19.0)]);
+            }
+            IconName::ArrowLeft => {
+                lines(&[(19.0, 12.0), (5.0, 12.0)]);
+                lines(&[(12.0, 19.0), (5.0, 12.0), (12.0, 5.0)]);
+            }
+            IconName::ArrowRight => {
+                lines(&[(5.0, 12.0), (19.0, 12.0)]);
+                lines(&[(12.0, 5.0), (19.0, 12.0), (12.0, 19.0)]);
+            }
+            IconName::ArrowUp => {
+                lines(&[(12.0, 19.0), (12.0, 5.0)]);
+                lines(&[(5.0, 12.0), (12.0, 5.0), (19.0, 12.0)]);
+            }
+            IconName::ArrowDown => {
+                lines(&[(12.0, 5.0), (12.0, 19.0)]);
+                lines(&[(19.0, 12.0), (12.0, 19.0), (5.0, 12.0)]);
+            }
+        }
+        pb.finish()
+    }
+}
+
+/// Draws the icon `name` tinted in `tint` as large as fits
+/// in the middle of `rect`
+pub(crate) fn draw_icon(painter: &mut dyn Painter, name: IconName, rect: Layout, tint: Color) {
+    let size = rect.w.min(rect.h) as f32;
+    if size <= 0.0 {
+        return;
+    }
+    let scale = size / GRID;
+    let x = rect.x as f32 + (rect.w as f32 - size) / 2.0;
+    let y = rect.y as f32 + (rect.h as f32 - size) / 2.0;
+    let Some(path) = name
+        .path()
+        .and_then(|path| path.transform(Transform::from_scale(scale, scale).post_translate(x, y)))
+    else {
+        return;
+    };
+    painter.draw_path(
+        &path,
+        None,
+        Some(Outline::new(f64::from(STROKE * scale), tint)),
+    );
+}
+
+/// A struct representing an icon widget.
+///
+/// The `Icon` struct shows one of the icons built into the crate so
+/// toolbars and buttons get standard icons without shipping assets.
+/// Icons are drawn as outlines in a tint color of their own and scale
+/// to the size of the widget. Assistive technologies read out the name
+/// of the icon unless the widget is named otherwise.
+///
+/// ## Example
+/// ```ignore
+/// let mut toolbar = Container::new().set_flex_layout(FlexLayout::Row);
+/// toolbar.add_widget(Icon::new(IconName::Save).set_size(16.0).set_tint(BLUE));
+/// toolbar.add_widget(Icon::new(IconName::Undo).set_size(16.0));
+/// ```
+pub struct Icon {
+    pub base: RefCell<BaseWidget>,
+    pub actions: RefCell<Vec<Action>>,
+    emitter: Option<Arc<dyn Thread>>,
+    trigger: RefCell<Option<Rc<Trigger>>>,
+    name: Cell<IconName>,
+    pub(crate) tint: Color,
+}
+impl Icon {
+    /// Creates the icon `name` 24px large
+    pub fn new(name: IconName) -> Self {
+        let mut base = BaseWidget::default();
+        base.semantics.name = Some(name.label().into());
+        Self {
+            base: RefCell::new(base),
+            actions: RefCell::default(),
+            emitter: None,
+            trigger: RefCell::default(),
+            name: Cell::new(name),
+            tint: BLACK,
+        }
+        .set_width(DEFAULT_SIZE)
+        .set_height(DEFAULT_SIZE)
+    }
+    /// Set the color the icon is drawn in
+    pub fn set_tint(mut self, color: Color) -> Self {
+        self.tint = color;
+        self
+    }
+    /// Set the width and height the icon is scaled to
+    pub fn set_size(self, size: f64) -> Self {
+        self.set_width(size).set_height(size)
+    }
+    /// Returns the icon shown
+    pub fn name(&self) -> IconName {
+        self.name.get()
+    }
+    /// Shows the icon `name` instead
+    ///
+    /// The widget is named after the new icon unless
+    /// it was named otherwise
+    pub fn set_icon(&self, name: IconName) {
+        let old = self.name.replace(name);
+        if old != name {
+            let mut base = self.base_mut();
+            if base.semantics.name.as_deref() == Some(old.label()) {
+                base.semantics.name = Some(name.label().into());
+            }
+            drop(base);
+            if let Some(trigger) = self.internal_trigger() {
+                trigger.update();
+            }
+        }
+    }
+}
+impl_widget! {Icon}
+impl WidgetHooks for Icon {
+    fn custom_draw(&self, painter: &mut dyn Painter) {
+        let rect = {
+            let base = self.base();
+            Layout {
+                x: base.offset.x + base.layout.x,
+                y: base.offset.y + base.layout.y,
+                ..base.layout
+            }
+        };
+        draw_icon(painter, self.name.get(), rect, self.tint);
+    }
+    fn default_role(&self) -> Role {
+        Role::Graphic
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::ui::{
+        color::{RED, TRANSPARENT},
+        dom::DOM,
+        widget::{Widget, WidgetI},
+    };
+
+    use super::{Icon, IconName};
+
+    #[test]
+    fn icons_are_scaled_and_tinted() {
+        let widgets: [Rc<dyn WidgetI>; 1] = [Rc::new(
+            Icon::new(IconName::Minus).set_size(48.0).set_tint(RED),
+        )];
+        let frame = DOM::render_once_to_buffer(&widgets, 48, 48).unwrap();
+
+        // The bar of the minus runs across the middle
+        assert_eq!(frame.pixel(24, 23), Some(RED));
+        assert_eq!(frame.pixel(36, 23), Some(RED));
+        assert_eq!(frame.pixel(24, 10), Some(TRANSPARENT));
+        assert_eq!(frame.pixel(4, 23), Some(TRANSPARENT));
+
+        let icon = Icon::new(IconName::Play);
+        assert_eq!(icon.base().semantics.name.as_deref(), Some("Play"));
+        icon.set_icon(IconName::Pause);
+        assert_eq!(icon.base().semantics.name.as_deref(), Some("Pause"));
+    }
+}
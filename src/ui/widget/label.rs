@@ -7,10 +7,13 @@ use std::{
 
 use crate::{
     action::Action,
-    ui::sync::{Thread, Trigger},
+    ui::{
+        semantics::Role,
+        sync::{Thread, Trigger},
+    },
 };
 
-use super::{impl_widget, BaseWidget, Widget, WidgetI, WidgetInternal};
+use super::{impl_widget, BaseWidget, Widget, WidgetHooks, WidgetI, WidgetInternal};
 
 /// A struct representing a heading widget.
 ///
@@ -27,5 +30,16 @@ impl Label {
     pub fn new() -> Self {
         Label::default()
     }
+    /// Lets the text be selected by dragging over it
+    /// and copied with Ctrl+C
+    pub fn set_selectable(self) -> Self {
+        self.base.borrow_mut().text.selectable = true;
+        self
+    }
 }
 impl_widget! {Label}
+impl WidgetHooks for Label {
+    fn default_role(&self) -> Role {
+        Role::Label
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    any::Any,
+    cell::{Cell, Ref, RefCell, RefMut},
+    collections::BTreeMap,
+    rc::Rc,
+    sync::Arc,
+};
+
+use crate::{
+    action::Action,
+    render::Painter,
+    ui::{
+        layout::Layout,
+        semantics::Role,
+        sync::{Thread, Trigger},
+    },
+};
+
+use super::{
+    impl_widget, scrollbar::ScrollBar, BaseWidget, Widget, WidgetHooks, WidgetI, WidgetInternal,
+};
+
+type RowProvider = Rc<dyn Fn(usize) -> Rc<dyn WidgetI>>;
+
+/// The shortest the scrollbar thumb gets for very long lists
+const MIN_THUMB: f64 = 16.0;
+
+/// A struct representing a virtualized list widget.
+///
+/// The `ListView` struct shows a list of rows of the same height
+/// which are built by a data provider only once they scroll into
+/// view. Rows scrolled out of view are dropped again so lists of
+/// tens of thousands of items only ever lay out, draw and hit the
+/// handful of rows visible.
+///
+/// ## Example
+/// ```ignore
+/// let names: Vec<String> = (0..50_000).map(|i| format!("Item {i}")).collect();
+/// let list = ListView::new(names.len(), move |index| {
+///     Label::new().set_label(&names[index])
+/// })
+/// .set_row_height(24.0)
+/// .set_width(300.0)
+/// .set_height(400.0);
+/// ```
+pub struct ListView {
+    pub base: RefCell<BaseWidget>,
+    pub actions: RefCell<Vec<Action>>,
+    emitter: Option<Arc<dyn Thread>>,
+    trigger: RefCell<Option<Rc<Trigger>>>,
+    provider: RowProvider,
+    count: Cell<usize>,
+    pub(crate) row_height: f64,
+    scroll: Cell<f64>,
+    rows: RefCell<BTreeMap<usize, Rc<dyn WidgetI>>>,
+    /// Rows dropped since the `DOM` last let go of them
+    removed: RefCell<Vec<Rc<dyn WidgetI>>>,
+    /// Whether the rows in view changed since last laid out
+    relayout: Cell<bool>,
+    pub(crate) scrollbar: ScrollBar,
+}
+impl ListView {
+    /// Creates a list of `count` rows where the row at every
+    /// index is built by `provider`
+    pub fn new<W, F>(count: usize, provider: F) -> Self
+    where
+        W: WidgetI + 'static,
+        F: Fn(usize) -> W + 'static,
+    {
+        Self {
+            base: RefCell::default(),
+            actions: RefCell::default(),
+            emitter: None,
+            trigger: RefCell::default(),
+            provider: Rc::new(move |index| Rc::new(provider(index))),
+            count: Cell::new(count),
+            row_height: 24.0,
+            scroll: Cell::default(),
+            rows: RefCell::default(),
+            removed: RefCell::default(),
+            relayout: Cell::default(),
+            scrollbar: ScrollBar::new_y(),
+        }
+    }
+    /// Set the height of every row
+    ///
+    /// Defaults to 24px
+    pub fn set_row_height(mut self, height: f64) -> Self {
+        self.row_height = height.max(1.0);
+        self
+    }
+    /// Returns how many rows the list holds
+    pub fn item_count(&self) -> usize {
+        self.count.get()
+    }
+    /// Changes how many rows the list holds rebuilding
+    /// the visible rows on the next redraw
+    ///
+    /// Rows are built again since the data behind
+    /// them may have changed as well
+    pub fn update_items(&self, count: usize) {
+        self.count.set(count);
+        let rows = std::mem::take(&mut *self.rows.borrow_mut());
+        self.removed.borrow_mut().extend(rows.into_values());
+        self.relayout.set(true);
+        if let Some(trigger) = self.internal_trigger() {
+            trigger.update();
+        }
+    }
+    /// Returns how many pixels the rows are scrolled
+    pub fn scroll_offset(&self) -> f64 {
+        self.scroll.get()
+    }
+    /// Scrolls the rows so they are moved `y` pixels
+    /// from where they start
+    pub fn scroll_to(&self, y: f64) {
+        let scroll = y.clamp(0.0, self.max_scroll());
+        if scroll == self.scroll.get() {
+            return;
+        }
+        self.scroll.set(scroll);
+        self.relayout.set(true);
+        if let Some(trigger) = self.internal_trigger() {
+            trigger.update();
+        }
+    }
+    /// Scrolls the least needed to show the whole row at `index`
+    pub fn scroll_to_item(&self, index: usize) {
+        let (top, height) = (index as f64 * self.row_height, self.base().layout.h);
+        let scroll = self.scroll.get();
+        if top < scroll {
+            self.scroll_to(top);
+        } else if top + self.row_height > scroll + height {
+            self.scroll_to(top + self.row_height - height);
+        }
+    }
+    pub(crate) fn scroll_by(&self, amount: f64) {
+        self.scroll_to(self.scroll.get() + amount);
+    }
+    fn content_height(&self) -> f64 {
+        self.count.get() as f64 * self.row_height
+    }
+    fn max_scroll(&self) -> f64 {
+        (self.content_height() - self.base().layout.h).max(0.0)
+    }
+}
+impl_widget! {ListView}
+impl WidgetHooks for ListView {
+    fn children(&self) -> Vec<Rc<dyn WidgetI>> {
+        self.rows.borrow().values().cloned().collect()
+    }
+    fn removed_children(&self) -> Vec<Rc<dyn WidgetI>> {
+        std::mem::take(&mut *self.removed.borrow_mut())
+    }
+    fn custom_layout(&self) {
+        self.relayout.set(false);
+        let layout = self.base().layout;
+        // Shrinking the list or the data may leave it
+        // scrolled past the end
+        let scroll = self.scroll.get().min(self.max_scroll());
+        self.scroll.set(scroll);
+
+        // Only rows reaching into the list are kept
+        let count = self.count.get();
+        let first = ((scroll / self.row_height).floor() as usize).min(count);
+        let last = (((scroll + layout.h) / self.row_height).ceil() as usize).min(count);
+        let mut rows = self.rows.borrow_mut();
+        let mut kept = rows.split_off(&first);
+        let below = kept.split_off(&last);
+        let mut removed = self.removed.borrow_mut();
+        removed.extend(std::mem::replace(&mut *rows, kept).into_values());
+        removed.extend(below.into_values());
+
+        for index in first..last {
+            let row = rows.entry(index).or_insert_with(|| (self.provider)(index));
+            let mut row_base = row.base_mut();
+            row_base.layout = Layout {
+                x: layout.x,
+                y: layout.y + index as f64 * self.row_height - scroll,
+                w: layout.w,
+                h: self.row_height,
+            };
+        }
+
+        // The thumb shows which part of the list is in view
+        let content = self.content_height();
+        let mut thumb = self.scrollbar.base_mut();
+        if content > layout.h && layout.h > 0.0 {
+            let h = (layout.h * layout.h / content).max(MIN_THUMB).min(layout.h);
+            thumb.layout = Layout {
+                x: layout.x + layout.w - self.scrollbar.thickness,
+                y: layout.y + (layout.h - h) * scroll / self.max_scroll(),
+                w: self.scrollbar.thickness,
+                h,
+            };
+        } else {
+            thumb.layout.h = -1.0;
+        }
+    }
+    fn needs_layout(&self) -> bool {
+        self.relayout.get()
+    }
+    fn custom_draw_over(&self, painter: &mut dyn Painter) {
+        if self.scrollbar.base().layout.h > 0.0 {
+            painter.draw_widget(&self.scrollbar);
+        }
+    }
+    fn children_clip(&self) -> Option<Layout> {
+        let base = self.base();
+        Some(Layout {
+            x: base.offset.x + base.layout.x,
+            y: base.offset.y + base.layout.y,
+            ..base.layout
+        })
+    }
+    fn default_role(&self) -> Role {
+        Role::Group
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use crate::ui::widget::{label::Label, Widget, WidgetHooks};
+
+    use super::ListView;
+
+    #[test]
+    fn only_builds_the_rows_in_view() {
+        let built = Rc::new(Cell::new(0));
+        let list = ListView::new(50_000, {
+            let built = built.clone();
+            move |_| {
+                built.set(built.get() + 1);
+                Label::new()
+            }
+        })
+        .set_row_height(20.0)
+        .set_height(100.0);
+
+        list.custom_layout();
+        assert_eq!(list.children().len(), 5);
+        assert_eq!(built.get(), 5);
+
+        // Rows still in view are kept
+        list.scroll_by(30.0);
+        list.custom_layout();
+        let rows = list.children();
+        assert_eq!(rows.len(), 6);
+        assert_eq!(built.get(), 7);
+        assert_eq!(rows[0].base().layout.y, -10.0);
+        assert_eq!(list.removed_children().len(), 1);
+
+        // Scrolling stops at the last row
+        list.scroll_to(f64::MAX);
+        list.custom_layout();
+        assert_eq!(list.scroll_offset(), 50_000.0 * 20.0 - 100.0);
+        assert_eq!(list.children().len(), 5);
+    }
+}
@@ -0,0 +1,414 @@
+use std::{
+    any::Any,
+    cell::{Cell, Ref, RefCell, RefMut},
+    rc::{Rc, Weak},
+    sync::Arc,
+};
+
+use crate::{
+    action::{pan::Pan, Action},
+    render::Painter,
+    ui::{
+        buffer::CanvasBuffer,
+        color::{Color, BLUE, TRANSPARENT},
+        layout::{Layout, Point},
+        semantics::Role,
+        sync::{Thread, Trigger},
+    },
+};
+
+use super::{
+    canvas::Canvas, container::Container, impl_widget, shape::Shape, BaseWidget, Widget,
+    WidgetHooks, WidgetI, WidgetInternal,
+};
+
+/// The size of minimaps unless set otherwise
+const DEFAULT_WIDTH: f64 = 160.0;
+const DEFAULT_HEIGHT: f64 = 120.0;
+/// How thick the outline of the viewport is
+const VIEWPORT_WIDTH: f64 = 2.0;
+/// How opaque the viewport is filled
+const VIEWPORT_ALPHA: u8 = 40;
+
+/// The `Scale` struct maps the content of the target onto
+/// the region of the minimap it is drawn in
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Scale {
+    /// Where the content starts as laid out before scrolling
+    origin: Point,
+    /// Where the target starts
+    start: Point,
+    /// How many pixels of the minimap a pixel of content spans
+    factor: f64,
+    /// Where the content is drawn on the minimap
+    area: Layout,
+    /// The part of the content scrolled into view
+    visible: Layout,
+}
+impl Scale {
+    /// Returns `layout` of the content as drawn on the minimap
+    fn to_minimap(self, layout: Layout) -> Layout {
+        Layout {
+            x: self.area.x + (layout.x - self.origin.x) * self.factor,
+            y: self.area.y + (layout.y - self.origin.y) * self.factor,
+            w: layout.w * self.factor,
+            h: layout.h * self.factor,
+        }
+    }
+    /// Returns the viewport as drawn on the minimap
+    fn viewport(self) -> Layout {
+        Layout {
+            x: self.area.x + self.visible.x * self.factor,
+            y: self.area.y + self.visible.y * self.factor,
+            w: self.visible.w * self.factor,
+            h: self.visible.h * self.factor,
+        }
+    }
+}
+
+/// A struct representing a minimap widget.
+///
+/// The `Minimap` struct shows a scaled-down view of the content of a
+/// target widget, usually a scrollable `Container` holding a large
+/// `Canvas` or the nodes of an editor. The part scrolled into view is
+/// marked by a viewport rectangle which follows the target as it is
+/// scrolled or zoomed. Dragging the viewport, or pressing anywhere
+/// on the minimap, scrolls the target along.
+///
+/// Widgets are drawn as boxes in their color and canvases by their
+/// pixels. Targets other than scrollable containers are shown whole.
+///
+/// ## Example
+/// ```ignore
+/// let id = dom.add_widget(
+///     Container::new()
+///         .set_width(200.0)
+///         .set_height(200.0)
+///         .on_scroll()
+///         .add_widget(Canvas::new().set_width(800.0).set_height(800.0).set_grid(64, 0.0, BLACK)),
+/// );
+/// let target = dom.widget(id).unwrap();
+/// dom.add_widget(Minimap::new(&target).set_x(220.0).set_viewport_color(RED));
+/// ```
+pub struct Minimap {
+    pub base: RefCell<BaseWidget>,
+    pub actions: RefCell<Vec<Action>>,
+    emitter: Option<Arc<dyn Thread>>,
+    trigger: RefCell<Option<Rc<Trigger>>>,
+    target: Weak<dyn WidgetI>,
+    viewport_color: Color,
+    /// The scale the minimap was drawn at last
+    shown: Cell<Option<Scale>>,
+}
+impl Minimap {
+    /// Creates a minimap of `target` 160px wide and 120px high
+    ///
+    /// The minimap does not keep the target alive
+    pub fn new(target: &Rc<dyn WidgetI>) -> Self {
+        Self {
+            base: RefCell::default(),
+            actions: RefCell::new(vec![Action::Pan(Pan::new())]),
+            emitter: None,
+            trigger: RefCell::default(),
+            target: Rc::downgrade(target),
+            viewport_color: BLUE,
+            shown: Cell::default(),
+        }
+        .set_width(DEFAULT_WIDTH)
+        .set_height(DEFAULT_HEIGHT)
+    }
+    /// Set the color the viewport is outlined in
+    pub fn set_viewport_color(mut self, color: Color) -> Self {
+        self.viewport_color = color;
+        self
+    }
+    /// Returns the widget shown
+    ///
+    /// Returns `None` once the target was dropped
+    pub fn target(&self) -> Option<Rc<dyn WidgetI>> {
+        self.target.upgrade()
+    }
+    /// Returns where the part of the target scrolled into
+    /// view is drawn on screen
+    ///
+    /// Returns `None` once the target was dropped or while
+    /// either has no size
+    pub fn viewport(&self) -> Option<Layout> {
+        self.scale().map(Scale::viewport)
+    }
+    /// Redraws the minimap to show content of the target
+    /// changed since
+    ///
+    /// Scrolling, zooming and resizing the target is
+    /// followed on its own
+    pub fn refresh(&self) {
+        if let Some(trigger) = self.internal_trigger() {
+            trigger.update();
+        }
+    }
+    /// Scrolls the target so the top-left corner of the viewport
+    /// is drawn at `x`, `y` on screen
+    ///
+    /// The target stops at the ends just like when scrolled
+    /// by the user
+    pub(crate) fn pan_to(&self, x: f64, y: f64) {
+        let (Some(scale), Some(target)) = (self.scale(), self.target()) else {
+            return;
+        };
+        if let Some(container) = target.downcast::<Container>() {
+            container.scroll_to(
+                (x - scale.area.x) / scale.factor + scale.origin.x - scale.start.x,
+                (y - scale.area.y) / scale.factor + scale.origin.y - scale.start.y,
+            );
+        }
+        self.refresh();
+    }
+    /// Returns how the content of the target is mapped onto
+    /// the minimap
+    fn scale(&self) -> Option<Scale> {
+        let target = self.target()?;
+        let view = target.base().layout;
+        // Content is measured as laid out before scrolling
+        let content = target
+            .children()
+            .iter()
+            .fold(view, |content, child| content.union(&child.base().layout));
+        let visible = match target.downcast::<Container>() {
+            Some(container) if container.scrollbar.is_some() => {
+                let offset = container.scroll_offset();
+                let clip = target.children_clip().unwrap_or(view);
+                Layout {
+                    x: view.x - content.x + offset.x,
+                    y: view.y - content.y + offset.y,
+                    w: clip.w.min(content.w),
+                    h: clip.h.min(content.h),
+                }
+            }
+            _ => Layout {
+                w: content.w,
+                h: content.h,
+                ..Layout::default()
+            },
+        };
+
+        let rect = {
+            let base = self.base();
+            Layout {
+                x: base.offset.x + base.layout.x,
+                y: base.offset.y + base.layout.y,
+                ..base.layout
+            }
+        };
+        if content.is_empty() || rect.is_empty() {
+            return None;
+        }
+        // The content is fit into the minimap and centered
+        let factor = (rect.w / content.w).min(rect.h / content.h);
+        let (w, h) = (content.w * factor, content.h * factor);
+        Some(Scale {
+            origin: Point::new(content.x, content.y),
+            start: Point::new(view.x, view.y),
+            factor,
+            area: Layout {
+                x: rect.x + (rect.w - w) / 2.0,
+                y: rect.y + (rect.h - h) / 2.0,
+                w,
+                h,
+            },
+            visible,
+        })
+    }
+}
+impl_widget! {Minimap}
+impl WidgetHooks for Minimap {
+    fn needs_layout(&self) -> bool {
+        // The target moved on since the minimap was drawn
+        self.shown.get() != self.scale()
+    }
+    fn custom_layout(&self) {
+        if self.needs_layout() {
+            self.shown.set(self.scale());
+            self.refresh();
+        }
+    }
+    fn custom_draw(&self, painter: &mut dyn Painter) {
+        let (Some(scale), Some(target)) = (self.scale(), self.target()) else {
+            return;
+        };
+        let color = target.base().style.color.color();
+        if color != TRANSPARENT {
+            painter.fill_rect(scale.area, color);
+        }
+        for child in target.children() {
+            draw_preview(painter, &child, scale);
+        }
+
+        let viewport = scale.viewport();
+        let (r, g, b): (u8, u8, u8) = self.viewport_color.into();
+        painter.fill_rect(viewport, Color::RGBA(r, g, b, VIEWPORT_ALPHA));
+        let width = VIEWPORT_WIDTH.min(viewport.w / 2.0).min(viewport.h / 2.0);
+        for edge in [
+            Layout {
+                h: width,
+                ..viewport
+            },
+            Layout {
+                y: viewport.y + viewport.h - width,
+                h: width,
+                ..viewport
+            },
+            Layout {
+                y: viewport.y + width,
+                w: width,
+                h: viewport.h - width * 2.0,
+                ..viewport
+            },
+            Layout {
+                x: viewport.x + viewport.w - width,
+                y: viewport.y + width,
+                w: width,
+                h: viewport.h - width * 2.0,
+            },
+        ] {
+            painter.fill_rect(edge, self.viewport_color);
+        }
+    }
+    fn default_role(&self) -> Role {
+        Role::Graphic
+    }
+}
+
+/// Draws `widget` and its children as boxes in their color
+/// scaled onto the minimap
+///
+/// Canvases are drawn by their pixels instead of their cells
+fn draw_preview(painter: &mut dyn Painter, widget: &Rc<dyn WidgetI>, scale: Scale) {
+    let (layout, color) = {
+        let base = widget.base();
+        (base.layout, base.style.color.color())
+    };
+    let color = widget
+        .downcast::<Shape>()
+        .map_or(color, |shape| shape.fill_color);
+    let rect = scale.to_minimap(layout);
+    if color != TRANSPARENT {
+        painter.fill_rect(rect, color);
+    }
+    if let Some(buffer) = widget.downcast::<Canvas>().and_then(Canvas::buffer) {
+        draw_pixels(painter, &buffer, rect);
+        return;
+    }
+    for child in widget.children() {
+        draw_preview(painter, &child, scale);
+    }
+}
+
+/// Draws the pixels of `buffer` stretched over `rect`
+///
+/// Cells smaller than a pixel are sampled so no more rects
+/// are filled than `rect` spans pixels
+fn draw_pixels(painter: &mut dyn Painter, buffer: &CanvasBuffer, rect: Layout) {
+    let cols = buffer.width().min(rect.w.ceil().max(1.0) as usize);
+    let rows = buffer.height().min(rect.h.ceil().max(1.0) as usize);
+    let (w, h) = (rect.w / cols as f64, rect.h / rows as f64);
+    for row in 0..rows {
+        for col in 0..cols {
+            let (x, y) = (col * buffer.width() / cols, row * buffer.height() / rows);
+            match buffer.get_pixel(x, y) {
+                Some(color) if color != TRANSPARENT => painter.fill_rect(
+                    Layout {
+                        x: rect.x + col as f64 * w,
+                        y: rect.y + row as f64 * h,
+                        w,
+                        h,
+                    },
+                    color,
+                ),
+                _ => (),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::ui::{
+        color::{BLUE, GREEN, RED, TRANSPARENT},
+        dom::DOM,
+        layout::Point,
+        widget::{canvas::Canvas, container::Container, Widget, WidgetI},
+    };
+
+    use super::Minimap;
+
+    fn target() -> Container {
+        let canvas = Canvas::new()
+            .set_width(400.0)
+            .set_height(400.0)
+            .set_grid(4, 0.0, TRANSPARENT);
+        canvas.paint(|buffer| {
+            buffer.set_pixel(3, 0, RED);
+            buffer.set_pixel(3, 3, BLUE);
+        });
+        let mut container = Container::new()
+            .set_width(100.0)
+            .set_height(100.0)
+            .on_scroll();
+        container.add_widget(canvas);
+        container
+    }
+
+    #[test]
+    fn content_is_scaled_down_under_the_viewport() {
+        let target: Rc<dyn WidgetI> = Rc::new(target());
+        let minimap: Rc<dyn WidgetI> = Rc::new(
+            Minimap::new(&target)
+                .set_x(150.0)
+                .set_width(100.0)
+                .set_height(100.0)
+                .set_viewport_color(GREEN),
+        );
+        let widgets = [target, minimap.clone()];
+        let frame = DOM::render_once_to_buffer(&widgets, 250, 100).unwrap();
+
+        // The canvas is drawn a quarter of its size
+        assert_eq!(frame.pixel(240, 10), Some(RED));
+        assert_eq!(frame.pixel(240, 90), Some(BLUE));
+        assert_eq!(frame.pixel(200, 60), Some(TRANSPARENT));
+        // The viewport covers the top-left corner
+        let viewport = minimap.downcast::<Minimap>().unwrap().viewport().unwrap();
+        assert_eq!((viewport.x, viewport.y), (150.0, 0.0));
+        assert_eq!(frame.pixel(150, 10), Some(GREEN));
+        assert_ne!(frame.pixel(160, 10), Some(TRANSPARENT));
+        assert_eq!(frame.pixel(180, 10), Some(TRANSPARENT));
+    }
+
+    #[test]
+    fn dragging_the_viewport_scrolls_the_target() {
+        let mut dom = DOM::headless(250, 100);
+        let id = dom.add_widget(target());
+        let target = dom.widget(id).unwrap();
+        let id = dom.add_widget(
+            Minimap::new(&target)
+                .set_x(150.0)
+                .set_width(100.0)
+                .set_height(100.0),
+        );
+        let widget = dom.widget(id).unwrap();
+        let minimap = widget.downcast::<Minimap>().unwrap();
+        let container = target.downcast::<Container>().unwrap();
+
+        dom.drag(Point::new(155.0, 5.0), Point::new(165.0, 25.0));
+        assert_eq!(container.scroll_offset(), Point::new(40.0, 80.0));
+        let viewport = minimap.viewport().unwrap();
+        assert_eq!((viewport.x, viewport.y), (160.0, 20.0));
+
+        // Pressing elsewhere centers the viewport there
+        dom.click(200.0, 50.0);
+        let viewport = minimap.viewport().unwrap();
+        assert_eq!(viewport.x + viewport.w / 2.0, 200.0);
+        assert_eq!(viewport.y + viewport.h / 2.0, 50.0);
+    }
+}
@@ -16,28 +16,55 @@
 use std::{
     any::Any,
     cell::{Ref, RefMut},
-    rc::Rc,
+    future::Future,
+    rc::{Rc, Weak},
     sync::Arc,
 };
 
-use crate::action::Action;
+use winit::window::CursorIcon;
+
+use crate::{
+    action::{
+        click::{Click, MouseButton},
+        hover::HoverHandler,
+        Action, ActionKind,
+    },
+    render::Painter,
+};
 
 use super::{
-    color::{Color, ColorState},
-    layout::{Layout, Point},
+    animate::Animation,
+    arena::WidgetId,
+    color::{themed, Color, ColorState, Fill},
+    layout::{Align, Arranged, Dimension, Dock, Flex, HitShape, Layout, Point, Position, Size},
+    runtime::AsyncEmitter,
+    semantics::{Role, Semantics},
     state::State,
-    style::Style,
+    style::{Border, CornerShape, Radius, Shadow, Style, Styled},
     sync::{Thread, Trigger},
-    text::Text,
+    text::{RichText, Text, TextOverflow},
+    theme::ThemeColor,
 };
 
 pub mod button;
 pub mod canvas;
 pub(crate) mod cell;
+pub mod checkbox;
 pub mod container;
 pub mod heading;
+pub mod icon;
 pub mod label;
+pub mod list;
+pub mod minimap;
+pub mod plot;
+pub mod progress;
 pub mod scrollbar;
+pub mod separator;
+pub mod shape;
+pub mod spacer;
+pub mod tabs;
+pub mod title_bar;
+pub mod tree;
 
 /// A base struct representing a generic UI widget.
 ///
@@ -59,17 +86,136 @@ pub mod scrollbar;
 ///   respond to, such as clicks, hover events, or other interactions.
 /// - `state`: A variety of transient visual states the widget is
 ///   currently in
-#[derive(Default, Debug, Clone, PartialEq, PartialOrd)]
+/// - `semantics`: How the widget is described to assistive technologies
+/// - `focusable`: Whether the widget can receive keyboard focus
+/// - `size`: The requested width and height which may be relative
+///   to the parent
+/// - `z_index`: The layer the widget is drawn on among its siblings
+/// - `drop_target`: Whether dragged widgets can be dropped onto it
+/// - `styled`: Which style fields the `Theme` must leave alone
+/// - `cursor_icon`: The cursor shown while hovering the widget
+/// - `bubbles`: Whether pointer events hitting it reach its parent too
+/// - `flex`: How the widget shares leftover space with its siblings
+/// - `dock`: The edge the widget is pinned to in a `Dock` layout
+/// - `position`: How the widget is placed inside its container
+/// - `origin`: The position set by the user which `Relative` widgets
+///   are moved by
+/// - `align_self`: How the widget is placed across a `Row` or `Col`
+///   layout in place of the alignment of the container
+/// - `hit_shape`: The part of the widget the pointer hits
+#[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BaseWidget {
     pub id: String,
     pub text: Text,
     pub style: Style,
     pub layout: Layout,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub offset: Point,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub state: State,
+    pub semantics: Semantics,
+    pub focusable: bool,
+    pub size: Size,
+    pub z_index: i32,
+    pub drop_target: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub styled: Styled,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub cursor_icon: Option<CursorIcon>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bubbles: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub flex: Flex,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub dock: Dock,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub position: Position,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub origin: Point,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub align_self: Option<Align>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hit_shape: HitShape,
+    /// What the widget was last laid out from
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) arranged: Option<Box<Arranged>>,
+}
+impl BaseWidget {
+    /// Translates `point` on screen into the space of the widget
+    /// with its top-left corner as drawn at the origin
+    ///
+    /// Scrolled widgets are translated as far as they moved
+    pub fn to_local(&self, point: Point) -> Point {
+        Point {
+            x: point.x - self.offset.x - self.layout.x,
+            y: point.y - self.offset.y - self.layout.y,
+        }
+    }
 }
 
-pub trait WidgetI: Widget + WidgetInternal {}
+pub trait WidgetI: Widget + WidgetInternal + WidgetHooks {}
+impl dyn WidgetI {
+    /// Returns the semantic role of the widget
+    ///
+    /// Falls back to the widget default role if none was set
+    pub fn role(&self) -> Role {
+        self.base()
+            .semantics
+            .role
+            .unwrap_or_else(|| self.default_role())
+    }
+    /// Returns the cursor shown while hovering the widget
+    ///
+    /// Falls back to the cursor fitting the widget role if none
+    /// was set. Returns `None` if the widget has no preference
+    pub fn cursor_icon(&self) -> Option<CursorIcon> {
+        self.base().cursor_icon.or_else(|| match self.role() {
+            Role::Button | Role::CheckBox | Role::Tab | Role::TreeItem => {
+                Some(CursorIcon::Pointer)
+            }
+            Role::ScrollBar => Some(CursorIcon::Grab),
+            _ => None,
+        })
+    }
+    /// Returns whether the widget can receive keyboard focus
+    /// right now
+    ///
+    /// Disabled widgets are skipped until enabled again
+    pub fn is_focusable(&self) -> bool {
+        let base = self.base();
+        base.focusable && !base.state.disabled
+    }
+    /// Returns the name describing the widget
+    ///
+    /// Falls back to the text label if none was set
+    pub fn accessible_name(&self) -> String {
+        let base = self.base();
+        base.semantics
+            .name
+            .clone()
+            .unwrap_or_else(|| base.text.label.clone())
+    }
+    /// Returns the widget as the concrete widget `T`
+    ///
+    /// Looks through to the widget a component is built around
+    /// when the widget itself is of another type. Returns `None`
+    /// if neither is a `T`
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let play = Icon::new(IconName::Play).on_click(|widget, _| {
+    ///     if let Some(icon) = widget.downcast::<Icon>() {
+    ///         icon.set_icon(IconName::Pause);
+    ///     }
+    /// });
+    /// ```
+    pub fn downcast<T: 'static>(&self) -> Option<&T> {
+        self.as_any()
+            .downcast_ref::<T>()
+            .or_else(|| self.inner()?.downcast::<T>())
+    }
+}
 
 /// A trait representing special
 /// internal methods known only to
@@ -80,6 +226,8 @@ pub(crate) trait WidgetInternal {
     fn internal_trigger(&self) -> Option<Rc<Trigger>>;
     /// Returns a mutable internal trigger for widget
     fn internal_trigger_mut(&self) -> RefMut<'_, Option<Rc<Trigger>>>;
+    /// Returns the name of the widget type
+    fn type_name(&self) -> &'static str;
 }
 /// A trait representing a basic UI component.
 ///
@@ -104,6 +252,12 @@ pub trait Widget: Any + WidgetInternal {
     /// widget. The thread is a user custom thread manager
     /// that may exist
     fn emitter(&self) -> Option<&Arc<dyn Thread>>;
+    /// Starts building an animation of the widget
+    ///
+    /// The widget must already be part of a `DOM`
+    fn animate(&self) -> Animation {
+        self.trigger().animate()
+    }
     /// Returns a trigger to aid for this widget redraws
     fn trigger(&self) -> Rc<Trigger> {
         self.internal_trigger()
@@ -114,7 +268,40 @@ pub trait Widget: Any + WidgetInternal {
     where
         Self: Sized,
     {
-        self.base_mut().text.label = label.into();
+        let mut base = self.base_mut();
+        base.text.label = label.into();
+        base.text.spans.clear();
+        drop(base);
+        self
+    }
+    /// Set the inside text made of differently styled spans
+    ///
+    /// The label holds the unstyled text so it is still
+    /// available to assistive technologies
+    fn set_rich_label(self, text: RichText) -> Self
+    where
+        Self: Sized,
+    {
+        let mut base = self.base_mut();
+        base.text.label = text.plain();
+        base.text.spans = text.into_spans();
+        drop(base);
+        self
+    }
+    /// Set the color of the inside text
+    fn set_label_color(self, color: Color) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().text.color = color;
+        self
+    }
+    /// Set how inside text too wide for the widget is shown
+    fn set_text_overflow(self, overflow: TextOverflow) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().text.overflow = overflow;
         self
     }
     /// Set the font size of the inside text
@@ -122,7 +309,10 @@ pub trait Widget: Any + WidgetInternal {
     where
         Self: Sized,
     {
-        self.base_mut().text.font_size = size;
+        let mut base = self.base_mut();
+        base.text.font_size = size;
+        base.styled.font_size = true;
+        drop(base);
         self
     }
     /// Set a unique id for widget
@@ -138,7 +328,10 @@ pub trait Widget: Any + WidgetInternal {
     where
         Self: Sized,
     {
-        self.base_mut().layout.x = x;
+        let mut base = self.base_mut();
+        base.layout.x = x;
+        base.origin.x = x;
+        drop(base);
         self
     }
     /// Set the y-axis position of the widget
@@ -146,31 +339,191 @@ pub trait Widget: Any + WidgetInternal {
     where
         Self: Sized,
     {
-        self.base_mut().layout.y = y;
+        let mut base = self.base_mut();
+        base.layout.y = y;
+        base.origin.y = y;
+        drop(base);
         self
     }
     /// Set the height dimension of the widget
-    fn set_height(self, height: f64) -> Self
+    ///
+    /// Accepts pixels directly or any `Dimension`
+    fn set_height(self, height: impl Into<Dimension>) -> Self
     where
         Self: Sized,
     {
-        self.base_mut().layout.h = height;
+        let height = height.into();
+        {
+            let mut base = self.base_mut();
+            if let Dimension::Px(px) = height {
+                base.layout.h = px;
+            }
+            base.size.height = height;
+        }
         self
     }
     /// Set the width dimension of the widget
-    fn set_width(self, width: f64) -> Self
+    ///
+    /// Accepts pixels directly or any `Dimension`
+    fn set_width(self, width: impl Into<Dimension>) -> Self
     where
         Self: Sized,
     {
-        self.base_mut().layout.w = width;
+        let width = width.into();
+        {
+            let mut base = self.base_mut();
+            if let Dimension::Px(px) = width {
+                base.layout.w = px;
+            }
+            base.size.width = width;
+        }
         self
     }
     /// Set the corner radius of the widget
-    fn set_radius(self, radius: u32) -> Self
+    ///
+    /// Accepts a radius for all corners or a `Radius`
+    /// for every corner on its own
+    fn set_radius(self, radius: impl Into<Radius>) -> Self
+    where
+        Self: Sized,
+    {
+        let mut base = self.base_mut();
+        base.style.radius = radius.into();
+        base.styled.radius = true;
+        drop(base);
+        self
+    }
+    /// Set the curve the rounded corners of the widget
+    /// are drawn along
+    fn set_corner_shape(self, shape: CornerShape) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().style.corner_shape = shape;
+        self
+    }
+    /// Set the part of the widget that is clicked and
+    /// hovered by the pointer
+    ///
+    /// Defaults to the bounds with rounded corners cut off
+    fn set_hit_shape(self, shape: HitShape) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().hit_shape = shape;
+        self
+    }
+    /// Set the layer the widget is drawn on among its siblings
+    ///
+    /// Widgets with a higher `z_index` are drawn on top and
+    /// receive clicks first. Siblings sharing a `z_index` keep
+    /// the order they were added in.
+    fn set_z_index(self, z_index: i32) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().z_index = z_index;
+        self
+    }
+    /// Set the share of the space left over in a `Row` or `Col`
+    /// layout the widget grows by compared to its siblings
+    ///
+    /// Defaults to 0 keeping the size set
+    fn set_flex_grow(self, grow: f64) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().flex.grow = grow.max(0.0);
+        self
+    }
+    /// Set the share of the space missing in a `Row` or `Col`
+    /// layout the widget shrinks by compared to its siblings
+    ///
+    /// Larger widgets give up more space for the same factor.
+    /// Defaults to 0 keeping the size set
+    fn set_flex_shrink(self, shrink: f64) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().flex.shrink = shrink.max(0.0);
+        self
+    }
+    /// Set how the widget is placed inside its container
+    ///
+    /// ## Example
+    /// ```ignore
+    /// // A badge nudged off the corner of the flow it sits in
+    /// let badge = Label::new()
+    ///     .set_label("3")
+    ///     .set_x(-4.0)
+    ///     .set_y(-4.0)
+    ///     .set_position(Position::Relative);
+    /// ```
+    fn set_position(self, position: Position) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().position = position;
+        self
+    }
+    /// Set how the widget is placed across a `Row` or `Col`
+    /// layout overriding the alignment of its container
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let mut toolbar = Container::new().set_flex_layout(FlexLayout::Row);
+    /// toolbar.add_widget(Button::new().set_label("Open"));
+    /// toolbar.add_widget(Label::new().set_label("Saved").set_align_self(Align::End));
+    /// ```
+    fn set_align_self(self, align: Align) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().align_self = Some(align);
+        self
+    }
+    /// Set the edge of a `Dock` layout the widget is pinned to
+    fn set_dock(self, dock: Dock) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().dock = dock;
+        self
+    }
+    /// Set the cursor shown while hovering the widget
+    fn set_cursor_icon(self, icon: CursorIcon) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().cursor_icon = Some(icon);
+        self
+    }
+    /// Let pointer events hitting the widget reach its
+    /// parent as well
+    ///
+    /// By default only the topmost widget under the cursor
+    /// handles clicks and hovers
+    fn set_bubbles(self) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().bubbles = true;
+        self
+    }
+    /// Allow dragged widgets to be dropped onto the widget
+    fn set_drop_target(self) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().drop_target = true;
+        self
+    }
+    /// Set the border stroked along the edges of the widget
+    fn set_border(self, border: Border) -> Self
     where
         Self: Sized,
     {
-        self.base_mut().style.radius = radius;
+        self.base_mut().style.border = Some(border);
         self
     }
     /// Set the background color of the widget
@@ -178,7 +531,22 @@ pub trait Widget: Any + WidgetInternal {
     where
         Self: Sized,
     {
-        self.base_mut().style.color = ColorState::new(color);
+        let mut base = self.base_mut();
+        base.style.color = ColorState::new(color);
+        base.styled.color = true;
+        drop(base);
+        self
+    }
+    /// Set the background color of the widget to follow
+    /// `key` of the active theme
+    fn set_theme_color(self, key: ThemeColor) -> Self
+    where
+        Self: Sized,
+    {
+        let mut base = self.base_mut();
+        base.style.color = themed(key);
+        base.styled.color = true;
+        drop(base);
         self
     }
     /// Align text in center vertically
@@ -197,6 +565,84 @@ pub trait Widget: Any + WidgetInternal {
         self.base_mut().text.halign = true;
         self
     }
+    /// Set the semantic role of the widget
+    fn set_role(self, role: Role) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().semantics.role = Some(role);
+        self
+    }
+    /// Set the name describing the widget to assistive technologies
+    fn set_accessible_name(self, name: &str) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().semantics.name = Some(name.into());
+        self
+    }
+    /// Set extra information describing the widget to
+    /// assistive technologies
+    fn set_accessible_description(self, description: &str) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().semantics.description = Some(description.into());
+        self
+    }
+    /// Allow the widget to receive keyboard focus
+    /// through clicks and Tab traversal
+    fn set_focusable(self) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().focusable = true;
+        self
+    }
+    /// Set whether the widget is disabled
+    ///
+    /// Disabled widgets ignore clicks and hovering, can not be
+    /// focused and are drawn dimmed
+    fn set_disabled(self, disabled: bool) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().state.disabled = disabled;
+        self
+    }
+    /// Shade the widget with a gradient in place of its color
+    fn set_fill(self, fill: Fill) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().style.fill = Some(fill);
+        self
+    }
+    /// Cast a blurred `shadow` beneath the widget
+    fn set_shadow(self, shadow: Shadow) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().style.shadow = Some(shadow);
+        self
+    }
+    /// Set how opaque the widget and its children are drawn
+    /// from `0.0` (invisible) to `1.0` (solid)
+    fn set_opacity(self, opacity: f32) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().style.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+    /// Set the color laid over the widget while it is disabled
+    fn set_disabled_overlay(self, color: Color) -> Self
+    where
+        Self: Sized,
+    {
+        self.base_mut().style.disabled_overlay = Some(color);
+        self
+    }
     /// Sets a trigger action for the widget
     ///
     /// See `Action` enum for the types of actions avaliable
@@ -207,12 +653,227 @@ pub trait Widget: Any + WidgetInternal {
         self.action_mut().push(action);
         self
     }
+    /// Sets a trigger action for the widget labeled with `tag`
+    ///
+    /// Tagged actions can be removed or swapped out at runtime
+    /// all at once, such as the actions of a tool mode
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let canvas = Canvas::new().on_action_tagged("tool", paint);
+    /// // Later on, once the user picks another tool
+    /// canvas.replace_action_tagged("tool", pan);
+    /// ```
+    fn on_action_tagged(self, tag: &str, action: Action) -> Self
+    where
+        Self: Sized,
+    {
+        self.on_action(Action::Tagged(tag.to_string(), Box::new(action)))
+    }
+    /// Removes every action of the `kind` given, tagged or not
+    ///
+    /// The actions a widget is built with count as well
+    ///
+    /// Actions cannot be removed by handlers of the same widget
+    /// while they run
+    fn remove_action(&self, kind: ActionKind) {
+        self.action_mut().retain(|action| action.kind() != kind);
+    }
+    /// Removes every action labeled with `tag`
+    fn remove_actions_tagged(&self, tag: &str) {
+        self.action_mut().retain(|action| action.tag() != Some(tag));
+    }
+    /// Replaces every action labeled with `tag` by `action`
+    /// labeled the same
+    fn replace_action_tagged(&self, tag: &str, action: Action) {
+        self.remove_actions_tagged(tag);
+        self.action_mut()
+            .push(Action::Tagged(tag.to_string(), Box::new(action)));
+    }
+    /// Removes every action of the widget, the actions it
+    /// was built with included
+    fn clear_actions(&self) {
+        self.action_mut().clear();
+    }
+    /// Calls `callback` every time the widget is clicked
+    /// with the left mouse button
+    ///
+    /// A shorthand for a `Click` action holding no state
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let button = Button::new().on_click(|widget, trigger| {
+    ///     widget.base_mut().text.label = "Clicked".into();
+    ///     trigger.update();
+    /// });
+    /// ```
+    fn on_click<F: Fn(&Rc<dyn WidgetI>, Rc<Trigger>) + 'static>(self, callback: F) -> Self
+    where
+        Self: Sized,
+    {
+        let callback = Rc::new(callback);
+        let click = Click::new(()).on(
+            MouseButton::LeftButtonRelease,
+            move |_, trigger, widget, _, _| callback(widget, trigger),
+        );
+        self.on_action(Action::Click(Box::new(click)))
+    }
+    /// Calls `callback` every time the cursor enters the widget
+    fn on_hover_enter<F: Fn(&Rc<dyn WidgetI>, Rc<Trigger>) + 'static>(self, callback: F) -> Self
+    where
+        Self: Sized,
+    {
+        let callback = Rc::new(callback);
+        let hover = HoverHandler::new(()).on_enter(move |_, trigger, widget, _, _| {
+            callback(widget, trigger)
+        });
+        self.on_action(Action::HoverChange(Box::new(hover)))
+    }
+    /// Calls `callback` every time the cursor leaves the widget
+    fn on_hover_exit<F: Fn(&Rc<dyn WidgetI>, Rc<Trigger>) + 'static>(self, callback: F) -> Self
+    where
+        Self: Sized,
+    {
+        let callback = Rc::new(callback);
+        let hover = HoverHandler::new(()).on_leave(move |_, trigger, widget, _, _| {
+            callback(widget, trigger)
+        });
+        self.on_action(Action::HoverChange(Box::new(hover)))
+    }
     /// Allows an emitter to be attached to this widget instance
     /// for triggering updates to the widget when signals come
     /// through UI event proxy
     fn connect<T: Thread + 'static>(self, emitter: T) -> Self
     where
         Self: Sized;
+    /// Allows an async emitter to be attached to this widget
+    /// instance whose future runs on the shared runtime in
+    /// place of a thread of its own
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let label = Label::new().connect_async(|trigger| async move {
+    ///     let status = fetch_status().await;
+    ///     trigger.update_callback(move |widget| {
+    ///         widget.base_mut().text.label = status.clone();
+    ///     });
+    /// });
+    /// ```
+    fn connect_async<F, Fut>(self, task: F) -> Self
+    where
+        Self: Sized,
+        F: Fn(Trigger) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.connect(AsyncEmitter::new(task))
+    }
+}
+
+/// A trait representing the hooks a widget can implement to take part
+/// in tree traversal, layout and drawing.
+///
+/// The DOM, pre-renderer and renderer only walk the tree through these
+/// hooks, so any widget nesting other widgets or needing custom drawing
+/// participates the same way `Container` and `Canvas` do. Every hook has
+/// a default so simple widgets only need an empty impl.
+pub trait WidgetHooks {
+    /// Returns the child widgets nested under this widget
+    fn children(&self) -> Vec<Rc<dyn WidgetI>> {
+        Vec::new()
+    }
+    /// Returns the child widgets kept aside while not shown
+    ///
+    /// They stay added to the `DOM` but are neither laid out,
+    /// drawn nor hit until returned by `children` again
+    fn hidden_children(&self) -> Vec<Rc<dyn WidgetI>> {
+        Vec::new()
+    }
+    /// Returns the child widgets dropped since last asked
+    ///
+    /// Widgets building their children while laid out hand the
+    /// ones let go of back so the `DOM` can let go of them too
+    fn removed_children(&self) -> Vec<Rc<dyn WidgetI>> {
+        Vec::new()
+    }
+    /// Called once the widget was added to a `DOM` handing it
+    /// a reference to itself
+    ///
+    /// Widgets recording commands that must reach them later
+    /// on may hold onto it
+    fn mounted(&self, _this: &Weak<dyn WidgetI>) {}
+    /// Lays out the widget and positions its children
+    ///
+    /// Called after the text layout of the whole subtree is resolved
+    /// and before the children lay themselves out
+    fn custom_layout(&self) {}
+    /// Draws extra content above the widget background but
+    /// beneath its text and children
+    fn custom_draw(&self, _painter: &mut dyn Painter) {}
+    /// Draws extra content above the widget children
+    fn custom_draw_over(&self, _painter: &mut dyn Painter) {}
+    /// Returns the region the children must be clipped to
+    ///
+    /// When `None` children inherit the clipping region of this widget
+    /// or else they are also cut off along its rounded corners
+    fn children_clip(&self) -> Option<Layout> {
+        None
+    }
+    /// Returns whether content drawn over the children covers
+    /// the position `x`, `y` so the widget is hit instead of them
+    fn covers_children(&self, _x: f64, _y: f64) -> bool {
+        false
+    }
+    /// Returns the semantic role used when none was set explicitly
+    fn default_role(&self) -> Role {
+        Role::Generic
+    }
+    /// Returns the checked state of widgets that can be toggled
+    fn checked(&self) -> Option<bool> {
+        None
+    }
+    /// Returns whether the widget must be laid out again even
+    /// though none of its layout properties changed
+    ///
+    /// Widgets arranging their children from state of their
+    /// own return `true` once that state changes
+    fn needs_layout(&self) -> bool {
+        false
+    }
+    /// Returns how far along widgets showing progress are
+    /// from `0.0` to `1.0`
+    fn value(&self) -> Option<f32> {
+        None
+    }
+    /// Returns the widget this one is built around
+    ///
+    /// `downcast` falls back to it for widgets wrapping
+    /// another one like components do
+    fn inner(&self) -> Option<&(dyn WidgetI + 'static)> {
+        None
+    }
+}
+
+/// Returns the handle of `widget` if it was added to the `DOM`
+pub(crate) fn widget_id(widget: &Rc<dyn WidgetI>) -> Option<WidgetId> {
+    widget.internal_trigger().map(|trigger| trigger.id())
+}
+
+/// Returns the rect `widget` covers on the screen
+pub(crate) fn screen_rect(widget: &dyn WidgetI) -> Layout {
+    let base = widget.base();
+    Layout {
+        x: base.offset.x + base.layout.x,
+        y: base.offset.y + base.layout.y,
+        ..base.layout
+    }
+}
+
+/// Orders `widgets` bottom to top by their `z_index`
+pub(crate) fn z_ordered(widgets: &[Rc<dyn WidgetI>]) -> Vec<Rc<dyn WidgetI>> {
+    let mut widgets = widgets.to_vec();
+    // Stable so equal layers keep insertion order
+    widgets.sort_by_key(|widget| widget.base().z_index);
+    widgets
 }
 
 /// Implements the [`Widget`] trait for a struct with common UI fields.
@@ -252,6 +913,9 @@ macro_rules! impl_widget {
             fn internal_trigger_mut(&self) -> RefMut<'_, Option<Rc<Trigger>>> {
                 self.trigger.borrow_mut()
             }
+            fn type_name(&self) -> &'static str {
+                stringify!($type)
+            }
         }
         impl WidgetI for $type {}
     };
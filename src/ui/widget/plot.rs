@@ -0,0 +1,482 @@
+use std::{
+    any::Any,
+    cell::{Cell, Ref, RefCell, RefMut},
+    rc::Rc,
+    sync::Arc,
+};
+
+use tiny_skia::PathBuilder;
+
+use crate::{
+    action::{plot_hover::PlotHover, Action},
+    render::Painter,
+    ui::{
+        color::{Color, BLACK, WHITE},
+        layout::{Layout, Point},
+        semantics::Role,
+        sync::{Thread, Trigger},
+    },
+};
+
+use super::{impl_widget, label::Label, BaseWidget, Widget, WidgetHooks, WidgetI, WidgetInternal};
+
+/// Room left of the data for the labels of the y-axis
+const MARGIN_LEFT: f64 = 40.0;
+/// Room below the data for the labels of the x-axis
+const MARGIN_BOTTOM: f64 = 20.0;
+/// Room above and right of the data so the last
+/// points are not cut off
+const MARGIN: f64 = 8.0;
+const AXIS: Color = Color::RGBA(120, 120, 120, 255);
+const LABEL_SIZE: f32 = 10.0;
+const LABEL_HEIGHT: f64 = 14.0;
+const POINT_RADIUS: f32 = 3.0;
+const LINE_WIDTH: f64 = 2.0;
+/// How much of the room between two bars a bar takes up
+const BAR_FILL: f64 = 0.8;
+/// How far from a point the cursor may be for its tooltip to show
+pub(crate) const HOVER_DISTANCE: f64 = 8.0;
+const TOOLTIP: Color = Color::RGBA(0, 0, 0, 200);
+const TOOLTIP_PADDING: f64 = 4.0;
+
+/// The `SeriesKind` enum lists the ways a `Series` is drawn
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SeriesKind {
+    /// A line through the points in order
+    #[default]
+    Line,
+    /// A bar from zero up to every point
+    Bars,
+    /// A dot at every point
+    Points,
+}
+
+/// The `Series` struct holds data points drawn
+/// by a `Plot` in one color
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    pub kind: SeriesKind,
+    pub data: Vec<(f64, f64)>,
+    pub color: Color,
+}
+impl Series {
+    /// Creates a series drawn as a line through `data`
+    pub fn line(data: Vec<(f64, f64)>, color: Color) -> Self {
+        Self {
+            kind: SeriesKind::Line,
+            data,
+            color,
+        }
+    }
+    /// Creates a series drawn as bars up to `data`
+    pub fn bars(data: Vec<(f64, f64)>, color: Color) -> Self {
+        Self {
+            kind: SeriesKind::Bars,
+            data,
+            color,
+        }
+    }
+    /// Creates a series drawn as dots at `data`
+    pub fn points(data: Vec<(f64, f64)>, color: Color) -> Self {
+        Self {
+            kind: SeriesKind::Points,
+            data,
+            color,
+        }
+    }
+    /// Returns the smallest distance between two neighbouring
+    /// bars or `1.0` when there are not two of them
+    fn bar_step(&self) -> f64 {
+        let mut xs: Vec<_> = self.data.iter().map(|(x, _)| *x).collect();
+        xs.sort_by(f64::total_cmp);
+        xs.windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .filter(|step| *step > 0.0)
+            .fold(None, |min: Option<f64>, step| {
+                Some(min.map_or(step, |min| min.min(step)))
+            })
+            .unwrap_or(1.0)
+    }
+}
+
+/// The `Scale` struct maps data onto the region of
+/// the plot it is drawn in
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Scale {
+    x: (f64, f64),
+    y: (f64, f64),
+    area: Layout,
+}
+impl Scale {
+    fn to_screen(self, x: f64, y: f64) -> Point {
+        Point::new(
+            self.area.x + (x - self.x.0) / (self.x.1 - self.x.0) * self.area.w,
+            self.area.y + self.area.h - (y - self.y.0) / (self.y.1 - self.y.0) * self.area.h,
+        )
+    }
+    /// Returns how many pixels a length of `dx` along
+    /// the x-axis spans
+    fn width(self, dx: f64) -> f64 {
+        dx / (self.x.1 - self.x.0) * self.area.w
+    }
+}
+
+/// A struct representing a plot widget.
+///
+/// The `Plot` struct charts any number of `Series` as lines, bars
+/// or dots over shared axes. The axes are scaled to fit all the data
+/// and labeled with their lowest and highest values. Hovering a data
+/// point shows its values in a tooltip.
+///
+/// ## Example
+/// ```ignore
+/// let plot = Plot::new()
+///     .set_width(300.0)
+///     .set_height(200.0)
+///     .add_series(Series::bars(vec![(1.0, 4.0), (2.0, 7.0)], LIGHT_GRAY))
+///     .add_series(Series::line(vec![(1.0, 3.0), (2.0, 5.0)], RED));
+///
+/// // Later on
+/// plot.set_data(1, vec![(1.0, 2.0), (2.0, 6.0)]);
+/// ```
+pub struct Plot {
+    pub base: RefCell<BaseWidget>,
+    pub actions: RefCell<Vec<Action>>,
+    emitter: Option<Arc<dyn Thread>>,
+    trigger: RefCell<Option<Rc<Trigger>>>,
+    series: RefCell<Vec<Series>>,
+    /// The series and index of the data point under the cursor
+    hovered: Cell<Option<(usize, usize)>>,
+}
+impl Default for Plot {
+    fn default() -> Self {
+        Self {
+            base: RefCell::default(),
+            actions: RefCell::new(vec![Action::PlotHover(PlotHover::new())]),
+            emitter: None,
+            trigger: RefCell::default(),
+            series: RefCell::default(),
+            hovered: Cell::default(),
+        }
+    }
+}
+impl Plot {
+    pub fn new() -> Self {
+        Plot::default()
+    }
+    /// Adds `series` drawn over the ones added before
+    pub fn add_series(self, series: Series) -> Self {
+        self.series.borrow_mut().push(series);
+        self
+    }
+    /// Returns the series charted
+    pub fn series(&self) -> Ref<'_, Vec<Series>> {
+        self.series.borrow()
+    }
+    /// Replaces the data of the series at `index` and rescales
+    /// the axes to fit
+    ///
+    /// NoOp if there is no series at `index`
+    pub fn set_data(&self, index: usize, data: Vec<(f64, f64)>) {
+        if let Some(series) = self.series.borrow_mut().get_mut(index) {
+            series.data = data;
+        } else {
+            return;
+        }
+        self.hovered.set(None);
+        if let Some(trigger) = self.internal_trigger() {
+            trigger.update();
+        }
+    }
+    /// Returns the data point under the cursor if any
+    pub fn hovered(&self) -> Option<(f64, f64)> {
+        let (series, index) = self.hovered.get()?;
+        self.series.borrow().get(series)?.data.get(index).copied()
+    }
+    /// Returns the ranges the x-axis and y-axis span
+    ///
+    /// Returns `None` when there is no data
+    pub fn range(&self) -> Option<((f64, f64), (f64, f64))> {
+        let series = self.series.borrow();
+        let mut x = (f64::INFINITY, f64::NEG_INFINITY);
+        let mut y = (f64::INFINITY, f64::NEG_INFINITY);
+        for series in series.iter() {
+            // Bars stand on zero and the outer ones take up
+            // half a step beyond their point
+            let pad = match series.kind {
+                SeriesKind::Bars if !series.data.is_empty() => {
+                    y = (y.0.min(0.0), y.1.max(0.0));
+                    series.bar_step() / 2.0
+                }
+                _ => 0.0,
+            };
+            for (px, py) in series
+                .data
+                .iter()
+                .filter(|(x, y)| x.is_finite() && y.is_finite())
+            {
+                x = (x.0.min(px - pad), x.1.max(px + pad));
+                y = (y.0.min(*py), y.1.max(*py));
+            }
+        }
+        if x.0 > x.1 {
+            return None;
+        }
+        Some((widen(x), widen(y)))
+    }
+    /// Returns how the data is mapped onto the plot
+    fn scale(&self) -> Option<Scale> {
+        let (x, y) = self.range()?;
+        let base = self.base();
+        let area = Layout {
+            x: base.offset.x + base.layout.x + MARGIN_LEFT,
+            y: base.offset.y + base.layout.y + MARGIN,
+            w: (base.layout.w - MARGIN_LEFT - MARGIN).max(0.0),
+            h: (base.layout.h - MARGIN_BOTTOM - MARGIN).max(0.0),
+        };
+        Some(Scale { x, y, area })
+    }
+    /// Finds the data point closest to `cursor` no further
+    /// than `HOVER_DISTANCE` away
+    ///
+    /// Returns the index of its series along with its own
+    pub(crate) fn point_at(&self, cursor: Point) -> Option<(usize, usize)> {
+        let scale = self.scale()?;
+        let mut closest = None;
+        let mut distance = HOVER_DISTANCE;
+        for (i, series) in self.series.borrow().iter().enumerate() {
+            for (j, (x, y)) in series.data.iter().enumerate() {
+                let point = scale.to_screen(*x, *y);
+                let d = (point.x - cursor.x).hypot(point.y - cursor.y);
+                if d <= distance {
+                    distance = d;
+                    closest = Some((i, j));
+                }
+            }
+        }
+        closest
+    }
+    /// Shows the tooltip of the point at `hovered` from now on
+    ///
+    /// Returns `true` if another point was hovered before
+    pub(crate) fn hover(&self, hovered: Option<(usize, usize)>) -> bool {
+        self.hovered.replace(hovered) != hovered
+    }
+}
+impl_widget! {Plot}
+impl WidgetHooks for Plot {
+    fn custom_draw(&self, painter: &mut dyn Painter) {
+        let Some(scale) = self.scale() else {
+            return;
+        };
+        let area = scale.area;
+
+        for series in self.series.borrow().iter() {
+            match series.kind {
+                SeriesKind::Line => {
+                    for pair in series.data.windows(2) {
+                        painter.stroke_line(
+                            scale.to_screen(pair[0].0, pair[0].1),
+                            scale.to_screen(pair[1].0, pair[1].1),
+                            LINE_WIDTH,
+                            series.color,
+                        );
+                    }
+                }
+                SeriesKind::Bars => {
+                    let w = scale.width(series.bar_step()) * BAR_FILL;
+                    let zero = scale.to_screen(0.0, 0.0).y;
+                    for (x, y) in &series.data {
+                        let top = scale.to_screen(*x, *y);
+                        painter.fill_rect(
+                            Layout {
+                                x: top.x - w / 2.0,
+                                y: top.y.min(zero),
+                                w,
+                                h: (top.y - zero).abs(),
+                            },
+                            series.color,
+                        );
+                    }
+                }
+                SeriesKind::Points => {
+                    for (x, y) in &series.data {
+                        let point = scale.to_screen(*x, *y);
+                        if let Some(dot) =
+                            PathBuilder::from_circle(point.x as f32, point.y as f32, POINT_RADIUS)
+                        {
+                            painter.draw_path(&dot, Some(series.color), None);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Axes run along the bottom and left of the data
+        painter.fill_rect(
+            Layout {
+                x: area.x,
+                y: area.y + area.h,
+                w: area.w,
+                h: 1.0,
+            },
+            AXIS,
+        );
+        painter.fill_rect(
+            Layout {
+                x: area.x - 1.0,
+                y: area.y,
+                w: 1.0,
+                h: area.h + 1.0,
+            },
+            AXIS,
+        );
+
+        let ticks = [
+            (format_value(scale.x.0), area.x, area.y + area.h + 2.0),
+            (
+                format_value(scale.x.1),
+                area.x + area.w,
+                area.y + area.h + 2.0,
+            ),
+            (format_value(scale.y.0), 0.0, area.y + area.h),
+            (format_value(scale.y.1), 0.0, area.y),
+        ];
+        for (i, (text, x, y)) in ticks.into_iter().enumerate() {
+            let label = axis_label(&text);
+            let width = label.base().text.get_true_dimensions().x;
+            let (x, y) = if i < 2 {
+                (x - width / 2.0, y)
+            } else {
+                (area.x - width - 4.0, y - LABEL_HEIGHT / 2.0)
+            };
+            let label = label
+                .set_x(x)
+                .set_y(y)
+                .set_width(width)
+                .set_height(LABEL_HEIGHT);
+            painter.draw_widget(&label);
+        }
+    }
+    fn custom_draw_over(&self, painter: &mut dyn Painter) {
+        let (Some(scale), Some((x, y))) = (self.scale(), self.hovered()) else {
+            return;
+        };
+        let point = scale.to_screen(x, y);
+        let label = Label::new()
+            .set_label(&format!("{}, {}", format_value(x), format_value(y)))
+            .set_label_color(WHITE)
+            .set_label_size(LABEL_SIZE);
+        let width = label.base().text.get_true_dimensions().x;
+
+        // The tooltip sits above right of the point but is kept
+        // inside the plot
+        let base = self.base();
+        let bounds = Layout {
+            x: base.offset.x + base.layout.x,
+            y: base.offset.y + base.layout.y,
+            ..base.layout
+        };
+        let w = width + TOOLTIP_PADDING * 2.0;
+        let h = LABEL_HEIGHT + TOOLTIP_PADDING * 2.0;
+        let rect = Layout {
+            x: (point.x + HOVER_DISTANCE)
+                .min(bounds.x + bounds.w - w)
+                .max(bounds.x),
+            y: (point.y - HOVER_DISTANCE - h)
+                .max(bounds.y)
+                .min(bounds.y + bounds.h - h),
+            w,
+            h,
+        };
+        painter.fill_rect(rect, TOOLTIP);
+        let label = label
+            .set_x(rect.x + TOOLTIP_PADDING)
+            .set_y(rect.y + TOOLTIP_PADDING)
+            .set_width(width)
+            .set_height(LABEL_HEIGHT);
+        painter.draw_widget(&label);
+    }
+    fn default_role(&self) -> Role {
+        Role::Graphic
+    }
+}
+
+/// Keeps `range` from spanning nothing so
+/// data can be scaled onto it
+fn widen((min, max): (f64, f64)) -> (f64, f64) {
+    if max > min {
+        (min, max)
+    } else {
+        (min - 1.0, max + 1.0)
+    }
+}
+
+/// Formats `value` without trailing digits
+/// for whole numbers
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+fn axis_label(text: &str) -> Label {
+    Label::new()
+        .set_label(text)
+        .set_label_color(BLACK)
+        .set_label_size(LABEL_SIZE)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::{
+        color::{BLUE, RED},
+        dom::DOM,
+        widget::Widget,
+    };
+
+    use super::{Plot, Series};
+
+    #[test]
+    fn axes_are_scaled_to_fit_all_series() {
+        let plot = Plot::new()
+            .add_series(Series::line(vec![(1.0, 3.0), (4.0, 5.0)], RED))
+            .add_series(Series::points(vec![(2.0, -1.0)], BLUE));
+        assert_eq!(plot.range(), Some(((1.0, 4.0), (-1.0, 5.0))));
+
+        // Bars stand on zero with room for the outer ones
+        let plot = Plot::new().add_series(Series::bars(vec![(1.0, 3.0), (2.0, 5.0)], RED));
+        assert_eq!(plot.range(), Some(((0.5, 2.5), (0.0, 5.0))));
+
+        // A single point is still scaled onto the plot
+        let plot = Plot::new().add_series(Series::points(vec![(2.0, 2.0)], RED));
+        assert_eq!(plot.range(), Some(((1.0, 3.0), (1.0, 3.0))));
+        assert_eq!(Plot::new().range(), None);
+    }
+
+    #[test]
+    fn hovered_points_are_shown_until_the_cursor_moves_away() {
+        let mut dom = DOM::headless(200, 100);
+        // The data spans from (40, 80) to (192, 8) on screen
+        let id = dom.add_widget(
+            Plot::new()
+                .set_width(200.0)
+                .set_height(100.0)
+                .add_series(Series::points(vec![(0.0, 0.0), (10.0, 10.0)], RED)),
+        );
+        let widget = dom.widget(id).unwrap();
+        let plot = || widget.as_any().downcast_ref::<Plot>().unwrap();
+
+        dom.move_cursor(190.0, 10.0);
+        assert_eq!(plot().hovered(), Some((10.0, 10.0)));
+        dom.move_cursor(100.0, 50.0);
+        assert_eq!(plot().hovered(), None);
+
+        dom.move_cursor(42.0, 78.0);
+        assert_eq!(plot().hovered(), Some((0.0, 0.0)));
+        plot().set_data(0, vec![(5.0, 5.0)]);
+        assert_eq!(plot().hovered(), None);
+    }
+}
@@ -0,0 +1,310 @@
+use std::{
+    any::Any,
+    cell::{Cell, Ref, RefCell, RefMut},
+    f64::consts::TAU,
+    rc::Rc,
+    sync::Arc,
+};
+
+use web_time::Instant;
+
+use crate::{
+    action::Action,
+    render::Painter,
+    ui::{
+        animate::FRAME,
+        color::{Color, ColorState, LIGHT_GRAY},
+        layout::{Layout, Point},
+        semantics::Role,
+        style::Style,
+        sync::{Thread, Trigger},
+        timer::TimerId,
+    },
+};
+
+use super::{impl_widget, BaseWidget, Widget, WidgetHooks, WidgetI, WidgetInternal};
+
+/// The color of the filled part of a progress bar
+/// and the spokes of a spinner
+const PROGRESS_COLOR: Color = Color::RGBA(66, 133, 244, 255);
+/// How many spokes a spinner is drawn with
+const SPOKES: usize = 12;
+
+/// A struct representing a determinate progress bar widget.
+///
+/// The `ProgressBar` struct fills a track from left to right as long
+/// running work gets done. Progress is set from the UI thread or from
+/// an emitter thread through the trigger handed to it.
+///
+/// ## Example
+/// ```ignore
+/// struct Download;
+/// impl Emitter for Download {
+///     fn run(self: Arc<Self>, trigger: Trigger) {
+///         for chunk in 0..=100 {
+///             trigger.update_callback(move |widget| {
+///                 if let Some(bar) = widget.downcast::<ProgressBar>() {
+///                     bar.set_progress(chunk as f32 / 100.0);
+///                 }
+///             });
+///         }
+///     }
+/// }
+///
+/// let bar = ProgressBar::new().set_width(200.0).connect(Download);
+/// ```
+pub struct ProgressBar {
+    pub base: RefCell<BaseWidget>,
+    pub actions: RefCell<Vec<Action>>,
+    emitter: Option<Arc<dyn Thread>>,
+    trigger: RefCell<Option<Rc<Trigger>>>,
+    progress: Cell<f32>,
+    pub(crate) fill_color: Color,
+}
+impl Default for ProgressBar {
+    fn default() -> Self {
+        Self {
+            base: RefCell::new(BaseWidget {
+                layout: Layout {
+                    w: 200.0,
+                    h: 8.0,
+                    ..Default::default()
+                },
+                style: Style {
+                    color: ColorState::new(LIGHT_GRAY),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            actions: RefCell::default(),
+            emitter: None,
+            trigger: RefCell::default(),
+            progress: Cell::default(),
+            fill_color: PROGRESS_COLOR,
+        }
+    }
+}
+impl ProgressBar {
+    pub fn new() -> Self {
+        ProgressBar::default()
+    }
+    /// Set the color of the filled part of the track
+    pub fn set_fill_color(mut self, color: Color) -> Self {
+        self.fill_color = color;
+        self
+    }
+    /// Returns how much of the work is done from `0.0` to `1.0`
+    pub fn progress(&self) -> f32 {
+        self.progress.get()
+    }
+    /// Set how much of the work is done from `0.0` to `1.0`
+    ///
+    /// Values outside the range are clamped into it
+    pub fn set_progress(&self, progress: f32) {
+        let progress = if progress.is_nan() {
+            0.0
+        } else {
+            progress.clamp(0.0, 1.0)
+        };
+        if progress == self.progress.get() {
+            return;
+        }
+        self.progress.set(progress);
+        if let Some(trigger) = self.internal_trigger() {
+            trigger.update();
+        }
+    }
+}
+impl_widget! {ProgressBar}
+impl WidgetHooks for ProgressBar {
+    fn custom_draw(&self, painter: &mut dyn Painter) {
+        let base = self.base();
+        let w = base.layout.w * f64::from(self.progress.get());
+        if w <= 0.0 {
+            return;
+        }
+        painter.fill_rect(
+            Layout {
+                x: base.offset.x + base.layout.x,
+                y: base.offset.y + base.layout.y,
+                w,
+                h: base.layout.h,
+            },
+            self.fill_color,
+        );
+    }
+    fn default_role(&self) -> Role {
+        Role::ProgressBar
+    }
+    fn value(&self) -> Option<f32> {
+        Some(self.progress.get())
+    }
+}
+
+/// A struct representing an indeterminate progress widget.
+///
+/// The `Spinner` struct shows a ring of spokes fading one after the
+/// other for work of unknown length. It animates on its own once
+/// added to the `DOM` until stopped.
+pub struct Spinner {
+    pub base: RefCell<BaseWidget>,
+    pub actions: RefCell<Vec<Action>>,
+    emitter: Option<Arc<dyn Thread>>,
+    trigger: RefCell<Option<Rc<Trigger>>>,
+    /// How far the spinner has turned
+    turns: Cell<f64>,
+    ticked: Cell<Instant>,
+    running: Cell<bool>,
+    timer: Cell<Option<TimerId>>,
+    pub(crate) color: Color,
+    pub(crate) speed: f64,
+}
+impl Default for Spinner {
+    fn default() -> Self {
+        Self {
+            base: RefCell::new(BaseWidget {
+                layout: Layout {
+                    w: 24.0,
+                    h: 24.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            actions: RefCell::default(),
+            emitter: None,
+            trigger: RefCell::default(),
+            turns: Cell::default(),
+            ticked: Cell::new(Instant::now()),
+            running: Cell::new(true),
+            timer: Cell::default(),
+            color: PROGRESS_COLOR,
+            speed: 1.0,
+        }
+    }
+}
+impl Spinner {
+    pub fn new() -> Self {
+        Spinner::default()
+    }
+    /// Set the color of the spokes
+    pub fn set_spinner_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+    /// Set how many turns the spinner makes every second
+    ///
+    /// Defaults to 1
+    pub fn set_speed(mut self, speed: f64) -> Self {
+        self.speed = speed.max(0.0);
+        self
+    }
+    /// Returns `true` if the spinner is animating
+    pub fn is_running(&self) -> bool {
+        self.running.get()
+    }
+    /// Starts or stops animating the spinner
+    ///
+    /// A stopped spinner keeps showing the spoke it stopped on
+    pub fn set_running(&self, running: bool) {
+        self.running.set(running);
+        if !running {
+            if let (Some(id), Some(trigger)) = (self.timer.take(), self.internal_trigger()) {
+                trigger.clear_timer(id);
+            }
+        } else if let Some(trigger) = self.internal_trigger() {
+            // Picked up again on the next layout
+            trigger.update();
+        }
+    }
+    /// Turns the spinner as far as it got since the last tick
+    fn tick(&self) {
+        let now = Instant::now();
+        let elapsed = now - self.ticked.replace(now);
+        let turns = self.turns.get() + elapsed.as_secs_f64() * self.speed;
+        self.turns.set(turns.fract());
+    }
+    /// Returns the index of the spoke drawn the brightest
+    fn leading_spoke(&self) -> usize {
+        (self.turns.get() * SPOKES as f64) as usize % SPOKES
+    }
+}
+impl_widget! {Spinner}
+impl WidgetHooks for Spinner {
+    fn needs_layout(&self) -> bool {
+        // Waiting on the trigger to start the animation
+        self.running.get() && self.timer.get().is_none()
+    }
+    fn custom_layout(&self) {
+        // Timers need the trigger handed out once added to the `DOM`
+        if !self.running.get() || self.timer.get().is_some() {
+            return;
+        }
+        if let Some(trigger) = self.internal_trigger() {
+            self.ticked.set(Instant::now());
+            let id = trigger.set_interval(FRAME, |widget| {
+                if let Some(spinner) = widget.downcast::<Spinner>() {
+                    spinner.tick();
+                }
+            });
+            self.timer.set(Some(id));
+        }
+    }
+    fn custom_draw(&self, painter: &mut dyn Painter) {
+        let (center, radius) = {
+            let base = self.base();
+            (
+                Point::new(
+                    base.offset.x + base.layout.x + base.layout.w / 2.0,
+                    base.offset.y + base.layout.y + base.layout.h / 2.0,
+                ),
+                base.layout.w.min(base.layout.h) / 2.0,
+            )
+        };
+        let width = (radius * 0.2).max(1.5);
+        // Room for the round caps
+        let (outer, inner) = (radius - width / 2.0, radius * 0.45);
+        if outer <= inner {
+            return;
+        }
+
+        let (r, g, b, a): (u8, u8, u8, u8) = self.color.into();
+        let lead = self.leading_spoke();
+        for spoke in 0..SPOKES {
+            // Spokes trailing behind the leading one fade out
+            let behind = (lead + SPOKES - spoke) % SPOKES;
+            let fade = 1.0 - behind as f64 / SPOKES as f64 * 0.85;
+            let alpha = (f64::from(a) * fade).round() as u8;
+
+            let angle = spoke as f64 / SPOKES as f64 * TAU;
+            let (sin, cos) = angle.sin_cos();
+            painter.stroke_line(
+                Point::new(center.x + sin * inner, center.y - cos * inner),
+                Point::new(center.x + sin * outer, center.y - cos * outer),
+                width,
+                Color::RGBA(r, g, b, alpha),
+            );
+        }
+    }
+    fn default_role(&self) -> Role {
+        Role::ProgressBar
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProgressBar;
+    use crate::ui::widget::WidgetHooks;
+
+    #[test]
+    fn progress_stays_within_the_track() {
+        let bar = ProgressBar::new();
+        bar.set_progress(0.25);
+        assert_eq!(bar.progress(), 0.25);
+        assert_eq!(WidgetHooks::value(&bar), Some(0.25));
+
+        bar.set_progress(1.5);
+        assert_eq!(bar.progress(), 1.0);
+        bar.set_progress(f32::NAN);
+        assert_eq!(bar.progress(), 0.0);
+    }
+}
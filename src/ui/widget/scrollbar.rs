@@ -5,20 +5,47 @@ use std::{
     sync::Arc,
 };
 
+use web_time::Duration;
+
 use crate::{
     action::Action,
     ui::{
         color::LIGHT_GRAY,
+        semantics::Role,
         sync::{Thread, Trigger},
     },
 };
 
-use super::{impl_widget, BaseWidget, Widget, WidgetI, WidgetInternal};
+use super::{impl_widget, BaseWidget, Widget, WidgetHooks, WidgetI, WidgetInternal};
 
 /// Scrollbar thickness
 const SCROLLBAR_SIZE: f64 = 10.0;
 const SCROLLBAR_BUFFER: f64 = 5.0;
 
+/// The `ScrollOptions` struct configures how the scrollbars
+/// of a scrollable `Container` behave
+///
+/// - `auto_hide`: Hides the scrollbars while the container is idle
+///   and fades them in on hover or while scrolling
+/// - `idle`: How long scrollbars stay after the last scroll
+///   before fading out
+/// - `hover_thickness`: The thickness of a hovered scrollbar
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollOptions {
+    pub auto_hide: bool,
+    pub idle: Duration,
+    pub hover_thickness: f64,
+}
+impl Default for ScrollOptions {
+    fn default() -> Self {
+        Self {
+            auto_hide: false,
+            idle: Duration::from_secs(1),
+            hover_thickness: SCROLLBAR_SIZE,
+        }
+    }
+}
+
 /// The `Scrollbar` struct represents a scrollbar along the
 /// x or y axis of a `Container`.
 ///
@@ -29,6 +56,8 @@ pub struct ScrollBar {
     pub actions: RefCell<Vec<Action>>,
     emitter: Option<Arc<dyn Thread>>,
     pub(crate) buffer: f64,
+    /// The thickness while not hovered
+    pub(crate) thickness: f64,
     trigger: RefCell<Option<Rc<Trigger>>>,
 }
 impl ScrollBar {
@@ -36,6 +65,7 @@ impl ScrollBar {
     pub fn new_y() -> Self {
         let mut scrollbar = ScrollBar::default();
         scrollbar.buffer = SCROLLBAR_BUFFER;
+        scrollbar.thickness = SCROLLBAR_SIZE;
 
         {
             let mut scrollbar_base = scrollbar.base_mut();
@@ -50,6 +80,7 @@ impl ScrollBar {
     pub fn new_x() -> Self {
         let mut scrollbar = ScrollBar::default();
         scrollbar.buffer = SCROLLBAR_BUFFER;
+        scrollbar.thickness = SCROLLBAR_SIZE;
 
         {
             let mut scrollbar_base = scrollbar.base_mut();
@@ -62,3 +93,8 @@ impl ScrollBar {
     }
 }
 impl_widget! {ScrollBar}
+impl WidgetHooks for ScrollBar {
+    fn default_role(&self) -> Role {
+        Role::ScrollBar
+    }
+}
@@ -0,0 +1,91 @@
+use std::{
+    any::Any,
+    cell::{Ref, RefCell, RefMut},
+    rc::Rc,
+    sync::Arc,
+};
+
+use crate::{
+    action::Action,
+    ui::{
+        color::{ColorState, LIGHT_GRAY},
+        layout::Dimension,
+        semantics::Role,
+        style::Style,
+        sync::{Thread, Trigger},
+    },
+};
+
+use super::{impl_widget, BaseWidget, Widget, WidgetHooks, WidgetI, WidgetInternal};
+
+/// A struct representing a separator widget.
+///
+/// The `Separator` struct draws a thin rule across the whole width
+/// or height of its parent to split content into sections. It takes
+/// the color of separators from the theme.
+///
+/// ## Example
+/// ```ignore
+/// let mut menu = Container::new().set_flex_layout(FlexLayout::Col);
+/// menu.add_widget(Label::new().set_label("Open"));
+/// menu.add_widget(Separator::new_x());
+/// menu.add_widget(Label::new().set_label("Quit"));
+/// ```
+pub struct Separator {
+    pub base: RefCell<BaseWidget>,
+    pub actions: RefCell<Vec<Action>>,
+    emitter: Option<Arc<dyn Thread>>,
+    trigger: RefCell<Option<Rc<Trigger>>>,
+    vertical: bool,
+}
+impl Separator {
+    /// Creates a horizontal rule spanning the width of its parent
+    pub fn new_x() -> Self {
+        Separator::new(false)
+    }
+    /// Creates a vertical rule spanning the height of its parent
+    pub fn new_y() -> Self {
+        Separator::new(true)
+    }
+    fn new(vertical: bool) -> Self {
+        let separator = Self {
+            base: RefCell::new(BaseWidget {
+                style: Style {
+                    color: ColorState::new(LIGHT_GRAY),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            actions: RefCell::default(),
+            emitter: None,
+            trigger: RefCell::default(),
+            vertical,
+        };
+        let separator = if vertical {
+            separator.set_height(Dimension::Percent(100.0))
+        } else {
+            separator.set_width(Dimension::Percent(100.0))
+        };
+        separator.set_thickness(1.0)
+    }
+    /// Set how thick the rule is
+    ///
+    /// Defaults to 1px
+    pub fn set_thickness(self, thickness: f64) -> Self {
+        if self.vertical {
+            self.set_width(thickness)
+        } else {
+            self.set_height(thickness)
+        }
+    }
+    /// Returns `true` if the rule runs from top to bottom
+    pub fn is_vertical(&self) -> bool {
+        self.vertical
+    }
+}
+impl_widget! {Separator}
+impl WidgetHooks for Separator {
+    fn default_role(&self) -> Role {
+        Role::Separator
+    }
+}
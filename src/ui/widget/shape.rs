@@ -0,0 +1,229 @@
+use std::{
+    any::Any,
+    cell::{Ref, RefCell, RefMut},
+    rc::Rc,
+    sync::Arc,
+};
+
+use tiny_skia::{Path, PathBuilder, Rect, Transform};
+
+use crate::{
+    action::Action,
+    render::Painter,
+    ui::{
+        color::{Color, BLACK, TRANSPARENT},
+        layout::{HitShape, Layout, Point},
+        semantics::Role,
+        style::Outline,
+        sync::{Thread, Trigger},
+    },
+};
+
+use super::{impl_widget, BaseWidget, Widget, WidgetHooks, WidgetI, WidgetInternal};
+
+/// The `Geometry` enum lists the figures a `Shape` draws
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    /// The largest circle centered inside the widget
+    Circle,
+    /// The ellipse filling the widget
+    Ellipse,
+    /// The closed polygon through points relative to the
+    /// top-left of the widget
+    Polygon(Vec<Point>),
+    /// Any path laid out relative to the top-left of the widget
+    Path(Path),
+}
+
+/// A struct representing a shape widget.
+///
+/// The `Shape` struct draws a circle, ellipse, polygon or any
+/// `tiny_skia::Path` filled and outlined in colors of its own. It is
+/// laid out like any other widget and only hit within its figure so
+/// diagrams and graphs are built from shapes reacting to actions.
+///
+/// Arbitrary paths are hit anywhere within the widget.
+///
+/// ## Example
+/// ```ignore
+/// let node = Shape::circle()
+///     .set_width(40.0)
+///     .set_height(40.0)
+///     .set_fill_color(BLUE)
+///     .set_outline(2.0, BLACK)
+///     .on_action(Action::Click(Box::new(Click::new(()))));
+/// ```
+pub struct Shape {
+    pub base: RefCell<BaseWidget>,
+    pub actions: RefCell<Vec<Action>>,
+    emitter: Option<Arc<dyn Thread>>,
+    trigger: RefCell<Option<Rc<Trigger>>>,
+    geometry: Geometry,
+    pub(crate) fill_color: Color,
+    pub(crate) outline: Option<Outline>,
+}
+impl Shape {
+    /// Creates a circle as large as fits the widget
+    pub fn circle() -> Self {
+        Shape::new(Geometry::Circle, HitShape::Circle)
+    }
+    /// Creates an ellipse filling the widget
+    pub fn ellipse() -> Self {
+        Shape::new(Geometry::Ellipse, HitShape::Ellipse)
+    }
+    /// Creates a closed polygon through `points` relative to
+    /// the top-left of the widget
+    ///
+    /// The widget is sized to reach the furthest point
+    pub fn polygon(points: Vec<Point>) -> Self {
+        let (w, h) = points
+            .iter()
+            .fold((0.0, 0.0), |(w, h), point| (point.x.max(w), point.y.max(h)));
+        Shape::new(Geometry::Polygon(points.clone()), HitShape::Path(points))
+            .set_width(w)
+            .set_height(h)
+    }
+    /// Creates a shape drawing `path` relative to the top-left
+    /// of the widget
+    ///
+    /// The widget is sized to reach the furthest point
+    pub fn path(path: Path) -> Self {
+        let bounds = path.bounds();
+        let (w, h) = (bounds.right().max(0.0), bounds.bottom().max(0.0));
+        Shape::new(Geometry::Path(path), HitShape::Rect)
+            .set_width(f64::from(w))
+            .set_height(f64::from(h))
+    }
+    fn new(geometry: Geometry, hit_shape: HitShape) -> Self {
+        Self {
+            base: RefCell::new(BaseWidget {
+                hit_shape,
+                ..Default::default()
+            }),
+            actions: RefCell::default(),
+            emitter: None,
+            trigger: RefCell::default(),
+            geometry,
+            fill_color: BLACK,
+            outline: None,
+        }
+    }
+    /// Set the color the inside of the shape is filled with
+    ///
+    /// A transparent fill only draws the outline
+    pub fn set_fill_color(mut self, color: Color) -> Self {
+        self.fill_color = color;
+        self
+    }
+    /// Strokes the outline of the shape `width` wide in `color`
+    pub fn set_outline(mut self, width: f64, color: Color) -> Self {
+        self.outline = Some(Outline::new(width, color));
+        self
+    }
+    /// Returns the figure the shape draws
+    pub fn geometry(&self) -> &Geometry {
+        &self.geometry
+    }
+    /// Returns the figure laid out over `rect` on screen
+    ///
+    /// Returns `None` for figures covering no area
+    fn path_in(&self, rect: Layout) -> Option<Path> {
+        match &self.geometry {
+            Geometry::Circle => PathBuilder::from_circle(
+                (rect.x + rect.w / 2.0) as f32,
+                (rect.y + rect.h / 2.0) as f32,
+                (rect.w.min(rect.h) / 2.0) as f32,
+            ),
+            Geometry::Ellipse => PathBuilder::from_oval(Rect::from_xywh(
+                rect.x as f32,
+                rect.y as f32,
+                rect.w as f32,
+                rect.h as f32,
+            )?),
+            Geometry::Polygon(points) => {
+                let (first, rest) = points.split_first()?;
+                let mut pb = PathBuilder::new();
+                pb.move_to((rect.x + first.x) as f32, (rect.y + first.y) as f32);
+                for point in rest {
+                    pb.line_to((rect.x + point.x) as f32, (rect.y + point.y) as f32);
+                }
+                pb.close();
+                pb.finish()
+            }
+            Geometry::Path(path) => path
+                .clone()
+                .transform(Transform::from_translate(rect.x as f32, rect.y as f32)),
+        }
+    }
+}
+impl_widget! {Shape}
+impl WidgetHooks for Shape {
+    fn custom_draw(&self, painter: &mut dyn Painter) {
+        let rect = {
+            let base = self.base();
+            Layout {
+                x: base.offset.x + base.layout.x,
+                y: base.offset.y + base.layout.y,
+                ..base.layout
+            }
+        };
+        if let Some(path) = self.path_in(rect) {
+            let fill = (self.fill_color != TRANSPARENT).then_some(self.fill_color);
+            painter.draw_path(&path, fill, self.outline);
+        }
+    }
+    fn default_role(&self) -> Role {
+        Role::Graphic
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::ui::{
+        color::{BLUE, RED, TRANSPARENT},
+        dom::DOM,
+        layout::Point,
+        widget::{Widget, WidgetI},
+    };
+
+    use super::Shape;
+
+    #[test]
+    fn shapes_are_drawn_and_hit_within_their_figure() {
+        let widgets: [Rc<dyn WidgetI>; 2] = [
+            Rc::new(
+                Shape::circle()
+                    .set_width(40.0)
+                    .set_height(40.0)
+                    .set_fill_color(RED),
+            ),
+            Rc::new(
+                Shape::polygon(vec![
+                    Point::new(0.0, 0.0),
+                    Point::new(40.0, 0.0),
+                    Point::new(0.0, 40.0),
+                ])
+                .set_x(50.0)
+                .set_fill_color(TRANSPARENT)
+                .set_outline(2.0, BLUE),
+            ),
+        ];
+        let frame = DOM::render_once_to_buffer(&widgets, 100, 50).unwrap();
+
+        assert_eq!(frame.pixel(20, 20), Some(RED));
+        assert_eq!(frame.pixel(2, 2), Some(TRANSPARENT));
+        // Only the outline of the triangle is drawn
+        assert_ne!(frame.pixel(70, 0), Some(TRANSPARENT));
+        assert_eq!(frame.pixel(60, 10), Some(TRANSPARENT));
+
+        let mut dom = DOM::headless(100, 50);
+        let id = dom.add_widget(Shape::circle().set_width(40.0).set_height(40.0));
+        let circle = dom.widget(id).unwrap();
+        dom.move_cursor(3.0, 3.0);
+        assert!(!circle.base().state.hit);
+        dom.move_cursor(20.0, 3.0);
+        assert!(circle.base().state.hit);
+    }
+}
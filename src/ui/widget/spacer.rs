@@ -0,0 +1,68 @@
+use std::{
+    any::Any,
+    cell::{Ref, RefCell, RefMut},
+    rc::Rc,
+    sync::Arc,
+};
+
+use crate::{
+    action::Action,
+    ui::{
+        layout::Flex,
+        sync::{Thread, Trigger},
+    },
+};
+
+use super::{impl_widget, BaseWidget, Widget, WidgetHooks, WidgetI, WidgetInternal};
+
+/// A struct representing an empty spacer widget.
+///
+/// The `Spacer` struct draws nothing and takes up the space left over
+/// in a `Row` or `Col` layout pushing its siblings apart. Several
+/// spacers share the leftover space by their grow factors.
+///
+/// ## Example
+/// ```ignore
+/// // The save button is pushed all the way to the right
+/// let mut toolbar = Container::new()
+///     .set_flex_layout(FlexLayout::Row)
+///     .set_width(Dimension::Percent(100.0))
+///     .set_height(32.0);
+/// toolbar.add_widget(Button::new().set_label("Open"));
+/// toolbar.add_widget(Spacer::new());
+/// toolbar.add_widget(Button::new().set_label("Save"));
+/// ```
+pub struct Spacer {
+    pub base: RefCell<BaseWidget>,
+    pub actions: RefCell<Vec<Action>>,
+    emitter: Option<Arc<dyn Thread>>,
+    trigger: RefCell<Option<Rc<Trigger>>>,
+}
+impl Default for Spacer {
+    fn default() -> Self {
+        Spacer::with_grow(1.0)
+    }
+}
+impl Spacer {
+    pub fn new() -> Self {
+        Spacer::default()
+    }
+    /// Creates a spacer taking `grow` shares of the leftover
+    /// space compared to its siblings
+    pub fn with_grow(grow: f64) -> Self {
+        Self {
+            base: RefCell::new(BaseWidget {
+                flex: Flex {
+                    grow: grow.max(0.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            actions: RefCell::default(),
+            emitter: None,
+            trigger: RefCell::default(),
+        }
+    }
+}
+impl_widget! {Spacer}
+impl WidgetHooks for Spacer {}
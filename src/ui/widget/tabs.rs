@@ -0,0 +1,271 @@
+use std::{
+    any::Any,
+    cell::{Cell, Ref, RefCell, RefMut},
+    rc::Rc,
+    sync::Arc,
+};
+
+use crate::{
+    action::{
+        click::{Click, MouseButton},
+        Action,
+    },
+    render::Painter,
+    ui::{
+        color::{Color, BLACK},
+        layout::{FlexLayout, Layout},
+        semantics::Role,
+        sync::{Thread, Trigger},
+    },
+};
+
+use super::{
+    button::Button, container::Container, impl_widget, screen_rect, BaseWidget, Widget,
+    WidgetHooks, WidgetI, WidgetInternal,
+};
+
+type TabCallback = Rc<dyn Fn(usize, Rc<Trigger>)>;
+
+/// The state shared between `Tabs` and the headers
+/// switching between its panes
+#[derive(Clone)]
+struct TabState {
+    active: Rc<Cell<usize>>,
+    strip: Rc<Container>,
+    panes: Rc<RefCell<Vec<Rc<dyn WidgetI>>>>,
+    on_tab_change: Rc<RefCell<Vec<TabCallback>>>,
+}
+
+/// A struct representing a tabs widget.
+///
+/// The `Tabs` struct shows a strip of tab headers above a pane of
+/// content. Clicking a header shows its pane in place of the one
+/// shown before. Only the active pane is laid out, drawn and hit
+/// while the others wait aside.
+///
+/// ## Example
+/// ```ignore
+/// let mut tabs = Tabs::new()
+///     .set_width(400.0)
+///     .set_height(300.0)
+///     .on_tab_change(|index, _| println!("switched to tab {index}"));
+/// tabs.add_tab("General", Container::new());
+/// tabs.add_tab("Advanced", Container::new());
+/// ```
+pub struct Tabs {
+    pub base: RefCell<BaseWidget>,
+    pub actions: RefCell<Vec<Action>>,
+    emitter: Option<Arc<dyn Thread>>,
+    trigger: RefCell<Option<Rc<Trigger>>>,
+    state: TabState,
+    pub(crate) header_height: f64,
+    pub(crate) tab_width: f64,
+    pub(crate) indicator_color: Color,
+}
+impl Default for Tabs {
+    fn default() -> Self {
+        Self {
+            base: RefCell::default(),
+            actions: RefCell::default(),
+            emitter: None,
+            trigger: RefCell::default(),
+            state: TabState {
+                active: Rc::default(),
+                strip: Rc::new(
+                    Container::new()
+                        .set_flex_layout(FlexLayout::Row)
+                        .set_role(Role::TabList),
+                ),
+                panes: Rc::default(),
+                on_tab_change: Rc::default(),
+            },
+            header_height: 32.0,
+            tab_width: 96.0,
+            indicator_color: BLACK,
+        }
+    }
+}
+impl Tabs {
+    pub fn new() -> Self {
+        Tabs::default()
+    }
+    /// Adds a tab titled `title` showing `pane` while active
+    ///
+    /// The first tab added is active from the start
+    pub fn add_tab<T: WidgetI + 'static>(&mut self, title: &str, pane: T) {
+        let index = self.state.panes.borrow().len();
+        let click = Click::new(self.state.clone()).on(
+            MouseButton::LeftButtonRelease,
+            move |state, trigger, _, _, _| Tabs::select(state, index, trigger),
+        );
+        let header = Button::new()
+            .set_label(title)
+            .set_label_horizontal()
+            .set_label_vertical()
+            .set_width(self.tab_width)
+            .set_height(self.header_height)
+            .set_role(Role::Tab);
+        header.action_mut().push(Action::Click(Box::new(click)));
+
+        self.state.strip.children.borrow_mut().push(Rc::new(header));
+        self.state.panes.borrow_mut().push(Rc::new(pane));
+    }
+    /// Set which tab is active from the start
+    pub fn set_active_tab(self, index: usize) -> Self {
+        self.state.active.set(index);
+        self
+    }
+    /// Set the height of the strip of tab headers
+    ///
+    /// Defaults to 32px
+    pub fn set_header_height(mut self, height: f64) -> Self {
+        self.header_height = height;
+        for header in self.state.strip.children.borrow().iter() {
+            header.base_mut().layout.h = height;
+        }
+        self
+    }
+    /// Set the width of every tab header
+    ///
+    /// Defaults to 96px
+    pub fn set_tab_width(mut self, width: f64) -> Self {
+        self.tab_width = width;
+        for header in self.state.strip.children.borrow().iter() {
+            header.base_mut().layout.w = width;
+        }
+        self
+    }
+    /// Set the color of the line marking the active tab
+    pub fn set_indicator_color(mut self, color: Color) -> Self {
+        self.indicator_color = color;
+        self
+    }
+    /// Returns the index of the active tab
+    pub fn active_tab(&self) -> usize {
+        self.state.active.get()
+    }
+    /// Set a callback receiving the index of the newly active
+    /// tab every time another tab is selected
+    pub fn on_tab_change<F: Fn(usize, Rc<Trigger>) + 'static>(self, callback: F) -> Self {
+        self.state
+            .on_tab_change
+            .borrow_mut()
+            .push(Rc::new(callback));
+        self
+    }
+    fn select(state: &mut TabState, index: usize, trigger: Rc<Trigger>) {
+        if state.active.get() == index {
+            return;
+        }
+
+        // The new pane is laid out where the old one was
+        // on the next redraw
+        if let Some(pane) = state.panes.borrow().get(state.active.get()) {
+            trigger.invalidate(screen_rect(pane.as_ref()));
+        }
+        trigger.invalidate(screen_rect(state.strip.as_ref()));
+        state.active.set(index);
+
+        for callback in state.on_tab_change.borrow().iter() {
+            callback(index, trigger.clone());
+        }
+    }
+    fn active_pane(&self) -> Option<Rc<dyn WidgetI>> {
+        self.state
+            .panes
+            .borrow()
+            .get(self.state.active.get())
+            .cloned()
+    }
+}
+impl_widget! {Tabs}
+impl WidgetHooks for Tabs {
+    fn children(&self) -> Vec<Rc<dyn WidgetI>> {
+        let strip: Rc<dyn WidgetI> = self.state.strip.clone();
+        std::iter::once(strip).chain(self.active_pane()).collect()
+    }
+    fn hidden_children(&self) -> Vec<Rc<dyn WidgetI>> {
+        let active = self.state.active.get();
+        self.state
+            .panes
+            .borrow()
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != active)
+            .map(|(_, pane)| pane.clone())
+            .collect()
+    }
+    fn custom_layout(&self) {
+        let layout = self.base().layout;
+
+        // Headers line up along the top
+        self.state.strip.base_mut().layout = Layout {
+            h: self.header_height,
+            ..layout
+        };
+
+        // The active pane fills the rest
+        if let Some(pane) = self.active_pane() {
+            pane.base_mut().layout = Layout {
+                x: layout.x,
+                y: layout.y + self.header_height,
+                w: layout.w,
+                h: (layout.h - self.header_height).max(0.0),
+            };
+        }
+    }
+    fn custom_draw_over(&self, painter: &mut dyn Painter) {
+        let Some(header) = self
+            .state
+            .strip
+            .children
+            .borrow()
+            .get(self.state.active.get())
+            .cloned()
+        else {
+            return;
+        };
+
+        // The active header is underlined
+        let rect = screen_rect(header.as_ref());
+        painter.fill_rect(
+            Layout {
+                y: rect.y + rect.h - 2.0,
+                h: 2.0,
+                ..rect
+            },
+            self.indicator_color,
+        );
+    }
+    fn default_role(&self) -> Role {
+        Role::Group
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::widget::{container::Container, Widget, WidgetHooks};
+
+    use super::Tabs;
+
+    #[test]
+    fn only_the_active_pane_is_shown() {
+        let mut tabs = Tabs::new().set_width(200.0).set_height(100.0);
+        tabs.add_tab("One", Container::new().set_id("one"));
+        tabs.add_tab("Two", Container::new().set_id("two"));
+
+        let children = tabs.children();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].children().len(), 2);
+        assert_eq!(children[1].base().id, "one");
+        assert_eq!(tabs.hidden_children()[0].base().id, "two");
+
+        tabs.custom_layout();
+        let pane = children[1].base().layout;
+        assert_eq!((pane.y, pane.h), (32.0, 68.0));
+
+        let tabs = tabs.set_active_tab(1);
+        assert_eq!(tabs.children()[1].base().id, "two");
+        assert_eq!(tabs.hidden_children()[0].base().id, "one");
+    }
+}
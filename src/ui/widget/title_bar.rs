@@ -0,0 +1,237 @@
+use std::{
+    any::Any,
+    cell::{Ref, RefCell, RefMut},
+    rc::Rc,
+    sync::Arc,
+};
+
+use crate::{
+    action::{
+        click::{Click, MouseButton},
+        hover::Hover,
+        Action,
+    },
+    render::Painter,
+    ui::{
+        color::{Color, ColorState, BLACK, LIGHT_GRAY},
+        layout::{Dimension, Layout, Point},
+        semantics::Role,
+        style::Style,
+        sync::{Thread, Trigger},
+        text::Text,
+        window::WindowRequest,
+    },
+};
+
+use super::{
+    button::Button, impl_widget, screen_rect, BaseWidget, Widget, WidgetHooks, WidgetI,
+    WidgetInternal,
+};
+
+/// The layer title bars are drawn on so they stay above
+/// and take the clicks of the content scrolled under them
+pub const TITLE_BAR_LAYER: i32 = 1000;
+/// The color the close button turns while hovered
+const CLOSE_HOVER: Color = Color::RGBA(232, 17, 35, 255);
+/// How far the title is kept from the left edge
+const TITLE_PADDING: f64 = 8.0;
+/// How large the icons on the window buttons are drawn
+const ICON_SIZE: f64 = 10.0;
+
+/// A struct representing the title bar of an undecorated window.
+///
+/// The `TitleBar` struct stands in for the title bar drawn by the
+/// platform once the window is built without decorations. Dragging
+/// the bar moves the window and double clicking it maximizes the
+/// window. Buttons to minimize, maximize and close the window sit at
+/// its right end.
+///
+/// The bar stretches across the window and is drawn on a layer above
+/// everything else so content under it never receives the drag.
+///
+/// ## Example
+/// ```ignore
+/// let mut dom = DOM::builder().decorations(false).build();
+/// dom.add_widget(TitleBar::new().set_label("Notes"));
+/// ```
+pub struct TitleBar {
+    pub base: RefCell<BaseWidget>,
+    pub actions: RefCell<Vec<Action>>,
+    emitter: Option<Arc<dyn Thread>>,
+    trigger: RefCell<Option<Rc<Trigger>>>,
+    /// The minimize, maximize and close buttons from left to right
+    buttons: [Rc<Button>; 3],
+    pub(crate) button_width: f64,
+    pub(crate) icon_color: Color,
+}
+impl Default for TitleBar {
+    fn default() -> Self {
+        // Presses start moving the window while double
+        // clicks maximize it
+        let drag = Click::new(())
+            .on_count(MouseButton::LeftButton, 1, |_, trigger, _, _, _| {
+                trigger.request_window(WindowRequest::Drag)
+            })
+            .on_count(MouseButton::LeftButton, 2, |_, trigger, _, _, _| {
+                trigger.request_window(WindowRequest::ToggleMaximize)
+            });
+
+        Self {
+            base: RefCell::new(BaseWidget {
+                layout: Layout {
+                    h: 32.0,
+                    ..Default::default()
+                },
+                style: Style {
+                    color: ColorState::new(LIGHT_GRAY),
+                    ..Default::default()
+                },
+                text: Text {
+                    pos: Point::new(TITLE_PADDING, 0.0),
+                    valign: true,
+                    ..Default::default()
+                },
+                z_index: TITLE_BAR_LAYER,
+                ..Default::default()
+            }),
+            actions: RefCell::new(vec![Action::Click(Box::new(drag))]),
+            emitter: None,
+            trigger: RefCell::default(),
+            buttons: [
+                TitleBar::button("Minimize", WindowRequest::Minimize, LIGHT_GRAY),
+                TitleBar::button("Maximize", WindowRequest::ToggleMaximize, LIGHT_GRAY),
+                TitleBar::button("Close", WindowRequest::Close, CLOSE_HOVER),
+            ],
+            button_width: 46.0,
+            icon_color: BLACK,
+        }
+        .set_width(Dimension::Fill)
+    }
+}
+impl TitleBar {
+    pub fn new() -> Self {
+        TitleBar::default()
+    }
+    /// Set the width of every window button
+    ///
+    /// Defaults to 46px
+    pub fn set_button_width(mut self, width: f64) -> Self {
+        self.button_width = width;
+        self
+    }
+    /// Set the color the icons of the window buttons are drawn in
+    pub fn set_icon_color(mut self, color: Color) -> Self {
+        self.icon_color = color;
+        self
+    }
+    /// Set the color of the bar along with its buttons
+    pub fn set_bar_color(self, color: Color) -> Self {
+        for button in &self.buttons {
+            button.base_mut().style.color = ColorState::new(color);
+        }
+        self.set_color(color)
+    }
+    fn button(name: &str, request: WindowRequest, hover: Color) -> Rc<Button> {
+        let click = Click::new(()).on(
+            MouseButton::LeftButtonRelease,
+            move |_, trigger, _, _, _| trigger.request_window(request),
+        );
+        let button = Button::new()
+            .set_accessible_name(name)
+            .set_color(LIGHT_GRAY);
+        button.action_mut().extend([
+            Action::Hover(Hover::new(hover)),
+            Action::Click(Box::new(click)),
+        ]);
+        Rc::new(button)
+    }
+}
+impl_widget! {TitleBar}
+impl WidgetHooks for TitleBar {
+    fn children(&self) -> Vec<Rc<dyn WidgetI>> {
+        self.buttons
+            .iter()
+            .map(|button| button.clone() as Rc<dyn WidgetI>)
+            .collect()
+    }
+    fn custom_layout(&self) {
+        let layout = self.base().layout;
+
+        // Buttons line up against the right end
+        let right = layout.x + layout.w;
+        for (i, button) in self.buttons.iter().enumerate() {
+            button.base_mut().layout = Layout {
+                x: right - (3 - i) as f64 * self.button_width,
+                y: layout.y,
+                w: self.button_width,
+                h: layout.h,
+            };
+        }
+    }
+    fn custom_draw_over(&self, painter: &mut dyn Painter) {
+        let [minimize, maximize, close] = self.buttons.each_ref().map(|button| {
+            let rect = screen_rect(button.as_ref());
+            (
+                Point::new(
+                    rect.x + (rect.w - ICON_SIZE) / 2.0,
+                    rect.y + (rect.h - ICON_SIZE) / 2.0,
+                ),
+                Point::new(
+                    rect.x + (rect.w + ICON_SIZE) / 2.0,
+                    rect.y + (rect.h + ICON_SIZE) / 2.0,
+                ),
+            )
+        });
+        let mut line = |from: Point, to: Point| painter.stroke_line(from, to, 1.0, self.icon_color);
+
+        // A dash for minimizing
+        let middle = (minimize.0.y + minimize.1.y) / 2.0;
+        line(
+            Point::new(minimize.0.x, middle),
+            Point::new(minimize.1.x, middle),
+        );
+
+        // A square for maximizing
+        let (from, to) = maximize;
+        line(from, Point::new(to.x, from.y));
+        line(Point::new(to.x, from.y), to);
+        line(to, Point::new(from.x, to.y));
+        line(Point::new(from.x, to.y), from);
+
+        // A cross for closing
+        let (from, to) = close;
+        line(from, to);
+        line(Point::new(to.x, from.y), Point::new(from.x, to.y));
+    }
+    fn default_role(&self) -> Role {
+        Role::Group
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::{
+        dom::DOM,
+        widget::{checkbox::Checkbox, Widget, WidgetHooks},
+    };
+
+    use super::TitleBar;
+
+    #[test]
+    fn content_under_the_bar_is_not_hit() {
+        let bar = TitleBar::new().set_width(300.0);
+        bar.custom_layout();
+        let close = bar.children()[2].base().layout;
+        assert_eq!((close.x, close.w), (254.0, 46.0));
+
+        // Added first yet the bar stays on top
+        let mut dom = DOM::headless(300, 100);
+        let checkbox = dom.add_widget(Checkbox::new().set_x(10.0).set_y(10.0));
+        dom.add_widget(TitleBar::new().set_label("Title"));
+        dom.click(15.0, 15.0);
+
+        let widget = dom.widget(checkbox).unwrap();
+        let checkbox = widget.as_any().downcast_ref::<Checkbox>().unwrap();
+        assert!(!checkbox.is_checked());
+    }
+}
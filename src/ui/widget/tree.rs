@@ -0,0 +1,499 @@
+use std::{
+    any::Any,
+    cell::{Cell, Ref, RefCell, RefMut},
+    rc::Rc,
+    sync::Arc,
+};
+
+use winit::{event::Event, window::CursorIcon};
+
+use crate::{
+    action::{
+        click::{Click, MouseButton},
+        Action,
+    },
+    render::Painter,
+    ui::{
+        color::{Color, BLACK, LIGHT_GRAY},
+        layout::{Layout, Point},
+        semantics::Role,
+        sync::{Signal, Thread, Trigger},
+    },
+};
+
+use super::{
+    impl_widget, label::Label, screen_rect, BaseWidget, Widget, WidgetHooks, WidgetI,
+    WidgetInternal,
+};
+
+type SelectCallback = Rc<dyn Fn(&[usize], Rc<Trigger>)>;
+type LoadCallback = Rc<dyn Fn(&[usize]) -> Vec<TreeNode>>;
+
+/// A struct representing a node of a `TreeView`.
+///
+/// Nodes are either given their children up front or created
+/// with `TreeNode::lazy` to have them loaded by the tree the
+/// first time they are expanded.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TreeNode {
+    pub label: String,
+    /// `None` until loaded
+    children: Option<Vec<TreeNode>>,
+    expanded: bool,
+}
+impl TreeNode {
+    /// Creates a node holding the children added to it
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            children: Some(Vec::new()),
+            expanded: false,
+        }
+    }
+    /// Creates a node whose children are loaded by
+    /// `TreeView::on_load` once it is expanded
+    pub fn lazy(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            children: None,
+            expanded: false,
+        }
+    }
+    /// Adds `child` below this node
+    pub fn add_child(&mut self, child: TreeNode) {
+        self.children.get_or_insert_with(Vec::new).push(child);
+    }
+    /// Set whether the children are shown from the start
+    pub fn set_expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self
+    }
+    /// Determines if the children are shown
+    pub fn is_expanded(&self) -> bool {
+        self.expanded
+    }
+    /// Returns the children or `None` if not loaded yet
+    pub fn children(&self) -> Option<&[TreeNode]> {
+        self.children.as_deref()
+    }
+    /// Determines if the node can be expanded which nodes
+    /// not loaded yet are assumed to be
+    fn is_expandable(&self) -> bool {
+        self.children
+            .as_ref()
+            .is_none_or(|children| !children.is_empty())
+    }
+}
+
+/// A visible node of the tree along with the
+/// widgets showing it
+struct Row {
+    path: Vec<usize>,
+    depth: usize,
+    expanded: bool,
+    label: Rc<dyn WidgetI>,
+    arrow: Option<Rc<dyn WidgetI>>,
+}
+
+/// The state shared between `TreeView` and its rows
+#[derive(Clone)]
+struct TreeState {
+    roots: Rc<RefCell<Vec<TreeNode>>>,
+    selected: Rc<RefCell<Option<Vec<usize>>>>,
+    /// Set once the visible rows changed
+    dirty: Rc<Cell<bool>>,
+    /// Where the tree is on screen
+    bounds: Rc<Cell<Layout>>,
+    on_select: Rc<RefCell<Vec<SelectCallback>>>,
+    on_load: Rc<RefCell<Option<LoadCallback>>>,
+}
+impl TreeState {
+    /// Expands the node at `path` if collapsed or collapses it
+    /// otherwise loading its children when expanded the first time
+    ///
+    /// Returns `false` if there is no such node
+    fn toggle(&self, path: &[usize]) -> bool {
+        let unloaded = node_mut(&mut self.roots.borrow_mut(), path)
+            .is_some_and(|node| node.children.is_none());
+        // The loader is free to read the tree
+        let loaded = match unloaded {
+            true => self.on_load.borrow().clone().map(|load| load(path)),
+            false => None,
+        };
+
+        let mut roots = self.roots.borrow_mut();
+        let Some(node) = node_mut(&mut roots, path) else {
+            return false;
+        };
+        if let Some(children) = loaded {
+            node.children = Some(children);
+        }
+        node.children.get_or_insert_with(Vec::new);
+        node.expanded = !node.expanded;
+        self.dirty.set(true);
+        true
+    }
+    fn select(&self, path: &[usize], trigger: Rc<Trigger>) {
+        self.selected.replace(Some(path.to_vec()));
+        trigger.invalidate(self.bounds.get());
+
+        for callback in self.on_select.borrow().iter() {
+            callback(path, trigger.clone());
+        }
+    }
+}
+
+/// A struct representing a tree view widget.
+///
+/// The `TreeView` struct shows a hierarchy of nodes one per row
+/// indented below their parents. Clicking the arrow in front of a
+/// node or double clicking the node expands or collapses it while
+/// a single click selects it. Guides line up the children of
+/// every expanded node.
+///
+/// ## Example
+/// ```ignore
+/// let mut src = TreeNode::new("src").set_expanded(true);
+/// src.add_child(TreeNode::new("main.rs"));
+/// src.add_child(TreeNode::lazy("ui"));
+///
+/// let mut tree = TreeView::new()
+///     .set_width(240.0)
+///     .set_height(400.0)
+///     .on_load(|path| vec![TreeNode::new("widget.rs")])
+///     .on_select(|path, _| println!("selected {path:?}"));
+/// tree.add_root(src);
+/// ```
+pub struct TreeView {
+    pub base: RefCell<BaseWidget>,
+    pub actions: RefCell<Vec<Action>>,
+    emitter: Option<Arc<dyn Thread>>,
+    trigger: RefCell<Option<Rc<Trigger>>>,
+    state: TreeState,
+    rows: RefCell<Vec<Row>>,
+    /// Widgets of rows dropped since the `DOM` last let go of them
+    removed: RefCell<Vec<Rc<dyn WidgetI>>>,
+    pub(crate) row_height: f64,
+    pub(crate) indent: f64,
+    pub(crate) guide_color: Color,
+    pub(crate) arrow_color: Color,
+    pub(crate) selection_color: Color,
+}
+impl Default for TreeView {
+    fn default() -> Self {
+        Self {
+            base: RefCell::default(),
+            actions: RefCell::default(),
+            emitter: None,
+            trigger: RefCell::default(),
+            state: TreeState {
+                roots: Rc::default(),
+                selected: Rc::default(),
+                dirty: Rc::new(Cell::new(true)),
+                bounds: Rc::default(),
+                on_select: Rc::default(),
+                on_load: Rc::default(),
+            },
+            rows: RefCell::default(),
+            removed: RefCell::default(),
+            row_height: 24.0,
+            indent: 16.0,
+            guide_color: LIGHT_GRAY,
+            arrow_color: BLACK,
+            selection_color: Color::RGBA(0, 120, 215, 64),
+        }
+    }
+}
+impl TreeView {
+    pub fn new() -> Self {
+        TreeView::default()
+    }
+    /// Adds `node` below the nodes at the top of the tree
+    pub fn add_root(&mut self, node: TreeNode) {
+        self.state.roots.borrow_mut().push(node);
+        self.state.dirty.set(true);
+    }
+    /// Set the height of every row
+    ///
+    /// Defaults to 24px
+    pub fn set_row_height(mut self, height: f64) -> Self {
+        self.row_height = height;
+        self
+    }
+    /// Set how far children are indented past their parent
+    /// which is also the room given to the arrows
+    ///
+    /// Defaults to 16px
+    pub fn set_indent(mut self, indent: f64) -> Self {
+        self.indent = indent;
+        self
+    }
+    /// Set the color of the lines guiding along the
+    /// children of expanded nodes
+    pub fn set_guide_color(mut self, color: Color) -> Self {
+        self.guide_color = color;
+        self
+    }
+    /// Set the color of the arrows expanding nodes
+    pub fn set_arrow_color(mut self, color: Color) -> Self {
+        self.arrow_color = color;
+        self
+    }
+    /// Set the color highlighting the selected node
+    pub fn set_selection_color(mut self, color: Color) -> Self {
+        self.selection_color = color;
+        self
+    }
+    /// Set a callback receiving the path of indices leading to
+    /// the node selected every time a node is selected
+    pub fn on_select<F: Fn(&[usize], Rc<Trigger>) + 'static>(self, callback: F) -> Self {
+        self.state.on_select.borrow_mut().push(Rc::new(callback));
+        self
+    }
+    /// Set a callback loading the children of the lazy node at
+    /// the path of indices given the first time it is expanded
+    pub fn on_load<F: Fn(&[usize]) -> Vec<TreeNode> + 'static>(self, callback: F) -> Self {
+        self.state.on_load.replace(Some(Rc::new(callback)));
+        self
+    }
+    /// Returns the path of indices leading to the selected node
+    pub fn selected(&self) -> Option<Vec<usize>> {
+        self.state.selected.borrow().clone()
+    }
+    /// Expands the node at `path` if collapsed or collapses it
+    /// otherwise
+    pub fn toggle(&self, path: &[usize]) {
+        if self.state.toggle(path) {
+            if let Some(trigger) = self.internal_trigger() {
+                trigger.update();
+            }
+        }
+    }
+    /// Builds the rows of every node whose parents are all expanded
+    fn rebuild(&self) {
+        let mut rows = Vec::new();
+        self.flatten(&self.state.roots.borrow(), &mut Vec::new(), &mut rows);
+
+        let old = std::mem::replace(&mut *self.rows.borrow_mut(), rows);
+        let mut removed = self.removed.borrow_mut();
+        for row in old {
+            removed.extend(std::iter::once(row.label).chain(row.arrow));
+        }
+    }
+    fn flatten(&self, nodes: &[TreeNode], path: &mut Vec<usize>, rows: &mut Vec<Row>) {
+        for (i, node) in nodes.iter().enumerate() {
+            path.push(i);
+
+            let select = {
+                let path = path.clone();
+                move |state: &mut TreeState,
+                      trigger,
+                      _: &Rc<dyn WidgetI>,
+                      _: Event<Signal>,
+                      _: Point| { state.select(&path, trigger) }
+            };
+            let toggle = {
+                let path = path.clone();
+                move |state: &mut TreeState,
+                      trigger: Rc<Trigger>,
+                      _: &Rc<dyn WidgetI>,
+                      _: Event<Signal>,
+                      _: Point| {
+                    if state.toggle(&path) {
+                        trigger.invalidate(state.bounds.get());
+                    }
+                }
+            };
+
+            let label = Label::new()
+                .set_label(&node.label)
+                .set_label_vertical()
+                .set_role(Role::TreeItem);
+            let mut click =
+                Click::new(self.state.clone()).on(MouseButton::LeftButtonRelease, select);
+            if node.is_expandable() {
+                click = click.on_count(MouseButton::LeftButton, 2, toggle.clone());
+            }
+            label.action_mut().push(Action::Click(Box::new(click)));
+
+            let arrow = node.is_expandable().then(|| {
+                let arrow = Label::new().set_cursor_icon(CursorIcon::Pointer);
+                let click =
+                    Click::new(self.state.clone()).on(MouseButton::LeftButtonRelease, toggle);
+                arrow.action_mut().push(Action::Click(Box::new(click)));
+                Rc::new(arrow) as Rc<dyn WidgetI>
+            });
+
+            rows.push(Row {
+                path: path.clone(),
+                depth: path.len() - 1,
+                expanded: node.expanded,
+                label: Rc::new(label),
+                arrow,
+            });
+            if node.expanded {
+                if let Some(children) = &node.children {
+                    self.flatten(children, path, rows);
+                }
+            }
+
+            path.pop();
+        }
+    }
+}
+impl_widget! {TreeView}
+impl WidgetHooks for TreeView {
+    fn children(&self) -> Vec<Rc<dyn WidgetI>> {
+        self.rows
+            .borrow()
+            .iter()
+            .flat_map(|row| std::iter::once(row.label.clone()).chain(row.arrow.clone()))
+            .collect()
+    }
+    fn removed_children(&self) -> Vec<Rc<dyn WidgetI>> {
+        std::mem::take(&mut *self.removed.borrow_mut())
+    }
+    fn needs_layout(&self) -> bool {
+        self.state.dirty.get()
+    }
+    fn custom_layout(&self) {
+        if self.state.dirty.replace(false) {
+            self.rebuild();
+        }
+        self.state.bounds.set(screen_rect(self));
+
+        let layout = self.base().layout;
+        for (i, row) in self.rows.borrow().iter().enumerate() {
+            let x = layout.x + row.depth as f64 * self.indent;
+            let y = layout.y + i as f64 * self.row_height;
+            if let Some(arrow) = &row.arrow {
+                arrow.base_mut().layout = Layout {
+                    x,
+                    y,
+                    w: self.indent,
+                    h: self.row_height,
+                };
+            }
+            row.label.base_mut().layout = Layout {
+                x: x + self.indent,
+                y,
+                w: (layout.x + layout.w - x - self.indent).max(0.0),
+                h: self.row_height,
+            };
+        }
+    }
+    fn custom_draw(&self, painter: &mut dyn Painter) {
+        let bounds = screen_rect(self);
+        let selected = self.state.selected.borrow();
+        for row in self.rows.borrow().iter() {
+            let rect = screen_rect(row.label.as_ref());
+
+            // The selected row is highlighted across the whole tree
+            if selected.as_ref() == Some(&row.path) {
+                painter.fill_rect(
+                    Layout {
+                        x: bounds.x,
+                        w: bounds.w,
+                        ..rect
+                    },
+                    self.selection_color,
+                );
+            }
+
+            // Every ancestor leaves a guide beside the row
+            for depth in 0..row.depth {
+                painter.fill_rect(
+                    Layout {
+                        x: bounds.x + (depth as f64 + 0.5) * self.indent,
+                        w: 1.0,
+                        ..rect
+                    },
+                    self.guide_color,
+                );
+            }
+        }
+    }
+    fn custom_draw_over(&self, painter: &mut dyn Painter) {
+        for row in self.rows.borrow().iter() {
+            let Some(arrow) = &row.arrow else {
+                continue;
+            };
+
+            // Arrows point down at the children of expanded nodes
+            // and sideways otherwise
+            let rect = screen_rect(arrow.as_ref());
+            let (cx, cy) = (rect.x + rect.w / 2.0, rect.y + rect.h / 2.0);
+            let size = self.indent.min(self.row_height) / 4.0;
+            let points = if row.expanded {
+                [
+                    Point::new(cx - size, cy - size / 2.0),
+                    Point::new(cx, cy + size / 2.0),
+                    Point::new(cx + size, cy - size / 2.0),
+                ]
+            } else {
+                [
+                    Point::new(cx - size / 2.0, cy - size),
+                    Point::new(cx + size / 2.0, cy),
+                    Point::new(cx - size / 2.0, cy + size),
+                ]
+            };
+            painter.stroke_line(points[0], points[1], 1.5, self.arrow_color);
+            painter.stroke_line(points[1], points[2], 1.5, self.arrow_color);
+        }
+    }
+    fn children_clip(&self) -> Option<Layout> {
+        Some(screen_rect(self))
+    }
+    fn default_role(&self) -> Role {
+        Role::Tree
+    }
+}
+
+/// Finds the node `path` leads to through `nodes`
+fn node_mut<'a>(nodes: &'a mut [TreeNode], path: &[usize]) -> Option<&'a mut TreeNode> {
+    let (first, rest) = path.split_first()?;
+    let node = nodes.get_mut(*first)?;
+    if rest.is_empty() {
+        Some(node)
+    } else {
+        node_mut(node.children.as_deref_mut()?, rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::widget::{Widget, WidgetHooks};
+
+    use super::{TreeNode, TreeView};
+
+    #[test]
+    fn expands_nodes_and_loads_lazy_children() {
+        let mut root = TreeNode::new("root");
+        root.add_child(TreeNode::new("leaf"));
+        root.add_child(TreeNode::lazy("lazy"));
+        let mut tree = TreeView::new()
+            .set_width(200.0)
+            .on_load(|path| vec![TreeNode::new(&format!("{path:?}"))]);
+        tree.add_root(root);
+
+        // A collapsed root with its arrow
+        tree.custom_layout();
+        assert_eq!(tree.children().len(), 2);
+
+        tree.toggle(&[0]);
+        tree.custom_layout();
+        // Only the lazy child can be expanded
+        assert_eq!(tree.children().len(), 5);
+        assert_eq!(tree.removed_children().len(), 2);
+        let leaf = &tree.children()[2];
+        assert_eq!(leaf.base().layout.x, 32.0);
+        assert_eq!(leaf.base().layout.y, 24.0);
+
+        tree.toggle(&[0, 1]);
+        tree.custom_layout();
+        let rows = tree.rows.borrow();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[3].path, vec![0, 1, 0]);
+        assert_eq!(rows[3].label.base().text.label, "[0, 1]");
+    }
+}
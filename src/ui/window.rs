@@ -0,0 +1,191 @@
+//! Options for the native window hosting the UI.
+//!
+//! [`DOM::builder`] sets up the chrome of the window before it is
+//! shown so apps never have to reach into winit themselves.
+
+use tiny_skia::Pixmap;
+use winit::{
+    dpi::LogicalSize,
+    window::{Icon, WindowBuilder},
+};
+
+#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+use crate::error::Error;
+
+use super::dom::DOM;
+
+/// The `WindowRequest` enum describes what widgets may ask
+/// of the window hosting them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowRequest {
+    /// Moves the window along with the mouse while the
+    /// pressed button is held
+    Drag,
+    Minimize,
+    /// Maximizes the window or restores its size
+    /// when already maximized
+    ToggleMaximize,
+    Close,
+}
+
+/// The title windows are shown with unless told otherwise
+const DEFAULT_TITLE: &str = "Gemini - UI Framework";
+
+/// The `DOMBuilder` struct describes the window a `DOM`
+/// is created with
+///
+/// ## Example
+/// ```ignore
+/// let dom = DOM::builder()
+///     .size(800, 600)
+///     .title("Notes")
+///     .resizable(false)
+///     .min_size(400, 300)
+///     .icon(include_bytes!("icon.png"))
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct DOMBuilder {
+    pub(crate) size: Option<(u32, u32)>,
+    pub(crate) title: String,
+    pub(crate) resizable: bool,
+    pub(crate) maximized: bool,
+    pub(crate) min_size: Option<(u32, u32)>,
+    pub(crate) icon: Option<Vec<u8>>,
+    pub(crate) decorations: bool,
+}
+impl Default for DOMBuilder {
+    fn default() -> Self {
+        Self {
+            size: None,
+            title: DEFAULT_TITLE.into(),
+            resizable: true,
+            maximized: false,
+            min_size: None,
+            icon: None,
+            decorations: true,
+        }
+    }
+}
+impl DOMBuilder {
+    pub fn new() -> Self {
+        DOMBuilder::default()
+    }
+    /// Set the size of the inside of the window
+    ///
+    /// The platform picks a size when left unset
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+    /// Set the title shown in the title bar of the window
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.into();
+        self
+    }
+    /// Set whether the user can resize the window
+    ///
+    /// Defaults to `true`
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+    /// Open the window maximized
+    pub fn maximized(mut self) -> Self {
+        self.maximized = true;
+        self
+    }
+    /// Set the smallest size the user can shrink the window to
+    pub fn min_size(mut self, width: u32, height: u32) -> Self {
+        self.min_size = Some((width, height));
+        self
+    }
+    /// Set the icon of the window from the bytes of a PNG
+    ///
+    /// Icons that are not valid PNGs are left out
+    pub fn icon(mut self, png: &[u8]) -> Self {
+        self.icon = Some(png.to_vec());
+        self
+    }
+    /// Set whether the window is drawn with the borders and
+    /// title bar of the platform
+    ///
+    /// Defaults to `true`
+    pub fn decorations(mut self, decorations: bool) -> Self {
+        self.decorations = decorations;
+        self
+    }
+    /// Creates the window and the `DOM` hosting the UI inside it
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the window can not be created,
+    /// see `DOMBuilder::try_build`
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+    pub fn build(self) -> DOM {
+        self.try_build()
+            .unwrap_or_else(|err| panic!("window should be created: {err}"))
+    }
+    /// Creates the window and the `DOM` hosting the UI inside it
+    ///
+    /// Returns an error if the platform refused to create
+    /// the window or a surface to draw on
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+    pub fn try_build(self) -> Result<DOM, Error> {
+        DOM::from_builder(&self)
+    }
+    /// Returns the winit window described
+    pub(crate) fn window(&self) -> WindowBuilder {
+        let mut builder = WindowBuilder::new()
+            .with_title(&self.title)
+            .with_resizable(self.resizable)
+            .with_maximized(self.maximized)
+            .with_decorations(self.decorations)
+            .with_window_icon(self.icon.as_deref().and_then(decode_icon));
+
+        // Phones decide the size of the window themselves
+        if let Some((width, height)) = self.size.filter(|(w, h)| *w > 0 && *h > 0) {
+            builder = builder.with_inner_size(LogicalSize::new(width, height));
+        }
+        if let Some((width, height)) = self.min_size {
+            builder = builder.with_min_inner_size(LogicalSize::new(width, height));
+        }
+        builder
+    }
+}
+
+/// Decodes the bytes of a PNG into a window icon
+fn decode_icon(png: &[u8]) -> Option<Icon> {
+    let pixmap = match Pixmap::decode_png(png) {
+        Ok(pixmap) => pixmap,
+        Err(err) => {
+            log::error!("window icon is not a valid PNG: {err}");
+            return None;
+        }
+    };
+    // Icons take colors that are not premultiplied
+    let rgba = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    Icon::from_rgba(rgba, pixmap.width(), pixmap.height()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use tiny_skia::{Color, Pixmap};
+
+    use super::decode_icon;
+
+    #[test]
+    fn icons_are_decoded_from_png() {
+        let mut pixmap = Pixmap::new(4, 4).unwrap();
+        pixmap.fill(Color::from_rgba8(255, 0, 0, 128));
+        assert!(decode_icon(&pixmap.encode_png().unwrap()).is_some());
+        assert!(decode_icon(b"not a png").is_none());
+    }
+}